@@ -0,0 +1,19 @@
+contract target_conditional {
+	/// Returns the name of the target this contract was compiled for.
+	function chain() public pure returns (string) {
+		if (target == "solana") {
+			return "solana";
+		} else {
+			return "substrate";
+		}
+	}
+
+	/// Each branch uses a builtin which only exists on its own target.
+	function threshold() public view returns (uint64) {
+		if (target == "substrate") {
+			return uint64(block.minimum_balance);
+		} else {
+			return block.slot;
+		}
+	}
+}
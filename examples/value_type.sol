@@ -0,0 +1,20 @@
+contract wallet {
+	valueType received;
+
+	/// Keep a tally of the value sent, whatever the width of balances on the target.
+	function deposit() public payable returns (valueType) {
+		received += msg.value;
+
+		return received;
+	}
+
+	/// The largest balance the target can represent.
+	function max() public pure returns (valueType) {
+		return type(valueType).max;
+	}
+
+	/// Addresses are 32 bytes on both Substrate and Solana.
+	function me() public view returns (bytes32) {
+		return bytes32(address(this));
+	}
+}
@@ -1083,6 +1083,64 @@ int  /** x */ constant /** x */ y/** dev:  */ = /** x */1 /** x */ + /** x */2/*
     assert_eq!(actual_parse_tree.0.len(), 1);
 }
 
+#[test]
+fn parse_for_multiple_init_next() {
+    let src = r#"
+        function f(uint n) pure {
+            for (uint i = 0, j = n; i < j; i++, j--) {}
+            for (i = 0, j = n; i < j; i++) {}
+        }
+        "#;
+
+    let (actual_parse_tree, _) = crate::parse(src, 0).unwrap();
+
+    let body = match &actual_parse_tree.0[0] {
+        SourceUnitPart::FunctionDefinition(def) => def.body.as_ref().unwrap(),
+        _ => panic!("function expected"),
+    };
+
+    let statements = match body {
+        Statement::Block { statements, .. } => statements,
+        _ => panic!("block expected"),
+    };
+
+    match &statements[0] {
+        Statement::For(_, Some(init), _, Some(next), _) => {
+            match init.as_ref() {
+                Statement::Block { statements, .. } => {
+                    assert_eq!(statements.len(), 2);
+
+                    for (stmt, name) in statements.iter().zip(["i", "j"]) {
+                        match stmt {
+                            Statement::VariableDefinition(_, decl, Some(_)) => {
+                                assert_eq!(decl.name.name, name);
+                                assert_eq!(
+                                    decl.ty,
+                                    Expression::Type(Loc::File(0, 52, 56), Type::Uint(256))
+                                );
+                            }
+                            _ => panic!("variable definition expected"),
+                        }
+                    }
+                }
+                _ => panic!("block expected"),
+            }
+
+            assert!(
+                matches!(next.as_ref(), Statement::Block { statements, .. } if statements.len() == 2)
+            );
+        }
+        _ => panic!("for statement expected"),
+    }
+
+    assert!(matches!(
+        &statements[1],
+        Statement::For(_, Some(init), _, Some(next), _)
+            if matches!(init.as_ref(), Statement::Block { statements, .. } if statements.len() == 2)
+                && matches!(next.as_ref(), Statement::Expression(..))
+    ));
+}
+
 #[test]
 fn test_libsolidity() {
     fn timeout_after<T, F>(d: Duration, f: F) -> Result<T, String>
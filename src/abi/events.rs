@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decode the topics and data of events emitted by a contract back into named, typed fields,
+//! using the resolved event definitions. This saves indexers which use solang as a library
+//! from having to re-implement the encoding rules.
+
+use super::scale::{decode_compact, take};
+use crate::sema::ast::{ArrayLength, Namespace, Type};
+use num_bigint::{BigInt, Sign};
+use num_traits::ToPrimitive;
+
+/// A single decoded value
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DecodedValue {
+    Bool(bool),
+    /// Any signed or unsigned integer, including enums
+    Int(BigInt),
+    Address(Vec<u8>),
+    /// Either `bytesN` or dynamic `bytes`
+    Bytes(Vec<u8>),
+    String(String),
+    Array(Vec<DecodedValue>),
+    /// Struct fields in declaration order
+    Struct(Vec<(String, DecodedValue)>),
+    /// External function, i.e. the address and the function selector
+    ExternalFunction(Vec<u8>, u32),
+    /// Indexed fields of type `string`, `bytes`, struct or array are hashed with keccak256
+    /// before they are stored in a topic, so only the hash can be recovered
+    Hash([u8; 32]),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DecodedField {
+    pub name: String,
+    pub indexed: bool,
+    pub value: DecodedValue,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DecodedEvent {
+    pub event_no: usize,
+    pub name: String,
+    pub fields: Vec<DecodedField>,
+}
+
+/// Decode an event emitted by contract `contract_no`. The namespace must have been through
+/// codegen, since that is where the list of events a contract emits is built.
+///
+/// On Substrate, the first byte of the data is the index of the event in that list, so
+/// anonymous events can be decoded too. The topics hold the indexed fields; the remaining fields
+/// are SCALE encoded in the data.
+pub fn decode_event(
+    contract_no: usize,
+    topics: &[[u8; 32]],
+    data: &[u8],
+    ns: &Namespace,
+) -> Result<DecodedEvent, String> {
+    if !ns.target.is_substrate() {
+        return Err(format!(
+            "decoding events is not supported on target {}",
+            ns.target
+        ));
+    }
+
+    let contract = &ns.contracts[contract_no];
+
+    let (event_id, mut data) = data
+        .split_first()
+        .ok_or_else(|| "event data is empty".to_string())?;
+
+    let event_no = *contract
+        .sends_events
+        .get(*event_id as usize)
+        .ok_or_else(|| {
+            format!(
+                "contract '{}' does not emit an event with index {}",
+                contract.name, event_id
+            )
+        })?;
+
+    let event = &ns.events[event_no];
+
+    let indexed_fields = event.fields.iter().filter(|field| field.indexed).count();
+
+    if indexed_fields != topics.len() {
+        return Err(format!(
+            "event '{}' has {} indexed fields but {} topics given",
+            event.name,
+            indexed_fields,
+            topics.len()
+        ));
+    }
+
+    let mut topics = topics.iter();
+    let mut fields = Vec::new();
+
+    for field in &event.fields {
+        let value = if field.indexed {
+            decode_topic(&field.ty, topics.next().unwrap(), ns)?
+        } else {
+            decode_ty(&field.ty, &mut data, ns)?
+        };
+
+        fields.push(DecodedField {
+            name: field.name_as_str().to_string(),
+            indexed: field.indexed,
+            value,
+        });
+    }
+
+    if !data.is_empty() {
+        return Err(format!(
+            "event '{}' has {} bytes of trailing data",
+            event.name,
+            data.len()
+        ));
+    }
+
+    Ok(DecodedEvent {
+        event_no,
+        name: event.name.to_string(),
+        fields,
+    })
+}
+
+/// Topics hold the value in packed encoding, padded with zeros. Dynamic values are hashed.
+fn decode_topic(ty: &Type, topic: &[u8; 32], ns: &Namespace) -> Result<DecodedValue, String> {
+    match ty {
+        Type::String | Type::DynamicBytes | Type::Struct(_) | Type::Array(..) => {
+            Ok(DecodedValue::Hash(*topic))
+        }
+        _ => decode_ty(ty, &mut &topic[..], ns),
+    }
+}
+
+/// Decode a single SCALE encoded value, advancing `data` past it
+fn decode_ty(ty: &Type, data: &mut &[u8], ns: &Namespace) -> Result<DecodedValue, String> {
+    match ty {
+        Type::Bool => Ok(DecodedValue::Bool(take(data, 1)?[0] != 0)),
+        Type::Uint(n) | Type::Int(n) => {
+            // substrate only supports power-of-two types, so the value is extended
+            let bytes = take(data, (*n as usize / 8).next_power_of_two())?;
+
+            Ok(DecodedValue::Int(if ty.is_signed_int() {
+                BigInt::from_signed_bytes_le(bytes)
+            } else {
+                BigInt::from_bytes_le(Sign::Plus, bytes)
+            }))
+        }
+        Type::Address(_) | Type::Contract(_) => Ok(DecodedValue::Address(
+            take(data, ns.address_length)?.to_vec(),
+        )),
+        Type::Bytes(n) => Ok(DecodedValue::Bytes(take(data, *n as usize)?.to_vec())),
+        Type::Enum(enum_no) => decode_ty(&ns.enums[*enum_no].ty, data, ns),
+        Type::UserType(user_type_no) => decode_ty(&ns.user_types[*user_type_no].ty, data, ns),
+        Type::DynamicBytes => {
+            let len = decode_compact(data)?;
+
+            Ok(DecodedValue::Bytes(take(data, len)?.to_vec()))
+        }
+        Type::String => {
+            let len = decode_compact(data)?;
+
+            String::from_utf8(take(data, len)?.to_vec())
+                .map(DecodedValue::String)
+                .map_err(|_| "string is not valid utf-8".to_string())
+        }
+        Type::Array(_, dims) => {
+            let len = match dims.last() {
+                Some(ArrayLength::Fixed(len)) => len
+                    .to_usize()
+                    .ok_or_else(|| format!("array length {} too large", len))?,
+                _ => decode_compact(data)?,
+            };
+
+            let elem_ty = ty.array_elem();
+
+            let elems = (0..len)
+                .map(|_| decode_ty(&elem_ty, data, ns))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(DecodedValue::Array(elems))
+        }
+        Type::Struct(str_ty) => {
+            let fields = str_ty
+                .definition(ns)
+                .fields
+                .iter()
+                .map(|field| {
+                    decode_ty(&field.ty, data, ns)
+                        .map(|value| (field.name_as_str().to_string(), value))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(DecodedValue::Struct(fields))
+        }
+        Type::ExternalFunction { .. } => {
+            let address = take(data, ns.address_length)?.to_vec();
+            let selector = take(data, 4)?;
+
+            Ok(DecodedValue::ExternalFunction(
+                address,
+                u32::from_le_bytes(selector.try_into().unwrap()),
+            ))
+        }
+        _ => Err(format!("cannot decode type {}", ty.to_string(ns))),
+    }
+}
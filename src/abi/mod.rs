@@ -8,6 +8,8 @@ use crate::sema::ast::Namespace;
 use crate::Target;
 
 pub mod ethereum;
+pub mod events;
+pub mod scale;
 pub mod substrate;
 
 pub fn generate_abi(
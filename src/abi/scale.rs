@@ -0,0 +1,307 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! SCALE encode and decode Solidity values, driven by the resolved types in the namespace. This
+//! is the encoding Substrate contracts use for their arguments and return values, so it can be
+//! used to call contracts without hand-encoding bytes.
+
+use crate::sema::ast::{ArrayLength, Namespace, Type};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::ToPrimitive;
+
+/// A value of a Solidity type
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Value {
+    Bool(bool),
+    /// Unsigned integers and enums
+    Uint(BigUint),
+    Int(BigInt),
+    /// Address or contract
+    Address(Vec<u8>),
+    /// `bytesN`
+    FixedBytes(Vec<u8>),
+    /// Dynamic `bytes`
+    Bytes(Vec<u8>),
+    String(String),
+    /// Fixed length or dynamic array
+    Array(Vec<Value>),
+    /// Struct fields in declaration order
+    Struct(Vec<Value>),
+}
+
+/// Encode the values for the given types, e.g. the arguments of a function
+pub fn encode(tys: &[Type], values: &[Value], ns: &Namespace) -> Result<Vec<u8>, String> {
+    if tys.len() != values.len() {
+        return Err(format!(
+            "{} values given but {} expected",
+            values.len(),
+            tys.len()
+        ));
+    }
+
+    let mut data = Vec::new();
+
+    for (ty, value) in tys.iter().zip(values) {
+        encode_ty(ty, value, &mut data, ns)?;
+    }
+
+    Ok(data)
+}
+
+/// Decode values of the given types, e.g. the return values of a function. All of the data
+/// must be consumed.
+pub fn decode(tys: &[Type], mut data: &[u8], ns: &Namespace) -> Result<Vec<Value>, String> {
+    let values = tys
+        .iter()
+        .map(|ty| decode_ty(ty, &mut data, ns))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !data.is_empty() {
+        return Err(format!("{} bytes of trailing data", data.len()));
+    }
+
+    Ok(values)
+}
+
+fn encode_ty(ty: &Type, value: &Value, data: &mut Vec<u8>, ns: &Namespace) -> Result<(), String> {
+    match (ty, value) {
+        (Type::Bool, Value::Bool(b)) => data.push(*b as u8),
+        (Type::Uint(n), Value::Uint(v)) => {
+            if v.bits() > *n as u64 {
+                return Err(format!("value {} does not fit into type uint{}", v, n));
+            }
+
+            // substrate only supports power-of-two types, so the value is extended
+            let len = (*n as usize / 8).next_power_of_two();
+            let mut bytes = v.to_bytes_le();
+
+            bytes.resize(len, 0);
+
+            data.extend(bytes);
+        }
+        (Type::Int(n), Value::Int(v)) => {
+            if v.to_signed_bytes_le().len() * 8 > *n as usize {
+                return Err(format!("value {} does not fit into type int{}", v, n));
+            }
+
+            let len = (*n as usize / 8).next_power_of_two();
+            let mut bytes = v.to_signed_bytes_le();
+
+            bytes.resize(len, if v.sign() == Sign::Minus { 0xff } else { 0 });
+
+            data.extend(bytes);
+        }
+        (Type::Address(_) | Type::Contract(_), Value::Address(address))
+            if address.len() == ns.address_length =>
+        {
+            data.extend(address);
+        }
+        (Type::Bytes(n), Value::FixedBytes(bytes)) if bytes.len() == *n as usize => {
+            data.extend(bytes);
+        }
+        (Type::Enum(enum_no), Value::Uint(_)) => {
+            encode_ty(&ns.enums[*enum_no].ty, value, data, ns)?
+        }
+        (Type::UserType(user_type_no), _) => {
+            encode_ty(&ns.user_types[*user_type_no].ty, value, data, ns)?
+        }
+        (Type::DynamicBytes, Value::Bytes(bytes)) => {
+            encode_compact(bytes.len(), data);
+            data.extend(bytes);
+        }
+        (Type::String, Value::String(s)) => {
+            encode_compact(s.len(), data);
+            data.extend(s.as_bytes());
+        }
+        (Type::Array(_, dims), Value::Array(elems)) => {
+            match dims.last() {
+                Some(ArrayLength::Fixed(len)) => {
+                    if len.to_usize() != Some(elems.len()) {
+                        return Err(format!(
+                            "array of {} elements given for type {}",
+                            elems.len(),
+                            ty.to_string(ns)
+                        ));
+                    }
+                }
+                _ => encode_compact(elems.len(), data),
+            }
+
+            let elem_ty = ty.array_elem();
+
+            for elem in elems {
+                encode_ty(&elem_ty, elem, data, ns)?;
+            }
+        }
+        (Type::Struct(str_ty), Value::Struct(fields)) => {
+            let def = str_ty.definition(ns);
+
+            if def.fields.len() != fields.len() {
+                return Err(format!(
+                    "struct of {} fields given for type {}",
+                    fields.len(),
+                    ty.to_string(ns)
+                ));
+            }
+
+            for (field, value) in def.fields.iter().zip(fields) {
+                encode_ty(&field.ty, value, data, ns)?;
+            }
+        }
+        _ => {
+            return Err(format!(
+                "cannot encode {:?} as type {}",
+                value,
+                ty.to_string(ns)
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+fn decode_ty(ty: &Type, data: &mut &[u8], ns: &Namespace) -> Result<Value, String> {
+    match ty {
+        Type::Bool => Ok(Value::Bool(take(data, 1)?[0] != 0)),
+        Type::Uint(n) => {
+            let bytes = take(data, (*n as usize / 8).next_power_of_two())?;
+
+            Ok(Value::Uint(BigUint::from_bytes_le(bytes)))
+        }
+        Type::Int(n) => {
+            let bytes = take(data, (*n as usize / 8).next_power_of_two())?;
+
+            Ok(Value::Int(BigInt::from_signed_bytes_le(bytes)))
+        }
+        Type::Address(_) | Type::Contract(_) => {
+            Ok(Value::Address(take(data, ns.address_length)?.to_vec()))
+        }
+        Type::Bytes(n) => Ok(Value::FixedBytes(take(data, *n as usize)?.to_vec())),
+        Type::Enum(enum_no) => decode_ty(&ns.enums[*enum_no].ty, data, ns),
+        Type::UserType(user_type_no) => decode_ty(&ns.user_types[*user_type_no].ty, data, ns),
+        Type::DynamicBytes => {
+            let len = decode_compact(data)?;
+
+            Ok(Value::Bytes(take(data, len)?.to_vec()))
+        }
+        Type::String => {
+            let len = decode_compact(data)?;
+
+            String::from_utf8(take(data, len)?.to_vec())
+                .map(Value::String)
+                .map_err(|_| "string is not valid utf-8".to_string())
+        }
+        Type::Array(_, dims) => {
+            let len = match dims.last() {
+                Some(ArrayLength::Fixed(len)) => len
+                    .to_usize()
+                    .ok_or_else(|| format!("array length {} too large", len))?,
+                _ => decode_compact(data)?,
+            };
+
+            let elem_ty = ty.array_elem();
+
+            let elems = (0..len)
+                .map(|_| decode_ty(&elem_ty, data, ns))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(Value::Array(elems))
+        }
+        Type::Struct(str_ty) => {
+            let fields = str_ty
+                .definition(ns)
+                .fields
+                .iter()
+                .map(|field| decode_ty(&field.ty, data, ns))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(Value::Struct(fields))
+        }
+        _ => Err(format!("cannot decode type {}", ty.to_string(ns))),
+    }
+}
+
+/// Encode a SCALE compact integer, as used for the length of strings and vectors
+fn encode_compact(len: usize, data: &mut Vec<u8>) {
+    if len < 1 << 6 {
+        data.push((len as u8) << 2);
+    } else if len < 1 << 14 {
+        data.extend(((len as u16) << 2 | 1).to_le_bytes());
+    } else if len < 1 << 30 {
+        data.extend(((len as u32) << 2 | 2).to_le_bytes());
+    } else {
+        let mut bytes = BigUint::from(len).to_bytes_le();
+
+        while bytes.len() < 4 {
+            bytes.push(0);
+        }
+
+        data.push(((bytes.len() - 4) as u8) << 2 | 3);
+        data.extend(bytes);
+    }
+}
+
+/// Decode a SCALE compact integer, as used for the length of strings and vectors
+pub(super) fn decode_compact(data: &mut &[u8]) -> Result<usize, String> {
+    let first = take(data, 1)?[0];
+
+    match first & 3 {
+        0 => Ok((first >> 2) as usize),
+        1 => {
+            let second = take(data, 1)?[0];
+
+            Ok((u16::from_le_bytes([first, second]) >> 2) as usize)
+        }
+        2 => {
+            let rest = take(data, 3)?;
+
+            Ok((u32::from_le_bytes([first, rest[0], rest[1], rest[2]]) >> 2) as usize)
+        }
+        _ => {
+            let bytes = take(data, (first >> 2) as usize + 4)?;
+
+            BigUint::from_bytes_le(bytes)
+                .to_usize()
+                .ok_or_else(|| "compact length too large".to_string())
+        }
+    }
+}
+
+pub(super) fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+    if data.len() < len {
+        return Err(format!(
+            "data too short: {} bytes needed but {} left",
+            len,
+            data.len()
+        ));
+    }
+
+    let (bytes, rest) = data.split_at(len);
+
+    *data = rest;
+
+    Ok(bytes)
+}
+
+#[test]
+fn compact() {
+    for len in [
+        0,
+        1,
+        63,
+        64,
+        16383,
+        16384,
+        (1 << 30) - 1,
+        1 << 30,
+        usize::MAX,
+    ] {
+        let mut data = Vec::new();
+
+        encode_compact(len, &mut data);
+
+        let mut slice = &data[..];
+
+        assert_eq!(decode_compact(&mut slice), Ok(len));
+        assert!(slice.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 // Parity Substrate style ABIs/Abi
+use crate::codegen::panic::PanicCode;
 use crate::sema::ast;
 use crate::sema::tags::render;
 use contract_metadata::*;
@@ -9,6 +10,7 @@ use semver::Version;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use solang_parser::pt;
+use std::collections::HashMap;
 use std::convert::TryInto;
 
 use super::non_unique_function_names;
@@ -18,6 +20,9 @@ pub struct Abi {
     storage: Storage,
     types: Vec<Type>,
     pub spec: Spec,
+    /// Index into types, so that registering a type does not search the whole registry
+    #[serde(skip)]
+    type_index: HashMap<Type, usize>,
 }
 
 impl Abi {
@@ -26,30 +31,30 @@ impl Abi {
     }
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 pub struct ArrayDef {
     array: Array,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 pub struct Array {
     len: usize,
     #[serde(rename = "type")]
     ty: usize,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 pub struct SequenceDef {
     sequence: Sequence,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 pub struct Sequence {
     #[serde(rename = "type")]
     ty: usize,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 #[serde(untagged)]
 enum Type {
     Builtin { def: PrimitiveDef },
@@ -59,44 +64,44 @@ enum Type {
     Enum { path: Vec<String>, def: EnumDef },
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 struct BuiltinType {
     id: String,
     def: String,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 struct EnumVariant {
     name: String,
     discriminant: usize,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 struct EnumDef {
     variant: Enum,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 struct Enum {
     variants: Vec<EnumVariant>,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 struct Composite {
     composite: StructFields,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 struct StructFields {
     fields: Vec<StructField>,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 struct PrimitiveDef {
     primitive: String,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 struct StructField {
     #[serde(skip_serializing_if = "Option::is_none")]
     name: Option<String>,
@@ -190,8 +195,10 @@ struct StorageLayout {
 }
 
 #[derive(Deserialize, Serialize)]
-struct LayoutField {
-    cell: LayoutFieldCell,
+#[serde(rename_all = "lowercase")]
+enum LayoutField {
+    Cell(LayoutFieldCell),
+    Hash(LayoutFieldHash),
 }
 
 #[derive(Deserialize, Serialize)]
@@ -200,19 +207,35 @@ struct LayoutFieldCell {
     ty: usize,
 }
 
+/// The entries of a mapping are stored at keccak256 of the slot of the mapping, followed by
+/// the key. Integer keys are little endian, bytesN keys are used as is.
+#[derive(Deserialize, Serialize)]
+struct LayoutFieldHash {
+    offset: String,
+    strategy: LayoutHashingStrategy,
+    layout: Box<LayoutField>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct LayoutHashingStrategy {
+    hasher: String,
+    prefix: String,
+    postfix: String,
+}
+
 /// Create a new registry and create new entries. Note that the registry is
 /// accessed by number, and the first entry is 1, not 0.
 impl Abi {
     /// Add a type to the list unless already present
     fn register_ty(&mut self, ty: Type) -> usize {
-        match self.types.iter().position(|t| *t == ty) {
-            Some(i) => i + 1,
-            None => {
-                self.types.push(ty);
-
-                self.types.len()
-            }
+        if let Some(i) = self.type_index.get(&ty) {
+            return *i;
         }
+
+        self.types.push(ty.clone());
+        self.type_index.insert(ty, self.types.len());
+
+        self.types.len()
     }
 
     /// Returns index to builtin type in registry. Type is added if not already present
@@ -349,7 +372,30 @@ pub fn metadata(contract_no: usize, code: &[u8], ns: &ast::Namespace) -> Value {
         serde_json::to_value(&abi.storage).unwrap(),
     );
 
-    let metadata = ContractMetadata::new(source, contract, None, abi_json);
+    // record the code size and the limit it was checked against, and the meaning of the codes
+    // a Panic(uint256) revert can have. The code hash is in the source section.
+    let user = match ns.target {
+        crate::Target::Substrate { max_code_size, .. } => {
+            let panic_codes: Map<String, Value> = PanicCode::ALL
+                .iter()
+                .map(|code| {
+                    (
+                        format!("0x{:02x}", *code as u8),
+                        Value::from(code.description()),
+                    )
+                })
+                .collect();
+
+            let mut json = Map::new();
+            json.insert(String::from("code_size"), Value::from(code.len()));
+            json.insert(String::from("max_code_size"), Value::from(max_code_size));
+            json.insert(String::from("panic_codes"), Value::from(panic_codes));
+            Some(User::new(json))
+        }
+        _ => None,
+    };
+
+    let metadata = ContractMetadata::new(source, contract, user, abi_json);
 
     // serialize to json
     serde_json::to_value(&metadata).unwrap()
@@ -366,6 +412,7 @@ fn gen_abi(contract_no: usize, ns: &ast::Namespace) -> Abi {
             messages: Vec::new(),
             events: Vec::new(),
         },
+        type_index: HashMap::new(),
     };
 
     let fields = ns.contracts[contract_no]
@@ -374,17 +421,38 @@ fn gen_abi(contract_no: usize, ns: &ast::Namespace) -> Abi {
         .filter_map(|layout| {
             let var = &ns.contracts[layout.contract_no].variables[layout.var_no];
 
-            // mappings and large types cannot be represented
+            let slot = format!("0x{:064X}", layout.slot);
+
+            // nested mappings and large types cannot be represented
             if !var.ty.contains_mapping(ns) && var.ty.fits_in_memory(ns) {
                 Some(StorageLayout {
                     name: var.name.to_string(),
-                    layout: LayoutField {
-                        cell: LayoutFieldCell {
-                            key: format!("0x{:064X}", layout.slot),
-                            ty: ty_to_abi(&var.ty, ns, &mut abi).ty,
-                        },
-                    },
+                    layout: LayoutField::Cell(LayoutFieldCell {
+                        key: slot,
+                        ty: ty_to_abi(&var.ty, ns, &mut abi).ty,
+                    }),
                 })
+            } else if let ast::Type::Mapping(_, value) = &var.ty {
+                if !value.contains_mapping(ns) && value.fits_in_memory(ns) {
+                    Some(StorageLayout {
+                        name: var.name.to_string(),
+                        layout: LayoutField::Hash(LayoutFieldHash {
+                            offset: slot,
+                            strategy: LayoutHashingStrategy {
+                                hasher: "Keccak256".to_string(),
+                                prefix: String::new(),
+                                postfix: String::new(),
+                            },
+                            // the key of each entry is the hash, so the cell has no key of its own
+                            layout: Box::new(LayoutField::Cell(LayoutFieldCell {
+                                key: format!("0x{:064X}", 0),
+                                ty: ty_to_abi(value, ns, &mut abi).ty,
+                            })),
+                        }),
+                    })
+                } else {
+                    None
+                }
             } else {
                 None
             }
@@ -531,6 +599,105 @@ fn gen_abi(contract_no: usize, ns: &ast::Namespace) -> Abi {
 }
 
 fn ty_to_abi(ty: &ast::Type, ns: &ast::Namespace, registry: &mut Abi) -> ParamType {
+    /// A type which can only be registered once its element or field types are
+    enum Pending<'a> {
+        Array(&'a [ast::ArrayLength]),
+        /// The struct, and where the types of its fields start on the converted stack
+        Struct(&'a ast::StructDecl, usize),
+    }
+
+    // Structs and arrays can be nested arbitrarily deep, so rather than recursing, keep a stack
+    // of the types which are waiting for their element or field types to be converted.
+    let mut pending: Vec<Pending> = Vec::new();
+    let mut converted: Vec<ParamType> = Vec::new();
+    let mut next = Some(ty);
+
+    loop {
+        if let Some(ty) = next.take() {
+            match ty {
+                ast::Type::Mapping(..) => unreachable!(),
+                ast::Type::Array(elem, dims) => {
+                    pending.push(Pending::Array(dims));
+                    next = Some(elem);
+                    continue;
+                }
+                ast::Type::StorageRef(_, ty) | ast::Type::Ref(ty) => {
+                    next = Some(ty);
+                    continue;
+                }
+                ast::Type::UserType(no) => {
+                    next = Some(&ns.user_types[*no].ty);
+                    continue;
+                }
+                ast::Type::Struct(struct_type) => {
+                    let def = struct_type.definition(ns);
+
+                    pending.push(Pending::Struct(def, converted.len()));
+                    next = def.fields.first().map(|f| &f.ty);
+                    continue;
+                }
+                _ => converted.push(primitive_to_abi(ty, ns, registry)),
+            }
+        }
+
+        // a type was converted; register the type waiting for it, if it has all it needs
+        match pending.last() {
+            None => return converted.pop().unwrap(),
+            Some(&Pending::Array(dims)) => {
+                let mut param_ty = converted.pop().unwrap();
+
+                for d in dims {
+                    if let ast::ArrayLength::Fixed(d) = d {
+                        param_ty = ParamType {
+                            ty: registry.builtin_array_type(param_ty.ty, d.to_usize().unwrap()),
+                            display_name: vec![],
+                        }
+                    } else {
+                        param_ty = ParamType {
+                            ty: registry.builtin_slice_type(param_ty.ty),
+                            display_name: vec![],
+                        }
+                    }
+                }
+
+                converted.push(param_ty);
+            }
+            Some(&Pending::Struct(def, start)) => {
+                let done = converted.len() - start;
+
+                if done < def.fields.len() {
+                    next = Some(&def.fields[done].ty);
+                    continue;
+                }
+
+                let mut display_name = vec![def.name.to_owned()];
+
+                if let Some(contract_name) = &def.contract {
+                    display_name.insert(0, contract_name.to_owned());
+                }
+
+                let fields = converted
+                    .drain(start..)
+                    .zip(def.fields.iter())
+                    .map(|(param_ty, f)| StructField {
+                        name: Some(f.name_as_str().to_owned()),
+                        ty: param_ty.ty,
+                    })
+                    .collect();
+
+                converted.push(ParamType {
+                    ty: registry.struct_type(display_name.clone(), fields),
+                    display_name,
+                });
+            }
+        }
+
+        pending.pop();
+    }
+}
+
+/// Convert a type which has no element or field types
+fn primitive_to_abi(ty: &ast::Type, ns: &ast::Namespace, registry: &mut Abi) -> ParamType {
     match ty {
         ast::Type::Enum(n) => ParamType {
             ty: registry.builtin_enum_type(&ns.enums[*n]),
@@ -543,29 +710,6 @@ fn ty_to_abi(ty: &ast::Type, ns: &ast::Namespace, registry: &mut Abi) -> ParamTy
                 display_name: vec![],
             }
         }
-        ast::Type::Mapping(..) => unreachable!(),
-        ast::Type::Array(ty, dims) => {
-            let mut param_ty = ty_to_abi(ty, ns, registry);
-
-            for d in dims {
-                if let ast::ArrayLength::Fixed(d) = d {
-                    param_ty = ParamType {
-                        ty: registry.builtin_array_type(param_ty.ty, d.to_usize().unwrap()),
-                        display_name: vec![],
-                    }
-                } else {
-                    param_ty = ParamType {
-                        ty: registry.builtin_slice_type(param_ty.ty),
-                        display_name: vec![],
-                    }
-                }
-            }
-
-            param_ty
-        }
-        ast::Type::StorageRef(_, ty) => ty_to_abi(ty, ns, registry),
-        ast::Type::Ref(ty) => ty_to_abi(ty, ns, registry),
-        ast::Type::UserType(no) => ty_to_abi(&ns.user_types[*no].ty, ns, registry),
         ast::Type::Bool | ast::Type::Uint(_) | ast::Type::Int(_) => {
             let scalety = match ty {
                 ast::Type::Bool => "bool".into(),
@@ -581,40 +725,7 @@ fn ty_to_abi(ty: &ast::Type, ns: &ast::Namespace, registry: &mut Abi) -> ParamTy
                 display_name: vec![scalety.to_string()],
             }
         }
-        ast::Type::Address(_) | ast::Type::Contract(_) => {
-            let elem = registry.builtin_type("u8");
-            let ty = registry.builtin_array_type(elem, 32);
-
-            ParamType {
-                ty: registry.struct_type(
-                    vec!["AccountId".to_owned()],
-                    vec![StructField { name: None, ty }],
-                ),
-                display_name: vec!["AccountId".to_owned()],
-            }
-        }
-        ast::Type::Struct(struct_type) => {
-            let mut display_name = vec![struct_type.definition(ns).name.to_owned()];
-
-            if let Some(contract_name) = &struct_type.definition(ns).contract {
-                display_name.insert(0, contract_name.to_owned());
-            }
-
-            let def = struct_type.definition(ns);
-            let fields = def
-                .fields
-                .iter()
-                .map(|f| StructField {
-                    name: Some(f.name_as_str().to_owned()),
-                    ty: ty_to_abi(&f.ty, ns, registry).ty,
-                })
-                .collect();
-
-            ParamType {
-                ty: registry.struct_type(display_name.clone(), fields),
-                display_name,
-            }
-        }
+        ast::Type::Address(_) | ast::Type::Contract(_) => account_id_to_abi(ns, registry),
         ast::Type::DynamicBytes => {
             let elem = registry.builtin_type("u8");
 
@@ -635,11 +746,11 @@ fn ty_to_abi(ty: &ast::Type, ns: &ast::Namespace, registry: &mut Abi) -> ParamTy
             let fields = vec![
                 StructField {
                     name: None,
-                    ty: ty_to_abi(&ast::Type::Address(false), ns, registry).ty,
+                    ty: account_id_to_abi(ns, registry).ty,
                 },
                 StructField {
                     name: None,
-                    ty: ty_to_abi(&ast::Type::Uint(32), ns, registry).ty,
+                    ty: registry.builtin_type("u32"),
                 },
             ];
 
@@ -654,6 +765,19 @@ fn ty_to_abi(ty: &ast::Type, ns: &ast::Namespace, registry: &mut Abi) -> ParamTy
     }
 }
 
+fn account_id_to_abi(ns: &ast::Namespace, registry: &mut Abi) -> ParamType {
+    let elem = registry.builtin_type("u8");
+    let ty = registry.builtin_array_type(elem, ns.address_length);
+
+    ParamType {
+        ty: registry.struct_type(
+            vec!["AccountId".to_owned()],
+            vec![StructField { name: None, ty }],
+        ),
+        display_name: vec!["AccountId".to_owned()],
+    }
+}
+
 fn parameter_to_abi(param: &ast::Parameter, ns: &ast::Namespace, registry: &mut Abi) -> Param {
     Param {
         name: param.name_as_str().to_owned(),
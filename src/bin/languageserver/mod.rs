@@ -364,6 +364,11 @@ impl SolangServer {
                     SolangServer::construct_expr(arg, lookup_tbl, symtab, ns);
                 }
             }
+            ast::Statement::Revert { args, .. } => {
+                for arg in args {
+                    SolangServer::construct_expr(arg, lookup_tbl, symtab, ns);
+                }
+            }
             ast::Statement::TryCatch(_, _, try_stmt) => {
                 SolangServer::construct_expr(&try_stmt.expr, lookup_tbl, symtab, ns);
                 for vecstmt in &try_stmt.catch_stmt {
@@ -411,6 +416,13 @@ impl SolangServer {
                     val,
                 });
             }
+            ast::Expression::CodeHash(locs, _) => {
+                lookup_tbl.push(HoverEntry {
+                    start: locs.start(),
+                    stop: locs.end(),
+                    val: "(bytes32)".to_string(),
+                });
+            }
             ast::Expression::CodeLiteral(locs, _val, _) => {
                 let val = format!("({})", _val);
                 lookup_tbl.push(HoverEntry {
@@ -485,7 +497,7 @@ impl SolangServer {
                 SolangServer::construct_expr(expr1, lookup_tbl, symtab, ns);
                 SolangServer::construct_expr(expr2, lookup_tbl, symtab, ns);
             }
-            ast::Expression::Divide(locs, ty, expr1, expr2) => {
+            ast::Expression::Divide(locs, ty, _, expr1, expr2) => {
                 lookup_tbl.push(HoverEntry {
                     start: locs.start(),
                     stop: locs.end(),
@@ -665,7 +677,7 @@ impl SolangServer {
             ast::Expression::Complement(_locs, _typ, expr1) => {
                 SolangServer::construct_expr(expr1, lookup_tbl, symtab, ns);
             }
-            ast::Expression::UnaryMinus(_locs, _typ, expr1) => {
+            ast::Expression::UnaryMinus(_locs, _typ, _unchecked, expr1) => {
                 SolangServer::construct_expr(expr1, lookup_tbl, symtab, ns);
             }
 
@@ -11,10 +11,11 @@ use num_traits::cast::ToPrimitive;
 use solang::{
     abi,
     codegen::{codegen, OptimizationLevel, Options},
-    emit::Generate,
+    emit::{source_map::source_map_json, Generate},
     file_resolver::FileResolver,
-    sema::ast::Namespace,
+    sema::ast::{Level, Namespace},
     standard_json::{EwasmContract, JsonContract, JsonResult},
+    watch::Watcher,
     Target,
 };
 use std::{
@@ -24,6 +25,8 @@ use std::{
     io::prelude::*,
     path::{Path, PathBuf},
     process::exit,
+    thread,
+    time::Duration,
 };
 
 mod doc;
@@ -68,29 +71,15 @@ fn main() {
                     )
                     .arg(
                         Arg::new("TARGET")
-                            .help("Target to build for [possible values: solana, substrate]")
+                            .help("Target to build for, may be given more than once [possible values: solana, substrate]")
                             .long("target")
                             .num_args(1)
                             .value_parser(["solana", "substrate", "evm", "olive"])
                             .hide_possible_values(true)
+                            .action(ArgAction::Append)
                             .required(true),
                     )
-                    .arg(
-                        Arg::new("ADDRESS_LENGTH")
-                            .help("Address length on Substrate")
-                            .long("address-length")
-                            .num_args(1)
-                            .value_parser(value_parser!(u64).range(4..1024))
-                            .default_value("32"),
-                    )
-                    .arg(
-                        Arg::new("VALUE_LENGTH")
-                            .help("Value length on Substrate")
-                            .long("value-length")
-                            .value_parser(value_parser!(u64).range(4..1024))
-                            .num_args(1)
-                            .default_value("16"),
-                    )
+                    .args(substrate_target_args())
                     .arg(
                         Arg::new("STD-JSON")
                             .help("mimic solidity json output on stdout")
@@ -165,11 +154,22 @@ fn main() {
                             .action(ArgAction::SetTrue)
                             .display_order(5),
                     )
+                    .arg(
+                        Arg::new("MATH")
+                            .help("Revert on arithmetic overflow, or let it wrap around")
+                            .long("math")
+                            .num_args(1)
+                            .value_parser(["checked", "wrapping"])
+                            .default_value("checked")
+                            .display_order(6),
+                    )
                     .arg(
                         Arg::new("MATHOVERFLOW")
-                            .help("Enable math overflow checking")
+                            .help("Enable math overflow checking; this is the default")
                             .long("math-overflow")
-                            .display_order(6),
+                            .action(ArgAction::SetTrue)
+                            .conflicts_with("MATH")
+                            .hide(true),
                     )
                     .arg(
                         Arg::new("GENERATEDEBUGINFORMATION")
@@ -177,6 +177,45 @@ fn main() {
                             .short('g')
                             .long("generate-debug-info")
                             .hide(true),
+                    )
+                    .arg(
+                        Arg::new("SOURCEMAP")
+                            .help("Write source map of code offsets to Solidity source ranges")
+                            .long("source-map")
+                            .action(ArgAction::SetTrue)
+                            .conflicts_with("STD-JSON")
+                            .display_order(7),
+                    )
+                    .arg(
+                        Arg::new("LOOPREPORT")
+                            .help("Report the bounds of the loops in public functions")
+                            .long("loop-report")
+                            .num_args(0..=1)
+                            .value_parser(["text", "json"])
+                            .default_missing_value("text")
+                            .display_order(8),
+                    )
+                    .arg(
+                        Arg::new("LOGPRINTS")
+                            .help("Keep print() calls on Substrate, which only work on development chains")
+                            .long("log-prints")
+                            .action(ArgAction::SetTrue)
+                            .display_order(9),
+                    )
+                    .arg(
+                        Arg::new("WATCH")
+                            .help("Check the input files again when they or their imports change")
+                            .long("watch")
+                            .action(ArgAction::SetTrue)
+                            .conflicts_with("STD-JSON")
+                            .display_order(10),
+                    )
+                    .arg(
+                        Arg::new("STRIPDEBUGASSERTS")
+                            .help("Remove debugAssert() checks from the generated code")
+                            .long("strip-debug-asserts")
+                            .action(ArgAction::SetTrue)
+                            .display_order(11),
                     ),
             )
             .subcommand(
@@ -197,22 +236,7 @@ fn main() {
                             .value_parser(["solana", "substrate", "evm"])
                             .required(true),
                     )
-                    .arg(
-                        Arg::new("ADDRESS_LENGTH")
-                            .help("Address length on Substrate")
-                            .long("address-length")
-                            .num_args(1)
-                            .value_parser(value_parser!(u64).range(4..1024))
-                            .default_value("32"),
-                    )
-                    .arg(
-                        Arg::new("VALUE_LENGTH")
-                            .help("Value length on Substrate")
-                            .long("value-length")
-                            .value_parser(value_parser!(u64).range(4..1024))
-                            .num_args(1)
-                            .default_value("16"),
-                    )
+                    .args(substrate_target_args())
                     .arg(
                         Arg::new("IMPORTPATH")
                             .help("Directory to search for solidity files")
@@ -243,22 +267,7 @@ fn main() {
                             .value_parser(["solana", "substrate", "evm"])
                             .required(true),
                     )
-                    .arg(
-                        Arg::new("ADDRESS_LENGTH")
-                            .help("Address length on Substrate")
-                            .long("address-length")
-                            .num_args(1)
-                            .value_parser(value_parser!(u64).range(4..1024))
-                            .default_value("32"),
-                    )
-                    .arg(
-                        Arg::new("VALUE_LENGTH")
-                            .help("Value length on Substrate")
-                            .long("value-length")
-                            .value_parser(value_parser!(u64).range(4..1024))
-                            .num_args(1)
-                            .default_value("16"),
-                    )
+                    .args(substrate_target_args())
                     .arg(
                         Arg::new("IMPORTPATH")
                             .help("Directory to search for solidity files")
@@ -360,8 +369,49 @@ fn doc(matches: &ArgMatches) {
 }
 
 fn compile(matches: &ArgMatches) {
-    let target = target_arg(matches);
+    let targets = targets_arg(matches);
+
+    // with more than one target, each target gets its own output directory
+    let multiple_targets = targets.len() > 1;
+
+    if multiple_targets {
+        if matches.contains_id("STD-JSON") {
+            eprintln!("error: --standard-json cannot be used with more than one target");
+            exit(1);
+        }
+
+        if *matches.get_one::<bool>("WATCH").unwrap() {
+            eprintln!("error: --watch cannot be used with more than one target");
+            exit(1);
+        }
+    }
+
+    let mut errors = false;
+
+    for target in targets {
+        let output_dir = if multiple_targets {
+            output_dir(matches).join(target.to_string())
+        } else {
+            output_dir(matches)
+        };
 
+        if !compile_target(matches, target, &output_dir, multiple_targets) {
+            errors = true;
+        }
+    }
+
+    if errors {
+        exit(1);
+    }
+}
+
+/// Compile all the input files for the given target. Returns false if there were errors.
+fn compile_target(
+    matches: &ArgMatches,
+    target: Target,
+    output_dir: &Path,
+    multiple_targets: bool,
+) -> bool {
     let verbose = *matches.get_one::<bool>("VERBOSE").unwrap();
     let mut json = JsonResult {
         errors: Vec::new(),
@@ -374,9 +424,13 @@ fn compile(matches: &ArgMatches) {
         eprintln!("info: Solang version {}", env!("SOLANG_VERSION"));
     }
 
-    let math_overflow_check = matches.contains_id("MATHOVERFLOW");
+    let math_overflow_check = *matches.get_one::<bool>("MATHOVERFLOW").unwrap()
+        || matches.get_one::<String>("MATH").unwrap() == "checked";
+
+    let source_map = *matches.get_one::<bool>("SOURCEMAP").unwrap();
 
-    let generate_debug_info = matches.contains_id("GENERATEDEBUGINFORMATION");
+    // the source map is derived from the debug info
+    let generate_debug_info = matches.contains_id("GENERATEDEBUGINFORMATION") || source_map;
 
     let mut resolver = imports_arg(matches);
 
@@ -395,18 +449,33 @@ fn compile(matches: &ArgMatches) {
         vector_to_slice: *matches.get_one::<bool>("VECTORTOSLICE").unwrap(),
         math_overflow_check,
         generate_debug_information: generate_debug_info,
+        log_prints: *matches.get_one::<bool>("LOGPRINTS").unwrap(),
+        strip_debug_asserts: *matches.get_one::<bool>("STRIPDEBUGASSERTS").unwrap(),
         common_subexpression_elimination: *matches
             .get_one::<bool>("COMMONSUBEXPRESSIONELIMINATION")
             .unwrap(),
         opt_level,
     };
 
+    if *matches.get_one::<bool>("WATCH").unwrap() {
+        watch(matches, target, &opt);
+    }
+
     let mut namespaces = Vec::new();
 
     let mut errors = false;
 
     for filename in matches.get_many::<OsString>("INPUT").unwrap() {
-        match process_file(filename, &mut resolver, target, matches, &mut json, &opt) {
+        match process_file(
+            filename,
+            &mut resolver,
+            target,
+            matches,
+            &mut json,
+            &opt,
+            output_dir,
+            multiple_targets,
+        ) {
             Ok(ns) => namespaces.push(ns),
             Err(_) => {
                 errors = true;
@@ -417,16 +486,19 @@ fn compile(matches: &ArgMatches) {
     let namespaces = namespaces.iter().collect::<Vec<_>>();
 
     if let Some("ast-dot") = matches.get_one::<String>("EMIT").map(|v| v.as_str()) {
-        exit(0);
+        return true;
     }
 
     if errors {
         if matches.contains_id("STD-JSON") {
             println!("{}", serde_json::to_string(&json).unwrap());
             exit(0);
+        } else if multiple_targets {
+            eprintln!("error: not all contracts are valid for target '{}'", target);
+            return false;
         } else {
             eprintln!("error: not all contracts are valid");
-            exit(1);
+            return false;
         }
     }
 
@@ -442,8 +514,8 @@ fn compile(matches: &ArgMatches) {
             generate_debug_info,
         );
 
-        if !save_intermediates(&binary, matches) {
-            let bin_filename = output_file(matches, "bundle", target.file_extension());
+        if !save_intermediates(&binary, matches, output_dir) {
+            let bin_filename = output_file(output_dir, "bundle", target.file_extension());
 
             if *matches.get_one::<bool>("VERBOSE").unwrap() {
                 eprintln!(
@@ -476,6 +548,17 @@ fn compile(matches: &ArgMatches) {
                 let mut file = create_file(&bin_filename);
                 file.write_all(&code).unwrap();
 
+                if source_map {
+                    if let [ns] = namespaces.as_slice() {
+                        let map_filename = output_file(output_dir, "bundle", "map");
+
+                        save_source_map(&binary, &code, ns, &map_filename, verbose);
+                    } else {
+                        eprintln!("error: source map requires a single input file on Solana");
+                        exit(1);
+                    }
+                }
+
                 // Write all ABI files
                 for ns in &namespaces {
                     for contract_no in 0..ns.contracts.len() {
@@ -487,7 +570,7 @@ fn compile(matches: &ArgMatches) {
 
                         let (abi_bytes, abi_ext) =
                             abi::generate_abi(contract_no, ns, &code, verbose);
-                        let abi_filename = output_file(matches, &contract.name, abi_ext);
+                        let abi_filename = output_file(output_dir, &contract.name, abi_ext);
 
                         if verbose {
                             eprintln!(
@@ -509,6 +592,62 @@ fn compile(matches: &ArgMatches) {
     if matches.contains_id("STD-JSON") {
         println!("{}", serde_json::to_string(&json).unwrap());
     }
+
+    true
+}
+
+/// Check the input files again whenever they or one of their imports change. Only inputs which
+/// are affected by a change are checked, and contracts are only generated when there are no errors.
+fn watch(matches: &ArgMatches, target: Target, opt: &Options) -> ! {
+    if target == Target::Solana {
+        eprintln!("error: --watch is not supported on Solana, since all contracts are built into one binary");
+        exit(1);
+    }
+
+    let filenames: Vec<OsString> = matches
+        .get_many::<OsString>("INPUT")
+        .unwrap()
+        .cloned()
+        .collect();
+
+    let mut resolver = imports_arg(matches);
+    let mut watcher = Watcher::new(target, &filenames);
+
+    loop {
+        let checked = watcher.check(&mut resolver);
+        let any_checked = !checked.is_empty();
+
+        for (input_no, ns) in checked {
+            let mut json = JsonResult {
+                errors: Vec::new(),
+                target: target.to_string(),
+                program: String::new(),
+                contracts: HashMap::new(),
+            };
+
+            // the namespace was resolved by the watcher, so only codegen and emit are left
+            let _ = process_namespace(
+                ns,
+                watcher.filename(input_no),
+                &resolver,
+                target,
+                matches,
+                &mut json,
+                opt,
+                &output_dir(matches),
+                false,
+            );
+        }
+
+        if any_checked {
+            eprintln!(
+                "info: watching {} files for changes",
+                watcher.watched_files().len()
+            );
+        }
+
+        thread::sleep(Duration::from_millis(500));
+    }
 }
 
 fn shell_complete(mut app: Command, matches: &ArgMatches) {
@@ -520,13 +659,16 @@ fn shell_complete(mut app: Command, matches: &ArgMatches) {
     }
 }
 
-fn output_file(matches: &ArgMatches, stem: &str, ext: &str) -> PathBuf {
-    Path::new(
+fn output_dir(matches: &ArgMatches) -> PathBuf {
+    PathBuf::from(
         matches
             .get_one::<OsString>("OUTPUT")
             .unwrap_or(&OsString::from(".")),
     )
-    .join(format!("{}.{}", stem, ext))
+}
+
+fn output_file(output_dir: &Path, stem: &str, ext: &str) -> PathBuf {
+    output_dir.join(format!("{}.{}", stem, ext))
 }
 
 fn process_file(
@@ -536,14 +678,41 @@ fn process_file(
     matches: &ArgMatches,
     json: &mut JsonResult,
     opt: &Options,
+    output_dir: &Path,
+    multiple_targets: bool,
+) -> Result<Namespace, ()> {
+    // resolve phase
+    let ns = solang::parse_and_resolve(filename, resolver, target);
+
+    process_namespace(
+        ns,
+        filename,
+        resolver,
+        target,
+        matches,
+        json,
+        opt,
+        output_dir,
+        multiple_targets,
+    )
+}
+
+/// Generate code for a resolved namespace, print its diagnostics and write the output files
+fn process_namespace(
+    mut ns: Namespace,
+    filename: &OsStr,
+    resolver: &FileResolver,
+    target: solang::Target,
+    matches: &ArgMatches,
+    json: &mut JsonResult,
+    opt: &Options,
+    output_dir: &Path,
+    multiple_targets: bool,
 ) -> Result<Namespace, ()> {
     let verbose = *matches.get_one::<bool>("VERBOSE").unwrap();
 
     let mut json_contracts = HashMap::new();
 
-    // resolve phase
-    let mut ns = solang::parse_and_resolve(filename, resolver, target);
-
     // codegen all the contracts; some additional errors/warnings will be detected here
     codegen(&mut ns, opt);
 
@@ -551,13 +720,23 @@ fn process_file(
         let mut out = ns.diagnostics_as_json(resolver);
         json.errors.append(&mut out);
     } else {
+        // say which target the diagnostics are for, since they may differ between targets
+        if multiple_targets
+            && ns
+                .diagnostics
+                .iter()
+                .any(|diagnostic| verbose || diagnostic.level != Level::Debug)
+        {
+            eprintln!("info: diagnostics for target '{}'", target);
+        }
+
         ns.print_diagnostics(resolver, verbose);
     }
 
     if let Some("ast-dot") = matches.get_one::<String>("EMIT").map(|v| v.as_str()) {
         let filepath = PathBuf::from(filename);
         let stem = filepath.file_stem().unwrap().to_string_lossy();
-        let dot_filename = output_file(matches, &stem, "dot");
+        let dot_filename = output_file(output_dir, &stem, "dot");
 
         if verbose {
             eprintln!("info: Saving graphviz dot {}", dot_filename.display());
@@ -579,6 +758,16 @@ fn process_file(
         return Err(());
     }
 
+    if let Some(format) = matches.get_one::<String>("LOOPREPORT") {
+        let report = ns.loop_report();
+
+        if format == "json" {
+            println!("{}", ns.loop_report_json(&report));
+        } else {
+            print!("{}", ns.loop_report_text(&report));
+        }
+    }
+
     // emit phase
     for contract_no in 0..ns.contracts.len() {
         let resolved_contract = &ns.contracts[contract_no];
@@ -633,7 +822,7 @@ fn process_file(
             opt.generate_debug_information,
         );
 
-        if save_intermediates(&binary, matches) {
+        if save_intermediates(&binary, matches, output_dir) {
             continue;
         }
 
@@ -649,7 +838,7 @@ fn process_file(
                 },
             );
         } else {
-            let bin_filename = output_file(matches, &binary.name, target.file_extension());
+            let bin_filename = output_file(output_dir, &binary.name, target.file_extension());
 
             if verbose {
                 eprintln!(
@@ -662,9 +851,30 @@ fn process_file(
             let mut file = create_file(&bin_filename);
             file.write_all(&resolved_contract.code).unwrap();
 
+            if verbose {
+                eprintln!(
+                    "info: contract {} code size {} bytes code hash 0x{}",
+                    binary.name,
+                    resolved_contract.code.len(),
+                    hex::encode(resolved_contract.code_hash())
+                );
+            }
+
+            if *matches.get_one::<bool>("SOURCEMAP").unwrap() {
+                let map_filename = output_file(output_dir, &binary.name, "map");
+
+                save_source_map(
+                    &binary,
+                    &resolved_contract.code,
+                    &ns,
+                    &map_filename,
+                    verbose,
+                );
+            }
+
             let (abi_bytes, abi_ext) =
                 abi::generate_abi(contract_no, &ns, &resolved_contract.code, verbose);
-            let abi_filename = output_file(matches, &binary.name, abi_ext);
+            let abi_filename = output_file(output_dir, &binary.name, abi_ext);
 
             if verbose {
                 eprintln!(
@@ -685,12 +895,16 @@ fn process_file(
     Ok(ns)
 }
 
-fn save_intermediates(binary: &solang::emit::binary::Binary, matches: &ArgMatches) -> bool {
+fn save_intermediates(
+    binary: &solang::emit::binary::Binary,
+    matches: &ArgMatches,
+    output_dir: &Path,
+) -> bool {
     let verbose = *matches.get_one::<bool>("VERBOSE").unwrap();
 
     match matches.get_one::<String>("EMIT").map(|v| v.as_str()) {
         Some("llvm-ir") => {
-            let llvm_filename = output_file(matches, &binary.name, "ll");
+            let llvm_filename = output_file(output_dir, &binary.name, "ll");
 
             if verbose {
                 eprintln!(
@@ -706,7 +920,7 @@ fn save_intermediates(binary: &solang::emit::binary::Binary, matches: &ArgMatche
         }
 
         Some("llvm-bc") => {
-            let bc_filename = output_file(matches, &binary.name, "bc");
+            let bc_filename = output_file(output_dir, &binary.name, "bc");
 
             if verbose {
                 eprintln!(
@@ -730,7 +944,7 @@ fn save_intermediates(binary: &solang::emit::binary::Binary, matches: &ArgMatche
                 }
             };
 
-            let obj_filename = output_file(matches, &binary.name, "o");
+            let obj_filename = output_file(output_dir, &binary.name, "o");
 
             if verbose {
                 eprintln!(
@@ -753,7 +967,7 @@ fn save_intermediates(binary: &solang::emit::binary::Binary, matches: &ArgMatche
                 }
             };
 
-            let obj_filename = output_file(matches, &binary.name, "asm");
+            let obj_filename = output_file(output_dir, &binary.name, "asm");
 
             if verbose {
                 eprintln!(
@@ -773,6 +987,35 @@ fn save_intermediates(binary: &solang::emit::binary::Binary, matches: &ArgMatche
     }
 }
 
+fn save_source_map(
+    binary: &solang::emit::binary::Binary,
+    code: &[u8],
+    ns: &Namespace,
+    map_filename: &Path,
+    verbose: bool,
+) {
+    if verbose {
+        eprintln!(
+            "info: Saving source map {} for {}",
+            map_filename.display(),
+            binary.name
+        );
+    }
+
+    match binary.source_map(code) {
+        Ok(map) => {
+            let mut file = create_file(map_filename);
+
+            file.write_all(source_map_json(&map, ns).as_bytes())
+                .unwrap();
+        }
+        Err(err) => {
+            eprintln!("{}: error: {}", map_filename.display(), err);
+            exit(1);
+        }
+    }
+}
+
 fn create_file(path: &Path) -> File {
     if let Some(parent) = path.parent() {
         if let Err(err) = create_dir_all(parent) {
@@ -794,25 +1037,94 @@ fn create_file(path: &Path) -> File {
     }
 }
 
+/// The arguments which set the Substrate target parameters, shared by the subcommands which
+/// resolve or compile Solidity
+fn substrate_target_args() -> [Arg; 4] {
+    [
+        Arg::new("ADDRESS_LENGTH")
+            .help("Address length on Substrate")
+            .long("address-length")
+            .num_args(1)
+            .value_parser(value_parser!(u64).range(4..1024))
+            .default_value("32"),
+        Arg::new("VALUE_LENGTH")
+            .help("Value length on Substrate")
+            .long("value-length")
+            .value_parser(value_parser!(u64).range(4..1024))
+            .num_args(1)
+            .default_value("16"),
+        Arg::new("SUBSTRATE_API_VERSION")
+            .help("Version of the contracts pallet api on Substrate")
+            .long("substrate-api-version")
+            .value_parser(value_parser!(u32).range(1..=3))
+            .num_args(1)
+            .default_value("3"),
+        Arg::new("MAX_CODE_SIZE")
+            .help("Maximum size in bytes of a contract on Substrate")
+            .long("max-code-size")
+            .value_parser(value_parser!(u64).range(1..))
+            .num_args(1)
+            .default_value("131072"),
+    ]
+}
+
 fn target_arg(matches: &ArgMatches) -> Target {
+    let target = parse_target(matches.get_one::<String>("TARGET").unwrap(), matches);
+
+    check_substrate_args(matches, &[target]);
+
+    target
+}
+
+/// The compile subcommand accepts more than one target
+fn targets_arg(matches: &ArgMatches) -> Vec<Target> {
+    let mut targets: Vec<Target> = Vec::new();
+
+    for name in matches.get_many::<String>("TARGET").unwrap() {
+        let target = parse_target(name, matches);
+
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+
+    check_substrate_args(matches, &targets);
+
+    targets
+}
+
+fn parse_target(name: &str, matches: &ArgMatches) -> Target {
     let address_length = matches.get_one::<u64>("ADDRESS_LENGTH").unwrap();
 
     let value_length = matches.get_one::<u64>("VALUE_LENGTH").unwrap();
 
-    let target = match matches.get_one::<String>("TARGET").unwrap().as_str() {
+    let api_version = matches.get_one::<u32>("SUBSTRATE_API_VERSION").unwrap();
+
+    let max_code_size = matches.get_one::<u64>("MAX_CODE_SIZE").unwrap();
+
+    match name {
         "solana" => solang::Target::Solana,
         "substrate" => solang::Target::Substrate {
             address_length: *address_length as usize,
             value_length: *value_length as usize,
+            api_version: *api_version,
+            max_code_size: *max_code_size as usize,
         },
         "evm" => solang::Target::EVM,
         "olive" => solang::Target::Olive,
         _ => unreachable!(),
-    };
+    }
+}
 
-    if !target.is_substrate()
-        && matches.value_source("ADDRESS_LENGTH") == Some(ValueSource::CommandLine)
-    {
+/// The Substrate options may only be given if one of the targets is Substrate
+fn check_substrate_args(matches: &ArgMatches, targets: &[Target]) {
+    if targets.iter().any(|target| target.is_substrate()) {
+        return;
+    }
+
+    let target = targets.iter().join(", ");
+
+    if matches.value_source("ADDRESS_LENGTH") == Some(ValueSource::CommandLine) {
         eprintln!(
             "error: address length cannot be modified for target '{}'",
             target
@@ -820,9 +1132,7 @@ fn target_arg(matches: &ArgMatches) -> Target {
         exit(1);
     }
 
-    if !target.is_substrate()
-        && matches.value_source("VALUE_LENGTH") == Some(ValueSource::CommandLine)
-    {
+    if matches.value_source("VALUE_LENGTH") == Some(ValueSource::CommandLine) {
         eprintln!(
             "error: value length cannot be modified for target '{}'",
             target
@@ -830,7 +1140,21 @@ fn target_arg(matches: &ArgMatches) -> Target {
         exit(1);
     }
 
-    target
+    if matches.value_source("SUBSTRATE_API_VERSION") == Some(ValueSource::CommandLine) {
+        eprintln!(
+            "error: substrate api version cannot be set for target '{}'",
+            target
+        );
+        exit(1);
+    }
+
+    if matches.value_source("MAX_CODE_SIZE") == Some(ValueSource::CommandLine) {
+        eprintln!(
+            "error: maximum code size cannot be set for target '{}'",
+            target
+        );
+        exit(1);
+    }
 }
 
 fn imports_arg(matches: &ArgMatches) -> FileResolver {
@@ -61,6 +61,11 @@ pub enum Instr {
     Store { dest: Expression, data: Expression },
     /// Abort execution
     AssertFailure { expr: Option<Expression> },
+    /// Abort execution with a custom error, abi encoding the arguments after the error selector
+    RevertError {
+        error_no: usize,
+        args: Vec<Expression>,
+    },
     /// Print to log message
     Print { expr: Expression },
     /// Load storage (this is an instruction rather than an expression
@@ -308,6 +313,12 @@ impl Instr {
                 value.recurse(cx, f);
             }
 
+            Instr::RevertError { args, .. } => {
+                for expr in args {
+                    expr.recurse(cx, f);
+                }
+            }
+
             Instr::EmitEvent { data, topics, .. } => {
                 for expr in data {
                     expr.recurse(cx, f);
@@ -578,7 +589,7 @@ impl ControlFlowGraph {
                 format!("{}", String::from_utf8_lossy(s))
             }
             Expression::BytesLiteral(_, _, s) => format!("hex\"{}\"", hex::encode(s)),
-            Expression::NumberLiteral(_, ty @ Type::Address(_), n) => {
+            Expression::NumberLiteral(_, ty @ (Type::Address(_) | Type::Contract(_)), n) => {
                 format!("{} {:#x}", ty.to_string(ns), n)
             }
             Expression::NumberLiteral(_, ty, n) => {
@@ -652,7 +663,7 @@ impl ControlFlowGraph {
                 self.expr_to_string(contract, ns, l),
                 self.expr_to_string(contract, ns, r)
             ),
-            Expression::SignedDivide(_, _, l, r) => format!(
+            Expression::SignedDivide(_, _, _, l, r) => format!(
                 "(signed divide {} / {})",
                 self.expr_to_string(contract, ns, l),
                 self.expr_to_string(contract, ns, r),
@@ -754,7 +765,9 @@ impl ControlFlowGraph {
             ),
             Expression::Not(_, e) => format!("!{}", self.expr_to_string(contract, ns, e)),
             Expression::Complement(_, _, e) => format!("~{}", self.expr_to_string(contract, ns, e)),
-            Expression::UnaryMinus(_, _, e) => format!("-{}", self.expr_to_string(contract, ns, e)),
+            Expression::UnaryMinus(_, _, _, e) => {
+                format!("-{}", self.expr_to_string(contract, ns, e))
+            }
             Expression::Poison => "☠".to_string(),
             Expression::AllocDynamicArray(_, ty, size, None) => format!(
                 "(alloc {} len {})",
@@ -989,6 +1002,14 @@ impl ControlFlowGraph {
             Instr::AssertFailure { expr: Some(expr) } => {
                 format!("assert-failure:{}", self.expr_to_string(contract, ns, expr))
             }
+            Instr::RevertError { error_no, args } => format!(
+                "revert-error {} {}",
+                ns.errors[*error_no].symbol_name(ns),
+                args.iter()
+                    .map(|expr| self.expr_to_string(contract, ns, expr))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
             Instr::Call {
                 res,
                 call: InternalCallTy::Builtin { ast_func_no },
@@ -93,6 +93,17 @@ pub fn constant_folding(cfg: &mut ControlFlowGraph, ns: &mut Namespace) {
                     cfg.blocks[block_no].instr[instr_no].1 =
                         Instr::AssertFailure { expr: Some(expr) };
                 }
+                Instr::RevertError { error_no, args } => {
+                    let args = args
+                        .iter()
+                        .map(|e| expression(e, Some(&vars), cfg, ns).0)
+                        .collect();
+
+                    cfg.blocks[block_no].instr[instr_no].1 = Instr::RevertError {
+                        error_no: *error_no,
+                        args,
+                    };
+                }
                 Instr::Print { expr } => {
                     let (expr, _) = expression(expr, Some(&vars), cfg, ns);
 
@@ -576,7 +587,7 @@ fn expression(
             )
         }
         Expression::UnsignedDivide(loc, ty, left, right)
-        | Expression::SignedDivide(loc, ty, left, right) => {
+        | Expression::SignedDivide(loc, ty, _, left, right) => {
             let left = expression(left, vars, cfg, ns);
             let right = expression(right, vars, cfg, ns);
 
@@ -589,8 +600,14 @@ fn expression(
                 }
             }
             (
-                if matches!(expr, Expression::SignedDivide(..)) {
-                    Expression::SignedDivide(*loc, ty.clone(), Box::new(left.0), Box::new(right.0))
+                if let Expression::SignedDivide(_, _, unchecked, ..) = expr {
+                    Expression::SignedDivide(
+                        *loc,
+                        ty.clone(),
+                        *unchecked,
+                        Box::new(left.0),
+                        Box::new(right.0),
+                    )
                 } else {
                     Expression::UnsignedDivide(
                         *loc,
@@ -674,13 +691,13 @@ fn expression(
                 )
             }
         }
-        Expression::UnaryMinus(loc, ty, expr) => {
+        Expression::UnaryMinus(loc, ty, unchecked, expr) => {
             let expr = expression(expr, vars, cfg, ns);
             if let Expression::NumberLiteral(_, _, n) = expr.0 {
                 bigint_to_expression(loc, ty, -n)
             } else {
                 (
-                    Expression::UnaryMinus(*loc, ty.clone(), Box::new(expr.0)),
+                    Expression::UnaryMinus(*loc, ty.clone(), *unchecked, Box::new(expr.0)),
                     expr.1,
                 )
             }
@@ -849,11 +866,11 @@ fn expression(
                                         bs.resize(*bits as usize / 8, v);
                                     }
                                     Type::Bytes(n) => {
-                                        while (*n as usize) < bs.len() {
-                                            bs.insert(0, 0);
-                                        }
+                                        // bytesN are hashed in the order they are written
+                                        bs.resize(*n as usize, 0);
+                                        bs.reverse();
                                     }
-                                    Type::Address(_) => {
+                                    Type::Address(_) | Type::Contract(_) => {
                                         bs.resize(ns.address_length, 0);
                                     }
                                     _ => unreachable!(),
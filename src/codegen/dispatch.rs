@@ -1,9 +1,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::codegen::cfg::{ASTFunction, ControlFlowGraph, Instr, InternalCallTy, ReturnCode};
+use crate::codegen::panic::{panic, PanicCode};
 use crate::codegen::vartable::Vartable;
 use crate::codegen::{Builtin, Expression};
-use crate::sema::ast::{Namespace, Parameter, RetrieveType, Type};
+use crate::sema::ast::{ArrayLength, CallTy, Namespace, Parameter, RetrieveType, StructType, Type};
+use crate::Target;
 use num_bigint::{BigInt, Sign};
 use num_traits::Zero;
 use solang_parser::pt;
@@ -151,7 +153,9 @@ pub(super) fn function_dispatch(
     }
 
     match fallback {
-        Some((cfg_no, _)) => {
+        Some((cfg_no, fallback_cfg)) => {
+            value_transfer(fallback_cfg.nonpayable, &mut vartab, &mut cfg);
+
             cfg.add(
                 &mut vartab,
                 Instr::Call {
@@ -197,6 +201,8 @@ fn add_dispatch_case(
     let bb = cfg.new_basic_block(format!("function_cfg_{}", cfg_no));
     cfg.set_basic_block(bb);
 
+    value_transfer(func_cfg.nonpayable, vartab, cfg);
+
     let truncated_len = Expression::Trunc(Loc::Codegen, Type::Uint(32), Box::new(argslen));
 
     let mut vars: Vec<usize> = Vec::with_capacity(func_cfg.params.len());
@@ -321,6 +327,12 @@ pub(super) fn constructor_dispatch(
     let data = Expression::FunctionArg(Loc::Codegen, Type::BufferPointer, 0);
     let data_len = Expression::FunctionArg(Loc::Codegen, Type::Uint(64), 1);
 
+    value_transfer(
+        all_cfg[constructor_cfg_no].nonpayable,
+        &mut vartab,
+        &mut cfg,
+    );
+
     if !res.is_empty() {
         cfg.add(
             &mut vartab,
@@ -358,3 +370,117 @@ pub(super) fn constructor_dispatch(
 
     cfg
 }
+
+/// Lamports sent with the instruction are moved from the sender to the data account by the
+/// system program before the function is called, so the amount in the instruction cannot be
+/// forged. Functions which are not payable revert when any lamports are sent.
+fn value_transfer(nonpayable: bool, vartab: &mut Vartable, cfg: &mut ControlFlowGraph) {
+    let value = Expression::Builtin(Loc::Codegen, vec![Type::Uint(64)], Builtin::Value, vec![]);
+
+    let transfer_block = cfg.new_basic_block("value_transfer".to_string());
+    let no_transfer_block = cfg.new_basic_block("no_value_transfer".to_string());
+
+    cfg.add(
+        vartab,
+        Instr::BranchCond {
+            cond: Expression::NotEqual(
+                Loc::Codegen,
+                Box::new(value.clone()),
+                Box::new(Expression::NumberLiteral(
+                    Loc::Codegen,
+                    Type::Uint(64),
+                    BigInt::zero(),
+                )),
+            ),
+            true_block: transfer_block,
+            false_block: no_transfer_block,
+        },
+    );
+
+    cfg.set_basic_block(transfer_block);
+
+    if nonpayable {
+        panic(
+            PanicCode::NonPayableValueTransfer,
+            cfg,
+            vartab,
+            &Target::Solana,
+        );
+    } else {
+        let account_meta = |pubkey: Builtin, is_signer: bool| {
+            Expression::StructLiteral(
+                Loc::Codegen,
+                Type::Struct(StructType::AccountMeta),
+                vec![
+                    Expression::GetRef(
+                        Loc::Codegen,
+                        Type::Ref(Box::new(Type::Address(false))),
+                        Box::new(Expression::Builtin(
+                            Loc::Codegen,
+                            vec![Type::Address(false)],
+                            pubkey,
+                            vec![],
+                        )),
+                    ),
+                    Expression::BoolLiteral(Loc::Codegen, true),
+                    Expression::BoolLiteral(Loc::Codegen, is_signer),
+                ],
+            )
+        };
+
+        let accounts = Expression::ArrayLiteral(
+            Loc::Codegen,
+            Type::Array(
+                Box::new(Type::Struct(StructType::AccountMeta)),
+                vec![ArrayLength::Fixed(BigInt::from(2u8))],
+            ),
+            vec![2],
+            vec![
+                account_meta(Builtin::Sender, true),
+                account_meta(Builtin::GetAddress, false),
+            ],
+        );
+
+        // system instruction transfer: bincode encoded u32 instruction number 2 followed by u64 lamports
+        let payload = Expression::AbiEncode {
+            loc: Loc::Codegen,
+            tys: vec![Type::Uint(32), Type::Uint(64)],
+            packed: vec![],
+            args: vec![
+                Expression::NumberLiteral(Loc::Codegen, Type::Uint(32), BigInt::from(2u8)),
+                value,
+            ],
+        };
+
+        cfg.add(
+            vartab,
+            Instr::ExternalCall {
+                success: None,
+                address: Some(Expression::NumberLiteral(
+                    Loc::Codegen,
+                    Type::Address(false),
+                    BigInt::zero(),
+                )),
+                accounts: Some(accounts),
+                seeds: None,
+                payload,
+                value: Expression::NumberLiteral(Loc::Codegen, Type::Uint(64), BigInt::zero()),
+                gas: Expression::NumberLiteral(
+                    Loc::Codegen,
+                    Type::Uint(64),
+                    BigInt::from(i64::MAX),
+                ),
+                callty: CallTy::Regular,
+            },
+        );
+
+        cfg.add(
+            vartab,
+            Instr::Branch {
+                block: no_transfer_block,
+            },
+        );
+    }
+
+    cfg.set_basic_block(no_transfer_block);
+}
@@ -1,4 +1,5 @@
 use crate::codegen::cfg::{ControlFlowGraph, Instr};
+use crate::codegen::panic::{panic, PanicCode};
 use crate::codegen::vartable::Vartable;
 use crate::codegen::Expression;
 use crate::sema::ast::{Namespace, Type};
@@ -69,10 +70,11 @@ impl BufferValidator<'_> {
     pub(super) fn validate_offset(
         &self,
         offset: Expression,
+        ns: &Namespace,
         vartab: &mut Vartable,
         cfg: &mut ControlFlowGraph,
     ) {
-        self.build_branch(offset, vartab, cfg);
+        self.build_branch(offset, ns, vartab, cfg);
     }
 
     /// Checks if a buffer validation is necessary
@@ -90,6 +92,7 @@ impl BufferValidator<'_> {
         &mut self,
         offset: &Expression,
         size: &Expression,
+        ns: &Namespace,
         vartab: &mut Vartable,
         cfg: &mut ControlFlowGraph,
     ) {
@@ -101,7 +104,7 @@ impl BufferValidator<'_> {
                 Box::new(offset.clone()),
                 Box::new(size.clone()),
             );
-            self.validate_offset(offset_to_validate, vartab, cfg);
+            self.validate_offset(offset_to_validate, ns, vartab, cfg);
         }
     }
 
@@ -109,6 +112,7 @@ impl BufferValidator<'_> {
     pub(super) fn validate_all_bytes_read(
         &self,
         end_offset: Expression,
+        ns: &Namespace,
         vartab: &mut Vartable,
         cfg: &mut ControlFlowGraph,
     ) {
@@ -131,8 +135,7 @@ impl BufferValidator<'_> {
 
         cfg.set_basic_block(invalid);
 
-        // TODO: This needs a proper error message
-        cfg.add(vartab, Instr::AssertFailure { expr: None });
+        panic(PanicCode::DecodingFailed, cfg, vartab, &ns.target);
 
         cfg.set_basic_block(valid);
     }
@@ -179,11 +182,17 @@ impl BufferValidator<'_> {
         );
 
         self.verified_until = Some(maximum_verifiable);
-        self.build_branch(reach, vartab, cfg);
+        self.build_branch(reach, ns, vartab, cfg);
     }
 
     /// Builds a branch for failing if we are out of bounds
-    fn build_branch(&self, offset: Expression, vartab: &mut Vartable, cfg: &mut ControlFlowGraph) {
+    fn build_branch(
+        &self,
+        offset: Expression,
+        ns: &Namespace,
+        vartab: &mut Vartable,
+        cfg: &mut ControlFlowGraph,
+    ) {
         let cond = Expression::LessEqual(
             Loc::Codegen,
             Box::new(offset),
@@ -203,8 +212,7 @@ impl BufferValidator<'_> {
         );
 
         cfg.set_basic_block(out_of_bounds_block);
-        // TODO: Add an error message here
-        cfg.add(vartab, Instr::AssertFailure { expr: None });
+        panic(PanicCode::DecodingFailed, cfg, vartab, &ns.target);
         cfg.set_basic_block(inbounds_block);
     }
 
@@ -80,6 +80,8 @@ impl EventEmitter for SubstrateEventEmitter<'_> {
                         topic_tys.push(Type::Bytes(32));
                     }
                     _ => {
+                        // value types fit in a topic so they are not hashed. This includes
+                        // fixed length bytesN, which end up right-padded with zeros
                         let e = expression(arg, cfg, contract_no, Some(func), self.ns, vartab, opt);
 
                         topics.push(e);
@@ -12,7 +12,9 @@ use crate::codegen::array_boundary::handle_array_assign;
 use crate::codegen::constructor::call_constructor;
 use crate::codegen::encoding::create_encoder;
 use crate::codegen::encoding::AbiEncoding;
-use crate::codegen::unused_variable::should_remove_assignment;
+use crate::codegen::panic::{panic, PanicCode};
+use crate::codegen::statements::process_side_effects_expressions;
+use crate::codegen::unused_variable::{should_remove_assignment, SideEffectsCheckParameters};
 use crate::codegen::{Builtin, Expression};
 use crate::sema::{
     ast,
@@ -23,12 +25,14 @@ use crate::sema::{
     diagnostics::Diagnostics,
     eval::{eval_const_number, eval_const_rational},
     expression::{bigint_to_expression, ResolveTo},
+    Recurse,
 };
 use crate::Target;
 use num_bigint::BigInt;
 use num_traits::{FromPrimitive, One, ToPrimitive, Zero};
 use solang_parser::pt;
 use solang_parser::pt::{CodeLocation, Loc};
+use std::cmp::Ordering;
 use std::ops::Mul;
 
 pub fn expression(
@@ -91,11 +95,11 @@ pub fn expression(
                 )
             }
         }
-        ast::Expression::Divide(loc, ty, left, right) => {
+        ast::Expression::Divide(loc, ty, unchecked, left, right) => {
             let l = expression(left, cfg, contract_no, func, ns, vartab, opt);
             let r = expression(right, cfg, contract_no, func, ns, vartab, opt);
             if ty.is_signed_int() {
-                Expression::SignedDivide(*loc, ty.clone(), Box::new(l), Box::new(r))
+                Expression::SignedDivide(*loc, ty.clone(), *unchecked, Box::new(l), Box::new(r))
             } else {
                 Expression::UnsignedDivide(*loc, ty.clone(), Box::new(l), Box::new(r))
             }
@@ -147,6 +151,11 @@ pub fn expression(
             Box::new(expression(right, cfg, contract_no, func, ns, vartab, opt)),
             *sign,
         ),
+        ast::Expression::Equal(loc, left, right)
+            if matches!(left.ty().deref_any(), Type::Struct(_)) =>
+        {
+            struct_equal(loc, left, right, cfg, contract_no, func, ns, vartab, opt)
+        }
         ast::Expression::Equal(loc, left, right) => Expression::Equal(
             *loc,
             Box::new(expression(left, cfg, contract_no, func, ns, vartab, opt)),
@@ -215,9 +224,10 @@ pub fn expression(
             ty.clone(),
             Box::new(expression(expr, cfg, contract_no, func, ns, vartab, opt)),
         ),
-        ast::Expression::UnaryMinus(loc, ty, expr) => Expression::UnaryMinus(
+        ast::Expression::UnaryMinus(loc, ty, unchecked, expr) => Expression::UnaryMinus(
             *loc,
             ty.clone(),
+            *unchecked,
             Box::new(expression(expr, cfg, contract_no, func, ns, vartab, opt)),
         ),
         ast::Expression::StructLiteral(loc, ty, exprs) => Expression::StructLiteral(
@@ -255,6 +265,12 @@ pub fn expression(
                 }
             }
 
+            if let Some(res) =
+                storage_compound_assign(left, right, cfg, contract_no, func, ns, vartab, opt)
+            {
+                return res;
+            }
+
             let mut cfg_right = expression(right, cfg, contract_no, func, ns, vartab, opt);
 
             // If an assignment where the left hand side is an array, call a helper function that updates the temp variable.
@@ -416,7 +432,10 @@ pub fn expression(
         | ast::Expression::ExternalFunctionCall { .. }
         | ast::Expression::ExternalFunctionCallRaw { .. }
         | ast::Expression::Builtin(_, _, ast::Builtin::AbiDecode, _)
-        | ast::Expression::Builtin(_, _, ast::Builtin::AbiBorshDecode, _) => {
+        | ast::Expression::Builtin(_, _, ast::Builtin::AbiBorshDecode, _)
+        | ast::Expression::Builtin(_, _, ast::Builtin::CheckedAdd, _)
+        | ast::Expression::Builtin(_, _, ast::Builtin::CheckedSub, _)
+        | ast::Expression::Builtin(_, _, ast::Builtin::CheckedMul, _) => {
             let mut returns = emit_function_call(expr, contract_no, cfg, func, ns, vartab, opt);
 
             returns.remove(0)
@@ -501,6 +520,13 @@ pub fn expression(
         ast::Expression::CheckingTrunc(loc, ty, e) => {
             checking_trunc(loc, e, ty, cfg, contract_no, func, ns, vartab, opt)
         }
+        ast::Expression::Trunc(loc, ty @ Type::Enum(_), e)
+        | ast::Expression::ZeroExt(loc, ty @ Type::Enum(_), e)
+        | ast::Expression::Cast(loc, ty @ Type::Enum(_), e)
+            if matches!(e.ty(), Type::Uint(_) | Type::Int(_)) =>
+        {
+            checking_enum_cast(loc, e, ty, cfg, contract_no, func, ns, vartab, opt)
+        }
         ast::Expression::Trunc(loc, ty, e) => Expression::Trunc(
             *loc,
             ty.clone(),
@@ -566,6 +592,16 @@ pub fn expression(
         | ast::Expression::Builtin(_, _, ast::Builtin::UserTypeUnwrap, args) => {
             expression(&args[0], cfg, contract_no, func, ns, vartab, opt)
         }
+        ast::Expression::Builtin(loc, ty, ast::Builtin::StorageSlot, args) => {
+            // a storage reference evaluates to its slot
+            let slot = expression(&args[0], cfg, contract_no, func, ns, vartab, opt);
+
+            if slot.ty() == ty[0] {
+                slot
+            } else {
+                Expression::Cast(*loc, ty[0].clone(), Box::new(slot))
+            }
+        }
         ast::Expression::Builtin(loc, ty, ast::Builtin::ArrayPush, args) => {
             if args[0].ty().is_contract_storage() {
                 if ns.target == Target::Solana || args[0].ty().is_storage_bytes() {
@@ -639,17 +675,41 @@ pub fn expression(
         ast::Expression::Builtin(_, _, ast::Builtin::Assert, args) => {
             expr_assert(cfg, &args[0], contract_no, func, ns, vartab, opt)
         }
+        ast::Expression::Builtin(_, _, ast::Builtin::DebugAssert, args) => {
+            // sema has checked that the condition has no side effects, so nothing needs to
+            // be evaluated when the check is stripped
+            if opt.strip_debug_asserts {
+                Expression::Poison
+            } else {
+                expr_assert(cfg, &args[0], contract_no, func, ns, vartab, opt)
+            }
+        }
         ast::Expression::Builtin(_, _, ast::Builtin::Print, args) => {
-            let expr = expression(&args[0], cfg, contract_no, func, ns, vartab, opt);
+            if opt.log_prints || !ns.target.is_substrate() {
+                let expr = expression(&args[0], cfg, contract_no, func, ns, vartab, opt);
 
-            cfg.add(vartab, Instr::Print { expr });
+                cfg.add(vartab, Instr::Print { expr });
+            } else {
+                // Printing is a no-op outside of development chains, so do not bother converting
+                // the arguments to text. Anything with side effects must still be evaluated.
+                let mut params = SideEffectsCheckParameters {
+                    cfg,
+                    contract_no,
+                    func,
+                    ns,
+                    vartab,
+                    opt,
+                };
+
+                args[0].recurse(&mut params, process_side_effects_expressions);
+            }
 
             Expression::Poison
         }
         ast::Expression::Builtin(_, _, ast::Builtin::Require, args) => {
             require(cfg, args, contract_no, func, ns, vartab, opt)
         }
-        ast::Expression::Builtin(_, _, ast::Builtin::Revert, args) => {
+        ast::Expression::Builtin(_, _, ast::Builtin::Revert | ast::Builtin::RevertWith, args) => {
             revert(args, cfg, contract_no, func, ns, vartab, opt)
         }
         ast::Expression::Builtin(_, _, ast::Builtin::SelfDestruct, args) => {
@@ -714,6 +774,14 @@ pub fn expression(
             opt,
         ),
         ast::Expression::InterfaceId(loc, contract_no) => interfaceid(ns, contract_no, loc),
+        ast::Expression::CodeHash(loc, contract_no) => {
+            // the contract has already been compiled, since it is listed in creates
+            Expression::BytesLiteral(
+                *loc,
+                Type::Bytes(32),
+                ns.contracts[*contract_no].code_hash().to_vec(),
+            )
+        }
         ast::Expression::BoolLiteral(loc, value) => Expression::BoolLiteral(*loc, *value),
         ast::Expression::BytesLiteral(loc, ty, arr) => {
             Expression::BytesLiteral(*loc, ty.clone(), arr.clone())
@@ -794,7 +862,11 @@ fn post_incdec(
     opt: &Options,
 ) -> Expression {
     let res = vartab.temp_anonymous(ty);
-    let v = expression(var, cfg, contract_no, func, ns, vartab, opt);
+    let slot = incdec_storage_slot(var, cfg, contract_no, func, ns, vartab, opt);
+    let v = match &slot {
+        Some(slot) => slot.clone(),
+        None => expression(var, cfg, contract_no, func, ns, vartab, opt),
+    };
     let v = match var.ty() {
         Type::Ref(ty) => Expression::Load(var.loc(), ty.as_ref().clone(), Box::new(v)),
         Type::StorageRef(_, ty) => load_storage(&var.loc(), ty.as_ref(), v, cfg, vartab),
@@ -838,7 +910,10 @@ fn post_incdec(
             );
         }
         _ => {
-            let dest = expression(var, cfg, contract_no, func, ns, vartab, opt);
+            let dest = match slot {
+                Some(slot) => slot,
+                None => expression(var, cfg, contract_no, func, ns, vartab, opt),
+            };
             let res = vartab.temp_anonymous(ty);
             cfg.add(
                 vartab,
@@ -890,7 +965,11 @@ fn pre_incdec(
     opt: &Options,
 ) -> Expression {
     let res = vartab.temp_anonymous(ty);
-    let v = expression(var, cfg, contract_no, func, ns, vartab, opt);
+    let slot = incdec_storage_slot(var, cfg, contract_no, func, ns, vartab, opt);
+    let v = match &slot {
+        Some(slot) => slot.clone(),
+        None => expression(var, cfg, contract_no, func, ns, vartab, opt),
+    };
     let v = match var.ty() {
         Type::Ref(ty) => Expression::Load(var.loc(), ty.as_ref().clone(), Box::new(v)),
         Type::StorageRef(_, ty) => load_storage(&var.loc(), ty.as_ref(), v, cfg, vartab),
@@ -926,7 +1005,10 @@ fn pre_incdec(
             );
         }
         _ => {
-            let dest = expression(var, cfg, contract_no, func, ns, vartab, opt);
+            let dest = match slot {
+                Some(slot) => slot,
+                None => expression(var, cfg, contract_no, func, ns, vartab, opt),
+            };
 
             match var.ty() {
                 Type::StorageRef(..) => {
@@ -955,6 +1037,45 @@ fn pre_incdec(
     Expression::Variable(*loc, ty.clone(), res)
 }
 
+/// For an increment or decrement of a storage location like `arr[i]++`, evaluate the slot once
+/// into a temporary, so that it is not computed again for storing the result. Returns None if
+/// the slot is a constant or the location is not in storage.
+fn incdec_storage_slot(
+    var: &ast::Expression,
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    func: Option<&Function>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    opt: &Options,
+) -> Option<Expression> {
+    let slot_ty = var.ty();
+
+    match var {
+        ast::Expression::StorageVariable(..) => return None,
+        // a byte in storage bytes is not a slot of its own
+        ast::Expression::Subscript(_, _, array_ty, _, _) if array_ty.is_storage_bytes() => {
+            return None
+        }
+        _ if !matches!(slot_ty, Type::StorageRef(..)) => return None,
+        _ => (),
+    }
+
+    let slot = vartab.temp_name("slot", &slot_ty);
+    let dest = expression(var, cfg, contract_no, func, ns, vartab, opt);
+
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: pt::Loc::Codegen,
+            res: slot,
+            expr: dest,
+        },
+    );
+
+    Some(Expression::Variable(var.loc(), slot_ty, slot))
+}
+
 fn expr_or(
     left: &ast::Expression,
     cfg: &mut ControlFlowGraph,
@@ -1063,6 +1184,97 @@ fn and(
     Expression::Variable(*loc, Type::Bool, pos)
 }
 
+/// Compare two structs field by field. Both sides are evaluated once, since every field
+/// comparison needs them.
+fn struct_equal(
+    loc: &pt::Loc,
+    left: &ast::Expression,
+    right: &ast::Expression,
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    func: Option<&Function>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    opt: &Options,
+) -> Expression {
+    let ty = left.ty().deref_any().clone();
+
+    let mut sides = Vec::new();
+
+    for expr in [left, right] {
+        let expr = expression(expr, cfg, contract_no, func, ns, vartab, opt);
+        let res = vartab.temp_anonymous(&ty);
+
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc: *loc,
+                res,
+                expr,
+            },
+        );
+
+        sides.push(Expression::Variable(*loc, ty.clone(), res));
+    }
+
+    let right = sides.pop().unwrap();
+    let left = sides.pop().unwrap();
+
+    if let Type::Struct(str_ty) = &ty {
+        struct_fields_equal(loc, str_ty, left, right, ns)
+    } else {
+        unreachable!();
+    }
+}
+
+/// Build the conjunction of the comparisons of every field. Nested structs are stored inline,
+/// so they are compared through their member pointers.
+fn struct_fields_equal(
+    loc: &pt::Loc,
+    str_ty: &StructType,
+    left: Expression,
+    right: Expression,
+    ns: &Namespace,
+) -> Expression {
+    let mut res: Option<Expression> = None;
+
+    for (field_no, field) in str_ty.definition(ns).fields.iter().enumerate() {
+        let member = |expr: &Expression| {
+            Expression::StructMember(
+                *loc,
+                Type::Ref(Box::new(field.ty.clone())),
+                Box::new(expr.clone()),
+                field_no,
+            )
+        };
+
+        let cmp = if let Type::Struct(field_ty) = &field.ty {
+            struct_fields_equal(loc, field_ty, member(&left), member(&right), ns)
+        } else {
+            Expression::Equal(
+                *loc,
+                Box::new(Expression::Load(
+                    *loc,
+                    field.ty.clone(),
+                    Box::new(member(&left)),
+                )),
+                Box::new(Expression::Load(
+                    *loc,
+                    field.ty.clone(),
+                    Box::new(member(&right)),
+                )),
+            )
+        };
+
+        res = Some(match res {
+            Some(res) => Expression::BitwiseAnd(*loc, Type::Bool, Box::new(res), Box::new(cmp)),
+            None => cmp,
+        });
+    }
+
+    res.unwrap_or(Expression::BoolLiteral(*loc, true))
+}
+
 fn expr_assert(
     cfg: &mut ControlFlowGraph,
     args: &ast::Expression,
@@ -1084,7 +1296,7 @@ fn expr_assert(
         },
     );
     cfg.set_basic_block(false_);
-    cfg.add(vartab, Instr::AssertFailure { expr: None });
+    panic(PanicCode::Assertion, cfg, vartab, &ns.target);
     cfg.set_basic_block(true_);
     Expression::Poison
 }
@@ -1114,8 +1326,8 @@ fn require(
         .get(1)
         .map(|s| expression(s, cfg, contract_no, func, ns, vartab, opt));
     match ns.target {
-        // On Solana and Substrate, print the reason, do not abi encoding it
-        Target::Solana | Target::Substrate { .. } => {
+        // On Solana, print the reason, do not abi encoding it
+        Target::Solana => {
             if let Some(expr) = expr {
                 cfg.add(vartab, Instr::Print { expr });
             }
@@ -1574,7 +1786,7 @@ fn expr_builtin(
             );
 
             cfg.set_basic_block(out_of_bounds);
-            cfg.add(vartab, Instr::AssertFailure { expr: None });
+            panic(PanicCode::ArrayIndexOutOfBounds, cfg, vartab, &ns.target);
 
             cfg.set_basic_block(in_bounds);
 
@@ -1625,7 +1837,7 @@ fn expr_builtin(
             );
 
             cfg.set_basic_block(out_ouf_bounds);
-            cfg.add(vartab, Instr::AssertFailure { expr: None });
+            panic(PanicCode::ArrayIndexOutOfBounds, cfg, vartab, &ns.target);
 
             cfg.set_basic_block(in_bounds);
             let advanced_ptr = Expression::AdvancePointer {
@@ -1693,7 +1905,7 @@ fn expr_builtin(
             );
 
             cfg.set_basic_block(out_of_bounds);
-            cfg.add(vartab, Instr::AssertFailure { expr: None });
+            panic(PanicCode::ArrayIndexOutOfBounds, cfg, vartab, &ns.target);
 
             cfg.set_basic_block(in_bounds);
 
@@ -1890,7 +2102,7 @@ fn checking_trunc(
     );
 
     cfg.set_basic_block(out_of_bounds);
-    cfg.add(vartab, Instr::AssertFailure { expr: None });
+    panic(PanicCode::MathOverflow, cfg, vartab, &ns.target);
 
     cfg.set_basic_block(in_bounds);
 
@@ -1901,6 +2113,92 @@ fn checking_trunc(
     )
 }
 
+/// Convert an integer to an enum, checking that the value is a valid ordinal of the enum. The
+/// enum is resolved by number, so this works for enums declared in base contracts or in
+/// imported files too.
+fn checking_enum_cast(
+    loc: &pt::Loc,
+    expr: &ast::Expression,
+    ty: &Type,
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    func: Option<&Function>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    opt: &Options,
+) -> Expression {
+    let enum_no = match ty {
+        Type::Enum(enum_no) => *enum_no,
+        _ => unreachable!(),
+    };
+
+    let source_ty = expr.ty();
+
+    let bits = match source_ty {
+        Type::Uint(bits) | Type::Int(bits) => bits,
+        _ => unreachable!(),
+    };
+
+    let pos = vartab.temp(
+        &pt::Identifier {
+            name: "value".to_owned(),
+            loc: *loc,
+        },
+        &source_ty,
+    );
+
+    let expr = expression(expr, cfg, contract_no, func, ns, vartab, opt);
+
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: expr.loc(),
+            res: pos,
+            expr,
+        },
+    );
+
+    let out_of_bounds = cfg.new_basic_block("out_of_bounds".to_string());
+    let in_bounds = cfg.new_basic_block("in_bounds".to_string());
+
+    // compare unsigned, so that negative values are out of bounds too
+    let value = Expression::Cast(
+        *loc,
+        Type::Uint(bits),
+        Box::new(Expression::Variable(*loc, source_ty.clone(), pos)),
+    );
+
+    cfg.add(
+        vartab,
+        Instr::BranchCond {
+            cond: Expression::MoreEqual(
+                *loc,
+                Box::new(value),
+                Box::new(Expression::NumberLiteral(
+                    *loc,
+                    Type::Uint(bits),
+                    BigInt::from(ns.enums[enum_no].values.len()),
+                )),
+            ),
+            true_block: out_of_bounds,
+            false_block: in_bounds,
+        },
+    );
+
+    cfg.set_basic_block(out_of_bounds);
+    panic(PanicCode::EnumCastOutOfRange, cfg, vartab, &ns.target);
+
+    cfg.set_basic_block(in_bounds);
+
+    let value = Box::new(Expression::Variable(*loc, source_ty, pos));
+
+    match bits.cmp(&ty.bits(ns)) {
+        Ordering::Greater => Expression::Trunc(*loc, ty.clone(), value),
+        Ordering::Less => Expression::ZeroExt(*loc, ty.clone(), value),
+        Ordering::Equal => Expression::Cast(*loc, ty.clone(), value),
+    }
+}
+
 fn format_string(
     args: &[(FormatArg, ast::Expression)],
     cfg: &mut ControlFlowGraph,
@@ -2113,6 +2411,93 @@ pub fn assign_single(
     }
 }
 
+/// Sema resolves a compound assignment like `balances[key(user)] += amount` into
+/// `balances[key(user)] = balances[key(user)] + amount`. When the left hand side is in storage,
+/// evaluate the slot once into a temporary, so that the key expression and the mapping hash are
+/// not evaluated twice. Returns None if the assignment does not have this form.
+fn storage_compound_assign(
+    left: &ast::Expression,
+    right: &ast::Expression,
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    func: Option<&Function>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    opt: &Options,
+) -> Option<Expression> {
+    let slot_ty = left.ty();
+
+    let ty = match &slot_ty {
+        // the slot of a storage variable is a constant, so there is nothing to gain
+        Type::StorageRef(_, ty) if !matches!(left, ast::Expression::StorageVariable(..)) => {
+            ty.as_ref().clone()
+        }
+        _ => return None,
+    };
+
+    let mut right = right.clone();
+
+    let operand = match &mut right {
+        ast::Expression::Add(_, _, _, operand, _)
+        | ast::Expression::Subtract(_, _, _, operand, _)
+        | ast::Expression::Multiply(_, _, _, operand, _)
+        | ast::Expression::Divide(_, _, _, operand, _)
+        | ast::Expression::Modulo(_, _, operand, _)
+        | ast::Expression::BitwiseOr(_, _, operand, _)
+        | ast::Expression::BitwiseAnd(_, _, operand, _)
+        | ast::Expression::BitwiseXor(_, _, operand, _)
+        | ast::Expression::ShiftLeft(_, _, operand, _)
+        | ast::Expression::ShiftRight(_, _, operand, _, _) => operand,
+        _ => return None,
+    };
+
+    match operand.as_ref() {
+        ast::Expression::StorageLoad(_, _, var) if var.as_ref() == left => (),
+        _ => return None,
+    }
+
+    let slot = vartab.temp_name("slot", &slot_ty);
+    let dest = expression(left, cfg, contract_no, func, ns, vartab, opt);
+
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: pt::Loc::Codegen,
+            res: slot,
+            expr: dest,
+        },
+    );
+
+    *operand = Box::new(ast::Expression::StorageLoad(
+        left.loc(),
+        ty.clone(),
+        Box::new(ast::Expression::Variable(left.loc(), slot_ty.clone(), slot)),
+    ));
+
+    let value = expression(&right, cfg, contract_no, func, ns, vartab, opt);
+    let pos = vartab.temp_anonymous(&ty);
+
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: pt::Loc::Codegen,
+            res: pos,
+            expr: value,
+        },
+    );
+
+    cfg.add(
+        vartab,
+        Instr::SetStorage {
+            value: Expression::Variable(left.loc(), ty.clone(), pos),
+            ty: ty.clone(),
+            storage: Expression::Variable(pt::Loc::Codegen, slot_ty, slot),
+        },
+    );
+
+    Some(Expression::Variable(left.loc(), ty, pos))
+}
+
 /// Convert a function call expression to CFG in expression context
 pub fn emit_function_call(
     expr: &ast::Expression,
@@ -2493,16 +2878,14 @@ pub fn emit_function_call(
                             returns.push(Expression::Variable(id.loc, ret.ty.clone(), temp_pos));
                         }
 
-                        cfg.add(
+                        decode_return_data(
+                            loc,
+                            res,
+                            (*dest_func.returns).clone(),
+                            call_args.relaxed_decode,
+                            ns,
                             vartab,
-                            Instr::AbiDecode {
-                                res,
-                                selector: None,
-                                exception_block: None,
-                                tys: (*dest_func.returns).clone(),
-                                data: Expression::ReturnData(*loc),
-                                data_len: None,
-                            },
+                            cfg,
                         );
                     }
 
@@ -2612,17 +2995,7 @@ pub fn emit_function_call(
                         });
                     }
 
-                    cfg.add(
-                        vartab,
-                        Instr::AbiDecode {
-                            res,
-                            selector: None,
-                            exception_block: None,
-                            tys,
-                            data: Expression::ReturnData(*loc),
-                            data_len: None,
-                        },
-                    );
+                    decode_return_data(loc, res, tys, call_args.relaxed_decode, ns, vartab, cfg);
 
                     returns
                 } else {
@@ -2674,20 +3047,213 @@ pub fn emit_function_call(
 
             returns
         }
+        ast::Expression::Builtin(
+            loc,
+            tys,
+            builtin @ (ast::Builtin::CheckedAdd
+            | ast::Builtin::CheckedSub
+            | ast::Builtin::CheckedMul),
+            args,
+        ) => {
+            let ty = &tys[1];
+
+            // both the overflow flag and the result need the arguments, so evaluate them once
+            let args: Vec<Expression> = args
+                .iter()
+                .map(|arg| {
+                    let expr = expression(arg, cfg, callee_contract_no, func, ns, vartab, opt);
+                    let res = vartab.temp_anonymous(ty);
+
+                    cfg.add(
+                        vartab,
+                        Instr::Set {
+                            loc: *loc,
+                            res,
+                            expr,
+                        },
+                    );
+
+                    Expression::Variable(*loc, ty.clone(), res)
+                })
+                .collect();
+
+            let left = Box::new(args[0].clone());
+            let right = Box::new(args[1].clone());
+
+            let result = match builtin {
+                ast::Builtin::CheckedAdd => Expression::Add(*loc, ty.clone(), true, left, right),
+                ast::Builtin::CheckedSub => {
+                    Expression::Subtract(*loc, ty.clone(), true, left, right)
+                }
+                _ => Expression::Multiply(*loc, ty.clone(), true, left, right),
+            };
+
+            let ok = vartab.temp_name("ok", &Type::Bool);
+
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: *loc,
+                    res: ok,
+                    expr: Expression::Builtin(*loc, vec![Type::Bool], builtin.into(), args),
+                },
+            );
+
+            let res = vartab.temp_name("result", ty);
+
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: *loc,
+                    res,
+                    expr: result,
+                },
+            );
+
+            vec![
+                Expression::Variable(*loc, Type::Bool, ok),
+                Expression::Variable(*loc, ty.clone(), res),
+            ]
+        }
         _ => unreachable!(),
     }
 }
 
+/// Decode the return data of an external call into the variables in `res`. A callee which
+/// does not return anything at all, like some non-standard ERC20 tokens, would make the decoder
+/// trap; instead, revert with a message saying so, or if `relaxed_decode` is set, use the zero
+/// values of the return types.
+fn decode_return_data(
+    loc: &pt::Loc,
+    res: Vec<usize>,
+    tys: Vec<Parameter>,
+    relaxed_decode: bool,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) {
+    let no_return_data = cfg.new_basic_block("no_return_data".to_string());
+    let decode = cfg.new_basic_block("decode_return_data".to_string());
+
+    let is_empty = Expression::Equal(
+        *loc,
+        Box::new(Expression::Builtin(
+            *loc,
+            vec![Type::Uint(32)],
+            Builtin::ArrayLength,
+            vec![Expression::ReturnData(*loc)],
+        )),
+        Box::new(Expression::NumberLiteral(
+            *loc,
+            Type::Uint(32),
+            BigInt::zero(),
+        )),
+    );
+
+    cfg.add(
+        vartab,
+        Instr::BranchCond {
+            cond: is_empty,
+            true_block: no_return_data,
+            false_block: decode,
+        },
+    );
+
+    cfg.set_basic_block(no_return_data);
+
+    // function types have no zero value, so revert for those
+    let zero_values: Option<Vec<Expression>> = if relaxed_decode {
+        tys.iter().map(|param| param.ty.default(ns)).collect()
+    } else {
+        None
+    };
+
+    if let Some(zero_values) = zero_values {
+        let done = cfg.new_basic_block("return_data_decoded".to_string());
+
+        vartab.new_dirty_tracker();
+
+        for (res, expr) in res.iter().zip(zero_values) {
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: *loc,
+                    res: *res,
+                    expr,
+                },
+            );
+        }
+
+        cfg.add(vartab, Instr::Branch { block: done });
+
+        cfg.set_basic_block(decode);
+
+        cfg.add(
+            vartab,
+            Instr::AbiDecode {
+                res,
+                selector: None,
+                exception_block: None,
+                tys,
+                data: Expression::ReturnData(*loc),
+                data_len: None,
+            },
+        );
+
+        cfg.add(vartab, Instr::Branch { block: done });
+
+        cfg.set_basic_block(done);
+        cfg.set_phis(done, vartab.pop_dirty_tracker());
+    } else {
+        panic(PanicCode::MissingReturnData, cfg, vartab, &ns.target);
+
+        cfg.set_basic_block(decode);
+
+        cfg.add(
+            vartab,
+            Instr::AbiDecode {
+                res,
+                selector: None,
+                exception_block: None,
+                tys,
+                data: Expression::ReturnData(*loc),
+                data_len: None,
+            },
+        );
+    }
+}
+
 pub fn default_gas(ns: &Namespace) -> Expression {
-    Expression::NumberLiteral(
+    // Solana has no gas
+    if ns.target == Target::Solana {
+        return Expression::NumberLiteral(pt::Loc::Codegen, Type::Uint(64), BigInt::zero());
+    }
+
+    // Forward all but one 64th of the remaining gas, see EIP-150. The gas left is read twice,
+    // so the second read is a little lower; this only makes the reserved part a little smaller.
+    let gasleft = Expression::Builtin(
+        pt::Loc::Codegen,
+        vec![Type::Uint(64)],
+        Builtin::Gasleft,
+        Vec::new(),
+    );
+
+    Expression::Subtract(
         pt::Loc::Codegen,
         Type::Uint(64),
-        // See EIP150
-        if ns.target == Target::EVM {
-            BigInt::from(i64::MAX)
-        } else {
-            BigInt::zero()
-        },
+        true,
+        Box::new(gasleft.clone()),
+        Box::new(Expression::ShiftRight(
+            pt::Loc::Codegen,
+            Type::Uint(64),
+            Box::new(gasleft),
+            Box::new(Expression::NumberLiteral(
+                pt::Loc::Codegen,
+                Type::Uint(64),
+                BigInt::from(6),
+            )),
+            false,
+        )),
     )
 }
 
@@ -2855,7 +3421,15 @@ fn array_subscript(
     );
 
     cfg.set_basic_block(out_of_bounds);
-    cfg.add(vartab, Instr::AssertFailure { expr: None });
+
+    // on Solana, indexing past the end of tx.accounts means the instruction was given too few
+    let code = if *elem_ty.deref_any() == Type::Struct(StructType::AccountInfo) {
+        PanicCode::NotEnoughAccounts
+    } else {
+        PanicCode::ArrayIndexOutOfBounds
+    };
+
+    panic(code, cfg, vartab, &ns.target);
 
     cfg.set_basic_block(in_bounds);
 
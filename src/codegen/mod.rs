@@ -10,6 +10,7 @@ mod encoding;
 mod events;
 mod expression;
 mod external_functions;
+pub(crate) mod panic;
 mod reaching_definitions;
 mod statements;
 mod storage;
@@ -89,6 +90,10 @@ pub struct Options {
     pub math_overflow_check: bool,
     pub common_subexpression_elimination: bool,
     pub generate_debug_information: bool,
+    /// Keep print() calls on Substrate, where they only work on development chains
+    pub log_prints: bool,
+    /// Remove debugAssert() checks, e.g. for release builds
+    pub strip_debug_asserts: bool,
     pub opt_level: OptimizationLevel,
 }
 
@@ -99,9 +104,11 @@ impl Default for Options {
             constant_folding: true,
             strength_reduce: true,
             vector_to_slice: true,
-            math_overflow_check: false,
+            math_overflow_check: true,
             common_subexpression_elimination: true,
             generate_debug_information: false,
+            log_prints: false,
+            strip_debug_asserts: false,
             opt_level: OptimizationLevel::Default,
         }
     }
@@ -168,6 +175,28 @@ pub fn codegen(ns: &mut Namespace, opt: &Options) {
 
                     drop(binary);
 
+                    // the chain rejects contracts which import host functions it does not have
+                    if let Target::Substrate {
+                        api_version,
+                        max_code_size,
+                        ..
+                    } = ns.target
+                    {
+                        for message in
+                            crate::linker::unavailable_substrate_imports(&code, api_version)
+                        {
+                            ns.diagnostics.push(ast::Diagnostic::error(
+                                ns.contracts[contract_no].loc,
+                                format!(
+                                    "contract '{}' cannot be deployed: {}",
+                                    ns.contracts[contract_no].name, message
+                                ),
+                            ));
+                        }
+
+                        check_code_size(contract_no, &code, max_code_size, ns);
+                    }
+
                     ns.contracts[contract_no].code = code;
                 }
             }
@@ -177,6 +206,42 @@ pub fn codegen(ns: &mut Namespace, opt: &Options) {
     }
 }
 
+/// The chain rejects contracts larger than the maximum code size, so report an error listing the
+/// largest functions if the contract is too large, and warn when it is getting close.
+#[cfg(feature = "llvm")]
+fn check_code_size(contract_no: usize, code: &[u8], max_code_size: usize, ns: &mut Namespace) {
+    let contract = &ns.contracts[contract_no];
+
+    if code.len() > max_code_size {
+        let functions = crate::linker::wasm_function_sizes(code)
+            .into_iter()
+            .take(10)
+            .map(|(name, size)| format!("{} ({} bytes)", name, size))
+            .collect::<Vec<String>>();
+
+        ns.diagnostics.push(ast::Diagnostic::error(
+            contract.loc,
+            format!(
+                "contract '{}' code size of {} bytes exceeds the maximum of {} bytes; largest functions: {}",
+                contract.name,
+                code.len(),
+                max_code_size,
+                functions.join(", ")
+            ),
+        ));
+    } else if code.len() * 10 >= max_code_size * 9 {
+        ns.diagnostics.push(ast::Diagnostic::warning(
+            contract.loc,
+            format!(
+                "contract '{}' code size of {} bytes is close to the maximum of {} bytes",
+                contract.name,
+                code.len(),
+                max_code_size
+            ),
+        ));
+    }
+}
+
 fn contract(contract_no: usize, ns: &mut Namespace, opt: &Options) {
     if !ns.diagnostics.any_errors() && ns.contracts[contract_no].instantiable {
         layout(contract_no, ns);
@@ -275,6 +340,12 @@ fn storage_initializer(contract_no: usize, ns: &mut Namespace, opt: &Options) ->
         let var = &ns.contracts[layout.contract_no].variables[layout.var_no];
 
         if let Some(init) = &var.initializer {
+            // storage is empty when the constructor runs, so writing the default value
+            // would only cost gas
+            if is_default_value(init) {
+                continue;
+            }
+
             let storage = ns.contracts[contract_no].get_storage_slot(
                 pt::Loc::Codegen,
                 layout.contract_no,
@@ -305,6 +376,18 @@ fn storage_initializer(contract_no: usize, ns: &mut Namespace, opt: &Options) ->
     cfg
 }
 
+/// Is this initializer a literal which is the same as the default value for its type? Storage
+/// which was never written reads as the default value.
+fn is_default_value(init: &ast::Expression) -> bool {
+    match init {
+        ast::Expression::BoolLiteral(_, value) => !value,
+        ast::Expression::NumberLiteral(_, _, value) => value.is_zero(),
+        ast::Expression::BytesLiteral(_, Type::Bytes(_), bs) => bs.iter().all(|b| *b == 0),
+        ast::Expression::BytesLiteral(_, Type::String | Type::DynamicBytes, bs) => bs.is_empty(),
+        _ => false,
+    }
+}
+
 /// Layout the contract. We determine the layout of variables and deal with overriding variables
 fn layout(contract_no: usize, ns: &mut Namespace) {
     let mut slot = if ns.target == Target::Solana {
@@ -318,6 +401,19 @@ fn layout(contract_no: usize, ns: &mut Namespace) {
             if !ns.contracts[base_contract_no].variables[var_no].constant {
                 let ty = ns.contracts[base_contract_no].variables[var_no].ty.clone();
 
+                // variables with a @storage tag do not take up a sequential slot
+                if let Some(pinned) = ns.contracts[base_contract_no].variables[var_no].pinned_slot()
+                {
+                    ns.contracts[contract_no].layout.push(Layout {
+                        slot: pinned,
+                        contract_no: base_contract_no,
+                        var_no,
+                        ty,
+                    });
+
+                    continue;
+                }
+
                 if ns.target == Target::Solana {
                     // elements need to be aligned on solana
                     let alignment = ty.align_of(ns);
@@ -392,7 +488,7 @@ pub enum Expression {
     Complement(pt::Loc, Type, Box<Expression>),
     ConstArrayLiteral(pt::Loc, Type, Vec<u32>, Vec<Expression>),
     UnsignedDivide(pt::Loc, Type, Box<Expression>, Box<Expression>),
-    SignedDivide(pt::Loc, Type, Box<Expression>, Box<Expression>),
+    SignedDivide(pt::Loc, Type, bool, Box<Expression>, Box<Expression>),
     Equal(pt::Loc, Box<Expression>, Box<Expression>),
     FormatString(pt::Loc, Vec<(FormatArg, Expression)>),
     FunctionArg(pt::Loc, Type, usize),
@@ -442,7 +538,7 @@ pub enum Expression {
     Subscript(pt::Loc, Type, Type, Box<Expression>, Box<Expression>),
     Subtract(pt::Loc, Type, bool, Box<Expression>, Box<Expression>),
     Trunc(pt::Loc, Type, Box<Expression>),
-    UnaryMinus(pt::Loc, Type, Box<Expression>),
+    UnaryMinus(pt::Loc, Type, bool, Box<Expression>),
     Undefined(Type),
     Variable(pt::Loc, Type, usize),
     ZeroExt(pt::Loc, Type, Box<Expression>),
@@ -537,7 +633,7 @@ impl Recurse for Expression {
             Expression::BitwiseAnd(_, _, left, right)
             | Expression::BitwiseOr(_, _, left, right)
             | Expression::UnsignedDivide(_, _, left, right)
-            | Expression::SignedDivide(_, _, left, right)
+            | Expression::SignedDivide(_, _, _, left, right)
             | Expression::Equal(_, left, right)
             | Expression::UnsignedLess(_, left, right)
             | Expression::SignedLess(_, left, right)
@@ -568,7 +664,7 @@ impl Recurse for Expression {
             | Expression::GetRef(_, _, exp)
             | Expression::Not(_, exp)
             | Expression::Trunc(_, _, exp)
-            | Expression::UnaryMinus(_, _, exp)
+            | Expression::UnaryMinus(_, _, _, exp)
             | Expression::ZeroExt(_, _, exp)
             | Expression::SignExt(_, _, exp)
             | Expression::Complement(_, _, exp)
@@ -1012,12 +1108,15 @@ impl Expression {
                     Box::new(filter(left, ctx)),
                     Box::new(filter(right, ctx)),
                 ),
-                Expression::SignedDivide(loc, ty, left, right) => Expression::SignedDivide(
-                    *loc,
-                    ty.clone(),
-                    Box::new(filter(left, ctx)),
-                    Box::new(filter(right, ctx)),
-                ),
+                Expression::SignedDivide(loc, ty, unchecked, left, right) => {
+                    Expression::SignedDivide(
+                        *loc,
+                        ty.clone(),
+                        *unchecked,
+                        Box::new(filter(left, ctx)),
+                        Box::new(filter(right, ctx)),
+                    )
+                }
                 Expression::Power(loc, ty, unchecked, left, right) => Expression::Power(
                     *loc,
                     ty.clone(),
@@ -1130,9 +1229,12 @@ impl Expression {
                 Expression::Complement(loc, ty, expr) => {
                     Expression::Complement(*loc, ty.clone(), Box::new(filter(expr, ctx)))
                 }
-                Expression::UnaryMinus(loc, ty, expr) => {
-                    Expression::UnaryMinus(*loc, ty.clone(), Box::new(filter(expr, ctx)))
-                }
+                Expression::UnaryMinus(loc, ty, unchecked, expr) => Expression::UnaryMinus(
+                    *loc,
+                    ty.clone(),
+                    *unchecked,
+                    Box::new(filter(expr, ctx)),
+                ),
                 Expression::Subscript(loc, elem_ty, array_ty, left, right) => {
                     Expression::Subscript(
                         *loc,
@@ -1255,6 +1357,9 @@ pub enum Builtin {
     BlockHash,
     BlockNumber,
     Calldata,
+    CheckedAdd,
+    CheckedMul,
+    CheckedSub,
     Gasleft,
     GasLimit,
     Gasprice,
@@ -1304,6 +1409,9 @@ impl From<&ast::Builtin> for Builtin {
             ast::Builtin::BlockHash => Builtin::BlockHash,
             ast::Builtin::BlockNumber => Builtin::BlockNumber,
             ast::Builtin::Calldata => Builtin::Calldata,
+            ast::Builtin::CheckedAdd => Builtin::CheckedAdd,
+            ast::Builtin::CheckedMul => Builtin::CheckedMul,
+            ast::Builtin::CheckedSub => Builtin::CheckedSub,
             ast::Builtin::Gasleft => Builtin::Gasleft,
             ast::Builtin::GasLimit => Builtin::GasLimit,
             ast::Builtin::Gasprice => Builtin::Gasprice,
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{ControlFlowGraph, Instr};
+use super::vartable::Vartable;
+use super::Expression;
+use crate::sema::ast::Type;
+use crate::Target;
+use num_bigint::BigInt;
+use solang_parser::pt;
+
+/// The cause of a runtime failure, which is passed to the caller as the argument of
+/// `Panic(uint256)`. These are the codes Solidity uses, so tools which decode Solidity panics can
+/// decode ours too. Codes from 0x60 are for failures Solidity has no code for. The full list is
+/// included in the Substrate metadata.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanicCode {
+    Generic = 0x00,
+    Assertion = 0x01,
+    MathOverflow = 0x11,
+    DivisionByZero = 0x12,
+    EnumCastOutOfRange = 0x21,
+    StorageBytesEncodingIncorrect = 0x22,
+    EmptyArrayPop = 0x31,
+    ArrayIndexOutOfBounds = 0x32,
+    OutOfMemory = 0x41,
+    InternalFunctionUninitialized = 0x51,
+    DecodingFailed = 0x60,
+    ExternalCallFailed = 0x61,
+    MissingReturnData = 0x62,
+    NonPayableValueTransfer = 0x63,
+    NotEnoughAccounts = 0x64,
+}
+
+impl PanicCode {
+    /// All the panic codes, in numerical order
+    pub const ALL: [PanicCode; 15] = [
+        PanicCode::Generic,
+        PanicCode::Assertion,
+        PanicCode::MathOverflow,
+        PanicCode::DivisionByZero,
+        PanicCode::EnumCastOutOfRange,
+        PanicCode::StorageBytesEncodingIncorrect,
+        PanicCode::EmptyArrayPop,
+        PanicCode::ArrayIndexOutOfBounds,
+        PanicCode::OutOfMemory,
+        PanicCode::InternalFunctionUninitialized,
+        PanicCode::DecodingFailed,
+        PanicCode::ExternalCallFailed,
+        PanicCode::MissingReturnData,
+        PanicCode::NonPayableValueTransfer,
+        PanicCode::NotEnoughAccounts,
+    ];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            PanicCode::Generic => "generic failure",
+            PanicCode::Assertion => "assertion failed",
+            PanicCode::MathOverflow => "arithmetic overflow or underflow",
+            PanicCode::DivisionByZero => "division or modulo by zero",
+            PanicCode::EnumCastOutOfRange => "conversion of an out of range value to an enum",
+            PanicCode::StorageBytesEncodingIncorrect => "incorrectly encoded storage",
+            PanicCode::EmptyArrayPop => "pop() on an empty array",
+            PanicCode::ArrayIndexOutOfBounds => "array index out of bounds",
+            PanicCode::OutOfMemory => "out of memory",
+            PanicCode::InternalFunctionUninitialized => "call of an uninitialized function",
+            PanicCode::DecodingFailed => "input or return data could not be decoded",
+            PanicCode::ExternalCallFailed => "external call failed without revert data",
+            PanicCode::MissingReturnData => "external call returned no data",
+            PanicCode::NonPayableValueTransfer => "value sent to a function which is not payable",
+            PanicCode::NotEnoughAccounts => "not enough accounts passed to the instruction",
+        }
+    }
+
+    /// The encoded `Panic(uint256)` revert data. Substrate uses SCALE encoding, so the code
+    /// is a little endian integer there.
+    pub fn revert_data(self, target: &Target) -> Vec<u8> {
+        let mut data = vec![0x4e, 0x48, 0x7b, 0x71];
+        let mut code = [0u8; 32];
+
+        if target.is_substrate() {
+            code[0] = self as u8;
+        } else {
+            code[31] = self as u8;
+        }
+
+        data.extend_from_slice(&code);
+
+        data
+    }
+}
+
+/// Abort execution with `Panic(uint256)` for the given code
+pub(crate) fn panic(
+    code: PanicCode,
+    cfg: &mut ControlFlowGraph,
+    vartab: &mut Vartable,
+    target: &Target,
+) {
+    let data = code.revert_data(target);
+
+    cfg.add(
+        vartab,
+        Instr::AssertFailure {
+            expr: Some(Expression::AllocDynamicArray(
+                pt::Loc::Codegen,
+                Type::DynamicBytes,
+                Box::new(Expression::NumberLiteral(
+                    pt::Loc::Codegen,
+                    Type::Uint(32),
+                    BigInt::from(data.len()),
+                )),
+                Some(data),
+            )),
+        },
+    );
+}
@@ -582,6 +582,20 @@ pub(crate) fn statement(
             let emitter = new_event_emitter(loc, *event_no, args, ns);
             emitter.emit(contract_no, func, cfg, vartab, opt);
         }
+        Statement::Revert { error_no, args, .. } => {
+            let args = args
+                .iter()
+                .map(|arg| expression(arg, cfg, contract_no, Some(func), ns, vartab, opt))
+                .collect();
+
+            cfg.add(
+                vartab,
+                Instr::RevertError {
+                    error_no: *error_no,
+                    args,
+                },
+            );
+        }
         Statement::Underscore(_) => {
             // ensure we get phi nodes for the return values
             if let Some(instr @ Instr::Call { res, .. }) = placeholder {
@@ -790,6 +804,9 @@ fn returns(
         }
 
         ast::Expression::Builtin(_, _, ast::Builtin::AbiDecode, _)
+        | ast::Expression::Builtin(_, _, ast::Builtin::CheckedAdd, _)
+        | ast::Expression::Builtin(_, _, ast::Builtin::CheckedSub, _)
+        | ast::Expression::Builtin(_, _, ast::Builtin::CheckedMul, _)
         | ast::Expression::InternalFunctionCall { .. }
         | ast::Expression::ExternalFunctionCall { .. }
         | ast::Expression::ExternalFunctionCallRaw { .. } => {
@@ -996,6 +1013,7 @@ fn try_catch(
 
     let success_block = cfg.new_basic_block("success".to_string());
     let catch_block = cfg.new_basic_block("catch".to_string());
+    let catchall_block = cfg.new_basic_block("catchall".to_string());
     let finally_block = cfg.new_basic_block("finally".to_string());
 
     match &try_stmt.expr {
@@ -1099,12 +1117,13 @@ fn try_catch(
                         })
                         .collect();
 
+                    // return data which cannot be decoded is handled by the catch all clause
                     cfg.add(
                         vartab,
                         Instr::AbiDecode {
                             res,
                             selector: None,
-                            exception_block: None,
+                            exception_block: Some(catchall_block),
                             tys,
                             data: Expression::ReturnData(pt::Loc::Codegen),
                             data_len: None,
@@ -1241,6 +1260,15 @@ fn try_catch(
         cfg.set_basic_block(no_reason_block);
     }
 
+    cfg.add(
+        vartab,
+        Instr::Branch {
+            block: catchall_block,
+        },
+    );
+
+    cfg.set_basic_block(catchall_block);
+
     if let Some(res) = try_stmt.catch_param_pos {
         cfg.add(
             vartab,
@@ -1328,14 +1356,13 @@ impl LoopScopes {
 
 impl Type {
     /// Default value for a type, e.g. an empty string. Some types cannot have a default value,
-    /// for example a reference to a variable in storage.
+    /// for example a reference to a variable in storage. Contract types are addresses, so their
+    /// default is the zero address.
     pub fn default(&self, ns: &Namespace) -> Option<Expression> {
         match self {
-            Type::Address(_) | Type::Uint(_) | Type::Int(_) => Some(Expression::NumberLiteral(
-                pt::Loc::Codegen,
-                self.clone(),
-                BigInt::from(0),
-            )),
+            Type::Address(_) | Type::Contract(_) | Type::Uint(_) | Type::Int(_) => Some(
+                Expression::NumberLiteral(pt::Loc::Codegen, self.clone(), BigInt::from(0)),
+            ),
             Type::Bool => Some(Expression::BoolLiteral(pt::Loc::Codegen, false)),
             Type::Bytes(n) => {
                 let mut l = Vec::new();
@@ -1379,9 +1406,7 @@ impl Type {
                 )),
                 None,
             )),
-            Type::InternalFunction { .. } | Type::Contract(_) | Type::ExternalFunction { .. } => {
-                None
-            }
+            Type::InternalFunction { .. } | Type::ExternalFunction { .. } => None,
             Type::Array(ty, dims) => {
                 ty.default(ns)?;
 
@@ -1466,6 +1491,7 @@ pub fn process_side_effects_expressions(
             // for they return no value. They should not bother the unused variable elimination.
             | ast::Builtin::PayableTransfer
             | ast::Builtin::Revert
+            | ast::Builtin::RevertWith
             | ast::Builtin::Require
             | ast::Builtin::SelfDestruct
             | ast::Builtin::WriteInt8
@@ -8,6 +8,7 @@ use num_traits::One;
 use num_traits::Zero;
 
 use super::expression::{expression, load_storage};
+use super::panic::{panic, PanicCode};
 use super::Options;
 use super::{
     cfg::{ControlFlowGraph, Instr},
@@ -209,7 +210,7 @@ pub fn storage_slots_array_pop(
     );
 
     cfg.set_basic_block(empty_array);
-    cfg.add(vartab, Instr::AssertFailure { expr: None });
+    panic(PanicCode::EmptyArrayPop, cfg, vartab, &ns.target);
 
     cfg.set_basic_block(has_elements);
     let new_length = vartab.temp_anonymous(&slot_ty);
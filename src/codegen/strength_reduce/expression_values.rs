@@ -27,14 +27,16 @@ pub(super) fn expression_values(
         Expression::Add(_, ty, _, left, right) => add_values(ty, left, right, vars, ns),
         Expression::Subtract(_, ty, _, left, right) => subtract_values(ty, left, right, vars, ns),
         Expression::Multiply(_, ty, _, left, right) => multiply_values(ty, left, right, vars, ns),
-        Expression::SignedMore(_, left, right) | Expression::UnsignedMore(_, left, right) => {
-            more_values(left, right, vars, ns)
+        Expression::SignedMore(_, left, right) => more_values(left, right, true, vars, ns),
+        Expression::UnsignedMore(_, left, right) => more_values(left, right, false, vars, ns),
+        Expression::MoreEqual(_, left, right) => {
+            more_equal_values(left, right, left.ty().is_signed_int(), vars, ns)
         }
-        Expression::MoreEqual(_, left, right) => more_equal_values(left, right, vars, ns),
-        Expression::SignedLess(_, left, right) | Expression::UnsignedLess(_, left, right) => {
-            less_values(left, right, vars, ns)
+        Expression::SignedLess(_, left, right) => less_values(left, right, true, vars, ns),
+        Expression::UnsignedLess(_, left, right) => less_values(left, right, false, vars, ns),
+        Expression::LessEqual(_, left, right) => {
+            less_equal_values(left, right, left.ty().is_signed_int(), vars, ns)
         }
-        Expression::LessEqual(_, left, right) => less_equal_values(left, right, vars, ns),
         Expression::Equal(_, left_expr, right_expr) => {
             equal_values(left_expr, right_expr, vars, ns)
         }
@@ -454,11 +456,10 @@ fn multiply_values(
 fn more_values(
     left: &Expression,
     right: &Expression,
+    signed: bool,
     vars: &Variables,
     ns: &Namespace,
 ) -> HashSet<Value> {
-    let ty = left.ty();
-
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
 
@@ -469,12 +470,12 @@ fn more_values(
             let mut known_bits = BitArray::new([0u8; 32]);
             let mut value = BitArray::new([0u8; 32]);
 
-            let is_true = if ty.is_signed_int() {
-                BigInt::from_signed_bytes_le(l.get_signed_max_value().as_buffer())
-                    > BigInt::from_signed_bytes_le(r.get_signed_min_value().as_buffer())
+            let is_true = if signed {
+                BigInt::from_signed_bytes_le(l.get_signed_min_value().as_buffer())
+                    > BigInt::from_signed_bytes_le(r.get_signed_max_value().as_buffer())
             } else {
-                BigInt::from_bytes_le(Sign::Plus, l.get_unsigned_max_value().as_buffer())
-                    > BigInt::from_bytes_le(Sign::Plus, r.get_unsigned_min_value().as_buffer())
+                BigInt::from_bytes_le(Sign::Plus, l.get_unsigned_min_value().as_buffer())
+                    > BigInt::from_bytes_le(Sign::Plus, r.get_unsigned_max_value().as_buffer())
             };
 
             if is_true {
@@ -483,12 +484,12 @@ fn more_values(
                 value.set(0, true);
             } else {
                 // maybe the comparison is always false
-                let is_false = if ty.is_signed_int() {
-                    BigInt::from_signed_bytes_le(l.get_signed_min_value().as_buffer())
-                        <= BigInt::from_signed_bytes_le(r.get_signed_max_value().as_buffer())
+                let is_false = if signed {
+                    BigInt::from_signed_bytes_le(l.get_signed_max_value().as_buffer())
+                        <= BigInt::from_signed_bytes_le(r.get_signed_min_value().as_buffer())
                 } else {
-                    BigInt::from_bytes_le(Sign::Plus, l.get_unsigned_min_value().as_buffer())
-                        <= BigInt::from_bytes_le(Sign::Plus, r.get_unsigned_max_value().as_buffer())
+                    BigInt::from_bytes_le(Sign::Plus, l.get_unsigned_max_value().as_buffer())
+                        <= BigInt::from_bytes_le(Sign::Plus, r.get_unsigned_min_value().as_buffer())
                 };
 
                 if is_false {
@@ -509,11 +510,10 @@ fn more_values(
 fn more_equal_values(
     left: &Expression,
     right: &Expression,
+    signed: bool,
     vars: &Variables,
     ns: &Namespace,
 ) -> HashSet<Value> {
-    let ty = left.ty();
-
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
 
@@ -524,12 +524,12 @@ fn more_equal_values(
             let mut known_bits = BitArray::new([0u8; 32]);
             let mut value = BitArray::new([0u8; 32]);
 
-            let is_true = if ty.is_signed_int() {
-                BigInt::from_signed_bytes_le(l.get_signed_max_value().as_buffer())
-                    >= BigInt::from_signed_bytes_le(r.get_signed_min_value().as_buffer())
+            let is_true = if signed {
+                BigInt::from_signed_bytes_le(l.get_signed_min_value().as_buffer())
+                    >= BigInt::from_signed_bytes_le(r.get_signed_max_value().as_buffer())
             } else {
-                BigInt::from_bytes_le(Sign::Plus, l.get_unsigned_max_value().as_buffer())
-                    >= BigInt::from_bytes_le(Sign::Plus, r.get_unsigned_min_value().as_buffer())
+                BigInt::from_bytes_le(Sign::Plus, l.get_unsigned_min_value().as_buffer())
+                    >= BigInt::from_bytes_le(Sign::Plus, r.get_unsigned_max_value().as_buffer())
             };
 
             if is_true {
@@ -538,12 +538,12 @@ fn more_equal_values(
                 value.set(0, true);
             } else {
                 // maybe the comparison is always false
-                let is_false = if ty.is_signed_int() {
-                    BigInt::from_signed_bytes_le(l.get_signed_min_value().as_buffer())
-                        < BigInt::from_signed_bytes_le(r.get_signed_max_value().as_buffer())
+                let is_false = if signed {
+                    BigInt::from_signed_bytes_le(l.get_signed_max_value().as_buffer())
+                        < BigInt::from_signed_bytes_le(r.get_signed_min_value().as_buffer())
                 } else {
-                    BigInt::from_bytes_le(Sign::Plus, l.get_unsigned_min_value().as_buffer())
-                        < BigInt::from_bytes_le(Sign::Plus, r.get_unsigned_max_value().as_buffer())
+                    BigInt::from_bytes_le(Sign::Plus, l.get_unsigned_max_value().as_buffer())
+                        < BigInt::from_bytes_le(Sign::Plus, r.get_unsigned_min_value().as_buffer())
                 };
 
                 if is_false {
@@ -564,11 +564,10 @@ fn more_equal_values(
 fn less_values(
     left: &Expression,
     right: &Expression,
+    signed: bool,
     vars: &Variables,
     ns: &Namespace,
 ) -> HashSet<Value> {
-    let ty = left.ty();
-
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
 
@@ -579,7 +578,7 @@ fn less_values(
             let mut known_bits = BitArray::new([0u8; 32]);
             let mut value = BitArray::new([0u8; 32]);
 
-            let is_true = if ty.is_signed_int() {
+            let is_true = if signed {
                 BigInt::from_signed_bytes_le(l.get_signed_max_value().as_buffer())
                     < BigInt::from_signed_bytes_le(r.get_signed_min_value().as_buffer())
             } else {
@@ -593,7 +592,7 @@ fn less_values(
                 value.set(0, true);
             } else {
                 // maybe the comparison is always false
-                let is_false = if ty.is_signed_int() {
+                let is_false = if signed {
                     BigInt::from_signed_bytes_le(l.get_signed_min_value().as_buffer())
                         >= BigInt::from_signed_bytes_le(r.get_signed_max_value().as_buffer())
                 } else {
@@ -619,11 +618,10 @@ fn less_values(
 fn less_equal_values(
     left: &Expression,
     right: &Expression,
+    signed: bool,
     vars: &Variables,
     ns: &Namespace,
 ) -> HashSet<Value> {
-    let ty = left.ty();
-
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
 
@@ -634,7 +632,7 @@ fn less_equal_values(
             let mut known_bits = BitArray::new([0u8; 32]);
             let mut value = BitArray::new([0u8; 32]);
 
-            let is_true = if ty.is_signed_int() {
+            let is_true = if signed {
                 BigInt::from_signed_bytes_le(l.get_signed_max_value().as_buffer())
                     <= BigInt::from_signed_bytes_le(r.get_signed_min_value().as_buffer())
             } else {
@@ -648,7 +646,7 @@ fn less_equal_values(
                 value.set(0, true);
             } else {
                 // maybe the comparison is always false
-                let is_false = if ty.is_signed_int() {
+                let is_false = if signed {
                     BigInt::from_signed_bytes_le(l.get_signed_min_value().as_buffer())
                         > BigInt::from_signed_bytes_le(r.get_signed_max_value().as_buffer())
                 } else {
@@ -125,6 +125,12 @@ fn block_reduce(
             Instr::AssertFailure { expr: Some(expr) } => {
                 *expr = expression_reduce(expr, &vars, ns);
             }
+            Instr::RevertError { args, .. } => {
+                *args = args
+                    .iter()
+                    .map(|e| expression_reduce(e, &vars, ns))
+                    .collect();
+            }
             Instr::Print { expr } => {
                 *expr = expression_reduce(expr, &vars, ns);
             }
@@ -313,7 +319,7 @@ fn expression_reduce(expr: &Expression, vars: &Variables, ns: &mut Namespace) ->
                 expr.clone()
             }
             Expression::UnsignedDivide(loc, ty, left, right)
-            | Expression::SignedDivide(loc, ty, left, right) => {
+            | Expression::SignedDivide(loc, ty, _, left, right) => {
                 let bits = ty.bits(ns) as usize;
 
                 if bits >= 128 {
@@ -635,7 +635,7 @@ fn expresson_known_bits() {
 
     vars.insert(1, var2);
 
-    // should always be true
+    // the function argument might be 0, so this is not always true
     let expr = Expression::UnsignedMore(
         loc,
         Box::new(Expression::Variable(loc, Type::Uint(64), 0)),
@@ -644,12 +644,58 @@ fn expresson_known_bits() {
 
     let res = expression_values(&expr, &vars, &ns);
 
+    assert_eq!(res.len(), 2);
+
+    // can be both unknown or true
+    let mut cmp_set = HashSet::new();
+
+    // unknown
+    cmp_set.insert(Value {
+        known_bits: BitArray::new([0u8; 32]),
+        value: BitArray::new([0u8; 32]),
+        bits: 1,
+    });
+
+    let mut known_bits = BitArray::new([0u8; 32]);
+    known_bits.set(0, true);
+
+    let mut value = BitArray::new([0u8; 32]);
+    value.set(0, true);
+
+    cmp_set.insert(Value {
+        known_bits,
+        value,
+        bits: 1,
+    });
+
+    assert_eq!(res, cmp_set);
+
+    // -1 is less than 1 when compared signed, but not unsigned
+    let vars = HashMap::new();
+
+    let minus_one = Expression::NumberLiteral(loc, Type::Uint(8), BigInt::from(255));
+    let one = Expression::NumberLiteral(loc, Type::Uint(8), BigInt::from(1));
+
+    let expr = Expression::SignedLess(loc, Box::new(minus_one.clone()), Box::new(one.clone()));
+
+    let res = expression_values(&expr, &vars, &ns);
+
     assert_eq!(res.len(), 1);
     let v = res.iter().next().unwrap();
 
     assert!(v.known_bits[0]);
     assert!(v.value[0]);
 
+    let expr = Expression::UnsignedLess(loc, Box::new(minus_one), Box::new(one));
+
+    let res = expression_values(&expr, &vars, &ns);
+
+    assert_eq!(res.len(), 1);
+    let v = res.iter().next().unwrap();
+
+    assert!(v.known_bits[0]);
+    assert!(!v.value[0]);
+
     /////////////
     // test: less
     /////////////
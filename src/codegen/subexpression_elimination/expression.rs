@@ -67,9 +67,10 @@ impl Expression {
                 Box::new(right.clone()),
             ),
 
-            Expression::SignedDivide(loc, expr_type, ..) => Expression::SignedDivide(
+            Expression::SignedDivide(loc, expr_type, unchecked, ..) => Expression::SignedDivide(
                 *loc,
                 expr_type.clone(),
+                *unchecked,
                 Box::new(left.clone()),
                 Box::new(right.clone()),
             ),
@@ -208,9 +209,12 @@ impl Expression {
                 Expression::Complement(*loc, expr_type.clone(), Box::new(operand.clone()))
             }
 
-            Expression::UnaryMinus(loc, expr_type, ..) => {
-                Expression::UnaryMinus(*loc, expr_type.clone(), Box::new(operand.clone()))
-            }
+            Expression::UnaryMinus(loc, expr_type, unchecked, ..) => Expression::UnaryMinus(
+                *loc,
+                expr_type.clone(),
+                *unchecked,
+                Box::new(operand.clone()),
+            ),
 
             _ => unreachable!("Cannot rebuild this unary expression"),
         }
@@ -236,7 +240,7 @@ impl Expression {
         match self {
             Expression::Subtract(_, _, _, left, right)
             | Expression::UnsignedDivide(_, _, left, right)
-            | Expression::SignedDivide(_, _, left, right)
+            | Expression::SignedDivide(_, _, _, left, right)
             | Expression::SignedModulo(_, _, left, right)
             | Expression::UnsignedModulo(_, _, left, right)
             | Expression::Power(_, _, _, left, right)
@@ -267,7 +271,7 @@ impl Expression {
             | Expression::BytesCast(_, _, _, operand)
             | Expression::Not(_, operand)
             | Expression::Complement(_, _, operand)
-            | Expression::UnaryMinus(_, _, operand) => Some(operand),
+            | Expression::UnaryMinus(_, _, _, operand) => Some(operand),
 
             _ => None,
         }
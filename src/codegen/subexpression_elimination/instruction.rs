@@ -140,6 +140,12 @@ impl AvailableExpressionSet {
                 let _ = self.gen_expression(value, ave, cst);
             }
 
+            Instr::RevertError { args, .. } => {
+                for expr in args {
+                    let _ = self.gen_expression(expr, ave, cst);
+                }
+            }
+
             Instr::EmitEvent { data, topics, .. } => {
                 for expr in data {
                     let _ = self.gen_expression(expr, ave, cst);
@@ -243,6 +249,14 @@ impl AvailableExpressionSet {
                 expr: Some(self.regenerate_expression(exp, ave, cst).1),
             },
 
+            Instr::RevertError { error_no, args } => Instr::RevertError {
+                error_no: *error_no,
+                args: args
+                    .iter()
+                    .map(|v| self.regenerate_expression(v, ave, cst).1)
+                    .collect::<Vec<Expression>>(),
+            },
+
             Instr::Print { expr } => Instr::Print {
                 expr: self.regenerate_expression(expr, ave, cst).1,
             },
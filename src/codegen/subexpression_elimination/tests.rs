@@ -170,7 +170,7 @@ fn not_tracked() {
     let var = Expression::Variable(Loc::Codegen, Type::Int(2), 1);
     let arg = Expression::FunctionArg(Loc::Codegen, Type::Int(1), 1);
     let load = Expression::Load(Loc::Codegen, Type::DynamicBytes, Box::new(var));
-    let minus = Expression::UnaryMinus(Loc::Codegen, Type::Int(32), Box::new(load.clone()));
+    let minus = Expression::UnaryMinus(Loc::Codegen, Type::Int(32), false, Box::new(load.clone()));
     let exp = Expression::ShiftLeft(
         Loc::Codegen,
         Type::Int(32),
@@ -243,6 +243,7 @@ fn complex_expression() {
     let div = Expression::SignedDivide(
         Loc::Codegen,
         Type::Int(8),
+        false,
         Box::new(sum.clone()),
         Box::new(sub.clone()),
     );
@@ -269,7 +270,7 @@ fn complex_expression() {
     );
 
     let zero = Expression::ZeroExt(Loc::Codegen, Type::Int(54), Box::new(shift.clone()));
-    let unary = Expression::UnaryMinus(Loc::Codegen, Type::Int(44), Box::new(modu.clone()));
+    let unary = Expression::UnaryMinus(Loc::Codegen, Type::Int(44), false, Box::new(modu.clone()));
 
     let pot = Expression::Power(
         Loc::Codegen,
@@ -381,6 +382,7 @@ fn kill() {
     let div = Expression::SignedDivide(
         Loc::Codegen,
         Type::Int(8),
+        false,
         Box::new(sum.clone()),
         Box::new(sub.clone()),
     );
@@ -407,7 +409,7 @@ fn kill() {
     );
 
     let zero = Expression::ZeroExt(Loc::Codegen, Type::Int(54), Box::new(shift.clone()));
-    let unary = Expression::UnaryMinus(Loc::Codegen, Type::Int(44), Box::new(modu.clone()));
+    let unary = Expression::UnaryMinus(Loc::Codegen, Type::Int(44), false, Box::new(modu.clone()));
 
     let pot = Expression::Power(
         Loc::Codegen,
@@ -470,6 +472,7 @@ fn clone() {
     let div = Expression::SignedDivide(
         Loc::Codegen,
         Type::Int(8),
+        false,
         Box::new(sum.clone()),
         Box::new(sub.clone()),
     );
@@ -496,7 +499,7 @@ fn clone() {
     );
 
     let zero = Expression::ZeroExt(Loc::Codegen, Type::Int(54), Box::new(shift.clone()));
-    let unary = Expression::UnaryMinus(Loc::Codegen, Type::Int(44), Box::new(modu.clone()));
+    let unary = Expression::UnaryMinus(Loc::Codegen, Type::Int(44), false, Box::new(modu.clone()));
 
     let pot = Expression::Power(
         Loc::Codegen,
@@ -557,6 +560,7 @@ fn intersect() {
     let div = Expression::SignedDivide(
         Loc::Codegen,
         Type::Int(8),
+        false,
         Box::new(sum.clone()),
         Box::new(sub.clone()),
     );
@@ -583,7 +587,7 @@ fn intersect() {
     );
 
     let zero = Expression::ZeroExt(Loc::Codegen, Type::Int(54), Box::new(shift.clone()));
-    let unary = Expression::UnaryMinus(Loc::Codegen, Type::Int(44), Box::new(modu.clone()));
+    let unary = Expression::UnaryMinus(Loc::Codegen, Type::Int(44), false, Box::new(modu.clone()));
 
     let pot = Expression::Power(
         Loc::Codegen,
@@ -17,6 +17,9 @@ fn test_builtin_conversion() {
         ast::Builtin::BlockHash,
         ast::Builtin::BlockNumber,
         ast::Builtin::Calldata,
+        ast::Builtin::CheckedAdd,
+        ast::Builtin::CheckedMul,
+        ast::Builtin::CheckedSub,
         ast::Builtin::Gasleft,
         ast::Builtin::GasLimit,
         ast::Builtin::Gasprice,
@@ -74,6 +77,9 @@ fn test_builtin_conversion() {
         codegen::Builtin::BlockHash,
         codegen::Builtin::BlockNumber,
         codegen::Builtin::Calldata,
+        codegen::Builtin::CheckedAdd,
+        codegen::Builtin::CheckedMul,
+        codegen::Builtin::CheckedSub,
         codegen::Builtin::Gasleft,
         codegen::Builtin::GasLimit,
         codegen::Builtin::Gasprice,
@@ -242,8 +242,14 @@ fn process_arithmetic(
             branch_if_zero(right, expr, cfg, vartab)
         }
         YulBuiltInFunction::SDiv => {
-            let expr =
-                Expression::SignedDivide(*loc, left.ty(), Box::new(left), Box::new(right.clone()));
+            // sdiv wraps around when the smallest value is divided by -1
+            let expr = Expression::SignedDivide(
+                *loc,
+                left.ty(),
+                true,
+                Box::new(left),
+                Box::new(right.clone()),
+            );
             branch_if_zero(right, expr, cfg, vartab)
         }
         YulBuiltInFunction::Mod => {
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::sema::ast::{ArrayLength, Contract, Namespace, StructType, Type};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::ffi::CStr;
 use std::path::Path;
 use std::str;
@@ -11,11 +11,14 @@ use num_traits::ToPrimitive;
 use std::collections::HashMap;
 
 use crate::codegen::cfg::ReturnCode;
-use crate::emit::substrate;
+use crate::codegen::panic::PanicCode;
 use crate::emit::olive;
+use crate::emit::source_map::{self, SourceMapEntry};
+use crate::emit::substrate;
 use crate::emit::{solana, BinaryOp, Generate};
 use crate::linker::link;
 use crate::Target;
+use inkwell::basic_block::BasicBlock;
 use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::debug_info::DICompileUnit;
@@ -32,6 +35,7 @@ use inkwell::AddressSpace;
 use inkwell::IntPredicate;
 use inkwell::OptimizationLevel;
 use once_cell::sync::OnceCell;
+use solang_parser::pt;
 
 static LLVM_INIT: OnceCell<()> = OnceCell::new();
 
@@ -57,6 +61,16 @@ pub struct Binary<'a> {
     pub(crate) scratch: Option<GlobalValue<'a>>,
     pub(crate) parameters: Option<PointerValue<'a>>,
     pub(crate) return_values: HashMap<ReturnCode, IntValue<'a>>,
+    /// Source location for each file, line and column we emitted debug locations for
+    pub(crate) source_locations: HashMap<(usize, u32, u32), pt::Loc>,
+    /// The file number for the name of each debug info file we created
+    pub(crate) source_files: HashMap<String, usize>,
+    /// If set, abi decoding branches here when the data is malformed, rather than aborting
+    pub(crate) decode_exception: Cell<Option<BasicBlock<'a>>>,
+    /// Describes the value being abi decoded, so that a debug build can report what failed
+    pub(crate) decode_label: RefCell<Option<String>>,
+    /// Constant strings already emitted in this module, so that identical literals share a global
+    global_strings: RefCell<Vec<(Vec<u8>, GlobalValue<'a>)>>,
 }
 
 impl<'a> Binary<'a> {
@@ -189,6 +203,16 @@ impl<'a> Binary<'a> {
         }
     }
 
+    /// Generate the source map for the linked code. This requires the binary to be built with
+    /// debug info, since the map is derived from the line table llvm emits.
+    pub fn source_map(&self, code: &[u8]) -> Result<Vec<SourceMapEntry>, String> {
+        if !self.generate_debug_info {
+            return Err("source map requires debug info".to_string());
+        }
+
+        source_map::generate(code, &self.source_locations, &self.source_files)
+    }
+
     /// Mark all functions as internal unless they're in the export_list. This helps the
     /// llvm globaldce pass eliminate unnecessary functions and reduce the wasm output.
     pub(crate) fn internalize(&self, export_list: &[&str]) {
@@ -335,6 +359,11 @@ impl<'a> Binary<'a> {
             scratch_len: None,
             parameters: None,
             return_values,
+            source_locations: HashMap::new(),
+            source_files: HashMap::new(),
+            decode_exception: Cell::new(None),
+            decode_label: RefCell::new(None),
+            global_strings: RefCell::new(Vec::new()),
         }
     }
 
@@ -364,14 +393,30 @@ impl<'a> Binary<'a> {
         self.context.i8_type().array_type(ns.address_length as u32)
     }
 
-    /// Creates global string in the llvm module with initializer
-    ///
+    /// Creates global string in the llvm module with initializer. Constant strings are interned:
+    /// if the same bytes, or a longer string starting with them, were emitted before, the
+    /// existing global is reused.
     pub(crate) fn emit_global_string(
         &self,
         name: &str,
         data: &[u8],
         constant: bool,
     ) -> PointerValue<'a> {
+        if constant {
+            if let Some((_, gv)) = self
+                .global_strings
+                .borrow()
+                .iter()
+                .find(|(bs, _)| bs.starts_with(data))
+            {
+                return self.builder.build_pointer_cast(
+                    gv.as_pointer_value(),
+                    self.context.i8_type().ptr_type(AddressSpace::Generic),
+                    name,
+                );
+            }
+        }
+
         let ty = self.context.i8_type().array_type(data.len() as u32);
 
         let gv = self
@@ -385,6 +430,8 @@ impl<'a> Binary<'a> {
         if constant {
             gv.set_constant(true);
             gv.set_unnamed_addr(true);
+
+            self.global_strings.borrow_mut().push((data.to_vec(), gv));
         }
 
         self.builder.build_pointer_cast(
@@ -394,6 +441,16 @@ impl<'a> Binary<'a> {
         )
     }
 
+    /// The encoded `Panic(uint256)` revert data for the given code, and its length
+    pub(crate) fn panic_data(&self, code: PanicCode) -> (PointerValue<'a>, IntValue<'a>) {
+        let data = code.revert_data(&self.target);
+
+        (
+            self.emit_global_string(&format!("panic_{:02x}", code as u8), &data, true),
+            self.context.i32_type().const_int(data.len() as u64, false),
+        )
+    }
+
     /// Wrapper for alloca. Ensures that the alloca is done on the first basic block.
     /// If alloca is not on the first basic block, llvm will get to llvm_unreachable
     /// for the BPF target.
@@ -1026,6 +1083,32 @@ impl<'a> Binary<'a> {
         }
     }
 
+    /// Number of elements the vector has space for. Field 1 of the vector is the capacity,
+    /// which is never less than the length.
+    pub(crate) fn vector_capacity(&self, vector: PointerValue<'a>) -> IntValue<'a> {
+        let capacity = unsafe {
+            self.builder.build_gep(
+                vector,
+                &[
+                    self.context.i32_type().const_zero(),
+                    self.context.i32_type().const_int(1, false),
+                ],
+                "vector_capacity",
+            )
+        };
+
+        self.builder
+            .build_select(
+                self.builder.build_is_null(vector, "vector_is_null"),
+                self.context.i32_type().const_zero(),
+                self.builder
+                    .build_load(capacity, "vector_capacity")
+                    .into_int_value(),
+                "capacity",
+            )
+            .into_int_value()
+    }
+
     /// Return the pointer to the actual bytes in the vector
     pub(crate) fn vector_bytes(&self, vector: BasicValueEnum<'a>) -> PointerValue<'a> {
         if vector.is_struct_value() {
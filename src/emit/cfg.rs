@@ -7,7 +7,7 @@ use crate::emit::instructions::process_instruction;
 use crate::emit::{TargetRuntime, Variable};
 use crate::sema::ast::{Contract, Namespace};
 use crate::Target;
-use inkwell::debug_info::{AsDIScope, DISubprogram, DIType};
+use inkwell::debug_info::{AsDIScope, DILexicalBlock, DISubprogram, DIType};
 use inkwell::types::BasicType;
 use inkwell::values::{FunctionValue, PhiValue};
 use inkwell::AddressSpace;
@@ -105,6 +105,9 @@ pub(super) fn emit_cfg<'a, T: TargetRuntime<'a> + ?Sized>(
 
     let mut blocks: HashMap<usize, BasicBlock> = HashMap::new();
 
+    // a debug scope for each source file, so the line table says which file each line is in
+    let mut file_scopes: HashMap<usize, DILexicalBlock> = HashMap::new();
+
     let mut work = VecDeque::new();
 
     blocks.insert(0, create_block(0, bin, cfg, function, ns));
@@ -183,18 +186,43 @@ pub(super) fn emit_cfg<'a, T: TargetRuntime<'a> + ?Sized>(
 
         for (_, ins) in &cfg.blocks[w.block_no].instr {
             if bin.generate_debug_info {
-                let debug_loc = ins.loc();
-                if let pt::Loc::File(file_offset, offset, _) = debug_loc {
+                let loc = ins.loc();
+                if let pt::Loc::File(file_offset, offset, _) = loc {
                     let (line, col) = ns.files[file_offset].offset_to_line_column(offset);
+
+                    let scope = match file_scopes.get(&file_offset) {
+                        Some(scope) => *scope,
+                        None => {
+                            let path = ns.files[file_offset].path.to_string_lossy().to_string();
+                            let di_file = dibuilder.create_file(&path, ".");
+                            let scope = dibuilder.create_lexical_block(
+                                di_func_scope.unwrap().as_debug_info_scope(),
+                                di_file,
+                                0,
+                                0,
+                            );
+
+                            bin.source_files.insert(path, file_offset);
+                            file_scopes.insert(file_offset, scope);
+
+                            scope
+                        }
+                    };
+
                     let debug_loc = dibuilder.create_debug_location(
                         bin.context,
                         line as u32,
                         col as u32,
-                        di_func_scope.unwrap().as_debug_info_scope(),
+                        scope.as_debug_info_scope(),
                         None,
                     );
                     bin.builder
                         .set_current_debug_location(bin.context, debug_loc);
+
+                    // remember the full source range, so we can build a source map later
+                    bin.source_locations
+                        .entry((file_offset, line as u32, col as u32))
+                        .or_insert(loc);
                 }
             }
 
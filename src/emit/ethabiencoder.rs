@@ -3132,6 +3132,18 @@ impl EthAbiDecoder {
             .build_int_compare(IntPredicate::ULE, offset, end, "");
 
         let success_block = binary.context.append_basic_block(function, "success");
+
+        // malformed data can be handled by the caller, e.g. in try/catch
+        if let Some(exception_block) = binary.decode_exception.get() {
+            binary
+                .builder
+                .build_conditional_branch(in_bounds, success_block, exception_block);
+
+            binary.builder.position_at_end(success_block);
+
+            return;
+        }
+
         let bail_block = binary.context.append_basic_block(function, "bail");
         binary
             .builder
@@ -3271,18 +3283,18 @@ pub fn encode_to_vector<'b>(
 
     binary.builder.build_store(data_len, length);
 
-    let data_size = unsafe {
+    let data_capacity = unsafe {
         binary.builder.build_gep(
             v,
             &[
                 binary.context.i32_type().const_zero(),
                 binary.context.i32_type().const_int(1, false),
             ],
-            "data_size",
+            "data_capacity",
         )
     };
 
-    binary.builder.build_store(data_size, length);
+    binary.builder.build_store(data_capacity, length);
 
     let data = unsafe {
         binary.builder.build_gep(
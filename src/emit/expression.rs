@@ -1,9 +1,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::codegen::cfg::{HashTy, ReturnCode};
+use crate::codegen::panic::PanicCode;
 use crate::codegen::{Builtin, Expression};
 use crate::emit::binary::Binary;
-use crate::emit::math::{build_binary_op_with_overflow_check, multiply, power};
+use crate::emit::math::{
+    build_binary_op_with_overflow_check, check_divisor, check_signed_division_overflow, multiply,
+    overflow_flag, power,
+};
 use crate::emit::strings::{format_string, string_location};
 use crate::emit::{BinaryOp, Generate, TargetRuntime, Variable};
 use crate::sema::ast::{Namespace, RetrieveType, StructType, Type};
@@ -33,7 +37,7 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
         Expression::BoolLiteral(_, val) => {
             bin.context.bool_type().const_int(*val as u64, false).into()
         }
-        Expression::NumberLiteral(_, Type::Address(_), val) => {
+        Expression::NumberLiteral(_, Type::Address(_) | Type::Contract(_), val) => {
             // address can be negative; "address(-1)" is 0xffff...
             let mut bs = val.to_signed_bytes_be();
 
@@ -290,15 +294,9 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
 
                 bin.builder.position_at_end(bail_block);
 
-                // throw division by zero error should be an assert
-                target.assert_failure(
-                    bin,
-                    bin.context
-                        .i8_type()
-                        .ptr_type(AddressSpace::Generic)
-                        .const_null(),
-                    bin.context.i32_type().const_zero(),
-                );
+                let (data, len) = bin.panic_data(PanicCode::DivisionByZero);
+
+                target.assert_failure(bin, data, len);
 
                 bin.builder.position_at_end(success_block);
 
@@ -315,15 +313,20 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                 }
                 .into()
             } else {
+                check_divisor(target, bin, function, right);
+
                 bin.builder.build_int_unsigned_div(left, right, "").into()
             }
         }
-        Expression::SignedDivide(_, _, l, r) => {
+        Expression::SignedDivide(_, _, unchecked, l, r) => {
             let left = expression(target, bin, l, vartab, function, ns).into_int_value();
             let right = expression(target, bin, r, vartab, function, ns).into_int_value();
 
             let bits = left.get_type().get_bit_width();
 
+            let right =
+                check_signed_division_overflow(target, bin, function, *unchecked, left, right);
+
             if bits > 64 {
                 let div_bits = if bits <= 128 { 128 } else { 256 };
 
@@ -384,15 +387,9 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
 
                 bin.builder.position_at_end(bail_block);
 
-                // throw division by zero error should be an assert
-                target.assert_failure(
-                    bin,
-                    bin.context
-                        .i8_type()
-                        .ptr_type(AddressSpace::Generic)
-                        .const_null(),
-                    bin.context.i32_type().const_zero(),
-                );
+                let (data, len) = bin.panic_data(PanicCode::DivisionByZero);
+
+                target.assert_failure(bin, data, len);
 
                 bin.builder.position_at_end(success_block);
 
@@ -408,56 +405,60 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                     quotient
                 }
                 .into()
-            } else if ns.target == Target::Solana {
-                // no signed div on BPF; do abs udev and then negate if needed
-                let left_negative = bin.builder.build_int_compare(
-                    IntPredicate::SLT,
-                    left,
-                    left.get_type().const_zero(),
-                    "left_negative",
-                );
+            } else {
+                check_divisor(target, bin, function, right);
 
-                let left = bin
-                    .builder
-                    .build_select(
-                        left_negative,
-                        bin.builder.build_int_neg(left, "signed_left"),
+                if ns.target == Target::Solana {
+                    // no signed div on BPF; do abs udev and then negate if needed
+                    let left_negative = bin.builder.build_int_compare(
+                        IntPredicate::SLT,
                         left,
-                        "left_abs",
-                    )
-                    .into_int_value();
+                        left.get_type().const_zero(),
+                        "left_negative",
+                    );
 
-                let right_negative = bin.builder.build_int_compare(
-                    IntPredicate::SLT,
-                    right,
-                    right.get_type().const_zero(),
-                    "right_negative",
-                );
+                    let left = bin
+                        .builder
+                        .build_select(
+                            left_negative,
+                            bin.builder.build_int_neg(left, "signed_left"),
+                            left,
+                            "left_abs",
+                        )
+                        .into_int_value();
 
-                let right = bin
-                    .builder
-                    .build_select(
-                        right_negative,
-                        bin.builder.build_int_neg(right, "signed_right"),
+                    let right_negative = bin.builder.build_int_compare(
+                        IntPredicate::SLT,
                         right,
-                        "right_abs",
-                    )
-                    .into_int_value();
+                        right.get_type().const_zero(),
+                        "right_negative",
+                    );
 
-                let res = bin.builder.build_int_unsigned_div(left, right, "");
+                    let right = bin
+                        .builder
+                        .build_select(
+                            right_negative,
+                            bin.builder.build_int_neg(right, "signed_right"),
+                            right,
+                            "right_abs",
+                        )
+                        .into_int_value();
 
-                let negate_result =
-                    bin.builder
-                        .build_xor(left_negative, right_negative, "negate_result");
+                    let res = bin.builder.build_int_unsigned_div(left, right, "");
 
-                bin.builder.build_select(
-                    negate_result,
-                    bin.builder.build_int_neg(res, "unsigned_res"),
-                    res,
-                    "res",
-                )
-            } else {
-                bin.builder.build_int_signed_div(left, right, "").into()
+                    let negate_result =
+                        bin.builder
+                            .build_xor(left_negative, right_negative, "negate_result");
+
+                    bin.builder.build_select(
+                        negate_result,
+                        bin.builder.build_int_neg(res, "unsigned_res"),
+                        res,
+                        "res",
+                    )
+                } else {
+                    bin.builder.build_int_signed_div(left, right, "").into()
+                }
             }
         }
         Expression::UnsignedModulo(_, _, l, r) => {
@@ -526,15 +527,9 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
 
                 bin.builder.position_at_end(bail_block);
 
-                // throw division by zero error should be an assert
-                target.assert_failure(
-                    bin,
-                    bin.context
-                        .i8_type()
-                        .ptr_type(AddressSpace::Generic)
-                        .const_null(),
-                    bin.context.i32_type().const_zero(),
-                );
+                let (data, len) = bin.panic_data(PanicCode::DivisionByZero);
+
+                target.assert_failure(bin, data, len);
 
                 bin.builder.position_at_end(success_block);
 
@@ -548,6 +543,8 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                 }
                 .into()
             } else {
+                check_divisor(target, bin, function, right);
+
                 bin.builder.build_int_unsigned_rem(left, right, "").into()
             }
         }
@@ -617,15 +614,9 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
 
                 bin.builder.position_at_end(bail_block);
 
-                // throw division by zero error should be an assert
-                target.assert_failure(
-                    bin,
-                    bin.context
-                        .i8_type()
-                        .ptr_type(AddressSpace::Generic)
-                        .const_null(),
-                    bin.context.i32_type().const_zero(),
-                );
+                let (data, len) = bin.panic_data(PanicCode::DivisionByZero);
+
+                target.assert_failure(bin, data, len);
 
                 bin.builder.position_at_end(success_block);
 
@@ -638,50 +629,54 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                     rem
                 }
                 .into()
-            } else if ns.target == Target::Solana {
-                // no signed rem on BPF; do abs udev and then negate if needed
-                let left_negative = bin.builder.build_int_compare(
-                    IntPredicate::SLT,
-                    left,
-                    left.get_type().const_zero(),
-                    "left_negative",
-                );
+            } else {
+                check_divisor(target, bin, function, right);
 
-                let left = bin.builder.build_select(
-                    left_negative,
-                    bin.builder.build_int_neg(left, "signed_left"),
-                    left,
-                    "left_abs",
-                );
+                if ns.target == Target::Solana {
+                    // no signed rem on BPF; do abs udev and then negate if needed
+                    let left_negative = bin.builder.build_int_compare(
+                        IntPredicate::SLT,
+                        left,
+                        left.get_type().const_zero(),
+                        "left_negative",
+                    );
 
-                let right_negative = bin.builder.build_int_compare(
-                    IntPredicate::SLT,
-                    right,
-                    right.get_type().const_zero(),
-                    "right_negative",
-                );
+                    let left = bin.builder.build_select(
+                        left_negative,
+                        bin.builder.build_int_neg(left, "signed_left"),
+                        left,
+                        "left_abs",
+                    );
 
-                let right = bin.builder.build_select(
-                    right_negative,
-                    bin.builder.build_int_neg(right, "signed_right"),
-                    right,
-                    "right_abs",
-                );
+                    let right_negative = bin.builder.build_int_compare(
+                        IntPredicate::SLT,
+                        right,
+                        right.get_type().const_zero(),
+                        "right_negative",
+                    );
 
-                let res = bin.builder.build_int_unsigned_rem(
-                    left.into_int_value(),
-                    right.into_int_value(),
-                    "",
-                );
+                    let right = bin.builder.build_select(
+                        right_negative,
+                        bin.builder.build_int_neg(right, "signed_right"),
+                        right,
+                        "right_abs",
+                    );
 
-                bin.builder.build_select(
-                    left_negative,
-                    bin.builder.build_int_neg(res, "unsigned_res"),
-                    res,
-                    "res",
-                )
-            } else {
-                bin.builder.build_int_signed_rem(left, right, "").into()
+                    let res = bin.builder.build_int_unsigned_rem(
+                        left.into_int_value(),
+                        right.into_int_value(),
+                        "",
+                    );
+
+                    bin.builder.build_select(
+                        left_negative,
+                        bin.builder.build_int_neg(res, "unsigned_res"),
+                        res,
+                        "res",
+                    )
+                } else {
+                    bin.builder.build_int_signed_rem(left, right, "").into()
+                }
             }
         }
         Expression::Power(_, res_ty, unchecked, l, r) => {
@@ -722,14 +717,9 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                     .build_conditional_branch(error_ret, error_block, return_block);
                 bin.builder.position_at_end(error_block);
 
-                target.assert_failure(
-                    bin,
-                    bin.context
-                        .i8_type()
-                        .ptr_type(AddressSpace::Generic)
-                        .const_null(),
-                    bin.context.i32_type().const_zero(),
-                );
+                let (data, len) = bin.panic_data(PanicCode::MathOverflow);
+
+                target.assert_failure(bin, data, len);
 
                 bin.builder.position_at_end(return_block);
 
@@ -956,10 +946,24 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                 .build_int_z_extend(e, ty.into_int_type(), "")
                 .into()
         }
-        Expression::UnaryMinus(_, _, e) => {
+        Expression::UnaryMinus(_, ty, unchecked, e) => {
             let e = expression(target, bin, e, vartab, function, ns).into_int_value();
 
-            bin.builder.build_int_neg(e, "").into()
+            if bin.math_overflow_check && !*unchecked && ty.is_signed_int() {
+                // negating the smallest value overflows
+                build_binary_op_with_overflow_check(
+                    target,
+                    bin,
+                    function,
+                    e.get_type().const_zero(),
+                    e,
+                    BinaryOp::Subtract,
+                    true,
+                )
+                .into()
+            } else {
+                bin.builder.build_int_neg(e, "").into()
+            }
         }
         Expression::SignExt(_, t, e) => {
             let e = expression(target, bin, e, vartab, function, ns).into_int_value();
@@ -1442,6 +1446,29 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                             "",
                         );
                     }
+                    Type::Bytes(n) => {
+                        // bytesN are hashed in the order they are written, e.g. a bytes32 key is
+                        // used as is rather than byte reversed
+                        let temp = bin.build_alloca(function, v.get_type(), "bytes");
+
+                        bin.builder.build_store(temp, v);
+
+                        bin.builder.build_call(
+                            bin.module.get_function("__leNtobeN").unwrap(),
+                            &[
+                                bin.builder
+                                    .build_pointer_cast(
+                                        temp,
+                                        bin.context.i8_type().ptr_type(AddressSpace::Generic),
+                                        "bytes",
+                                    )
+                                    .into(),
+                                elem.into(),
+                                bin.context.i32_type().const_int(n as u64, false).into(),
+                            ],
+                            "",
+                        );
+                    }
                     _ => {
                         let elem = bin.builder.build_pointer_cast(
                             elem,
@@ -1544,6 +1571,33 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
 
             bin.builder.build_load(selector, "selector")
         }
+        Expression::Builtin(_, _, builtin, args)
+            if matches!(
+                builtin,
+                Builtin::CheckedAdd | Builtin::CheckedSub | Builtin::CheckedMul
+            ) =>
+        {
+            let left = expression(target, bin, &args[0], vartab, function, ns).into_int_value();
+            let right = expression(target, bin, &args[1], vartab, function, ns).into_int_value();
+
+            let op = match builtin {
+                Builtin::CheckedAdd => BinaryOp::Add,
+                Builtin::CheckedSub => BinaryOp::Subtract,
+                _ => BinaryOp::Multiply,
+            };
+
+            let overflow = overflow_flag(
+                target,
+                bin,
+                function,
+                left,
+                right,
+                op,
+                args[0].ty().is_signed_int(),
+            );
+
+            bin.builder.build_not(overflow, "ok").into()
+        }
         Expression::Builtin(_, _, Builtin::AddMod, args) => {
             let arith_ty = bin.context.custom_width_int_type(512);
             let res_ty = bin.context.custom_width_int_type(256);
@@ -1,12 +1,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::codegen::panic::PanicCode;
 use crate::emit::binary::Binary;
 use crate::emit::cfg::emit_cfg;
 use crate::emit::TargetRuntime;
 use crate::sema::ast::{Contract, Namespace, Type};
 use inkwell::module::Linkage;
 use inkwell::values::FunctionValue;
-use inkwell::{AddressSpace, IntPredicate};
+use inkwell::IntPredicate;
 
 /// Emit all functions, constructors, fallback and receiver
 pub(super) fn emit_functions<'a, T: TargetRuntime<'a>>(
@@ -99,15 +100,9 @@ pub(super) fn abort_if_value_transfer<'a, T: TargetRuntime<'a> + ?Sized>(
 
     binary.builder.position_at_end(abort_value_transfer);
 
-    target.assert_failure(
-        binary,
-        binary
-            .context
-            .i8_type()
-            .ptr_type(AddressSpace::Generic)
-            .const_null(),
-        binary.context.i32_type().const_zero(),
-    );
+    let (data, len) = binary.panic_data(PanicCode::NonPayableValueTransfer);
+
+    target.assert_failure(binary, data, len);
 
     binary.builder.position_at_end(not_value_transfer);
 }
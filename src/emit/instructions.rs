@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::codegen::cfg::{ControlFlowGraph, Instr, InternalCallTy, ReturnCode};
+use crate::codegen::panic::PanicCode;
 use crate::codegen::Expression;
 use crate::emit::binary::Binary;
 use crate::emit::cfg::{create_block, BasicBlock, Work};
@@ -156,16 +157,37 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
             let llvm_ty = bin.llvm_type(ty, ns);
             let elem_ty = ty.array_elem();
 
-            // Calculate total size for reallocation
             let llvm_elem_ty = bin.llvm_field_ty(&elem_ty, ns);
             let elem_size = llvm_elem_ty
                 .size_of()
                 .unwrap()
                 .const_cast(bin.context.i32_type(), false);
             let len = bin.vector_len(arr);
+            let capacity = bin.vector_capacity(arr.into_pointer_value());
             let new_len =
                 bin.builder
                     .build_int_add(len, bin.context.i32_type().const_int(1, false), "");
+
+            let arr = bin.builder.build_pointer_cast(
+                arr.into_pointer_value(),
+                llvm_ty.ptr_type(AddressSpace::Generic),
+                "arr",
+            );
+
+            // If there is no space left for the new element, reallocate
+            let entry = bin.builder.get_insert_block().unwrap();
+            let grow = bin.context.append_basic_block(function, "grow");
+            let push = bin.context.append_basic_block(function, "push");
+
+            let is_full =
+                bin.builder
+                    .build_int_compare(IntPredicate::UGE, len, capacity, "is_full");
+
+            bin.builder.build_conditional_branch(is_full, grow, push);
+
+            bin.builder.position_at_end(grow);
+
+            // Calculate total size for reallocation
             let vec_size = bin
                 .module
                 .get_struct_type("struct.vector")
@@ -183,15 +205,43 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 size
             };
 
-            // Reallocate and reassign the array pointer
-            let new = bin
+            // An empty vector can be a null pointer, which cannot be reallocated
+            let allocate = bin.context.append_basic_block(function, "allocate");
+            let reallocate = bin.context.append_basic_block(function, "reallocate");
+            let allocated = bin.context.append_basic_block(function, "allocated");
+
+            bin.builder.build_conditional_branch(
+                bin.builder.build_is_null(arr, "vector_is_null"),
+                allocate,
+                reallocate,
+            );
+
+            bin.builder.position_at_end(allocate);
+
+            let malloced = bin
+                .builder
+                .build_call(
+                    bin.module.get_function("__malloc").unwrap(),
+                    &[size.into()],
+                    "",
+                )
+                .try_as_basic_value()
+                .left()
+                .unwrap()
+                .into_pointer_value();
+
+            bin.builder.build_unconditional_branch(allocated);
+
+            bin.builder.position_at_end(reallocate);
+
+            let realloced = bin
                 .builder
                 .build_call(
                     bin.module.get_function("__realloc").unwrap(),
                     &[
                         bin.builder
                             .build_pointer_cast(
-                                arr.into_pointer_value(),
+                                arr,
                                 bin.context.i8_type().ptr_type(AddressSpace::Generic),
                                 "a",
                             )
@@ -204,11 +254,53 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 .left()
                 .unwrap()
                 .into_pointer_value();
-            let dest = bin.builder.build_pointer_cast(
+
+            bin.builder.build_unconditional_branch(allocated);
+
+            bin.builder.position_at_end(allocated);
+
+            let new = bin.builder.build_phi(malloced.get_type(), "new");
+
+            new.add_incoming(&[(&malloced, allocate), (&realloced, reallocate)]);
+
+            let new = new.as_basic_value().into_pointer_value();
+
+            let grown = bin.builder.build_pointer_cast(
                 new,
                 llvm_ty.ptr_type(AddressSpace::Generic),
-                "dest",
+                "grown",
             );
+
+            // Update the capacity field of the vector struct
+            let capacity_ptr = unsafe {
+                bin.builder.build_gep(
+                    grown,
+                    &[
+                        bin.context.i32_type().const_zero(),
+                        bin.context.i32_type().const_int(1, false),
+                    ],
+                    "capacity",
+                )
+            };
+            let capacity_field = bin.builder.build_pointer_cast(
+                capacity_ptr,
+                bin.context.i32_type().ptr_type(AddressSpace::Generic),
+                "capacity field",
+            );
+            bin.builder.build_store(capacity_field, new_len);
+
+            bin.builder.build_unconditional_branch(push);
+
+            bin.builder.position_at_end(push);
+
+            let dest = bin
+                .builder
+                .build_phi(llvm_ty.ptr_type(AddressSpace::Generic), "dest");
+
+            dest.add_incoming(&[(&arr, entry), (&grown, allocated)]);
+
+            let dest = dest.as_basic_value().into_pointer_value();
+
             w.vars.get_mut(array).unwrap().value = dest.into();
 
             // Store the value into the last element
@@ -238,7 +330,7 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
             };
             bin.builder.build_store(elem_ptr, value);
 
-            // Update the len and size field of the vector struct
+            // Update the len field of the vector struct
             let len_ptr = unsafe {
                 bin.builder.build_gep(
                     dest,
@@ -255,37 +347,11 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 "len field",
             );
             bin.builder.build_store(len_field, new_len);
-
-            let size_ptr = unsafe {
-                bin.builder.build_gep(
-                    dest,
-                    &[
-                        bin.context.i32_type().const_zero(),
-                        bin.context.i32_type().const_int(1, false),
-                    ],
-                    "size",
-                )
-            };
-            let size_field = bin.builder.build_pointer_cast(
-                size_ptr,
-                bin.context.i32_type().ptr_type(AddressSpace::Generic),
-                "size field",
-            );
-            bin.builder.build_store(size_field, new_len);
         }
         Instr::PopMemory { res, ty, array } => {
             let a = w.vars[array].value.into_pointer_value();
-            let len = unsafe {
-                bin.builder.build_gep(
-                    a,
-                    &[
-                        bin.context.i32_type().const_zero(),
-                        bin.context.i32_type().const_zero(),
-                    ],
-                    "a_len",
-                )
-            };
-            let len = bin.builder.build_load(len, "a_len").into_int_value();
+            // an empty vector may be a null pointer
+            let len = bin.vector_len(a.into());
 
             // First check if the array is empty
             let is_array_empty = bin.builder.build_int_compare(
@@ -300,22 +366,16 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 .build_conditional_branch(is_array_empty, error, pop);
 
             bin.builder.position_at_end(error);
-            target.assert_failure(
-                bin,
-                bin.context
-                    .i8_type()
-                    .ptr_type(AddressSpace::Generic)
-                    .const_null(),
-                bin.context.i32_type().const_zero(),
-            );
+
+            let (panic, panic_len) = bin.panic_data(PanicCode::EmptyArrayPop);
+
+            target.assert_failure(bin, panic, panic_len);
 
             bin.builder.position_at_end(pop);
-            let llvm_ty = bin.llvm_type(ty, ns);
 
             let elem_ty = ty.array_elem();
             let llvm_elem_ty = bin.llvm_field_ty(&elem_ty, ns);
 
-            // Calculate total size for reallocation
             let elem_size = llvm_elem_ty
                 .size_of()
                 .unwrap()
@@ -323,15 +383,6 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
             let new_len =
                 bin.builder
                     .build_int_sub(len, bin.context.i32_type().const_int(1, false), "");
-            let vec_size = bin
-                .module
-                .get_struct_type("struct.vector")
-                .unwrap()
-                .size_of()
-                .unwrap()
-                .const_cast(bin.context.i32_type(), false);
-            let size = bin.builder.build_int_mul(elem_size, new_len, "");
-            let size = bin.builder.build_int_add(size, vec_size, "");
 
             // Get the pointer to the last element and return it
             let slot_ptr = unsafe {
@@ -357,42 +408,11 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 w.vars.get_mut(res).unwrap().value = ret_val;
             }
 
-            // Reallocate and reassign the array pointer
-            let a = bin.builder.build_pointer_cast(
-                a,
-                bin.context.i8_type().ptr_type(AddressSpace::Generic),
-                "a",
-            );
-
-            let realloc_size = if ns.target == Target::Solana {
-                bin.builder
-                    .build_int_z_extend(size, bin.context.i64_type(), "")
-            } else {
-                size
-            };
-
-            let new = bin
-                .builder
-                .build_call(
-                    bin.module.get_function("__realloc").unwrap(),
-                    &[a.into(), realloc_size.into()],
-                    "",
-                )
-                .try_as_basic_value()
-                .left()
-                .unwrap()
-                .into_pointer_value();
-            let dest = bin.builder.build_pointer_cast(
-                new,
-                llvm_ty.ptr_type(AddressSpace::Generic),
-                "dest",
-            );
-            w.vars.get_mut(array).unwrap().value = dest.into();
-
-            // Update the len and size field of the vector struct
+            // Update the len field of the vector struct. The capacity stays the same, so the
+            // space can be reused by the next push
             let len_ptr = unsafe {
                 bin.builder.build_gep(
-                    dest,
+                    a,
                     &[
                         bin.context.i32_type().const_zero(),
                         bin.context.i32_type().const_zero(),
@@ -406,23 +426,6 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 "len field",
             );
             bin.builder.build_store(len_field, new_len);
-
-            let size_ptr = unsafe {
-                bin.builder.build_gep(
-                    dest,
-                    &[
-                        bin.context.i32_type().const_zero(),
-                        bin.context.i32_type().const_int(1, false),
-                    ],
-                    "size",
-                )
-            };
-            let size_field = bin.builder.build_pointer_cast(
-                size_ptr,
-                bin.context.i32_type().ptr_type(AddressSpace::Generic),
-                "size field",
-            );
-            bin.builder.build_store(size_field, new_len);
         }
         Instr::AssertFailure { expr: None } => {
             target.assert_failure(
@@ -434,10 +437,22 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 bin.context.i32_type().const_zero(),
             );
         }
+        Instr::AssertFailure { expr: Some(expr) } if expr.ty() == Type::DynamicBytes => {
+            // revertWith() passes on the revert data as is
+            let v = expression(target, bin, expr, &w.vars, function, ns);
+
+            target.assert_failure(bin, bin.vector_bytes(v), bin.vector_len(v));
+        }
         Instr::AssertFailure { expr: Some(expr) } => {
             let v = expression(target, bin, expr, &w.vars, function, ns);
 
-            let selector = 0x08c3_79a0u32;
+            // selector for Error(string). Substrate stores the selector as a little endian
+            // integer, so swap it so that it is encoded as 08c379a0
+            let selector = if ns.target.is_substrate() {
+                0x08c3_79a0u32.to_be()
+            } else {
+                0x08c3_79a0u32
+            };
 
             let (data, len) = target.abi_encode(
                 bin,
@@ -451,6 +466,37 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
 
             target.assert_failure(bin, data, len);
         }
+        Instr::RevertError { error_no, args } => {
+            let error = &ns.errors[*error_no];
+
+            let args: Vec<BasicValueEnum> = args
+                .iter()
+                .map(|a| expression(target, bin, a, &w.vars, function, ns))
+                .collect();
+
+            let tys: Vec<Type> = error.fields.iter().map(|param| param.ty.clone()).collect();
+
+            // Substrate stores the selector as a little endian integer, like Error(string)
+            let selector = u32::from_be_bytes(error.selector().try_into().unwrap());
+
+            let selector = if ns.target.is_substrate() {
+                selector.to_be()
+            } else {
+                selector
+            };
+
+            let (data, len) = target.abi_encode(
+                bin,
+                Some(bin.context.i32_type().const_int(selector as u64, false)),
+                false,
+                function,
+                &args,
+                &tys,
+                ns,
+            );
+
+            target.assert_failure(bin, data, len);
+        }
         Instr::Print { expr } => {
             let expr = expression(target, bin, expr, &w.vars, function, ns);
 
@@ -944,9 +990,7 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 bin.vector_len(v)
             };
 
-            if let Some(selector) = selector {
-                let exception = exception.unwrap();
-
+            let exception_block = exception.map(|exception| {
                 let pos = bin.builder.get_insert_block().unwrap();
 
                 blocks.entry(exception).or_insert({
@@ -960,7 +1004,11 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
 
                 bin.builder.position_at_end(pos);
 
-                let exception_block = blocks.get(&exception).unwrap();
+                blocks.get(&exception).unwrap().bb
+            });
+
+            if let Some(selector) = selector {
+                let exception_block = exception_block.unwrap();
 
                 let has_selector = bin.builder.build_int_compare(
                     IntPredicate::UGT,
@@ -972,7 +1020,7 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 let ok1 = bin.context.append_basic_block(function, "ok1");
 
                 bin.builder
-                    .build_conditional_branch(has_selector, ok1, exception_block.bb);
+                    .build_conditional_branch(has_selector, ok1, exception_block);
                 bin.builder.position_at_end(ok1);
 
                 let selector_data = bin
@@ -987,11 +1035,8 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                     )
                     .into_int_value();
 
-                let selector = if ns.target.is_substrate() {
-                    *selector
-                } else {
-                    selector.to_be()
-                };
+                // the selector is encoded big endian on all targets
+                let selector = selector.to_be();
 
                 let correct_selector = bin.builder.build_int_compare(
                     IntPredicate::EQ,
@@ -1003,7 +1048,7 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 let ok2 = bin.context.append_basic_block(function, "ok2");
 
                 bin.builder
-                    .build_conditional_branch(correct_selector, ok2, exception_block.bb);
+                    .build_conditional_branch(correct_selector, ok2, exception_block);
 
                 bin.builder.position_at_end(ok2);
 
@@ -1028,8 +1073,12 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
 
             let mut returns = Vec::new();
 
+            bin.decode_exception.set(exception_block);
+
             target.abi_decode(bin, function, &mut returns, data, data_len, tys, ns);
 
+            bin.decode_exception.set(None);
+
             for (i, ret) in returns.into_iter().enumerate() {
                 w.vars.get_mut(&res[i]).unwrap().value = ret;
             }
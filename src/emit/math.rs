@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::codegen::panic::PanicCode;
 use crate::emit::binary::Binary;
 use crate::emit::{BinaryOp, TargetRuntime};
 use inkwell::types::IntType;
@@ -194,14 +195,9 @@ fn signed_ovf_detect<'b, 'a: 'b, T: TargetRuntime<'a> + ?Sized>(
 
     bin.builder.position_at_end(error_block);
 
-    target.assert_failure(
-        bin,
-        bin.context
-            .i8_type()
-            .ptr_type(AddressSpace::Generic)
-            .const_null(),
-        bin.context.i32_type().const_zero(),
-    );
+    let (data, len) = bin.panic_data(PanicCode::MathOverflow);
+
+    target.assert_failure(bin, data, len);
 
     bin.builder.position_at_end(return_block);
 
@@ -404,14 +400,9 @@ pub(super) fn multiply<'a, T: TargetRuntime<'a> + ?Sized>(
 
             bin.builder.position_at_end(error_block);
 
-            target.assert_failure(
-                bin,
-                bin.context
-                    .i8_type()
-                    .ptr_type(AddressSpace::Generic)
-                    .const_null(),
-                bin.context.i32_type().const_zero(),
-            );
+            let (data, len) = bin.panic_data(PanicCode::MathOverflow);
+
+            target.assert_failure(bin, data, len);
 
             bin.builder.position_at_end(return_block);
 
@@ -622,14 +613,9 @@ pub(super) fn build_binary_op_with_overflow_check<'a, T: TargetRuntime<'a> + ?Si
 
     bin.builder.position_at_end(error_block);
 
-    target.assert_failure(
-        bin,
-        bin.context
-            .i8_type()
-            .ptr_type(AddressSpace::Generic)
-            .const_null(),
-        bin.context.i32_type().const_zero(),
-    );
+    let (data, len) = bin.panic_data(PanicCode::MathOverflow);
+
+    target.assert_failure(bin, data, len);
 
     bin.builder.position_at_end(success_block);
 
@@ -638,3 +624,155 @@ pub(super) fn build_binary_op_with_overflow_check<'a, T: TargetRuntime<'a> + ?Si
         .unwrap()
         .into_int_value()
 }
+
+/// Revert with `Panic(0x12)` if the divisor is zero. Integers wider than 64 bits are divided by
+/// the runtime library, which does this check itself.
+pub(super) fn check_divisor<'a, T: TargetRuntime<'a> + ?Sized>(
+    target: &T,
+    bin: &Binary<'a>,
+    function: FunctionValue,
+    divisor: IntValue<'a>,
+) {
+    let is_zero = bin.builder.build_int_compare(
+        IntPredicate::EQ,
+        divisor,
+        divisor.get_type().const_zero(),
+        "is_zero",
+    );
+
+    let success_block = bin.context.append_basic_block(function, "success");
+    let error_block = bin.context.append_basic_block(function, "division_by_zero");
+
+    bin.builder
+        .build_conditional_branch(is_zero, error_block, success_block);
+
+    bin.builder.position_at_end(error_block);
+
+    let (data, len) = bin.panic_data(PanicCode::DivisionByZero);
+
+    target.assert_failure(bin, data, len);
+
+    bin.builder.position_at_end(success_block);
+}
+
+/// Dividing the smallest value of a signed type by -1 overflows. With overflow checking and outside
+/// unchecked code, revert with `Panic(0x11)` like Solidity does; otherwise the result wraps around
+/// to the smallest value.
+/// This returns the divisor to use, which is 1 in the wrapping case, since the division itself
+/// traps on wasm.
+pub(super) fn check_signed_division_overflow<'a, T: TargetRuntime<'a> + ?Sized>(
+    target: &T,
+    bin: &Binary<'a>,
+    function: FunctionValue,
+    unchecked: bool,
+    dividend: IntValue<'a>,
+    divisor: IntValue<'a>,
+) -> IntValue<'a> {
+    let ty = dividend.get_type();
+
+    let min = ty
+        .const_int(1, false)
+        .const_shl(ty.const_int(ty.get_bit_width() as u64 - 1, false));
+
+    let overflow = bin.builder.build_and(
+        bin.builder
+            .build_int_compare(IntPredicate::EQ, dividend, min, "dividend_is_min"),
+        bin.builder.build_int_compare(
+            IntPredicate::EQ,
+            divisor,
+            ty.const_all_ones(),
+            "divisor_is_minus_one",
+        ),
+        "overflow",
+    );
+
+    if bin.math_overflow_check && !unchecked {
+        let success_block = bin.context.append_basic_block(function, "success");
+        let error_block = bin
+            .context
+            .append_basic_block(function, "division_overflow");
+
+        bin.builder
+            .build_conditional_branch(overflow, error_block, success_block);
+
+        bin.builder.position_at_end(error_block);
+
+        let (data, len) = bin.panic_data(PanicCode::MathOverflow);
+
+        target.assert_failure(bin, data, len);
+
+        bin.builder.position_at_end(success_block);
+
+        divisor
+    } else {
+        bin.builder
+            .build_select(overflow, ty.const_int(1, false), divisor, "divisor")
+            .into_int_value()
+    }
+}
+
+/// Return a flag which is set if the operation overflows, without reverting. This is used for
+/// the checked arithmetic builtins like `a.checkedAdd(b)`.
+pub(super) fn overflow_flag<'a, T: TargetRuntime<'a> + ?Sized>(
+    target: &T,
+    bin: &Binary<'a>,
+    function: FunctionValue<'a>,
+    left: IntValue<'a>,
+    right: IntValue<'a>,
+    op: BinaryOp,
+    signed: bool,
+) -> IntValue<'a> {
+    let ty = left.get_type();
+    let bits = ty.get_bit_width();
+
+    // Mul with overflow is not supported beyond 32 bits (see multiply()), so multiply in
+    // double the width where the product cannot overflow, and check if it fits
+    if matches!(op, BinaryOp::Multiply) && bits > 32 {
+        let wide_ty = bin.context.custom_width_int_type(bits * 2);
+
+        let (left, right) = if signed {
+            (
+                bin.builder.build_int_s_extend(left, wide_ty, "left"),
+                bin.builder.build_int_s_extend(right, wide_ty, "right"),
+            )
+        } else {
+            (
+                bin.builder.build_int_z_extend(left, wide_ty, "left"),
+                bin.builder.build_int_z_extend(right, wide_ty, "right"),
+            )
+        };
+
+        let product = multiply(target, bin, function, true, left, right, signed);
+
+        let truncated = bin.builder.build_int_truncate(product, ty, "truncated");
+
+        let extended = if signed {
+            bin.builder.build_int_s_extend(truncated, wide_ty, "")
+        } else {
+            bin.builder.build_int_z_extend(truncated, wide_ty, "")
+        };
+
+        return bin
+            .builder
+            .build_int_compare(IntPredicate::NE, extended, product, "overflow");
+    }
+
+    let ret_ty = bin.context.struct_type(
+        &[ty.into(), bin.context.custom_width_int_type(1).into()],
+        false,
+    );
+    let binop = bin.llvm_overflow(ret_ty.into(), ty, signed, op);
+
+    let op_res = bin
+        .builder
+        .build_call(binop, &[left.into(), right.into()], "res")
+        .try_as_basic_value()
+        .left()
+        .unwrap()
+        .into_struct_value();
+
+    bin.builder
+        .build_extract_value(op_res, 1, "overflow")
+        .unwrap()
+        .into_int_value()
+}
@@ -22,6 +22,7 @@ mod instructions;
 mod loop_builder;
 mod math;
 pub mod solana;
+pub mod source_map;
 mod storage;
 mod strings;
 pub mod substrate;
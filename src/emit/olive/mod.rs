@@ -502,6 +502,18 @@ impl OliveTarget {
         );
 
         let success_block = binary.context.append_basic_block(function, "success");
+
+        // malformed data can be handled by the caller, e.g. in try/catch
+        if let Some(exception_block) = binary.decode_exception.get() {
+            binary
+                .builder
+                .build_conditional_branch(in_bounds, success_block, exception_block);
+
+            binary.builder.position_at_end(success_block);
+
+            return;
+        }
+
         let bail_block = binary.context.append_basic_block(function, "bail");
         binary
             .builder
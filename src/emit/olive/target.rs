@@ -685,13 +685,13 @@ impl<'a> TargetRuntime<'a> for OliveTarget {
             binary.builder.build_store(data_len, length);
         }
 
-        let data_size = unsafe {
+        let data_capacity = unsafe {
             binary
                 .builder
-                .build_gep(v, &[i32_zero!(), i32_const!(1)], "data_size")
+                .build_gep(v, &[i32_zero!(), i32_const!(1)], "data_capacity")
         };
 
-        binary.builder.build_store(data_size, length);
+        binary.builder.build_store(data_capacity, length);
 
         let data = unsafe {
             binary
@@ -2,6 +2,7 @@
 
 use crate::codegen;
 use crate::codegen::cfg::{HashTy, ReturnCode};
+use crate::codegen::panic::PanicCode;
 use crate::emit::binary::Binary;
 use crate::emit::expression::expression;
 use crate::emit::loop_builder::LoopBuilder;
@@ -1747,16 +1748,9 @@ impl<'a> TargetRuntime<'a> for SolanaTarget {
 
             binary.builder.position_at_end(bail_block);
 
-            // should we log "call failed?"
-            self.assert_failure(
-                binary,
-                binary
-                    .context
-                    .i8_type()
-                    .ptr_type(AddressSpace::Generic)
-                    .const_null(),
-                binary.context.i32_type().const_zero(),
-            );
+            let (data, len) = binary.panic_data(PanicCode::ExternalCallFailed);
+
+            self.assert_failure(binary, data, len);
 
             binary.builder.position_at_end(success_block);
         }
@@ -1842,18 +1836,18 @@ impl<'a> TargetRuntime<'a> for SolanaTarget {
 
         binary.builder.build_store(data_len, length);
 
-        let data_size = unsafe {
+        let data_capacity = unsafe {
             binary.builder.build_gep(
                 v,
                 &[
                     binary.context.i32_type().const_zero(),
                     binary.context.i32_type().const_int(1, false),
                 ],
-                "data_size",
+                "data_capacity",
             )
         };
 
-        binary.builder.build_store(data_size, length);
+        binary.builder.build_store(data_capacity, length);
 
         let data = unsafe {
             binary.builder.build_gep(
@@ -1904,9 +1898,20 @@ impl<'a> TargetRuntime<'a> for SolanaTarget {
         binary.builder.build_return(Some(&ret));
     }
 
-    /// Value received is not available on solana
-    fn value_transferred<'b>(&self, _binary: &Binary<'b>, _ns: &ast::Namespace) -> IntValue<'b> {
-        unreachable!();
+    /// Lamports sent with the instruction. The dispatcher has already moved these to the data account
+    fn value_transferred<'b>(&self, binary: &Binary<'b>, _ns: &ast::Namespace) -> IntValue<'b> {
+        let parameters = self.sol_parameters(binary);
+
+        binary
+            .builder
+            .build_load(
+                binary
+                    .builder
+                    .build_struct_gep(parameters, 14, "value")
+                    .unwrap(),
+                "value",
+            )
+            .into_int_value()
     }
 
     /// Send value to address
@@ -2096,6 +2101,9 @@ impl<'a> TargetRuntime<'a> for SolanaTarget {
 
                 binary.builder.build_load(sender_address, "sender_address")
             }
+            codegen::Expression::Builtin(_, _, codegen::Builtin::Value, _) => {
+                self.value_transferred(binary, ns).into()
+            }
             codegen::Expression::Builtin(_, _, codegen::Builtin::GetAddress, _) => {
                 let parameters = self.sol_parameters(binary);
 
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Map code offsets in the final binary back to the Solidity expressions they
+//! were generated from. This is similar to the `srcmap` which solc produces, but
+//! derived from the DWARF line table which llvm generates when debug info is enabled.
+
+use crate::sema::ast::Namespace;
+use gimli::{ColumnType, EndianSlice, RunTimeEndian, SectionId};
+use object::{Object, ObjectSection, SectionKind};
+use serde::Serialize;
+use solang_parser::pt;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// A single entry in the source map. All code from `offset` up to the offset of the
+/// next entry was generated for the source range `start`..`end` of file `file_no`.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct SourceMapEntry {
+    pub offset: u64,
+    #[serde(rename = "file")]
+    pub file_no: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Serialize)]
+struct SourceMapJson<'a> {
+    files: Vec<String>,
+    map: &'a [SourceMapEntry],
+}
+
+/// Build the source map for a linked binary. The `locations` map the file, line and column of
+/// each debug location we emitted back to the original source range, and `files` gives the file
+/// number for each file name in the line table.
+///
+/// Offsets are relative to the start of the code, i.e. the `.text` section on Solana and the
+/// code section on WebAssembly.
+pub(super) fn generate(
+    code: &[u8],
+    locations: &HashMap<(usize, u32, u32), pt::Loc>,
+    files: &HashMap<String, usize>,
+) -> Result<Vec<SourceMapEntry>, String> {
+    let object = object::File::parse(code).map_err(|e| e.to_string())?;
+
+    let endian = if object.is_little_endian() {
+        RunTimeEndian::Little
+    } else {
+        RunTimeEndian::Big
+    };
+
+    let load_section = |id: SectionId| -> Result<Cow<[u8]>, gimli::Error> {
+        Ok(object
+            .section_by_name(id.name())
+            .and_then(|section| section.uncompressed_data().ok())
+            .unwrap_or(Cow::Borrowed(&[][..])))
+    };
+
+    let dwarf_cow = gimli::Dwarf::load(&load_section).map_err(|e| e.to_string())?;
+    let dwarf = dwarf_cow.borrow(|section| EndianSlice::new(section, endian));
+
+    let code_start = object
+        .sections()
+        .find(|section| section.kind() == SectionKind::Text)
+        .map(|section| section.address())
+        .unwrap_or(0);
+
+    let mut entries = Vec::new();
+    let mut units = dwarf.units();
+
+    while let Some(header) = units.next().map_err(|e| e.to_string())? {
+        let unit = dwarf.unit(header).map_err(|e| e.to_string())?;
+
+        let program = match unit.line_program.clone() {
+            Some(program) => program,
+            None => continue,
+        };
+
+        let mut rows = program.rows();
+
+        while let Some((header, row)) = rows.next_row().map_err(|e| e.to_string())? {
+            if row.end_sequence() {
+                continue;
+            }
+
+            let file_no = match row.file(header) {
+                Some(file) => {
+                    let name = dwarf
+                        .attr_string(&unit, file.path_name())
+                        .map_err(|e| e.to_string())?;
+
+                    match files.get(name.to_string_lossy().as_ref()) {
+                        Some(file_no) => *file_no,
+                        None => continue,
+                    }
+                }
+                None => continue,
+            };
+
+            let line = row.line().map(|line| line.get()).unwrap_or(0) as u32;
+            let column = match row.column() {
+                ColumnType::LeftEdge => 0,
+                ColumnType::Column(column) => column.get() as u32,
+            };
+
+            if let Some(pt::Loc::File(file_no, start, end)) =
+                locations.get(&(file_no, line, column))
+            {
+                entries.push(SourceMapEntry {
+                    offset: row.address().saturating_sub(code_start),
+                    file_no: *file_no,
+                    start: *start,
+                    end: *end,
+                });
+            }
+        }
+    }
+
+    entries.sort_by_key(|entry| entry.offset);
+
+    // consecutive rows for the same expression can be merged
+    entries.dedup_by(|next, prev| {
+        next.file_no == prev.file_no && next.start == prev.start && next.end == prev.end
+    });
+
+    Ok(entries)
+}
+
+/// Serialize the source map to json, including the list of file names so that the `file`
+/// field of each entry can be resolved.
+pub fn source_map_json(map: &[SourceMapEntry], ns: &Namespace) -> String {
+    let files = ns
+        .files
+        .iter()
+        .map(|file| file.path.to_string_lossy().to_string())
+        .collect();
+
+    serde_json::to_string(&SourceMapJson { files, map }).unwrap()
+}
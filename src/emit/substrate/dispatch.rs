@@ -73,6 +73,8 @@ impl SubstrateTarget {
             )
         };
 
+        let calldata_len = argslen;
+
         let argslen =
             bin.builder
                 .build_int_sub(argslen, argslen.get_type().const_int(4, false), "argslen");
@@ -127,50 +129,48 @@ impl SubstrateTarget {
             return;
         }
 
-        let got_value = if bin.function_abort_value_transfers {
-            bin.context.bool_type().const_zero()
-        } else {
-            let value = self.value_transferred(bin, ns);
+        // Like Solidity, a call with empty calldata goes to receive, if there is one. Any other
+        // call which does not match a function goes to fallback.
+        let fallback_block = bin.context.append_basic_block(function, "fallback");
 
-            bin.builder.build_int_compare(
-                IntPredicate::NE,
-                value,
-                bin.value_type(ns).const_zero(),
-                "is_value_transfer",
-            )
-        };
+        if let Some((cfg_no, _)) = receive {
+            let receive_block = bin.context.append_basic_block(function, "receive");
 
-        let fallback_block = bin.context.append_basic_block(function, "fallback");
-        let receive_block = bin.context.append_basic_block(function, "receive");
+            let is_empty = bin.builder.build_int_compare(
+                IntPredicate::EQ,
+                calldata_len,
+                calldata_len.get_type().const_zero(),
+                "empty_calldata",
+            );
 
-        bin.builder
-            .build_conditional_branch(got_value, receive_block, fallback_block);
+            bin.builder
+                .build_conditional_branch(is_empty, receive_block, fallback_block);
 
-        bin.builder.position_at_end(fallback_block);
+            bin.builder.position_at_end(receive_block);
 
-        match fallback {
-            Some((cfg_no, _)) => {
-                let args = vec![];
-                bin.builder.build_call(functions[&cfg_no], &args, "");
-                self.return_empty_abi(bin);
-            }
-            None => {
-                self.return_code(bin, bin.context.i32_type().const_int(2, false));
-            }
+            let args = if ns.target == Target::Solana {
+                vec![function.get_last_param().unwrap().into()]
+            } else {
+                vec![]
+            };
+
+            bin.builder.build_call(functions[&cfg_no], &args, "");
+
+            self.return_empty_abi(bin);
+        } else {
+            bin.builder.build_unconditional_branch(fallback_block);
         }
 
-        bin.builder.position_at_end(receive_block);
+        bin.builder.position_at_end(fallback_block);
 
-        match receive {
-            Some((cfg_no, _)) => {
-                let args = if ns.target == Target::Solana {
-                    vec![function.get_last_param().unwrap().into()]
-                } else {
-                    vec![]
-                };
+        match fallback {
+            Some((cfg_no, cfg)) => {
+                if nonpayable(cfg) {
+                    abort_if_value_transfer(self, bin, function, ns);
+                }
 
+                let args = vec![];
                 bin.builder.build_call(functions[&cfg_no], &args, "");
-
                 self.return_empty_abi(bin);
             }
             None => {
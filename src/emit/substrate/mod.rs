@@ -1,9 +1,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::codegen::panic::PanicCode;
 use crate::sema::ast;
 use inkwell::context::Context;
 use inkwell::module::{Linkage, Module};
-use inkwell::types::BasicType;
+use inkwell::types::{BasicType, IntType};
 use inkwell::values::{BasicValueEnum, FunctionValue, IntValue, PointerValue};
 use inkwell::AddressSpace;
 use inkwell::IntPredicate;
@@ -22,6 +23,10 @@ pub(super) mod target;
 // When using the seal api, we use our own scratch buffer.
 const SCRATCH_SIZE: u32 = 32 * 1024;
 
+// The seal_call flag which forbids the callee from modifying any state. This was added in version 3
+// of the contracts pallet api; staticcall is rejected for older versions.
+const CALL_FLAG_READ_ONLY: u64 = 0x10;
+
 #[macro_export]
 macro_rules! emit_context {
     ($binary:expr) => {
@@ -192,6 +197,8 @@ impl SubstrateTarget {
             "seal_debug_message",
             "seal_instantiate",
             "seal_call",
+            "seal_code_hash",
+            "seal_delegate_call",
             "seal_value_transferred",
             "seal_minimum_balance",
             "seal_weight_to_fee",
@@ -339,6 +346,17 @@ impl SubstrateTarget {
             u8_ptr,
             u32_ptr
         );
+        external!("seal_code_hash", i32_type, u8_ptr, u8_ptr, u32_ptr);
+        external!(
+            "seal_delegate_call",
+            i32_type,
+            u32_val,
+            u8_ptr,
+            u8_ptr,
+            u32_val,
+            u8_ptr,
+            u32_ptr
+        );
         external!("seal_transfer", i32_type, u8_ptr, u32_val, u8_ptr, u32_val);
         external!("seal_value_transferred", void_type, u8_ptr, u32_ptr);
         external!("seal_address", void_type, u8_ptr, u32_ptr);
@@ -524,14 +542,70 @@ impl SubstrateTarget {
         }
     }
 
+    /// Continue in a new block if a call or instantiation succeeded. Otherwise fail too: if the
+    /// callee reverted, pass on its revert data. The output buffer is not written when the
+    /// callee trapped, so revert with a panic code instead.
+    fn bail_if_callee_failed<'b>(
+        &self,
+        binary: &Binary<'b>,
+        function: FunctionValue<'b>,
+        is_success: IntValue<'b>,
+        ret: IntValue<'b>,
+    ) {
+        emit_context!(binary);
+
+        let (scratch_buf, scratch_len) = scratch_buf!();
+
+        let success_block = binary.context.append_basic_block(function, "success");
+        let bail_block = binary.context.append_basic_block(function, "bail");
+
+        binary
+            .builder
+            .build_conditional_branch(is_success, success_block, bail_block);
+
+        binary.builder.position_at_end(bail_block);
+
+        let reverted_block = binary
+            .context
+            .append_basic_block(function, "callee_reverted");
+        let trapped_block = binary
+            .context
+            .append_basic_block(function, "callee_trapped");
+
+        let reverted = binary.builder.build_int_compare(
+            IntPredicate::EQ,
+            ret,
+            i32_const!(2),
+            "callee_reverted",
+        );
+
+        binary
+            .builder
+            .build_conditional_branch(reverted, reverted_block, trapped_block);
+
+        binary.builder.position_at_end(reverted_block);
+
+        let length = binary.builder.build_load(scratch_len, "revert_data_len");
+
+        self.assert_failure(binary, scratch_buf, length.into_int_value());
+
+        binary.builder.position_at_end(trapped_block);
+
+        let (data, len) = binary.panic_data(PanicCode::ExternalCallFailed);
+
+        self.assert_failure(binary, data, len);
+
+        binary.builder.position_at_end(success_block);
+    }
+
     /// Check that data has not overrun end, and whether end == data to check we do not have
     /// trailing data
-    fn check_overrun(
+    fn check_overrun<'b>(
         &self,
-        binary: &Binary,
+        binary: &Binary<'b>,
         function: FunctionValue,
-        data: PointerValue,
-        end: PointerValue,
+        data: PointerValue<'b>,
+        end: PointerValue<'b>,
         end_is_data: bool,
     ) {
         let in_bounds = binary.builder.build_int_compare(
@@ -549,24 +623,211 @@ impl SubstrateTarget {
             "is_done",
         );
 
+        self.check_decode(binary, function, in_bounds);
+    }
+
+    /// Continue decoding if ok is true, else handle the malformed data. In a debug build, the
+    /// value which failed to decode is printed before aborting.
+    fn check_decode<'b>(&self, binary: &Binary<'b>, function: FunctionValue, ok: IntValue<'b>) {
         let success_block = binary.context.append_basic_block(function, "success");
+
+        // malformed data can be handled by the caller, e.g. in try/catch
+        if let Some(exception_block) = binary.decode_exception.get() {
+            binary
+                .builder
+                .build_conditional_branch(ok, success_block, exception_block);
+
+            binary.builder.position_at_end(success_block);
+
+            return;
+        }
+
         let bail_block = binary.context.append_basic_block(function, "bail");
         binary
             .builder
-            .build_conditional_branch(in_bounds, success_block, bail_block);
+            .build_conditional_branch(ok, success_block, bail_block);
 
         binary.builder.position_at_end(bail_block);
 
-        self.assert_failure(
-            binary,
-            binary
-                .context
-                .i8_type()
-                .ptr_type(AddressSpace::Generic)
-                .const_null(),
-            binary.context.i32_type().const_zero(),
+        if binary.generate_debug_info {
+            if let Some(label) = binary.decode_label.borrow().as_ref() {
+                let msg = format!("abi decode failed: {}", label);
+
+                self.print(
+                    binary,
+                    binary.emit_global_string("decode_failure", msg.as_bytes(), true),
+                    binary.context.i32_type().const_int(msg.len() as u64, false),
+                );
+            }
+        }
+
+        let (data, len) = binary.panic_data(PanicCode::DecodingFailed);
+
+        self.assert_failure(binary, data, len);
+
+        binary.builder.position_at_end(success_block);
+    }
+
+    /// Read an integer from substrate storage. The signedness of the type is needed for checking
+    /// the width of the stored value.
+    pub(crate) fn load_storage_int<'b>(
+        &self,
+        binary: &Binary<'b>,
+        function: FunctionValue,
+        slot: PointerValue<'b>,
+        ty: IntType<'b>,
+        signed: bool,
+    ) -> IntValue<'b> {
+        emit_context!(binary);
+
+        let (scratch_buf, scratch_len) = scratch_buf!();
+        let ty_len = ty.size_of().const_cast(binary.context.i32_type(), false);
+
+        // With debug info, check that the stored value fits into the type. The stored value
+        // may be wider than expected, e.g. if the type of the variable changed
+        let check_width = binary.generate_debug_info && ty.get_bit_width() < 256;
+
+        if check_width {
+            binary.builder.build_store(scratch_len, i32_const!(32));
+        } else {
+            binary.builder.build_store(scratch_len, ty_len);
+        }
+
+        let exists = seal_get_storage!(
+            cast_byte_ptr!(slot).into(),
+            i32_const!(32).into(),
+            scratch_buf.into(),
+            scratch_len.into()
+        );
+
+        let exists = binary.builder.build_int_compare(
+            IntPredicate::EQ,
+            exists,
+            i32_zero!(),
+            "storage_exists",
+        );
+
+        let entry = binary.builder.get_insert_block().unwrap();
+        let retrieve_block = binary.context.append_basic_block(function, "in_storage");
+        let done_storage = binary.context.append_basic_block(function, "done_storage");
+
+        binary
+            .builder
+            .build_conditional_branch(exists, retrieve_block, done_storage);
+
+        binary.builder.position_at_end(retrieve_block);
+
+        if check_width {
+            self.check_storage_int_width(binary, function, ty, signed);
+        }
+
+        let retrieve_block = binary.builder.get_insert_block().unwrap();
+
+        let dest = binary.builder.build_pointer_cast(
+            binary.scratch.unwrap().as_pointer_value(),
+            ty.ptr_type(AddressSpace::Generic),
+            "scratch_ty_buf",
+        );
+
+        let loaded_int = binary.builder.build_load(dest, "int");
+
+        binary.builder.build_unconditional_branch(done_storage);
+
+        binary.builder.position_at_end(done_storage);
+
+        let res = binary.builder.build_phi(ty, "storage_res");
+
+        res.add_incoming(&[(&loaded_int, retrieve_block), (&ty.const_zero(), entry)]);
+
+        res.as_basic_value().into_int_value()
+    }
+
+    /// The value in the scratch buffer was read from storage for an integer of type ty. Check
+    /// that the stored value fits into ty, i.e. that it is the zero extension of ty, or the
+    /// sign extension if ty is signed. If it does not, the value would be silently truncated,
+    /// so revert with Panic(0x22).
+    pub(crate) fn check_storage_int_width<'b>(
+        &self,
+        binary: &Binary<'b>,
+        function: FunctionValue,
+        ty: IntType<'b>,
+        signed: bool,
+    ) {
+        let i256 = binary.context.custom_width_int_type(256);
+
+        let len = binary
+            .builder
+            .build_load(binary.scratch_len.unwrap().as_pointer_value(), "len")
+            .into_int_value();
+
+        let wide = binary
+            .builder
+            .build_load(
+                binary.builder.build_pointer_cast(
+                    binary.scratch.unwrap().as_pointer_value(),
+                    i256.ptr_type(AddressSpace::Generic),
+                    "scratch_wide",
+                ),
+                "wide",
+            )
+            .into_int_value();
+
+        // only the first len bytes of the scratch buffer were written
+        let bits = binary.builder.build_int_mul(
+            binary.builder.build_int_z_extend(len, i256, "len"),
+            i256.const_int(8, false),
+            "bits",
         );
 
+        let mask = binary.builder.build_select(
+            binary.builder.build_int_compare(
+                IntPredicate::UGE,
+                len,
+                binary.context.i32_type().const_int(32, false),
+                "full",
+            ),
+            i256.const_all_ones(),
+            binary.builder.build_int_sub(
+                binary
+                    .builder
+                    .build_left_shift(i256.const_int(1, false), bits, "shift"),
+                i256.const_int(1, false),
+                "mask",
+            ),
+            "mask",
+        );
+
+        let mask = mask.into_int_value();
+
+        let truncated = binary.builder.build_int_truncate(wide, ty, "truncated");
+
+        let wide = binary.builder.build_and(wide, mask, "wide");
+
+        let extended = if signed {
+            binary.builder.build_int_s_extend(truncated, i256, "sext")
+        } else {
+            binary.builder.build_int_z_extend(truncated, i256, "zext")
+        };
+
+        let extended = binary.builder.build_and(extended, mask, "extended");
+
+        let fits = binary
+            .builder
+            .build_int_compare(IntPredicate::EQ, wide, extended, "fits");
+
+        let success_block = binary.context.append_basic_block(function, "fits");
+        let bail_block = binary.context.append_basic_block(function, "truncated");
+
+        binary
+            .builder
+            .build_conditional_branch(fits, success_block, bail_block);
+
+        binary.builder.position_at_end(bail_block);
+
+        let (data, len) = binary.panic_data(PanicCode::StorageBytesEncodingIncorrect);
+
+        self.assert_failure(binary, data, len);
+
         binary.builder.position_at_end(success_block);
     }
 
@@ -587,11 +848,13 @@ impl SubstrateTarget {
             | ast::Type::Int(_)
             | ast::Type::Uint(_)
             | ast::Type::Bytes(_) => {
-                let (arg, arglen) = self.decode_primitive(binary, ty, *data, ns);
+                let start = *data;
+
+                let (arg, arglen) = self.decode_primitive(binary, ty, start, ns);
 
                 *data = unsafe {
                     binary.builder.build_gep(
-                        *data,
+                        start,
                         &[binary.context.i32_type().const_int(arglen, false)],
                         "abi_ptr",
                     )
@@ -599,9 +862,41 @@ impl SubstrateTarget {
 
                 self.check_overrun(binary, function, *data, end, false);
 
+                if *ty == ast::Type::Bool {
+                    // only 0 and 1 are valid encodings of bool
+                    let byte = binary
+                        .builder
+                        .build_load(start, "abi_bool")
+                        .into_int_value();
+
+                    let valid = binary.builder.build_int_compare(
+                        IntPredicate::ULE,
+                        byte,
+                        binary.context.i8_type().const_int(1, false),
+                        "valid_bool",
+                    );
+
+                    self.check_decode(binary, function, valid);
+                }
+
                 arg
             }
-            ast::Type::Enum(n) => self.decode_ty(binary, function, &ns.enums[*n].ty, data, end, ns),
+            ast::Type::Enum(n) => {
+                let val = self.decode_ty(binary, function, &ns.enums[*n].ty, data, end, ns);
+
+                let valid = binary.builder.build_int_compare(
+                    IntPredicate::ULT,
+                    val.into_int_value(),
+                    val.get_type()
+                        .into_int_type()
+                        .const_int(ns.enums[*n].values.len() as u64, false),
+                    "valid_enum",
+                );
+
+                self.check_decode(binary, function, valid);
+
+                val
+            }
             ast::Type::UserType(n) => {
                 self.decode_ty(binary, function, &ns.user_types[*n].ty, data, end, ns)
             }
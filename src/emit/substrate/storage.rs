@@ -338,11 +338,12 @@ impl StorageSlot for SubstrateTarget {
             _ => {
                 bin.builder.build_store(slot_ptr, *slot);
 
-                let ret = self.get_storage_int(
+                let ret = self.load_storage_int(
                     bin,
                     function,
                     slot_ptr,
                     bin.llvm_type(ty.deref_any(), ns).into_int_type(),
+                    ty.deref_any().clone().unwrap_user_type(ns).is_signed_int(),
                 );
 
                 *slot = bin.builder.build_int_add(
@@ -1,10 +1,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::codegen::cfg::{HashTy, ReturnCode};
+use crate::codegen::panic::PanicCode;
 use crate::emit::binary::Binary;
 use crate::emit::expression::expression;
 use crate::emit::storage::StorageSlot;
-use crate::emit::substrate::{event_id, SubstrateTarget, SCRATCH_SIZE};
+use crate::emit::substrate::{event_id, SubstrateTarget, CALL_FLAG_READ_ONLY, SCRATCH_SIZE};
 use crate::emit::{TargetRuntime, Variable};
 use crate::sema::ast;
 use crate::sema::ast::{Function, Namespace, Type};
@@ -72,6 +73,19 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             .unwrap()
             .into_pointer_value();
 
+        let ef = binary
+            .builder
+            .build_pointer_cast(ef, ty.into_pointer_type(), "function_type");
+
+        // if the slot was never written, the function is all zeros like any other default value
+        binary.builder.build_store(
+            ef,
+            ty.into_pointer_type()
+                .get_element_type()
+                .into_struct_type()
+                .const_zero(),
+        );
+
         let scratch_len = binary.scratch_len.unwrap().as_pointer_value();
         binary.builder.build_store(scratch_len, len);
 
@@ -80,7 +94,7 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             &[
                 cast_byte_ptr!(slot).into(),
                 i32_const!(32).into(),
-                ef.into(),
+                cast_byte_ptr!(ef).into(),
                 scratch_len.into()
             ]
         )
@@ -88,11 +102,7 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
         .left()
         .unwrap();
 
-        // TODO: decide behaviour if not exist
-
-        binary
-            .builder
-            .build_pointer_cast(ef, ty.into_pointer_type(), "function_type")
+        ef
     }
 
     fn set_storage_string(
@@ -152,53 +162,7 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
         slot: PointerValue<'a>,
         ty: IntType<'a>,
     ) -> IntValue<'a> {
-        emit_context!(binary);
-
-        let (scratch_buf, scratch_len) = scratch_buf!();
-        let ty_len = ty.size_of().const_cast(binary.context.i32_type(), false);
-        binary.builder.build_store(scratch_len, ty_len);
-
-        let exists = seal_get_storage!(
-            cast_byte_ptr!(slot).into(),
-            i32_const!(32).into(),
-            scratch_buf.into(),
-            scratch_len.into()
-        );
-
-        let exists = binary.builder.build_int_compare(
-            IntPredicate::EQ,
-            exists,
-            i32_zero!(),
-            "storage_exists",
-        );
-
-        let entry = binary.builder.get_insert_block().unwrap();
-        let retrieve_block = binary.context.append_basic_block(function, "in_storage");
-        let done_storage = binary.context.append_basic_block(function, "done_storage");
-
-        binary
-            .builder
-            .build_conditional_branch(exists, retrieve_block, done_storage);
-
-        binary.builder.position_at_end(retrieve_block);
-
-        let dest = binary.builder.build_pointer_cast(
-            binary.scratch.unwrap().as_pointer_value(),
-            ty.ptr_type(AddressSpace::Generic),
-            "scratch_ty_buf",
-        );
-
-        let loaded_int = binary.builder.build_load(dest, "int");
-
-        binary.builder.build_unconditional_branch(done_storage);
-
-        binary.builder.position_at_end(done_storage);
-
-        let res = binary.builder.build_phi(ty, "storage_res");
-
-        res.add_incoming(&[(&loaded_int, retrieve_block), (&ty.const_zero(), entry)]);
-
-        res.as_basic_value().into_int_value()
+        self.load_storage_int(binary, function, slot, ty, false)
     }
 
     /// Read string from substrate storage
@@ -340,7 +304,10 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             .build_conditional_branch(in_range, retrieve_block, bang_block);
 
         binary.builder.position_at_end(bang_block);
-        self.assert_failure(binary, byte_ptr!().const_null(), i32_zero!());
+
+        let (data, len) = binary.panic_data(PanicCode::ArrayIndexOutOfBounds);
+
+        self.assert_failure(binary, data, len);
 
         binary.builder.position_at_end(retrieve_block);
 
@@ -412,7 +379,10 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             .build_conditional_branch(in_range, retrieve_block, bang_block);
 
         binary.builder.position_at_end(bang_block);
-        self.assert_failure(binary, byte_ptr!().const_null(), i32_zero!());
+
+        let (data, len) = binary.panic_data(PanicCode::ArrayIndexOutOfBounds);
+
+        self.assert_failure(binary, data, len);
 
         binary.builder.position_at_end(retrieve_block);
 
@@ -571,7 +541,10 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             .build_conditional_branch(in_range, retrieve_block, bang_block);
 
         binary.builder.position_at_end(bang_block);
-        self.assert_failure(binary, byte_ptr!().const_null(), i32_zero!());
+
+        let (data, len) = binary.panic_data(PanicCode::EmptyArrayPop);
+
+        self.assert_failure(binary, data, len);
 
         binary.builder.position_at_end(retrieve_block);
 
@@ -594,6 +567,22 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             None
         };
 
+        // like an assignment of empty bytes, popping the last element removes the entry
+        let empty =
+            binary
+                .builder
+                .build_int_compare(IntPredicate::EQ, new_length, i32_zero!(), "empty");
+
+        let clear_block = binary.context.append_basic_block(function, "clear_block");
+        let set_block = binary.context.append_basic_block(function, "set_block");
+        let done_storage = binary.context.append_basic_block(function, "done_storage");
+
+        binary
+            .builder
+            .build_conditional_branch(empty, clear_block, set_block);
+
+        binary.builder.position_at_end(set_block);
+
         seal_set_storage!(
             cast_byte_ptr!(slot_ptr).into(),
             i32_const!(32).into(),
@@ -601,6 +590,19 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             new_length.into()
         );
 
+        binary.builder.build_unconditional_branch(done_storage);
+
+        binary.builder.position_at_end(clear_block);
+
+        call!(
+            "seal_clear_storage",
+            &[cast_byte_ptr!(slot_ptr).into(), i32_const!(32).into()]
+        );
+
+        binary.builder.build_unconditional_branch(done_storage);
+
+        binary.builder.position_at_end(done_storage);
+
         val
     }
 
@@ -721,7 +723,21 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             .build_return(Some(&binary.return_values[&ReturnCode::Success]));
     }
 
-    fn assert_failure<'b>(&self, binary: &'b Binary, _data: PointerValue, _length: IntValue) {
+    fn assert_failure<'b>(&self, binary: &'b Binary, data: PointerValue, length: IntValue) {
+        emit_context!(binary);
+
+        // with a reason, revert so that the caller receives the encoded Error(string)
+        if !data.is_null() {
+            call!(
+                "seal_return",
+                &[i32_const!(1).into(), data.into(), length.into()]
+            );
+
+            binary.builder.build_unreachable();
+
+            return;
+        }
+
         // insert "unreachable" instruction; not that build_unreachable() tells the compiler
         // that this code path is not reachable and may be discarded.
         let asm_fn = binary.context.void_type().fn_type(&[], false);
@@ -761,11 +777,23 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
 
         let argsend = unsafe { binary.builder.build_gep(argsdata, &[datalength], "argsend") };
 
-        for param in spec {
+        for (i, param) in spec.iter().enumerate() {
+            binary.decode_label.replace(Some(format!(
+                "parameter #{} of type {}",
+                i,
+                param.ty.to_string(ns)
+            )));
+
             args.push(self.decode_ty(binary, function, &param.ty, &mut argsdata, argsend, ns));
         }
 
+        binary
+            .decode_label
+            .replace(Some(String::from("trailing data")));
+
         self.check_overrun(binary, function, argsdata, argsend, true);
+
+        binary.decode_label.replace(None);
     }
 
     /// ABI encode into a vector for abi.encode* style builtin functions
@@ -847,13 +875,13 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             binary.builder.build_store(data_len, length);
         }
 
-        let data_size = unsafe {
+        let data_capacity = unsafe {
             binary
                 .builder
-                .build_gep(v, &[i32_zero!(), i32_const!(1)], "data_size")
+                .build_gep(v, &[i32_zero!(), i32_const!(1)], "data_capacity")
         };
 
-        binary.builder.build_store(data_size, length);
+        binary.builder.build_store(data_capacity, length);
 
         let data = unsafe {
             binary
@@ -1135,25 +1163,7 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             // return success or not in success variable; do not abort execution
             *success = is_success.into();
         } else {
-            let success_block = binary.context.append_basic_block(function, "success");
-            let bail_block = binary.context.append_basic_block(function, "bail");
-
-            binary
-                .builder
-                .build_conditional_branch(is_success, success_block, bail_block);
-
-            binary.builder.position_at_end(bail_block);
-
-            self.assert_failure(
-                binary,
-                scratch_buf,
-                binary
-                    .builder
-                    .build_load(scratch_len, "string_len")
-                    .into_int_value(),
-            );
-
-            binary.builder.position_at_end(success_block);
+            self.bail_if_callee_failed(binary, function, is_success, ret);
         }
     }
 
@@ -1170,7 +1180,7 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
         value: IntValue<'b>,
         _accounts: Option<(PointerValue<'b>, IntValue<'b>)>,
         _seeds: Option<(PointerValue<'b>, IntValue<'b>)>,
-        _ty: ast::CallTy,
+        ty: ast::CallTy,
         ns: &ast::Namespace,
     ) {
         emit_context!(binary);
@@ -1188,23 +1198,119 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             .build_store(scratch_len, i32_const!(SCRATCH_SIZE as u64));
 
         // do the actual call
-        let ret = call!(
-            "seal_call",
-            &[
-                i32_zero!().into(), // TODO implement flags (mostly used for proxy calls)
-                address.unwrap().into(),
-                gas.into(),
-                cast_byte_ptr!(value_ptr, "value_transfer").into(),
-                payload.into(),
-                payload_len.into(),
-                scratch_buf.into(),
-                scratch_len.into(),
-            ]
-        )
-        .try_as_basic_value()
-        .left()
-        .unwrap()
-        .into_int_value();
+        let ret = if ty == ast::CallTy::Delegate {
+            // delegate call runs the code of the callee with our storage, address and value.
+            // The contracts pallet takes the code hash of the callee rather than its address
+            let code_hash = binary.build_array_alloca(
+                function,
+                binary.context.i8_type(),
+                i32_const!(32),
+                "code_hash",
+            );
+            let code_hash_len =
+                binary.build_alloca(function, binary.context.i32_type(), "code_hash_len");
+
+            binary.builder.build_store(code_hash_len, i32_const!(32));
+
+            let code_hash_ret = call!(
+                "seal_code_hash",
+                &[
+                    address.unwrap().into(),
+                    code_hash.into(),
+                    code_hash_len.into()
+                ]
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+            let found_block = binary
+                .context
+                .append_basic_block(function, "code_hash_found");
+            let not_found_block = binary
+                .context
+                .append_basic_block(function, "code_hash_not_found");
+            let done_block = binary
+                .context
+                .append_basic_block(function, "delegate_call_done");
+
+            // if the address is not a contract, the call fails with the return code of
+            // seal_code_hash
+            let found = binary.builder.build_int_compare(
+                IntPredicate::EQ,
+                code_hash_ret,
+                i32_zero!(),
+                "code_hash_found",
+            );
+
+            binary
+                .builder
+                .build_conditional_branch(found, found_block, not_found_block);
+
+            // nothing was called, so there is no return data
+            binary.builder.position_at_end(not_found_block);
+
+            binary.builder.build_store(scratch_len, i32_zero!());
+
+            binary.builder.build_unconditional_branch(done_block);
+
+            binary.builder.position_at_end(found_block);
+
+            let delegate_call_ret = call!(
+                "seal_delegate_call",
+                &[
+                    i32_zero!().into(),
+                    code_hash.into(),
+                    payload.into(),
+                    payload_len.into(),
+                    scratch_buf.into(),
+                    scratch_len.into(),
+                ]
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+            binary.builder.build_unconditional_branch(done_block);
+
+            binary.builder.position_at_end(done_block);
+
+            let ret = binary.builder.build_phi(binary.context.i32_type(), "ret");
+
+            ret.add_incoming(&[
+                (&code_hash_ret, not_found_block),
+                (&delegate_call_ret, found_block),
+            ]);
+
+            ret.as_basic_value().into_int_value()
+        } else {
+            // the callee of a static call may not modify any state
+            let call_flags = if ty == ast::CallTy::Static {
+                i32_const!(CALL_FLAG_READ_ONLY)
+            } else {
+                i32_zero!()
+            };
+
+            call!(
+                "seal_call",
+                &[
+                    call_flags.into(),
+                    address.unwrap().into(),
+                    gas.into(),
+                    cast_byte_ptr!(value_ptr, "value_transfer").into(),
+                    payload.into(),
+                    payload_len.into(),
+                    scratch_buf.into(),
+                    scratch_len.into(),
+                ]
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value()
+        };
 
         let is_success =
             binary
@@ -1216,25 +1322,7 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
             // do not abort execution; return success or not in success variable
             *success = is_success.into();
         } else {
-            let success_block = binary.context.append_basic_block(function, "success");
-            let bail_block = binary.context.append_basic_block(function, "bail");
-
-            binary
-                .builder
-                .build_conditional_branch(is_success, success_block, bail_block);
-
-            binary.builder.position_at_end(bail_block);
-
-            self.assert_failure(
-                binary,
-                scratch_buf,
-                binary
-                    .builder
-                    .build_load(scratch_len, "string_len")
-                    .into_int_value(),
-            );
-
-            binary.builder.position_at_end(success_block);
+            self.bail_if_callee_failed(binary, function, is_success, ret);
         }
     }
 
@@ -2,9 +2,9 @@
 
 use crate::sema::ast;
 use solang_parser::pt::Loc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::{OsStr, OsString};
-use std::fs::File;
+use std::fs::{self, File};
 use std::io;
 use std::io::{prelude::*, Error, ErrorKind};
 use std::path::{Path, PathBuf};
@@ -17,6 +17,8 @@ pub struct FileResolver {
     cached_paths: HashMap<PathBuf, usize>,
     /// The actual file contents
     files: Vec<Arc<str>>,
+    /// Files which were read from the filesystem, rather than set with set_file_contents()
+    from_disk: HashSet<PathBuf>,
 }
 
 /// When we resolve a file, we need to know its base compared to the import so
@@ -46,6 +48,7 @@ impl FileResolver {
             import_paths: Vec::new(),
             cached_paths: HashMap::new(),
             files: Vec::new(),
+            from_disk: HashSet::new(),
         }
     }
 
@@ -79,6 +82,7 @@ impl FileResolver {
         self.files.push(Arc::from(contents));
 
         self.cached_paths.insert(PathBuf::from(path), pos);
+        self.from_disk.remove(Path::new(path));
     }
 
     /// Get file with contents. This must be a file which was previously
@@ -89,6 +93,33 @@ impl FileResolver {
         (self.files[file_no].clone(), file_no)
     }
 
+    /// Get the contents of a file, if it is in the cache
+    pub fn get_file_contents(&self, file: &Path) -> Option<Arc<str>> {
+        self.cached_paths
+            .get(file)
+            .map(|file_no| self.files[*file_no].clone())
+    }
+
+    /// Read the files which were loaded from the filesystem again, so that changes are picked up
+    /// the next time they are resolved. Returns the files which have changed.
+    pub fn reload_changed_files(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+
+        for path in &self.from_disk {
+            let file_no = self.cached_paths[path];
+
+            // a file which has been removed keeps its last contents
+            if let Ok(contents) = fs::read_to_string(path) {
+                if *self.files[file_no] != *contents {
+                    self.files[file_no] = Arc::from(contents);
+                    changed.push(path.clone());
+                }
+            }
+        }
+
+        changed
+    }
+
     /// Populate the cache with absolute file path
     fn load_file(&mut self, path: &Path) -> Result<(), String> {
         if self.cached_paths.get(path).is_some() {
@@ -117,6 +148,7 @@ impl FileResolver {
         self.files.push(Arc::from(contents));
 
         self.cached_paths.insert(path.to_path_buf(), pos);
+        self.from_disk.insert(path.to_path_buf());
 
         Ok(())
     }
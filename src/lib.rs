@@ -10,6 +10,7 @@ pub mod file_resolver;
 #[cfg(feature = "llvm")]
 mod linker;
 pub mod standard_json;
+pub mod watch;
 
 // In Sema, we use result unit for returning early
 // when code-misparses. The error will be added to the namespace diagnostics, no need to have anything but unit
@@ -31,6 +32,10 @@ pub enum Target {
     Substrate {
         address_length: usize,
         value_length: usize,
+        /// Version of the contracts pallet api, which determines the available host functions
+        api_version: u32,
+        /// Largest code blob in bytes the chain accepts
+        max_code_size: usize,
     },
     /// Ethereum EVM, see <https://ethereum.org/en/developers/docs/evm/>
     EVM,
@@ -78,6 +83,8 @@ impl Target {
         Target::Substrate {
             address_length: 32,
             value_length: 16,
+            api_version: 3,
+            max_code_size: 128 * 1024,
         }
     }
 
@@ -128,6 +135,7 @@ pub fn compile(
     opt_level: inkwell::OptimizationLevel,
     target: Target,
     math_overflow_check: bool,
+    generate_debug_info: bool,
 ) -> (Vec<(Vec<u8>, String)>, sema::ast::Namespace) {
     let mut ns = parse_and_resolve(filename, resolver, target);
 
@@ -140,6 +148,7 @@ pub fn compile(
         &mut ns,
         &codegen::Options {
             math_overflow_check,
+            generate_debug_information: generate_debug_info,
             opt_level: opt_level.into(),
             ..Default::default()
         },
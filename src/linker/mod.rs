@@ -23,6 +23,17 @@ pub fn link(input: &[u8], name: &str, target: Target) -> Vec<u8> {
     }
 }
 
+/// Check that a linked Substrate contract only imports host functions which are available in
+/// the given version of the contracts pallet api. Returns a message for each one which is not.
+pub fn unavailable_substrate_imports(code: &[u8], api_version: u32) -> Vec<String> {
+    wasm::unavailable_imports(code, api_version)
+}
+
+/// The size in bytes of each function in a linked wasm contract, largest first
+pub fn wasm_function_sizes(code: &[u8]) -> Vec<(String, usize)> {
+    wasm::function_sizes(code)
+}
+
 extern "C" {
     fn LLDELFLink(args: *const *const libc::c_char, size: libc::size_t) -> libc::c_int;
 }
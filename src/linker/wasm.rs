@@ -1,13 +1,130 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use parity_wasm::builder;
-use parity_wasm::elements::{InitExpr, Instruction, Module};
+use parity_wasm::elements::{ImportCountType, InitExpr, Instruction, Module};
 use std::ffi::CString;
 use std::fs::File;
 use std::io::Read;
 use std::io::Write;
 use tempfile::tempdir;
 
+const CODE_SECTION_ID: u8 = 10;
+
+/// The host functions a Substrate contract may import, with the module they are imported from
+/// and the first version of the contracts pallet api which provides them
+const SUBSTRATE_IMPORTS: &[(&str, &str, u32)] = &[
+    ("seal_input", "seal0", 1),
+    ("seal_hash_keccak_256", "seal0", 1),
+    ("seal_hash_sha2_256", "seal0", 1),
+    ("seal_hash_blake2_128", "seal0", 1),
+    ("seal_hash_blake2_256", "seal0", 1),
+    ("seal_set_storage", "__unstable__", 1),
+    ("seal_clear_storage", "__unstable__", 1),
+    ("seal_contains_storage", "__unstable__", 1),
+    ("seal_get_storage", "__unstable__", 1),
+    ("seal_debug_message", "seal0", 1),
+    ("seal_return", "seal0", 1),
+    ("seal_instantiate", "seal1", 1),
+    ("seal_call", "seal1", 1),
+    ("seal_transfer", "seal0", 1),
+    ("seal_value_transferred", "seal0", 1),
+    ("seal_address", "seal0", 1),
+    ("seal_balance", "seal0", 1),
+    ("seal_minimum_balance", "seal0", 1),
+    ("seal_block_number", "seal0", 1),
+    ("seal_now", "seal0", 1),
+    ("seal_weight_to_fee", "seal0", 1),
+    ("seal_gas_left", "seal0", 1),
+    ("seal_caller", "seal0", 1),
+    ("seal_terminate", "seal1", 1),
+    ("seal_deposit_event", "seal0", 1),
+    ("seal_random", "seal1", 2),
+    ("seal_code_hash", "seal0", 2),
+    ("seal_delegate_call", "seal0", 2),
+];
+
+/// Check the imports of a linked Substrate contract against the host functions available in the
+/// given api version. Returns a message for each import which is not available.
+pub fn unavailable_imports(code: &[u8], api_version: u32) -> Vec<String> {
+    let module: Module =
+        parity_wasm::deserialize_buffer(code).expect("linked wasm should be valid");
+
+    let imports = match module.import_section() {
+        Some(section) => section.entries(),
+        None => return Vec::new(),
+    };
+
+    imports
+        .iter()
+        .filter(|import| import.module() != "env")
+        .filter_map(|import| {
+            match SUBSTRATE_IMPORTS
+                .iter()
+                .find(|(name, module, _)| *name == import.field() && *module == import.module())
+            {
+                Some((_, _, version)) if *version <= api_version => None,
+                Some((name, module, version)) => Some(format!(
+                    "host function '{}.{}' requires substrate api version {}, but version {} was selected",
+                    module, name, version, api_version
+                )),
+                None => Some(format!(
+                    "host function '{}.{}' is not provided by substrate api version {}",
+                    import.module(),
+                    import.field(),
+                    api_version
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Return the size of the body of each function in a linked wasm module, largest first. The
+/// names come from the name section; functions without a name are listed by their index.
+pub fn function_sizes(code: &[u8]) -> Vec<(String, usize)> {
+    let module: Module =
+        parity_wasm::deserialize_buffer(code).expect("linked wasm should be valid");
+
+    let module = module.parse_names().unwrap_or_else(|(_, module)| module);
+
+    let imported = module.import_count(ImportCountType::Function);
+
+    let names = module
+        .names_section()
+        .and_then(|section| section.functions())
+        .map(|functions| functions.names());
+
+    let mut sizes = match sections(code).find(|(id, _)| *id == CODE_SECTION_ID) {
+        Some((_, section)) => {
+            // skip the section id and size
+            let mut offset = 1;
+            read_leb128(section, &mut offset);
+
+            let count = read_leb128(section, &mut offset);
+
+            (0..count)
+                .map(|no| {
+                    let size = read_leb128(section, &mut offset);
+                    offset += size;
+
+                    let index = (imported + no) as u32;
+
+                    let name = names
+                        .and_then(|names| names.get(index))
+                        .cloned()
+                        .unwrap_or_else(|| format!("function {}", index));
+
+                    (name, size)
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    sizes.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    sizes
+}
+
 pub fn link(input: &[u8], name: &str) -> Vec<u8> {
     let dir = tempdir().expect("failed to create temp directory for linking");
 
@@ -63,20 +180,22 @@ pub fn link(input: &[u8], name: &str) -> Vec<u8> {
     let mut module: Module =
         parity_wasm::deserialize_buffer(&output).expect("cannot deserialize llvm wasm");
 
+    let has_debug_info = module
+        .custom_sections()
+        .any(|section| section.name().starts_with(".debug_"));
+
     {
         let imports = module.import_section_mut().unwrap().entries_mut();
         let mut ind = 0;
 
         while ind < imports.len() {
             if imports[ind].field().starts_with("seal") {
-                let module_name = match imports[ind].field() {
-                    "seal_instantiate" | "seal_terminate" | "seal_random" | "seal_call" => "seal1",
-                    "seal_set_storage"
-                    | "seal_clear_storage"
-                    | "seal_contains_storage"
-                    | "seal_get_storage" => "__unstable__",
-                    _ => "seal0",
-                };
+                let module_name = SUBSTRATE_IMPORTS
+                    .iter()
+                    .find(|(name, ..)| *name == imports[ind].field())
+                    .map(|(_, module, _)| *module)
+                    .unwrap_or("seal0");
+
                 *imports[ind].module_mut() = module_name.to_owned();
             }
 
@@ -106,5 +225,72 @@ pub fn link(input: &[u8], name: &str) -> Vec<u8> {
 
     let linked = builder::module().with_module(module);
 
-    parity_wasm::serialize(linked.build()).expect("cannot serialize linked wasm")
+    let code = parity_wasm::serialize(linked.build()).expect("cannot serialize linked wasm");
+
+    if has_debug_info {
+        // parity-wasm encodes all immediates with the shortest LEB128 encoding, which moves
+        // instructions around and invalidates the code offsets in the DWARF line table. The code
+        // section was not modified, so put back the one wasm-ld produced.
+        replace_section(&code, &output, CODE_SECTION_ID)
+    } else {
+        code
+    }
+}
+
+/// Replace the section with the given id in `module` with the same section from `original`
+fn replace_section(module: &[u8], original: &[u8], id: u8) -> Vec<u8> {
+    let (_, replacement) = sections(original)
+        .find(|(section_id, _)| *section_id == id)
+        .expect("section should exist");
+
+    let mut out = module[..8].to_vec();
+
+    for (section_id, section) in sections(module) {
+        if section_id == id {
+            out.extend_from_slice(replacement);
+        } else {
+            out.extend_from_slice(section);
+        }
+    }
+
+    out
+}
+
+/// Iterate over the raw sections of a wasm module, after the magic and version. Each section
+/// is returned with its id and the raw bytes including the section header.
+fn sections(wasm: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+    let mut offset = 8;
+
+    std::iter::from_fn(move || {
+        if offset >= wasm.len() {
+            return None;
+        }
+
+        let start = offset;
+        let id = wasm[offset];
+        offset += 1;
+
+        let size = read_leb128(wasm, &mut offset);
+
+        offset += size;
+
+        Some((id, &wasm[start..offset]))
+    })
+}
+
+/// Read an unsigned LEB128 value and advance the offset past it
+fn read_leb128(wasm: &[u8], offset: &mut usize) -> usize {
+    let mut value = 0;
+    let mut shift = 0;
+
+    loop {
+        let byte = wasm[*offset];
+        *offset += 1;
+        value |= ((byte & 0x7f) as usize) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return value;
+        }
+    }
 }
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::symtable::Symtable;
+use super::tags;
 use crate::codegen::cfg::{ControlFlowGraph, Instr};
 use crate::diagnostics::Diagnostics;
 use crate::sema::yul::ast::{InlineAssembly, YulFunction};
@@ -157,6 +158,37 @@ impl EventDecl {
     }
 }
 
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ErrorDecl {
+    pub tags: Vec<Tag>,
+    pub name: String,
+    pub loc: pt::Loc,
+    pub contract: Option<usize>,
+    pub fields: Vec<Parameter>,
+    pub signature: String,
+}
+
+impl ErrorDecl {
+    pub fn symbol_name(&self, ns: &Namespace) -> String {
+        match &self.contract {
+            Some(c) => format!("{}.{}", ns.contracts[*c].name, self.name),
+            None => self.name.to_string(),
+        }
+    }
+
+    /// The selector is the first four bytes of the keccak256 hash of the error signature,
+    /// just like a function selector
+    pub fn selector(&self) -> Vec<u8> {
+        let mut res = [0u8; 32];
+
+        let mut hasher = Keccak::v256();
+        hasher.update(self.signature.as_bytes());
+        hasher.finalize(&mut res);
+
+        res[..4].to_vec()
+    }
+}
+
 impl fmt::Display for StructDecl {
     /// Make the struct name into a string for printing. The struct can be declared either
     /// inside or outside a contract.
@@ -459,6 +491,16 @@ pub struct Variable {
     pub read: bool,
 }
 
+impl Variable {
+    /// The storage slot this state variable is pinned to with a `@storage` tag, if any
+    pub fn pinned_slot(&self) -> Option<BigInt> {
+        self.tags
+            .iter()
+            .find(|tag| tag.tag == "storage")
+            .and_then(|tag| tags::parse_storage_slot(&tag.value))
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub enum Symbol {
     Enum(pt::Loc, usize),
@@ -466,6 +508,7 @@ pub enum Symbol {
     Variable(pt::Loc, Option<usize>, usize),
     Struct(pt::Loc, StructType),
     Event(Vec<(pt::Loc, usize)>),
+    Error(pt::Loc, usize),
     Contract(pt::Loc, usize),
     Import(pt::Loc, usize),
     UserType(pt::Loc, usize),
@@ -477,6 +520,7 @@ impl CodeLocation for Symbol {
             Symbol::Enum(loc, _)
             | Symbol::Variable(loc, ..)
             | Symbol::Struct(loc, _)
+            | Symbol::Error(loc, _)
             | Symbol::Contract(loc, _)
             | Symbol::Import(loc, _)
             | Symbol::UserType(loc, _) => *loc,
@@ -525,6 +569,8 @@ pub struct File {
     pub line_starts: Vec<usize>,
     /// Indicates the file number in FileResolver.files
     pub cache_no: Option<usize>,
+    /// The solidity pragma only allows versions before 0.8.0, so arithmetic wraps on overflow
+    pub wrapping_math: bool,
 }
 
 /// When resolving a Solidity file, this holds all the resolved items
@@ -534,6 +580,7 @@ pub struct Namespace {
     pub enums: Vec<EnumDecl>,
     pub structs: Vec<StructDecl>,
     pub events: Vec<EventDecl>,
+    pub errors: Vec<ErrorDecl>,
     pub contracts: Vec<Contract>,
     /// Global using declarations
     pub using: Vec<Using>,
@@ -645,6 +692,14 @@ impl Contract {
             .any(|func_no| ns.functions[*func_no].is_constructor())
     }
 
+    /// The blake2_256 hash of the compiled code, which is how Substrate identifies uploaded code
+    pub fn code_hash(&self) -> [u8; 32] {
+        blake2_rfc::blake2b::blake2b(32, &[], &self.code)
+            .as_bytes()
+            .try_into()
+            .unwrap()
+    }
+
     /// Return the constructor with no arguments
     pub fn no_args_constructor(&self, ns: &Namespace) -> Option<usize> {
         self.functions
@@ -671,7 +726,7 @@ pub enum Expression {
     Add(pt::Loc, Type, bool, Box<Expression>, Box<Expression>),
     Subtract(pt::Loc, Type, bool, Box<Expression>, Box<Expression>),
     Multiply(pt::Loc, Type, bool, Box<Expression>, Box<Expression>),
-    Divide(pt::Loc, Type, Box<Expression>, Box<Expression>),
+    Divide(pt::Loc, Type, bool, Box<Expression>, Box<Expression>),
     Modulo(pt::Loc, Type, Box<Expression>, Box<Expression>),
     Power(pt::Loc, Type, bool, Box<Expression>, Box<Expression>),
     BitwiseOr(pt::Loc, Type, Box<Expression>, Box<Expression>),
@@ -707,7 +762,7 @@ pub enum Expression {
 
     Not(pt::Loc, Box<Expression>),
     Complement(pt::Loc, Type, Box<Expression>),
-    UnaryMinus(pt::Loc, Type, Box<Expression>),
+    UnaryMinus(pt::Loc, Type, bool, Box<Expression>),
 
     Ternary(
         pt::Loc,
@@ -782,6 +837,7 @@ pub enum Expression {
     FormatString(pt::Loc, Vec<(FormatArg, Expression)>),
     Builtin(pt::Loc, Vec<Type>, Builtin, Vec<Expression>),
     InterfaceId(pt::Loc, usize),
+    CodeHash(pt::Loc, usize),
     List(pt::Loc, Vec<Expression>),
 }
 
@@ -793,6 +849,8 @@ pub struct CallArgs {
     pub space: Option<Box<Expression>>,
     pub accounts: Option<Box<Expression>>,
     pub seeds: Option<Box<Expression>>,
+    /// If the callee returns no data, use zero values rather than reverting
+    pub relaxed_decode: bool,
 }
 
 impl Recurse for CallArgs {
@@ -828,7 +886,7 @@ impl Recurse for Expression {
                 Expression::Add(_, _, _, left, right)
                 | Expression::Subtract(_, _, _, left, right)
                 | Expression::Multiply(_, _, _, left, right)
-                | Expression::Divide(_, _, left, right)
+                | Expression::Divide(_, _, _, left, right)
                 | Expression::Modulo(_, _, left, right)
                 | Expression::Power(_, _, _, left, right)
                 | Expression::BitwiseOr(_, _, left, right)
@@ -863,7 +921,7 @@ impl Recurse for Expression {
                 }
                 Expression::Not(_, expr)
                 | Expression::Complement(_, _, expr)
-                | Expression::UnaryMinus(_, _, expr) => expr.recurse(cx, f),
+                | Expression::UnaryMinus(_, _, _, expr) => expr.recurse(cx, f),
 
                 Expression::Ternary(_, _, cond, left, right) => {
                     cond.recurse(cx, f);
@@ -936,6 +994,11 @@ impl Recurse for Expression {
                         e.recurse(cx, f);
                     }
                 }
+                Expression::FormatString(_, args) => {
+                    for (_, e) in args {
+                        e.recurse(cx, f);
+                    }
+                }
                 _ => (),
             }
         }
@@ -1008,6 +1071,7 @@ impl CodeLocation for Expression {
             | Expression::List(loc, _)
             | Expression::FormatString(loc, _)
             | Expression::InterfaceId(loc, ..)
+            | Expression::CodeHash(loc, ..)
             | Expression::And(loc, ..) => *loc,
         }
     }
@@ -1029,6 +1093,7 @@ impl CodeLocation for Statement {
             | Statement::Break(loc, ..)
             | Statement::Return(loc, ..)
             | Statement::Emit { loc, .. }
+            | Statement::Revert { loc, .. }
             | Statement::TryCatch(loc, ..)
             | Statement::Underscore(loc, ..) => *loc,
             Statement::Assembly(..) => pt::Loc::Codegen,
@@ -1049,6 +1114,8 @@ impl CodeLocation for Instr {
             Instr::Return { value } => value[0].loc(),
             Instr::EmitEvent { data, .. } if data.is_empty() => pt::Loc::Codegen,
             Instr::EmitEvent { data, .. } => data[0].loc(),
+            Instr::RevertError { args, .. } if args.is_empty() => pt::Loc::Codegen,
+            Instr::RevertError { args, .. } => args[0].loc(),
             Instr::BranchCond { cond, .. } => cond.loc(),
             Instr::Store { dest, .. } => dest.loc(),
             Instr::SetStorageBytes { storage, .. }
@@ -1119,8 +1186,10 @@ pub enum Builtin {
     ArrayPop,
     ArrayLength,
     Assert,
+    DebugAssert,
     Print,
     Revert,
+    RevertWith,
     Require,
     SelfDestruct,
     Keccak256,
@@ -1156,6 +1225,9 @@ pub enum Builtin {
     AbiEncodeCall,
     MulMod,
     AddMod,
+    CheckedAdd,
+    CheckedSub,
+    CheckedMul,
     ExternalFunctionAddress,
     FunctionSelector,
     SignatureVerify,
@@ -1190,6 +1262,7 @@ pub enum Builtin {
     Accounts,
     UserTypeWrap,
     UserTypeUnwrap,
+    StorageSlot,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -1241,6 +1314,11 @@ pub enum Statement {
         event_loc: pt::Loc,
         args: Vec<Expression>,
     },
+    Revert {
+        loc: pt::Loc,
+        error_no: usize,
+        args: Vec<Expression>,
+    },
     TryCatch(pt::Loc, bool, TryCatch),
     Underscore(pt::Loc),
     Assembly(InlineAssembly, bool),
@@ -1355,7 +1433,10 @@ impl Statement {
             | Statement::Emit { .. }
             | Statement::Delete(..) => true,
 
-            Statement::Continue(_) | Statement::Break(_) | Statement::Return(..) => false,
+            Statement::Continue(_)
+            | Statement::Break(_)
+            | Statement::Return(..)
+            | Statement::Revert { .. } => false,
 
             Statement::If(_, reachable, ..)
             | Statement::While(_, reachable, ..)
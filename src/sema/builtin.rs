@@ -1,14 +1,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::ast::{
-    ArrayLength, Builtin, Diagnostic, Expression, File, Function, Namespace, Parameter, StructType,
-    Symbol, Type,
+    ArrayLength, Builtin, Diagnostic, Expression, File, FormatArg, Function, Mutability, Namespace,
+    Parameter, StringLocation, StructType, Symbol, Type,
 };
 use super::diagnostics::Diagnostics;
 use super::eval::eval_const_number;
 use super::expression::{expression, ExprContext, ResolveTo};
+use super::format::can_format;
 use super::symtable::Symtable;
+use super::unused_variable::used_variable;
 use crate::sema::ast::RetrieveType;
+use crate::sema::Recurse;
 use crate::Target;
 use num_bigint::BigInt;
 use num_traits::One;
@@ -30,8 +33,15 @@ pub struct Prototype {
     pub constant: bool,
 }
 
+impl Prototype {
+    /// Is this builtin available on the given target
+    pub fn is_available(&self, target: &Target) -> bool {
+        self.target.is_empty() || self.target.contains(target)
+    }
+}
+
 // A list of all Solidity builtins functions
-static BUILTIN_FUNCTIONS: Lazy<[Prototype; 28]> = Lazy::new(|| {
+static BUILTIN_FUNCTIONS: Lazy<[Prototype; 30]> = Lazy::new(|| {
     [
         Prototype {
             builtin: Builtin::Assert,
@@ -44,6 +54,17 @@ static BUILTIN_FUNCTIONS: Lazy<[Prototype; 28]> = Lazy::new(|| {
             doc: "Abort execution if argument evaluates to false",
             constant: false,
         },
+        Prototype {
+            builtin: Builtin::DebugAssert,
+            namespace: None,
+            method: None,
+            name: "debugAssert",
+            params: vec![Type::Bool],
+            ret: vec![Type::Void],
+            target: vec![],
+            doc: "Abort execution if argument evaluates to false. Removed when debug asserts are stripped",
+            constant: false,
+        },
         Prototype {
             builtin: Builtin::Print,
             namespace: None,
@@ -52,7 +73,7 @@ static BUILTIN_FUNCTIONS: Lazy<[Prototype; 28]> = Lazy::new(|| {
             params: vec![Type::String],
             ret: vec![Type::Void],
             target: vec![],
-            doc: "log string for debugging purposes. Runs on development chain only",
+            doc: "log values for debugging purposes. Runs on development chain only",
             constant: false,
         },
         Prototype {
@@ -99,6 +120,17 @@ static BUILTIN_FUNCTIONS: Lazy<[Prototype; 28]> = Lazy::new(|| {
             doc: "Revert execution and report string",
             constant: false,
         },
+        Prototype {
+            builtin: Builtin::RevertWith,
+            namespace: None,
+            method: None,
+            name: "revertWith",
+            params: vec![Type::DynamicBytes],
+            ret: vec![Type::Unreachable],
+            target: vec![],
+            doc: "Revert execution with the given bytes as revert data",
+            constant: false,
+        },
         Prototype {
             builtin: Builtin::SelfDestruct,
             namespace: None,
@@ -287,7 +319,7 @@ static BUILTIN_FUNCTIONS: Lazy<[Prototype; 28]> = Lazy::new(|| {
             name: "gasprice",
             params: vec![Type::Uint(64)],
             ret: vec![Type::Value],
-            target: vec![],
+            target: vec![Target::default_substrate(), Target::EVM],
             doc: "Calculate price of given gas units",
             constant: false,
         },
@@ -828,13 +860,88 @@ static BUILTIN_METHODS: Lazy<[Prototype; 27]> = Lazy::new(|| {
     ]
 });
 
+// The checked arithmetic methods exist for every integer width, so generate them
+static BUILTIN_CHECKED_METHODS: Lazy<Vec<Prototype>> = Lazy::new(|| {
+    let mut methods = Vec::new();
+
+    for bits in (8..=256).step_by(8) {
+        for ty in [Type::Uint(bits), Type::Int(bits)] {
+            for (builtin, name, doc) in [
+                (
+                    Builtin::CheckedAdd,
+                    "checkedAdd",
+                    "Add, returning false and the wrapped result on overflow rather than reverting",
+                ),
+                (
+                    Builtin::CheckedSub,
+                    "checkedSub",
+                    "Subtract, returning false and the wrapped result on overflow rather than reverting",
+                ),
+                (
+                    Builtin::CheckedMul,
+                    "checkedMul",
+                    "Multiply, returning false and the wrapped result on overflow rather than reverting",
+                ),
+            ] {
+                methods.push(Prototype {
+                    builtin,
+                    namespace: None,
+                    method: Some(ty.clone()),
+                    name,
+                    params: vec![ty.clone()],
+                    ret: vec![Type::Bool, ty.clone()],
+                    target: vec![],
+                    doc,
+                    constant: false,
+                });
+            }
+        }
+    }
+
+    methods
+});
+
 /// Does function call match builtin
 pub fn is_builtin_call(namespace: Option<&str>, fname: &str, ns: &Namespace) -> bool {
-    BUILTIN_FUNCTIONS.iter().any(|p| {
-        p.name == fname
-            && p.namespace == namespace
-            && (p.target.is_empty() || p.target.contains(&ns.target))
-    })
+    BUILTIN_FUNCTIONS
+        .iter()
+        .any(|p| p.name == fname && p.namespace == namespace && p.is_available(&ns.target))
+}
+
+/// Check whether the builtin exists, but is not available on the current target. If so,
+/// report this, so the user does not get a confusing "unknown function" error.
+pub fn builtin_unavailable(
+    loc: &pt::Loc,
+    namespace: Option<&str>,
+    fname: &str,
+    ns: &Namespace,
+    diagnostics: &mut Diagnostics,
+) -> bool {
+    let mut prototypes = BUILTIN_FUNCTIONS
+        .iter()
+        .chain(BUILTIN_VARIABLE.iter())
+        .filter(|p| p.name == fname && p.namespace == namespace && p.method.is_none())
+        .peekable();
+
+    if prototypes.peek().is_none() || prototypes.any(|p| p.is_available(&ns.target)) {
+        return false;
+    }
+
+    let name = if let Some(namespace) = namespace {
+        format!("{}.{}", namespace, fname)
+    } else {
+        fname.to_string()
+    };
+
+    diagnostics.push(Diagnostic::error(
+        *loc,
+        format!(
+            "builtin '{}' is not available for target {}",
+            name, ns.target
+        ),
+    ));
+
+    true
 }
 
 /// Get the prototype for a builtin. If the prototype has arguments, it is a function else
@@ -845,6 +952,11 @@ pub fn get_prototype(builtin: Builtin) -> Option<&'static Prototype> {
         .find(|p| p.builtin == builtin)
         .or_else(|| BUILTIN_VARIABLE.iter().find(|p| p.builtin == builtin))
         .or_else(|| BUILTIN_METHODS.iter().find(|p| p.builtin == builtin))
+        .or_else(|| {
+            BUILTIN_CHECKED_METHODS
+                .iter()
+                .find(|p| p.builtin == builtin)
+        })
 }
 
 /// Does variable name match builtin
@@ -859,7 +971,7 @@ pub fn builtin_var(
         .iter()
         .find(|p| p.name == fname && p.namespace == namespace)
     {
-        if p.target.is_empty() || p.target.contains(&ns.target) {
+        if p.is_available(&ns.target) {
             if ns.target.is_substrate() && p.builtin == Builtin::Gasprice {
                 diagnostics.push(Diagnostic::error(
                     *loc,
@@ -868,14 +980,6 @@ pub fn builtin_var(
                     ),
                 ));
             }
-            if ns.target == Target::Solana && p.builtin == Builtin::Value {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    String::from(
-                        "Solana Cross Program Invocation (CPI) cannot transfer native value. See https://solang.readthedocs.io/en/latest/language/functions.html#value_transfer",
-                    ),
-                ));
-            }
             return Some((p.builtin, p.ret[0].clone()));
         }
     }
@@ -1010,6 +1114,10 @@ pub fn resolve_call(
     symtable: &mut Symtable,
     diagnostics: &mut Diagnostics,
 ) -> Result<Expression, ()> {
+    if namespace.is_none() && id == "print" {
+        return resolve_print(loc, args, context, ns, symtable, diagnostics);
+    }
+
     let funcs = BUILTIN_FUNCTIONS
         .iter()
         .filter(|p| p.name == id && p.namespace == namespace && p.method.is_none())
@@ -1093,6 +1201,16 @@ pub fn resolve_call(
                 }
             }
 
+            // debugAssert() may be stripped from the build, so evaluating the condition must
+            // not change anything
+            if func.builtin == Builtin::DebugAssert && has_side_effects(&cast_args[0], ns) {
+                diagnostics.push(Diagnostic::error(
+                    cast_args[0].loc(),
+                    String::from("condition of debugAssert() must not have side effects"),
+                ));
+                return Err(());
+            }
+
             return Ok(Expression::Builtin(
                 *loc,
                 func.ret.to_vec(),
@@ -1114,6 +1232,72 @@ pub fn resolve_call(
     Err(())
 }
 
+/// Does evaluating the expression modify state or memory, or call anything which may do so.
+/// Calls to pure and view functions are allowed.
+fn has_side_effects(expr: &Expression, ns: &Namespace) -> bool {
+    let mut cx = (ns, false);
+
+    expr.recurse(&mut cx, check_side_effects);
+
+    cx.1
+}
+
+fn check_side_effects(expr: &Expression, cx: &mut (&Namespace, bool)) -> bool {
+    match expr {
+        Expression::InternalFunctionCall { function, .. } => {
+            if let Expression::InternalFunction { function_no, .. } = function.as_ref() {
+                if matches!(
+                    cx.0.functions[*function_no].mutability,
+                    Mutability::Pure(_) | Mutability::View(_)
+                ) {
+                    return true;
+                }
+            }
+
+            cx.1 = true;
+            false
+        }
+        Expression::ExternalFunctionCall { .. }
+        | Expression::ExternalFunctionCallRaw { .. }
+        | Expression::Constructor { .. }
+        | Expression::Assign(..)
+        | Expression::PreIncrement(..)
+        | Expression::PreDecrement(..)
+        | Expression::PostIncrement(..)
+        | Expression::PostDecrement(..)
+        | Expression::Builtin(
+            _,
+            _,
+            Builtin::PayableSend
+            | Builtin::PayableTransfer
+            | Builtin::ArrayPush
+            | Builtin::ArrayPop
+            | Builtin::Print
+            | Builtin::SelfDestruct
+            | Builtin::WriteInt8
+            | Builtin::WriteInt16LE
+            | Builtin::WriteInt32LE
+            | Builtin::WriteInt64LE
+            | Builtin::WriteInt128LE
+            | Builtin::WriteInt256LE
+            | Builtin::WriteUint8
+            | Builtin::WriteUint16LE
+            | Builtin::WriteUint32LE
+            | Builtin::WriteUint64LE
+            | Builtin::WriteUint128LE
+            | Builtin::WriteUint256LE
+            | Builtin::WriteAddress
+            | Builtin::WriteString
+            | Builtin::WriteBytes,
+            _,
+        ) => {
+            cx.1 = true;
+            false
+        }
+        _ => true,
+    }
+}
+
 /// Resolve a builtin namespace call. The takes the unresolved arguments, since it has
 /// to handle the special case "abi.decode(foo, (int32, bool, address))" where the
 /// second argument is a type list. The generic expression resolver cannot deal with
@@ -1312,21 +1496,50 @@ pub fn resolve_namespace_call(
                     | Type::InternalFunction { params, .. } => {
                         resolved_args.push(function);
 
-                        if args.len() - 1 != params.len() {
+                        // The arguments can be given as a tuple, like solc does: abi.encodeCall(f, (a, b))
+                        let call_args: Vec<&pt::Expression> = match &args[1..] {
+                            [pt::Expression::List(_, list)] => {
+                                let mut call_args = Vec::new();
+
+                                for (loc, param) in list {
+                                    match param {
+                                        Some(pt::Parameter {
+                                            ty,
+                                            storage: None,
+                                            name: None,
+                                            ..
+                                        }) => call_args.push(ty),
+                                        _ => {
+                                            diagnostics.push(Diagnostic::error(
+                                                *loc,
+                                                "expression expected".to_string(),
+                                            ));
+
+                                            return Err(());
+                                        }
+                                    }
+                                }
+
+                                call_args
+                            }
+                            _ => args_iter.collect(),
+                        };
+
+                        if call_args.len() != params.len() {
                             diagnostics.push(Diagnostic::error(
                                 *loc,
                                 format!(
                                     "function takes {} arguments, {} provided",
                                     params.len(),
-                                    args.len() - 1
+                                    call_args.len()
                                 ),
                             ));
 
                             return Err(());
                         }
 
-                        for (arg_no, arg) in args_iter.enumerate() {
-                            let mut expr = expression(
+                        for (arg_no, arg) in call_args.into_iter().enumerate() {
+                            let expr = expression(
                                 arg,
                                 context,
                                 ns,
@@ -1335,7 +1548,32 @@ pub fn resolve_namespace_call(
                                 ResolveTo::Type(&params[arg_no]),
                             )?;
 
-                            expr = expr.cast(&arg.loc(), &params[arg_no], true, ns, diagnostics)?;
+                            let mut cast_diagnostics = Diagnostics::default();
+
+                            let mut expr = match expr.cast(
+                                &arg.loc(),
+                                &params[arg_no],
+                                true,
+                                ns,
+                                &mut cast_diagnostics,
+                            ) {
+                                Ok(expr) => {
+                                    diagnostics.extend(cast_diagnostics);
+                                    expr
+                                }
+                                Err(()) => {
+                                    diagnostics.push(Diagnostic::error(
+                                        arg.loc(),
+                                        format!(
+                                            "argument type mismatch for abi.encodeCall: expected '{}', found '{}'",
+                                            params[arg_no].to_string(ns),
+                                            expr.ty().to_string(ns)
+                                        ),
+                                    ));
+
+                                    return Err(());
+                                }
+                            };
 
                             // A string or hex literal should be encoded as a string
                             if let Expression::BytesLiteral(..) = &expr {
@@ -1433,6 +1671,265 @@ pub fn resolve_namespace_call(
     ))
 }
 
+/// Resolve bytes.concat(). The arguments can be dynamic bytes, fixed length bytesN or strings;
+/// the result is always a newly allocated dynamic bytes.
+pub fn resolve_bytes_concat(
+    loc: &pt::Loc,
+    args: &[pt::Expression],
+    context: &ExprContext,
+    ns: &mut Namespace,
+    symtable: &mut Symtable,
+    diagnostics: &mut Diagnostics,
+) -> Result<Expression, ()> {
+    let mut parts = Vec::new();
+    let mut failed = false;
+
+    for arg in args {
+        let expr = match expression(
+            arg,
+            context,
+            ns,
+            symtable,
+            diagnostics,
+            ResolveTo::Type(&Type::DynamicBytes),
+        ) {
+            Ok(expr) => expr,
+            Err(()) => {
+                failed = true;
+                continue;
+            }
+        };
+
+        used_variable(ns, &expr, symtable);
+
+        // load any storage or reference operand
+        let ty = expr.ty();
+
+        let expr = match expr.cast(&arg.loc(), ty.deref_any(), true, ns, diagnostics) {
+            Ok(expr) => expr,
+            Err(()) => {
+                failed = true;
+                continue;
+            }
+        };
+
+        match (&expr, expr.ty()) {
+            (Expression::BytesLiteral(_, _, bs), _) => {
+                parts.push(StringLocation::CompileTime(bs.clone()));
+            }
+            (_, Type::DynamicBytes) => {
+                parts.push(StringLocation::RunTime(Box::new(expr)));
+            }
+            (_, Type::String) => {
+                parts.push(StringLocation::RunTime(Box::new(Expression::Cast(
+                    arg.loc(),
+                    Type::DynamicBytes,
+                    Box::new(expr),
+                ))));
+            }
+            (_, Type::Bytes(_)) => {
+                let expr = expr.cast(&arg.loc(), &Type::DynamicBytes, false, ns, diagnostics)?;
+
+                parts.push(StringLocation::RunTime(Box::new(expr)));
+            }
+            (_, ty) => {
+                diagnostics.push(Diagnostic::error(
+                    arg.loc(),
+                    format!(
+                        "bytes.concat() expects arguments of type bytes, bytesN or string, not '{}'",
+                        ty.to_string(ns)
+                    ),
+                ));
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        return Err(());
+    }
+
+    Ok(concat_parts(loc, Type::DynamicBytes, parts))
+}
+
+/// Resolve string.concat(). All the arguments must be strings; the result is always a newly
+/// allocated string.
+pub fn resolve_string_concat(
+    loc: &pt::Loc,
+    args: &[pt::Expression],
+    context: &ExprContext,
+    ns: &mut Namespace,
+    symtable: &mut Symtable,
+    diagnostics: &mut Diagnostics,
+) -> Result<Expression, ()> {
+    let mut parts = Vec::new();
+    let mut failed = false;
+
+    for arg in args {
+        let expr = match expression(
+            arg,
+            context,
+            ns,
+            symtable,
+            diagnostics,
+            ResolveTo::Type(&Type::String),
+        ) {
+            Ok(expr) => expr,
+            Err(()) => {
+                failed = true;
+                continue;
+            }
+        };
+
+        used_variable(ns, &expr, symtable);
+
+        // load any storage or reference operand
+        let ty = expr.ty();
+
+        let expr = match expr.cast(&arg.loc(), ty.deref_any(), true, ns, diagnostics) {
+            Ok(expr) => expr,
+            Err(()) => {
+                failed = true;
+                continue;
+            }
+        };
+
+        match (&expr, expr.ty()) {
+            (Expression::BytesLiteral(_, Type::String, bs), _) => {
+                parts.push(StringLocation::CompileTime(bs.clone()));
+            }
+            (_, Type::String) => {
+                parts.push(StringLocation::RunTime(Box::new(expr)));
+            }
+            (_, ty) => {
+                diagnostics.push(Diagnostic::error(
+                    arg.loc(),
+                    format!(
+                        "string.concat() expects arguments of type string, not '{}'",
+                        ty.to_string(ns)
+                    ),
+                ));
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        return Err(());
+    }
+
+    Ok(concat_parts(loc, Type::String, parts))
+}
+
+/// Concatenate the parts of bytes.concat() or string.concat() into a new value of the given type
+fn concat_parts(loc: &pt::Loc, ty: Type, parts: Vec<StringLocation<Expression>>) -> Expression {
+    // concatenate from the left; the first part is concatenated with nothing, so that the
+    // result is always a new copy
+    let mut res = StringLocation::CompileTime(Vec::new());
+
+    for part in parts {
+        res = match (res, part) {
+            (StringLocation::CompileTime(mut left), StringLocation::CompileTime(right)) => {
+                left.extend_from_slice(&right);
+                StringLocation::CompileTime(left)
+            }
+            (left, right) => StringLocation::RunTime(Box::new(Expression::StringConcat(
+                *loc,
+                ty.clone(),
+                left,
+                right,
+            ))),
+        };
+    }
+
+    match res {
+        StringLocation::RunTime(expr) => *expr,
+        StringLocation::CompileTime(bs) => Expression::AllocDynamicArray(
+            *loc,
+            ty,
+            Box::new(Expression::NumberLiteral(
+                *loc,
+                Type::Uint(32),
+                BigInt::from(bs.len()),
+            )),
+            Some(bs),
+        ),
+    }
+}
+
+/// Resolve a call to print(). Any number of arguments can be given, which are converted to text
+/// and separated by spaces, like a format string would do. A single string is printed as is.
+fn resolve_print(
+    loc: &pt::Loc,
+    args: &[pt::Expression],
+    context: &ExprContext,
+    ns: &mut Namespace,
+    symtable: &mut Symtable,
+    diagnostics: &mut Diagnostics,
+) -> Result<Expression, ()> {
+    if context.constant {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            "cannot call function 'print' in constant expression".to_string(),
+        ));
+        return Err(());
+    }
+
+    if args.is_empty() {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            "builtin function 'print' expects at least 1 argument, 0 provided".to_string(),
+        ));
+        return Err(());
+    }
+
+    let mut format_args = Vec::new();
+
+    for arg in args {
+        let resolve_to = if matches!(arg, pt::Expression::StringLiteral(_)) {
+            ResolveTo::Type(&Type::String)
+        } else {
+            ResolveTo::Unknown
+        };
+
+        let expr = expression(arg, context, ns, symtable, diagnostics, resolve_to)?;
+        let ty = expr.ty();
+        let ty = ty.deref_any();
+
+        if !can_format(ty) {
+            diagnostics.push(Diagnostic::error(
+                arg.loc(),
+                format!("cannot print value of type {}", ty.to_string(ns)),
+            ));
+            return Err(());
+        }
+
+        if !format_args.is_empty() {
+            format_args.push((
+                FormatArg::StringLiteral,
+                Expression::BytesLiteral(*loc, Type::String, b" ".to_vec()),
+            ));
+        }
+
+        format_args.push((
+            FormatArg::Default,
+            expr.cast(&arg.loc(), ty, true, ns, diagnostics)?,
+        ));
+    }
+
+    let expr = match format_args.as_slice() {
+        [(_, expr)] if expr.ty() == Type::String => expr.clone(),
+        _ => Expression::FormatString(*loc, format_args),
+    };
+
+    Ok(Expression::Builtin(
+        *loc,
+        vec![Type::Void],
+        Builtin::Print,
+        vec![expr],
+    ))
+}
+
 /// Resolve a builtin call
 pub fn resolve_method_call(
     expr: &Expression,
@@ -1447,6 +1944,10 @@ pub fn resolve_method_call(
     let funcs: Vec<_> = BUILTIN_METHODS
         .iter()
         .filter(|func| func.name == id.name && func.method.as_ref() == Some(&expr_ty))
+        // checked arithmetic works on the value, so it can be called on storage variables too
+        .chain(BUILTIN_CHECKED_METHODS.iter().filter(|func| {
+            func.name == id.name && func.method.as_ref() == Some(expr_ty.deref_any())
+        }))
         .collect();
     let mut errors = Diagnostics::default();
 
@@ -1515,7 +2016,13 @@ pub fn resolve_method_call(
                 return Err(());
             }
         } else {
-            cast_args.insert(0, expr.clone());
+            let method_ty = func.method.as_ref().unwrap();
+
+            cast_args.insert(0, expr.cast(&expr.loc(), method_ty, true, ns, diagnostics)?);
+
+            if let Some(expr) = checked_arithmetic_constant(&id.loc, func.builtin, &cast_args, ns) {
+                return Ok(Some(expr));
+            }
 
             let returns = if func.ret.is_empty() {
                 vec![Type::Void]
@@ -1550,6 +2057,49 @@ pub fn resolve_method_call(
     }
 }
 
+/// If both arguments of a checked arithmetic method are constant, fold it into a list of the
+/// overflow flag and the wrapped result
+fn checked_arithmetic_constant(
+    loc: &pt::Loc,
+    builtin: Builtin,
+    args: &[Expression],
+    ns: &Namespace,
+) -> Option<Expression> {
+    if !matches!(
+        builtin,
+        Builtin::CheckedAdd | Builtin::CheckedSub | Builtin::CheckedMul
+    ) {
+        return None;
+    }
+
+    let left = eval_const_number(&args[0], ns).ok()?.1;
+    let right = eval_const_number(&args[1], ns).ok()?.1;
+
+    let result = match builtin {
+        Builtin::CheckedAdd => left + right,
+        Builtin::CheckedSub => left - right,
+        _ => left * right,
+    };
+
+    let ty = args[0].ty();
+    let bits = ty.bits(ns) as usize;
+    let modulus = BigInt::one() << bits;
+
+    let mut wrapped = ((&result % &modulus) + &modulus) % &modulus;
+
+    if ty.is_signed_int() && wrapped.bits() as usize == bits {
+        wrapped -= modulus;
+    }
+
+    Some(Expression::List(
+        *loc,
+        vec![
+            Expression::BoolLiteral(*loc, wrapped == result),
+            Expression::NumberLiteral(*loc, ty, wrapped),
+        ],
+    ))
+}
+
 impl Namespace {
     pub fn add_solana_builtins(&mut self) {
         let file_no = self.files.len();
@@ -1558,6 +2108,7 @@ impl Namespace {
             path: PathBuf::from("solana"),
             line_starts: Vec::new(),
             cache_no: None,
+            wrapping_math: false,
         });
 
         let id = pt::Identifier {
@@ -88,12 +88,11 @@ impl ast::Contract {
 /// Resolve the following contract
 pub fn resolve(
     contracts: &[(usize, &pt::ContractDefinition)],
+    using: &[&pt::Using],
     comments: &[pt::Comment],
     file_no: usize,
     ns: &mut ast::Namespace,
 ) {
-    resolve_using(contracts, file_no, ns);
-
     // we need to resolve declarations first, so we call functions/constructors of
     // contracts before they are declared
     let mut delayed: ResolveLater = Default::default();
@@ -102,6 +101,9 @@ pub fn resolve(
         resolve_declarations(def, comments, file_no, *contract_no, ns, &mut delayed);
     }
 
+    // using declarations can list library functions, so these need to be declared first
+    resolve_using(contracts, using, file_no, ns);
+
     // Resolve base contract constructor arguments on contract definition (not constructor definitions)
     resolve_base_args(contracts, file_no, ns);
 
@@ -111,6 +113,7 @@ pub fn resolve(
         substrate_requires_public_functions(*contract_no, ns);
         substrate_unique_constructor_names(*contract_no, ns);
         check_mangled_function_names(*contract_no, ns);
+        check_pinned_storage(*contract_no, ns);
     }
 
     // Now we can resolve the initializers
@@ -225,7 +228,7 @@ fn resolve_base_args(
             function_no: None,
             contract_no: Some(*contract_no),
             file_no,
-            unchecked: false,
+            unchecked: ns.files[file_no].wrapping_math,
             constant: false,
             lvalue: false,
             yul_function: false,
@@ -362,6 +365,20 @@ fn check_inheritance(contract_no: usize, ns: &mut ast::Namespace) {
 
             let signature = cur.signature.to_owned();
 
+            // another implementation from a base which is unrelated to the bases in the entry,
+            // e.g. the second branch of a diamond. This needs to be overriden too.
+            if let Some(entry) = override_needed.get_mut(&signature) {
+                if cur.is_override.is_some()
+                    && cur.has_body
+                    && entry.iter().any(|(prev_contract_no, _)| {
+                        !is_base(*prev_contract_no, base_contract_no, ns)
+                    })
+                {
+                    entry.push((base_contract_no, function_no));
+                    continue;
+                }
+            }
+
             if let Some(entry) = override_needed.get(&signature) {
                 let non_virtual = entry
                     .iter()
@@ -497,6 +514,52 @@ fn check_inheritance(contract_no: usize, ns: &mut ast::Namespace) {
                     .cloned()
                     .collect::<Vec<usize>>();
 
+                // the most derived bases which declare the function; e.g. two interfaces which
+                // both declare it, but not the common base of a diamond
+                let declaring_bases: Vec<usize> = ns
+                    .contract_bases(base_contract_no)
+                    .into_iter()
+                    .filter(|base_no| {
+                        *base_no != base_contract_no
+                            && ns.contracts[*base_no].functions.iter().any(|function_no| {
+                                let func = &ns.functions[*function_no];
+
+                                func.ty != pt::FunctionTy::Constructor
+                                    && func.signature == signature
+                            })
+                    })
+                    .collect();
+
+                let declared: HashSet<usize> = declaring_bases
+                    .iter()
+                    .filter(|base_no| {
+                        !declaring_bases.iter().any(|other_no| {
+                            other_no != *base_no && is_base(**base_no, *other_no, ns)
+                        })
+                    })
+                    .cloned()
+                    .collect();
+
+                // only the most derived definitions are overriden; e.g. in a diamond, the function
+                // in the common base has already been overriden by both branches
+                let previous_defs = previous_defs
+                    .iter()
+                    .filter(|function_no| {
+                        let prev_contract_no = ns.functions[**function_no].contract_no;
+
+                        !previous_defs.iter().any(|other_no| {
+                            let other_contract_no = ns.functions[*other_no].contract_no;
+
+                            other_contract_no != prev_contract_no
+                                && match (prev_contract_no, other_contract_no) {
+                                    (Some(prev), Some(other)) => is_base(prev, other, ns),
+                                    _ => false,
+                                }
+                        })
+                    })
+                    .cloned()
+                    .collect::<Vec<usize>>();
+
                 if previous_defs.is_empty() && cur.is_override.is_some() {
                     diagnostics.push(ast::Diagnostic::error(
                         cur.loc,
@@ -505,6 +568,34 @@ fn check_inheritance(contract_no: usize, ns: &mut ast::Namespace) {
                     continue;
                 }
 
+                // every contract in the override list must declare the function
+                if let Some((loc, override_list)) = &cur.is_override {
+                    let extra: Vec<&str> = override_list
+                        .iter()
+                        .filter(|contract_no| !declared.contains(contract_no))
+                        .map(|contract_no| -> &str { &ns.contracts[*contract_no].name })
+                        .collect();
+
+                    if !extra.is_empty() {
+                        let source_override = previous_defs
+                            .iter()
+                            .filter_map(|function_no| ns.functions[*function_no].contract_no)
+                            .map(|contract_no| -> &str { &ns.contracts[contract_no].name })
+                            .collect::<Vec<&str>>()
+                            .join(",");
+
+                        diagnostics.push(ast::Diagnostic::error(
+                            *loc,
+                            format!(
+                                "function '{}' includes extraneous overrides '{}', specify 'override({})'",
+                                cur.name,
+                                extra.join(","),
+                                source_override
+                            ),
+                        ));
+                    }
+                }
+
                 // a function without body needs an override, if the contract is concrete
                 if previous_defs.is_empty()
                     && !cur.has_body
@@ -586,6 +677,23 @@ fn check_inheritance(contract_no: usize, ns: &mut ast::Namespace) {
                     // if a function needs an override, it was defined in a contract, not outside
                     let prev_contract_no = func_prev.contract_no.unwrap();
 
+                    // both functions are inherited from unrelated bases, so the contract has to
+                    // override them with an override list which names both
+                    if cur.is_override.is_some()
+                        && cur.has_body
+                        && !is_base(prev_contract_no, base_contract_no, ns)
+                    {
+                        if let Some(entry) = override_needed.get_mut(&signature) {
+                            entry.push((base_contract_no, function_no));
+                        } else {
+                            override_needed.insert(
+                                signature.clone(),
+                                vec![(prev_contract_no, prev), (base_contract_no, function_no)],
+                            );
+                        }
+                        continue;
+                    }
+
                     if let Some((loc, override_list)) = &cur.is_override {
                         if !func_prev.is_virtual {
                             diagnostics.push(ast::Diagnostic::error_with_note(
@@ -649,22 +757,25 @@ fn check_inheritance(contract_no: usize, ns: &mut ast::Namespace) {
                 ));
             }
 
-            if let Some(other_func_no) = selectors.get(&selector) {
-                let other = &ns.functions[*other_func_no];
+            // only public functions are dispatched by selector, so only those can collide
+            if cur.is_public() {
+                if let Some(other_func_no) = selectors.get(&selector) {
+                    let other = &ns.functions[*other_func_no];
 
-                if other.signature != cur.signature {
-                    diagnostics.push(ast::Diagnostic::error_with_note(
-                        cur.loc,
-                        format!(
-                            "{} '{}' selector is the same as {} '{}'",
-                            cur.ty, cur.name, other.ty, other.name
-                        ),
-                        other.loc,
-                        format!("definition of {} '{}'", other.ty, other.name),
-                    ));
+                    if other.signature != cur.signature {
+                        diagnostics.push(ast::Diagnostic::error_with_note(
+                            cur.loc,
+                            format!(
+                                "function selector collision between {} and {}",
+                                other.signature, cur.signature
+                            ),
+                            other.loc,
+                            format!("definition of {} '{}'", other.ty, other.name),
+                        ));
+                    }
+                } else {
+                    selectors.insert(selector, function_no);
                 }
-            } else {
-                selectors.insert(selector, function_no);
             }
 
             ns.contracts[contract_no]
@@ -775,6 +886,59 @@ fn check_mangled_function_names(contract_no: usize, ns: &mut ast::Namespace) {
     }
 }
 
+/// State variables with a `@storage` tag are pinned to a slot, rather than being assigned the next
+/// sequential slot. Check that pinned variables do not overlap with each other, or with the
+/// variables which are laid out sequentially.
+fn check_pinned_storage(contract_no: usize, ns: &mut ast::Namespace) {
+    let mut diagnostics = Diagnostics::default();
+    let mut sequential = BigInt::zero();
+    let mut pinned: Vec<(BigInt, BigInt, &ast::Variable)> = Vec::new();
+
+    for base_contract_no in ns.contract_bases(contract_no) {
+        for var in &ns.contracts[base_contract_no].variables {
+            if var.constant {
+                continue;
+            }
+
+            let slots = var.ty.storage_slots(ns);
+
+            if let Some(slot) = var.pinned_slot() {
+                pinned.push((slot.clone(), slot + slots, var));
+            } else {
+                sequential += slots;
+            }
+        }
+    }
+
+    for (no, (start, end, var)) in pinned.iter().enumerate() {
+        if *start < sequential {
+            diagnostics.push(ast::Diagnostic::error(
+                var.loc,
+                format!(
+                    "storage slot of '{}' collides with the sequentially assigned slots 0 to {}",
+                    var.name,
+                    &sequential - 1
+                ),
+            ));
+        } else if let Some((_, _, other)) = pinned[..no]
+            .iter()
+            .find(|(other_start, other_end, _)| start < other_end && other_start < end)
+        {
+            diagnostics.push(ast::Diagnostic::error_with_note(
+                var.loc,
+                format!(
+                    "storage slot of '{}' collides with '{}'",
+                    var.name, other.name
+                ),
+                other.loc,
+                format!("definition of '{}'", other.name),
+            ));
+        }
+    }
+
+    ns.diagnostics.extend(diagnostics);
+}
+
 /// A contract on substrate requires at least one public message
 fn substrate_requires_public_functions(contract_no: usize, ns: &mut ast::Namespace) {
     let contract = &mut ns.contracts[contract_no];
@@ -998,12 +1162,19 @@ fn resolve_declarations<'a>(
     }
 }
 
-/// Resolve the using declarations in a contract
+/// Resolve the using declarations in file scope and in the contracts
 fn resolve_using(
     contracts: &[(usize, &pt::ContractDefinition)],
+    using: &[&pt::Using],
     file_no: usize,
     ns: &mut ast::Namespace,
 ) {
+    for using in using {
+        if let Ok(using) = using::using_decl(using, file_no, None, ns) {
+            ns.using.push(using);
+        }
+    }
+
     for (contract_no, def) in contracts {
         for part in &def.parts {
             if let pt::ContractPart::Using(using) = part {
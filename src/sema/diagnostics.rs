@@ -8,7 +8,7 @@ use itertools::Itertools;
 use solang_parser::pt::Loc;
 use std::{
     collections::HashMap,
-    slice::Iter,
+    slice::{Iter, IterMut},
     {io, sync::Arc},
 };
 
@@ -31,6 +31,10 @@ impl Diagnostics {
         self.contents.iter()
     }
 
+    pub fn iter_mut(&mut self) -> IterMut<Diagnostic> {
+        self.contents.iter_mut()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.contents.is_empty()
     }
@@ -256,6 +260,7 @@ impl Namespace {
                 severity: msg.level.to_string().to_owned(),
                 message: msg.message.to_owned(),
                 formattedMessage: buffer.into_string(),
+                target: self.target.to_string(),
             });
         }
 
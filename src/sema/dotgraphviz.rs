@@ -394,12 +394,15 @@ impl Dot {
                 self.add_expression(left, func, ns, node, String::from("left"));
                 self.add_expression(right, func, ns, node, String::from("right"));
             }
-            Expression::Divide(loc, ty, left, right) => {
-                let labels = vec![
+            Expression::Divide(loc, ty, unchecked, left, right) => {
+                let mut labels = vec![
                     String::from("divide"),
                     ty.to_string(ns),
                     ns.loc_to_string(loc),
                 ];
+                if *unchecked {
+                    labels.push(String::from("unchecked"));
+                }
                 let node =
                     self.add_node(Node::new("divide", labels), Some(parent), Some(parent_rel));
 
@@ -821,15 +824,16 @@ impl Dot {
 
                 self.add_expression(expr, func, ns, node, String::from("expr"));
             }
-            Expression::UnaryMinus(loc, ty, expr) => {
+            Expression::UnaryMinus(loc, ty, unchecked, expr) => {
+                let mut labels = vec![
+                    format!("unary minus {}", ty.to_string(ns)),
+                    ns.loc_to_string(loc),
+                ];
+                if *unchecked {
+                    labels.push(String::from("unchecked"));
+                }
                 let node = self.add_node(
-                    Node::new(
-                        "unary_minus",
-                        vec![
-                            format!("unary minus {}", ty.to_string(ns)),
-                            ns.loc_to_string(loc),
-                        ],
-                    ),
+                    Node::new("unary_minus", labels),
                     Some(parent),
                     Some(parent_rel),
                 );
@@ -1164,6 +1168,18 @@ impl Dot {
                     Some(parent_rel),
                 );
             }
+            Expression::CodeHash(loc, contract_no) => {
+                let labels = vec![
+                    format!("code hash contract {}", ns.contracts[*contract_no].name),
+                    ns.loc_to_string(loc),
+                ];
+
+                self.add_node(
+                    Node::new("code_hash", labels),
+                    Some(parent),
+                    Some(parent_rel),
+                );
+            }
             Expression::List(loc, list) => {
                 let labels = vec![String::from("list"), ns.loc_to_string(loc)];
 
@@ -1437,6 +1453,24 @@ impl Dot {
                         self.add_expression(arg, Some(func), ns, parent, format!("arg #{}", no));
                     }
                 }
+                Statement::Revert {
+                    loc,
+                    error_no,
+                    args,
+                } => {
+                    let labels = vec![
+                        String::from("revert"),
+                        format!("error {}", ns.errors[*error_no].symbol_name(ns)),
+                        ns.loc_to_string(loc),
+                    ];
+
+                    parent =
+                        self.add_node(Node::new("revert", labels), Some(parent), Some(parent_rel));
+
+                    for (no, arg) in args.iter().enumerate() {
+                        self.add_expression(arg, Some(func), ns, parent, format!("arg #{}", no));
+                    }
+                }
                 Statement::TryCatch(loc, _, try_catch) => {
                     let labels = vec![String::from("try"), ns.loc_to_string(loc)];
 
@@ -2152,6 +2186,33 @@ impl Namespace {
             }
         }
 
+        // errors
+        if !self.errors.is_empty() {
+            let errors = dot.add_node(Node::new("errors", Vec::new()), None, None);
+
+            for decl in &self.errors {
+                let mut labels = vec![format!("name:{}", decl.name), self.loc_to_string(&decl.loc)];
+
+                if let Some(contract) = &decl.contract {
+                    labels.insert(1, format!("contract: {}", contract));
+                }
+
+                for field in &decl.fields {
+                    labels.push(format!(
+                        "field name:{} ty:{}",
+                        field.name_as_str(),
+                        field.ty.to_string(self),
+                    ));
+                }
+
+                let e = Node::new(&decl.name, labels);
+
+                let node = dot.add_node(e, Some(errors), None);
+
+                dot.add_tags(&decl.tags, node);
+            }
+        }
+
         // user types
         if !self.user_types.is_empty() {
             let types = dot.add_node(Node::new("types", Vec::new()), None, None);
@@ -29,7 +29,7 @@ pub fn eval_const_number(
             *loc,
             eval_const_number(l, ns)?.1 * eval_const_number(r, ns)?.1,
         )),
-        Expression::Divide(loc, _, l, r) => {
+        Expression::Divide(loc, _, _, l, r) => {
             let divisor = eval_const_number(r, ns)?.1;
 
             if divisor.is_zero() {
@@ -114,7 +114,7 @@ pub fn eval_const_number(
         Expression::Cast(loc, _, n) => Ok((*loc, eval_const_number(n, ns)?.1)),
         Expression::Not(loc, n) => Ok((*loc, !eval_const_number(n, ns)?.1)),
         Expression::Complement(loc, _, n) => Ok((*loc, !eval_const_number(n, ns)?.1)),
-        Expression::UnaryMinus(loc, _, n) => Ok((*loc, -eval_const_number(n, ns)?.1)),
+        Expression::UnaryMinus(loc, _, _, n) => Ok((*loc, -eval_const_number(n, ns)?.1)),
         Expression::ConstantVariable(_, _, Some(contract_no), var_no) => {
             let expr = ns.contracts[*contract_no].variables[*var_no]
                 .initializer
@@ -154,7 +154,7 @@ pub fn eval_const_rational(
             *loc,
             eval_const_rational(l, ns)?.1 * eval_const_rational(r, ns)?.1,
         )),
-        Expression::Divide(loc, _, l, r) => {
+        Expression::Divide(loc, _, _, l, r) => {
             let divisor = eval_const_rational(r, ns)?.1;
 
             if divisor.is_zero() {
@@ -175,7 +175,7 @@ pub fn eval_const_rational(
         Expression::NumberLiteral(loc, _, n) => Ok((*loc, BigRational::from_integer(n.clone()))),
         Expression::RationalNumberLiteral(loc, _, n) => Ok((*loc, n.clone())),
         Expression::Cast(loc, _, n) => Ok((*loc, eval_const_rational(n, ns)?.1)),
-        Expression::UnaryMinus(loc, _, n) => Ok((*loc, -eval_const_rational(n, ns)?.1)),
+        Expression::UnaryMinus(loc, _, _, n) => Ok((*loc, -eval_const_rational(n, ns)?.1)),
         Expression::ConstantVariable(_, _, Some(contract_no), var_no) => {
             let expr = ns.contracts[*contract_no].variables[*var_no]
                 .initializer
@@ -290,7 +290,7 @@ fn eval_constants_in_expression(
                 (None, true)
             }
         }
-        Expression::Divide(loc, ty, left, right) => {
+        Expression::Divide(loc, ty, _, left, right) => {
             let left = eval_constants_in_expression(left, ns).0;
             let right = eval_constants_in_expression(right, ns).0;
 
@@ -523,7 +523,7 @@ fn overflow_check(result: &BigInt, ty: &Type, loc: &Loc) -> Option<Diagnostic> {
         if let Sign::Minus = result.sign() {
             return Some(Diagnostic::error(
                 *loc,
-            format!( "negative value {} does not fit into type uint{}. Cannot implicitly convert signed literal to unsigned type.",result,ty.get_type_size()),
+            format!( "negative value {} does not fit into type uint{} ({}). Cannot implicitly convert signed literal to unsigned type.",format_number(result),ty.get_type_size(),type_range(ty)),
             ));
         }
 
@@ -532,9 +532,10 @@ fn overflow_check(result: &BigInt, ty: &Type, loc: &Loc) -> Option<Diagnostic> {
             return Some(Diagnostic::error(
                 *loc,
                 format!(
-                    "value {} does not fit into type uint{}.",
-                    result,
+                    "value {} does not fit into type uint{} ({}).",
+                    format_number(result),
                     ty.get_type_size(),
+                    type_range(ty),
                 ),
             ));
         }
@@ -546,12 +547,70 @@ fn overflow_check(result: &BigInt, ty: &Type, loc: &Loc) -> Option<Diagnostic> {
             return Some(Diagnostic::error(
                 *loc,
                 format!(
-                    "value {} does not fit into type int{}.",
-                    result,
+                    "value {} does not fit into type int{} ({}).",
+                    format_number(result),
                     ty.get_type_size(),
+                    type_range(ty),
                 ),
             ));
         }
     }
     None
 }
+
+/// Numbers with more digits than this are abbreviated in diagnostics
+const MAX_DIGITS: usize = 40;
+
+/// Format a number for use in a diagnostic, in decimal and hex, e.g. `133 (0x85)`. Numbers
+/// which are too long to be readable are abbreviated.
+pub(crate) fn format_number(n: &BigInt) -> String {
+    format!(
+        "{} ({})",
+        abbreviate_digits(n, 10, ""),
+        abbreviate_digits(n, 16, "0x")
+    )
+}
+
+/// The smallest and largest value an integer type can hold
+pub(crate) fn type_min_max(ty: &Type) -> (BigInt, BigInt) {
+    match ty {
+        Type::Uint(bits) => (BigInt::zero(), (BigInt::one() << *bits) - 1),
+        Type::Int(bits) => {
+            let half = BigInt::one() << (*bits - 1);
+
+            (-half.clone(), half - 1)
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Format the range of values an integer type can hold, e.g. `range -128 to 127`
+pub(crate) fn type_range(ty: &Type) -> String {
+    let (min, max) = type_min_max(ty);
+
+    format!(
+        "range {} to {}",
+        abbreviate_digits(&min, 10, ""),
+        abbreviate_digits(&max, 10, "")
+    )
+}
+
+/// Print the number in the given radix. If there are too many digits, only the first and last
+/// few are printed, followed by the number of digits.
+fn abbreviate_digits(n: &BigInt, radix: u32, prefix: &str) -> String {
+    let sign = if n.sign() == Sign::Minus { "-" } else { "" };
+    let digits = n.magnitude().to_str_radix(radix);
+
+    if digits.len() > MAX_DIGITS {
+        format!(
+            "{}{}{}…{} [{} digits]",
+            sign,
+            prefix,
+            &digits[..8],
+            &digits[digits.len() - 8..],
+            digits.len()
+        )
+    } else {
+        format!("{}{}{}", sign, prefix, digits)
+    }
+}
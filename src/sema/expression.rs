@@ -2,8 +2,8 @@
 
 use super::address::to_hexstr_eip55;
 use super::ast::{
-    ArrayLength, Builtin, CallArgs, CallTy, Diagnostic, Expression, Function, Mutability,
-    Namespace, RetrieveType, StringLocation, StructType, Symbol, Type,
+    ArrayLength, Builtin, CallArgs, CallTy, Diagnostic, Expression, FormatArg, Function,
+    Mutability, Namespace, RetrieveType, StringLocation, StructType, Symbol, Type,
 };
 use super::builtin;
 use super::contracts::is_base;
@@ -11,6 +11,7 @@ use super::diagnostics::Diagnostics;
 use super::eval::check_term_for_constant_overflow;
 use super::eval::eval_const_number;
 use super::eval::eval_const_rational;
+use super::eval::format_number;
 use super::format::string_format;
 use super::{symtable::Symtable, using};
 use crate::sema::unused_variable::{
@@ -77,7 +78,7 @@ impl RetrieveType for Expression {
             | Expression::Cast(_, ty, _)
             | Expression::BytesCast(_, _, ty, _)
             | Expression::Complement(_, ty, _)
-            | Expression::UnaryMinus(_, ty, _)
+            | Expression::UnaryMinus(_, ty, ..)
             | Expression::Ternary(_, ty, ..)
             | Expression::StructMember(_, ty, ..)
             | Expression::AllocDynamicArray(_, ty, ..)
@@ -104,6 +105,7 @@ impl RetrieveType for Expression {
             }
             Expression::Constructor { contract_no, .. } => Type::Contract(*contract_no),
             Expression::InterfaceId(..) => Type::Bytes(4),
+            Expression::CodeHash(..) => Type::Bytes(32),
             Expression::FormatString(..) => Type::String,
             // codegen Expressions
             Expression::InternalFunction { ty, .. } => ty.clone(),
@@ -458,7 +460,7 @@ impl Expression {
 
                 let to_width = enum_ty.ty.bits(ns);
 
-                // TODO needs runtime checks
+                // codegen checks that the value is in range for the enum
                 match from_width.cmp(&to_width) {
                     Ordering::Greater => {
                         Ok(Expression::Trunc(*loc, to.clone(), Box::new(self.clone())))
@@ -1535,7 +1537,10 @@ pub fn bigint_to_expression(
 
     if n.sign() == Sign::Minus {
         if bits > 255 {
-            diagnostics.push(Diagnostic::error(*loc, format!("{} is too large", n)));
+            diagnostics.push(Diagnostic::error(
+                *loc,
+                format!("{} is too large", format_number(n)),
+            ));
             Err(())
         } else {
             Ok(Expression::NumberLiteral(
@@ -1545,7 +1550,10 @@ pub fn bigint_to_expression(
             ))
         }
     } else if bits > 256 {
-        diagnostics.push(Diagnostic::error(*loc, format!("{} is too large", n)));
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            format!("{} is too large", format_number(n)),
+        ));
         Err(())
     } else {
         Ok(Expression::NumberLiteral(
@@ -1848,7 +1856,12 @@ pub fn expression(
                 } else {
                     get_int_length(&expr_type, loc, false, ns, diagnostics)?;
 
-                    Ok(Expression::UnaryMinus(*loc, expr_type, Box::new(expr)))
+                    Ok(Expression::UnaryMinus(
+                        *loc,
+                        expr_type,
+                        context.unchecked,
+                        Box::new(expr),
+                    ))
                 }
             }
         },
@@ -2910,6 +2923,7 @@ fn divide(
     Ok(Expression::Divide(
         *loc,
         ty.clone(),
+        context.unchecked,
         Box::new(left.cast(&l.loc(), &ty, true, ns, diagnostics)?),
         Box::new(right.cast(&r.loc(), &ty, true, ns, diagnostics)?),
     ))
@@ -3056,6 +3070,15 @@ fn constructor(
         }
     };
 
+    if ns.contracts[no].is_interface() {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            "cannot instantiate an interface".to_string(),
+        ));
+
+        return Err(());
+    }
+
     if !ns.contracts[no].instantiable {
         diagnostics.push(Diagnostic::error(
             *loc,
@@ -3254,6 +3277,15 @@ pub fn constructor_named_args(
         }
     };
 
+    if ns.contracts[no].is_interface() {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            "cannot instantiate an interface".to_string(),
+        ));
+
+        return Err(());
+    }
+
     if !ns.contracts[no].instantiable {
         diagnostics.push(Diagnostic::error(
             *loc,
@@ -3494,7 +3526,29 @@ pub fn type_name_expr(
                 Ok(Expression::InterfaceId(*loc, *n))
             }
         }
-        (Type::Contract(no), "creationCode") | (Type::Contract(no), "runtimeCode") => {
+        (Type::Contract(no), "creationCode")
+        | (Type::Contract(no), "runtimeCode")
+        | (Type::Contract(no), "codeHash") => {
+            if field.name == "codeHash" && !ns.target.is_substrate() {
+                diagnostics.push(Diagnostic::error(
+                    *loc,
+                    format!("type().codeHash is not available for target {}", ns.target),
+                ));
+                return Err(());
+            }
+
+            // on Solana all contracts are deployed together in one program
+            if ns.target == Target::Solana {
+                diagnostics.push(Diagnostic::error(
+                    *loc,
+                    format!(
+                        "type().{} is not available for target {}",
+                        field.name, ns.target
+                    ),
+                ));
+                return Err(());
+            }
+
             let contract_no = match context.contract_no {
                 Some(contract_no) => contract_no,
                 None => {
@@ -3536,11 +3590,15 @@ pub fn type_name_expr(
                 ns.contracts[contract_no].creates.push(*no);
             }
 
-            Ok(Expression::CodeLiteral(
-                *loc,
-                *no,
-                field.name == "runtimeCode",
-            ))
+            if field.name == "codeHash" {
+                Ok(Expression::CodeHash(*loc, *no))
+            } else {
+                Ok(Expression::CodeLiteral(
+                    *loc,
+                    *no,
+                    field.name == "runtimeCode",
+                ))
+            }
         }
         _ => {
             diagnostics.push(Diagnostic::error(
@@ -3760,6 +3818,10 @@ fn equal(
 
     let ty = coerce(&left_type, &l.loc(), &right_type, &r.loc(), ns, diagnostics)?;
 
+    if let Type::Struct(str_ty) = &ty {
+        check_struct_comparable(loc, str_ty, str_ty, ns, diagnostics)?;
+    }
+
     Ok(Expression::Equal(
         *loc,
         Box::new(left.cast(&l.loc(), &ty, true, ns, diagnostics)?),
@@ -3767,6 +3829,47 @@ fn equal(
     ))
 }
 
+/// Structs are compared field by field, so every field must be a value type or a struct
+/// which can be compared itself
+fn check_struct_comparable(
+    loc: &pt::Loc,
+    compared: &StructType,
+    str_ty: &StructType,
+    ns: &Namespace,
+    diagnostics: &mut Diagnostics,
+) -> Result<(), ()> {
+    for field in &str_ty.definition(ns).fields {
+        match &field.ty {
+            Type::Bool
+            | Type::Address(_)
+            | Type::Int(_)
+            | Type::Uint(_)
+            | Type::Bytes(_)
+            | Type::Enum(_)
+            | Type::Contract(_)
+            | Type::UserType(_) => (),
+            Type::Struct(field_ty) if !field.recursive => {
+                check_struct_comparable(loc, compared, field_ty, ns, diagnostics)?;
+            }
+            _ => {
+                diagnostics.push(Diagnostic::error(
+                    *loc,
+                    format!(
+                        "struct '{}' cannot be compared since field '{}' has type {}",
+                        compared.definition(ns),
+                        field.name_as_str(),
+                        field.ty.to_string(ns)
+                    ),
+                ));
+
+                return Err(());
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Try string concatenation
 fn addition(
     loc: &pt::Loc,
@@ -4120,9 +4223,13 @@ fn assign_expr(
                 Box::new(set),
                 ty.is_signed_int(),
             ),
-            pt::Expression::AssignDivide(..) => {
-                Expression::Divide(*loc, ty.clone(), Box::new(assign), Box::new(set))
-            }
+            pt::Expression::AssignDivide(..) => Expression::Divide(
+                *loc,
+                ty.clone(),
+                context.unchecked,
+                Box::new(assign),
+                Box::new(set),
+            ),
             pt::Expression::AssignModulo(..) => {
                 Expression::Modulo(*loc, ty.clone(), Box::new(assign), Box::new(set))
             }
@@ -4449,6 +4556,10 @@ fn member_access(
             return Ok(Expression::Builtin(*loc, vec![ty], builtin, vec![]));
         }
 
+        if builtin::builtin_unavailable(loc, Some(&namespace.name), &id.name, ns, diagnostics) {
+            return Err(());
+        }
+
         if builtin::builtin_namespace(&namespace.name) {
             diagnostics.push(Diagnostic::error(
                 e.loc(),
@@ -4597,6 +4708,16 @@ fn member_access(
         }
     }
 
+    // storage references have a '.slot' member, unless it is a struct with a field called slot
+    if let Type::StorageRef(_, ty) = &expr_ty {
+        let is_field = matches!(ty.as_ref(), Type::Struct(str_ty)
+            if str_ty.definition(ns).fields.iter().any(|field| field.name_as_str() == "slot"));
+
+        if id.name == "slot" && !is_field {
+            return storage_slot(loc, expr, context, ns, symtable, diagnostics);
+        }
+    }
+
     // Dereference if need to
     let (expr, expr_ty) = if let Type::Ref(ty) = &expr_ty {
         (
@@ -4845,6 +4966,35 @@ fn member_access(
     Err(())
 }
 
+/// Resolve `var.slot` for a storage reference. This is the slot for simple variables, and the
+/// computed key for mapping entries and array elements. The result is not an lvalue; writing to
+/// an arbitrary slot is only possible with inline assembly.
+fn storage_slot(
+    loc: &pt::Loc,
+    expr: Expression,
+    context: &ExprContext,
+    ns: &mut Namespace,
+    symtable: &mut Symtable,
+    diagnostics: &mut Diagnostics,
+) -> Result<Expression, ()> {
+    if context.lvalue {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            "cannot assign to slot of storage variable".to_string(),
+        ));
+        return Err(());
+    }
+
+    used_variable(ns, &expr, symtable);
+
+    Ok(Expression::Builtin(
+        *loc,
+        vec![ns.storage_type()],
+        Builtin::StorageSlot,
+        vec![expr],
+    ))
+}
+
 fn contract_constant(
     loc: &pt::Loc,
     e: &pt::Expression,
@@ -5698,6 +5848,34 @@ fn method_call_pos_args(
     diagnostics: &mut Diagnostics,
     resolve_to: ResolveTo,
 ) -> Result<Expression, ()> {
+    if let pt::Expression::Type(_, pt::Type::DynamicBytes) = var {
+        if func.name == "concat" {
+            if let Some(loc) = call_args_loc {
+                diagnostics.push(Diagnostic::error(
+                    loc,
+                    "call arguments not allowed on builtins".to_string(),
+                ));
+                return Err(());
+            }
+
+            return builtin::resolve_bytes_concat(loc, args, context, ns, symtable, diagnostics);
+        }
+    }
+
+    if let pt::Expression::Type(_, pt::Type::String) = var {
+        if func.name == "concat" {
+            if let Some(loc) = call_args_loc {
+                diagnostics.push(Diagnostic::error(
+                    loc,
+                    "call arguments not allowed on builtins".to_string(),
+                ));
+                return Err(());
+            }
+
+            return builtin::resolve_string_concat(loc, args, context, ns, symtable, diagnostics);
+        }
+    }
+
     if let pt::Expression::Variable(namespace) = var {
         if builtin::is_builtin_call(Some(&namespace.name), &func.name, ns) {
             if let Some(loc) = call_args_loc {
@@ -5720,6 +5898,10 @@ fn method_call_pos_args(
             );
         }
 
+        if builtin::builtin_unavailable(loc, Some(&namespace.name), &func.name, ns, diagnostics) {
+            return Err(());
+        }
+
         // is it a call to super
         if namespace.name == "super" {
             if let Some(cur_contract_no) = context.contract_no {
@@ -6366,6 +6548,86 @@ fn method_call_pos_args(
     }
 
     if let Type::Address(is_payable) = &var_ty.deref_any() {
+        if func.name == "transferAll" && ns.target.is_substrate() {
+            if !is_payable {
+                diagnostics.push(Diagnostic::error(
+                    *loc,
+                    "method 'transferAll' available on type 'address payable' not 'address'"
+                        .to_string(),
+                ));
+
+                return Err(());
+            }
+
+            if !args.is_empty() {
+                diagnostics.push(Diagnostic::error(
+                    *loc,
+                    format!("'transferAll' expects 0 arguments, {} provided", args.len()),
+                ));
+
+                return Err(());
+            }
+
+            if let Some(loc) = call_args_loc {
+                diagnostics.push(Diagnostic::error(
+                    loc,
+                    "call arguments not allowed on 'transferAll'".to_string(),
+                ));
+                return Err(());
+            }
+
+            let contract_no = match context.contract_no {
+                Some(contract_no) => contract_no,
+                None => {
+                    diagnostics.push(Diagnostic::error(
+                        *loc,
+                        "'transferAll' not allowed outside contract".to_string(),
+                    ));
+                    return Err(());
+                }
+            };
+
+            let address =
+                var_expr.cast(&var_expr.loc(), var_ty.deref_any(), true, ns, diagnostics)?;
+
+            // the account has to keep the minimum balance, else the transfer fails
+            let this = Expression::Cast(
+                *loc,
+                Type::Address(false),
+                Box::new(Expression::Builtin(
+                    *loc,
+                    vec![Type::Contract(contract_no)],
+                    Builtin::GetAddress,
+                    Vec::new(),
+                )),
+            );
+
+            let value = Expression::Subtract(
+                *loc,
+                Type::Value,
+                false,
+                Box::new(Expression::Builtin(
+                    *loc,
+                    vec![Type::Value],
+                    Builtin::Balance,
+                    vec![this],
+                )),
+                Box::new(Expression::Builtin(
+                    *loc,
+                    vec![Type::Value],
+                    Builtin::MinimumBalance,
+                    Vec::new(),
+                )),
+            );
+
+            return Ok(Expression::Builtin(
+                *loc,
+                vec![Type::Void],
+                Builtin::PayableTransfer,
+                vec![address, value],
+            ));
+        }
+
         if func.name == "transfer" || func.name == "send" {
             if !is_payable {
                 diagnostics.push(Diagnostic::error(
@@ -6435,14 +6697,27 @@ fn method_call_pos_args(
     if let Type::Address(payable) = &var_ty.deref_any() {
         let ty = match func.name.as_str() {
             "call" => Some(CallTy::Regular),
-            "delegatecall" if ns.target == Target::EVM => Some(CallTy::Delegate),
-            "staticcall" if ns.target == Target::EVM => Some(CallTy::Static),
+            "delegatecall" if ns.target == Target::EVM || ns.target.is_substrate() => {
+                Some(CallTy::Delegate)
+            }
+            "staticcall" if ns.target == Target::EVM || ns.target.is_substrate() => {
+                Some(CallTy::Static)
+            }
             _ => None,
         };
 
         if let Some(ty) = ty {
             let call_args = parse_call_args(call_args, true, context, ns, symtable, diagnostics)?;
 
+            if call_args.relaxed_decode {
+                diagnostics.push(Diagnostic::error(
+                    *loc,
+                    format!("'relaxedDecode' not valid for '{}'", func.name),
+                ));
+
+                return Err(());
+            }
+
             if ty != CallTy::Regular && call_args.value.is_some() {
                 diagnostics.push(Diagnostic::error(
                     *loc,
@@ -6452,6 +6727,31 @@ fn method_call_pos_args(
                 return Err(());
             }
 
+            // the read-only call flag was added in version 3 of the contracts pallet api
+            if let Target::Substrate { api_version, .. } = ns.target {
+                if ty == CallTy::Static && api_version < 3 {
+                    diagnostics.push(Diagnostic::error(
+                        *loc,
+                        format!(
+                            "'{}' requires substrate api version 3, but version {} was selected",
+                            func.name, api_version
+                        ),
+                    ));
+
+                    return Err(());
+                }
+            }
+
+            // the delegate call host function on Substrate has no gas limit
+            if ty == CallTy::Delegate && ns.target.is_substrate() && call_args.gas.is_some() {
+                diagnostics.push(Diagnostic::error(
+                    *loc,
+                    format!("'{}' cannot have gas specified on Substrate", func.name),
+                ));
+
+                return Err(());
+            }
+
             if args.len() != 1 {
                 diagnostics.push(Diagnostic::error(
                     *loc,
@@ -6531,6 +6831,35 @@ fn method_call_pos_args(
         }
     }
 
+    // unsigned integers have a toString() method, unless a library provides one
+    if let Type::Uint(_) = var_ty.deref_any() {
+        if func.name == "toString" {
+            if !args.is_empty() {
+                diagnostics.push(Diagnostic::error(
+                    func.loc,
+                    "method 'toString()' does not take any arguments".to_string(),
+                ));
+                return Err(());
+            }
+
+            if let Some(loc) = call_args_loc {
+                diagnostics.push(Diagnostic::error(
+                    loc,
+                    "call arguments not allowed on builtins".to_string(),
+                ));
+                return Err(());
+            }
+
+            let expr = var_expr.cast(&var.loc(), var_ty.deref_any(), true, ns, diagnostics)?;
+
+            // this is the same as "{}".format(expr)
+            return Ok(Expression::FormatString(
+                *loc,
+                vec![(FormatArg::Default, expr)],
+            ));
+        }
+    }
+
     diagnostics.push(Diagnostic::error(
         func.loc,
         format!("method '{}' does not exist", func.name),
@@ -7404,6 +7733,37 @@ fn parse_call_args(
 
                 res.seeds = Some(Box::new(expr));
             }
+            "relaxedDecode" => {
+                if !external_call {
+                    diagnostics.push(Diagnostic::error(
+                        arg.loc,
+                        "'relaxedDecode' not valid for constructors".to_string(),
+                    ));
+                    return Err(());
+                }
+
+                let expr = expression(
+                    &arg.expr,
+                    context,
+                    ns,
+                    symtable,
+                    diagnostics,
+                    ResolveTo::Type(&Type::Bool),
+                )?;
+
+                match expr.cast(&arg.expr.loc(), &Type::Bool, true, ns, diagnostics)? {
+                    Expression::BoolLiteral(_, value) => {
+                        res.relaxed_decode = value;
+                    }
+                    _ => {
+                        diagnostics.push(Diagnostic::error(
+                            arg.expr.loc(),
+                            "'relaxedDecode' must be a constant bool".to_string(),
+                        ));
+                        return Err(());
+                    }
+                }
+            }
             _ => {
                 diagnostics.push(Diagnostic::error(
                     arg.loc,
@@ -7483,6 +7843,43 @@ pub fn named_call_expr(
     Ok(expr)
 }
 
+/// Array lengths are `uint32`, or the storage type for storage arrays. Warn when an explicit
+/// cast truncates an array length to a narrower integer, e.g. to use it as a loop index.
+fn array_length_truncated(
+    loc: &pt::Loc,
+    expr: &Expression,
+    to: &Type,
+    ns: &Namespace,
+    diagnostics: &mut Diagnostics,
+) {
+    if !matches!(
+        expr,
+        Expression::Builtin(_, _, Builtin::ArrayLength, _) | Expression::StorageArrayLength { .. }
+    ) {
+        return;
+    }
+
+    let from = expr.ty();
+
+    let truncated = match (&from, to) {
+        (Type::Uint(from_bits), Type::Uint(to_bits)) => to_bits < from_bits,
+        // a signed type needs one more bit, else large lengths become negative
+        (Type::Uint(from_bits), Type::Int(to_bits)) => to_bits <= from_bits,
+        _ => false,
+    };
+
+    if truncated {
+        diagnostics.push(Diagnostic::warning(
+            *loc,
+            format!(
+                "array length of type '{}' is truncated to '{}'; lengths which do not fit will wrap",
+                from.to_string(ns),
+                to.to_string(ns)
+            ),
+        ));
+    }
+}
+
 /// Resolve any callable expression
 pub fn call_expr(
     loc: &pt::Loc,
@@ -7532,6 +7929,8 @@ pub fn call_expr(
                     ResolveTo::Unknown,
                 )?;
 
+                array_length_truncated(loc, &expr, &to, ns, diagnostics);
+
                 expr.cast(loc, &to, false, ns, diagnostics)
             };
         }
@@ -7633,6 +8032,10 @@ pub fn function_call_expr(
                 };
             }
 
+            if builtin::builtin_unavailable(&id.loc, None, &id.name, ns, diagnostics) {
+                return Err(());
+            }
+
             if context.constant {
                 diagnostics.push(Diagnostic::error(
                     *loc,
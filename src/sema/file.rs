@@ -18,6 +18,7 @@ impl File {
             path,
             line_starts,
             cache_no: Some(cache_no),
+            wrapping_math: false,
         }
     }
 
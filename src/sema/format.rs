@@ -89,18 +89,7 @@ pub fn string_format(
                         ));
                         return Err(());
                     }
-                } else if !matches!(
-                    arg_ty,
-                    Type::Uint(_)
-                        | Type::Int(_)
-                        | Type::Bytes(_)
-                        | Type::Enum(_)
-                        | Type::Address(_)
-                        | Type::Contract(_)
-                        | Type::String
-                        | Type::DynamicBytes
-                        | Type::Bool
-                ) {
+                } else if !can_format(arg_ty) {
                     diagnostics.push(Diagnostic::error(
                         arg.loc(),
                         String::from(
@@ -135,6 +124,22 @@ pub fn string_format(
     Ok(Expression::FormatString(*loc, format_args))
 }
 
+/// Can a value of this type be converted to text
+pub(super) fn can_format(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Uint(_)
+            | Type::Int(_)
+            | Type::Bytes(_)
+            | Type::Enum(_)
+            | Type::Address(_)
+            | Type::Contract(_)
+            | Type::String
+            | Type::DynamicBytes
+            | Type::Bool
+    )
+}
+
 fn parse_format_specifier(
     loc: pt::Loc,
     format_iterator: &mut Peekable<FormatIterator>,
@@ -266,7 +266,7 @@ pub fn contract_function(
             } else if func.ty == pt::FunctionTy::Constructor {
                 ns.diagnostics.push(Diagnostic::warning(
                     v.loc().unwrap(),
-                    format!("'{}': visibility for constructors is ignored", v),
+                    format!("'{}': visibility on constructors is deprecated", v),
                 ));
 
                 pt::Visibility::Public(v.loc())
@@ -0,0 +1,765 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Classify the loops in public functions by how many times they can iterate. Auditors can use
+//! this to spot functions whose gas cost grows with contract state, or worse, with values which
+//! are under the control of the caller.
+
+use super::ast::{
+    ArrayLength, DestructureField, Expression, Namespace, RetrieveType, Statement, Type,
+};
+use super::eval::{eval_const_number, type_min_max};
+use super::Recurse;
+use num_bigint::BigInt;
+use num_traits::{One, Signed, Zero};
+use serde::Serialize;
+use solang_parser::pt;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+/// The number of times a loop body can be executed
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LoopBound {
+    /// The number of iterations is known at compile time
+    Constant(BigInt),
+    /// The number of iterations depends on contract storage, e.g. the length of a storage array
+    State(String),
+    /// The number of iterations could not be determined; it may depend on the arguments
+    Unbounded,
+}
+
+impl LoopBound {
+    /// The bound of a loop nested inside another loop is the product of both bounds
+    pub fn multiply(&self, other: &LoopBound) -> LoopBound {
+        match (self, other) {
+            (LoopBound::Unbounded, _) | (_, LoopBound::Unbounded) => LoopBound::Unbounded,
+            (LoopBound::Constant(l), LoopBound::Constant(r)) => LoopBound::Constant(l * r),
+            (LoopBound::Constant(n), bound) | (bound, LoopBound::Constant(n)) if n.is_one() => {
+                bound.clone()
+            }
+            (l, r) => LoopBound::State(format!("{} * {}", l, r)),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            LoopBound::Constant(_) => "constant",
+            LoopBound::State(_) => "state",
+            LoopBound::Unbounded => "unbounded",
+        }
+    }
+}
+
+impl fmt::Display for LoopBound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoopBound::Constant(n) => write!(f, "{}", n),
+            LoopBound::State(s) => write!(f, "{}", s),
+            LoopBound::Unbounded => write!(f, "unbounded"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LoopKind {
+    For,
+    While,
+    DoWhile,
+}
+
+impl fmt::Display for LoopKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoopKind::For => write!(f, "for"),
+            LoopKind::While => write!(f, "while"),
+            LoopKind::DoWhile => write!(f, "do while"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoopInfo {
+    pub loc: pt::Loc,
+    pub kind: LoopKind,
+    /// Number of loops this loop is nested in
+    pub depth: usize,
+    /// Number of iterations of this loop on its own
+    pub bound: LoopBound,
+    /// Number of times the body is executed per function call, taking the enclosing loops
+    /// into account
+    pub total: LoopBound,
+}
+
+#[derive(Debug, Clone)]
+pub struct FunctionLoops {
+    pub contract_no: usize,
+    pub function_no: usize,
+    pub loops: Vec<LoopInfo>,
+}
+
+impl Namespace {
+    /// Classify the loops of all the public functions of concrete contracts. Functions without
+    /// loops are not listed.
+    pub fn loop_report(&self) -> Vec<FunctionLoops> {
+        let mut report = Vec::new();
+
+        for (contract_no, contract) in self.contracts.iter().enumerate() {
+            if !contract.is_concrete() {
+                continue;
+            }
+
+            for function_no in contract.all_functions.keys() {
+                let func = &self.functions[*function_no];
+
+                if !func.is_public() || !func.has_body {
+                    continue;
+                }
+
+                let mut analysis = Analysis::new(&func.body, self);
+
+                analysis.statements(&func.body, &LoopBound::Constant(BigInt::one()), 0);
+
+                if !analysis.loops.is_empty() {
+                    report.push(FunctionLoops {
+                        contract_no,
+                        function_no: *function_no,
+                        loops: analysis.loops,
+                    });
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Render the loop report in human readable form
+    pub fn loop_report_text(&self, report: &[FunctionLoops]) -> String {
+        let mut out = String::new();
+
+        for func in report {
+            out.push_str(&format!(
+                "{}.{}:\n",
+                self.contracts[func.contract_no].name, self.functions[func.function_no].signature
+            ));
+
+            for info in &func.loops {
+                let bound = match &info.bound {
+                    LoopBound::Constant(n) => format!("constant bound {}", n),
+                    LoopBound::State(s) => format!("bounded by state {}", s),
+                    LoopBound::Unbounded => "unbounded".to_string(),
+                };
+
+                out.push_str(&format!(
+                    "{}{} loop at {}: {}",
+                    "  ".repeat(info.depth + 1),
+                    info.kind,
+                    self.loc_to_string(&info.loc),
+                    bound
+                ));
+
+                if info.depth > 0 && info.bound != LoopBound::Unbounded {
+                    out.push_str(&format!(", total {}", info.total));
+                }
+
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+
+    /// Render the loop report as json
+    pub fn loop_report_json(&self, report: &[FunctionLoops]) -> String {
+        let functions: Vec<JsonFunction> = report
+            .iter()
+            .map(|func| JsonFunction {
+                contract: &self.contracts[func.contract_no].name,
+                function: &self.functions[func.function_no].signature,
+                loops: func
+                    .loops
+                    .iter()
+                    .map(|info| JsonLoop {
+                        location: self.loc_to_string(&info.loc),
+                        kind: info.kind.to_string(),
+                        depth: info.depth,
+                        bound: JsonBound::from(&info.bound),
+                        total: JsonBound::from(&info.total),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&functions).unwrap()
+    }
+}
+
+#[derive(Serialize)]
+struct JsonFunction<'a> {
+    contract: &'a str,
+    function: &'a str,
+    loops: Vec<JsonLoop>,
+}
+
+#[derive(Serialize)]
+struct JsonLoop {
+    location: String,
+    kind: String,
+    depth: usize,
+    bound: JsonBound,
+    total: JsonBound,
+}
+
+#[derive(Serialize)]
+struct JsonBound {
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+}
+
+impl From<&LoopBound> for JsonBound {
+    fn from(bound: &LoopBound) -> Self {
+        JsonBound {
+            kind: bound.kind(),
+            value: match bound {
+                LoopBound::Unbounded => None,
+                _ => Some(bound.to_string()),
+            },
+        }
+    }
+}
+
+/// A loop condition `var <cmp> limit`, normalized so that the variable is on the left
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cmp {
+    Less,
+    LessEqual,
+    More,
+    MoreEqual,
+    NotEqual,
+}
+
+impl Cmp {
+    fn flip(self) -> Cmp {
+        match self {
+            Cmp::Less => Cmp::More,
+            Cmp::LessEqual => Cmp::MoreEqual,
+            Cmp::More => Cmp::Less,
+            Cmp::MoreEqual => Cmp::LessEqual,
+            Cmp::NotEqual => Cmp::NotEqual,
+        }
+    }
+}
+
+/// A variable which is incremented or decremented by a constant step once per iteration
+struct Induction {
+    var_no: usize,
+    step: BigInt,
+    increasing: bool,
+}
+
+/// Variables which are assigned to in a list of statements
+#[derive(Default)]
+struct Assigned {
+    vars: HashSet<usize>,
+    /// Inline assembly can assign any variable
+    assembly: bool,
+    /// Initializers of variable declarations
+    decls: HashMap<usize, Arc<Expression>>,
+}
+
+impl Assigned {
+    fn new(stmts: &[Statement]) -> Self {
+        let mut assigned = Assigned::default();
+
+        for stmt in stmts {
+            stmt.recurse(&mut assigned, assigned_statement);
+        }
+
+        assigned
+    }
+}
+
+fn assigned_statement(stmt: &Statement, assigned: &mut Assigned) -> bool {
+    match stmt {
+        Statement::VariableDecl(_, var_no, _, Some(init)) => {
+            assigned.decls.insert(*var_no, init.clone());
+            init.recurse(assigned, assigned_expression);
+        }
+        Statement::If(_, _, expr, ..)
+        | Statement::While(_, _, expr, _)
+        | Statement::DoWhile(_, _, _, expr)
+        | Statement::Expression(_, _, expr)
+        | Statement::Delete(_, _, expr)
+        | Statement::Return(_, Some(expr))
+        | Statement::For {
+            cond: Some(expr), ..
+        } => {
+            expr.recurse(assigned, assigned_expression);
+        }
+        Statement::Destructure(_, fields, expr) => {
+            for field in fields {
+                if let DestructureField::Expression(Expression::Variable(_, _, var_no)) = field {
+                    assigned.vars.insert(*var_no);
+                }
+            }
+
+            expr.recurse(assigned, assigned_expression);
+        }
+        Statement::Emit { args, .. } | Statement::Revert { args, .. } => {
+            for arg in args {
+                arg.recurse(assigned, assigned_expression);
+            }
+        }
+        Statement::TryCatch(_, _, try_catch) => {
+            try_catch.expr.recurse(assigned, assigned_expression);
+
+            for (var_no, _) in &try_catch.returns {
+                if let Some(var_no) = var_no {
+                    assigned.vars.insert(*var_no);
+                }
+            }
+        }
+        Statement::Assembly(..) => {
+            assigned.assembly = true;
+        }
+        _ => (),
+    }
+
+    true
+}
+
+fn assigned_expression(expr: &Expression, assigned: &mut Assigned) -> bool {
+    match expr {
+        Expression::Assign(_, _, var, _)
+        | Expression::PreIncrement(_, _, _, var)
+        | Expression::PreDecrement(_, _, _, var)
+        | Expression::PostIncrement(_, _, _, var)
+        | Expression::PostDecrement(_, _, _, var) => {
+            if let Some((var_no, _)) = variable(var) {
+                assigned.vars.insert(var_no);
+            }
+        }
+        _ => (),
+    }
+
+    true
+}
+
+/// Remove any casts
+fn strip_casts(expr: &Expression) -> &Expression {
+    match expr {
+        Expression::ZeroExt(_, _, expr)
+        | Expression::SignExt(_, _, expr)
+        | Expression::Trunc(_, _, expr)
+        | Expression::CheckingTrunc(_, _, expr)
+        | Expression::Cast(_, _, expr) => strip_casts(expr),
+        _ => expr,
+    }
+}
+
+/// If the expression is a local variable, return its number and type
+fn variable(expr: &Expression) -> Option<(usize, &Type)> {
+    match strip_casts(expr) {
+        Expression::Variable(_, ty, var_no) => Some((*var_no, ty)),
+        _ => None,
+    }
+}
+
+/// Does this list of statements have a continue which applies to the enclosing loop
+fn has_continue(stmts: &[Statement]) -> bool {
+    let mut found = false;
+
+    for stmt in stmts {
+        stmt.recurse(&mut found, |stmt, found| match stmt {
+            Statement::Continue(_) => {
+                *found = true;
+                false
+            }
+            // continue in a nested loop applies to that loop
+            Statement::For { .. } | Statement::While(..) | Statement::DoWhile(..) => false,
+            _ => true,
+        });
+    }
+
+    found
+}
+
+struct Analysis<'a> {
+    ns: &'a Namespace,
+    /// Local variables which are never assigned after their declaration, with their initializer
+    constants: HashMap<usize, Arc<Expression>>,
+    loops: Vec<LoopInfo>,
+}
+
+impl<'a> Analysis<'a> {
+    fn new(body: &[Statement], ns: &'a Namespace) -> Self {
+        let assigned = Assigned::new(body);
+
+        let constants = if assigned.assembly {
+            HashMap::new()
+        } else {
+            assigned
+                .decls
+                .into_iter()
+                .filter(|(var_no, _)| !assigned.vars.contains(var_no))
+                .collect()
+        };
+
+        Analysis {
+            ns,
+            constants,
+            loops: Vec::new(),
+        }
+    }
+
+    fn statements(&mut self, stmts: &[Statement], outer: &LoopBound, depth: usize) {
+        for (i, stmt) in stmts.iter().enumerate() {
+            let prev = if i > 0 { stmts.get(i - 1) } else { None };
+
+            match stmt {
+                Statement::Block { statements, .. } => {
+                    self.statements(statements, outer, depth);
+                }
+                Statement::If(_, _, _, then_stmt, else_stmt) => {
+                    self.statements(then_stmt, outer, depth);
+                    self.statements(else_stmt, outer, depth);
+                }
+                Statement::For {
+                    loc,
+                    init,
+                    cond,
+                    next,
+                    body,
+                    ..
+                } => {
+                    self.statements(init, outer, depth);
+
+                    let bound = match (cond, next.as_slice()) {
+                        (Some(cond), [step]) => {
+                            self.loop_bound(init.last(), cond, step, body, false)
+                        }
+                        _ => LoopBound::Unbounded,
+                    };
+
+                    self.add_loop(*loc, LoopKind::For, bound, outer, depth, body);
+                }
+                Statement::While(loc, _, cond, body) => {
+                    let bound = match body.split_last() {
+                        Some((step, body)) if !has_continue(body) => {
+                            self.loop_bound(prev, cond, step, body, false)
+                        }
+                        _ => LoopBound::Unbounded,
+                    };
+
+                    self.add_loop(*loc, LoopKind::While, bound, outer, depth, body);
+                }
+                Statement::DoWhile(loc, _, body, cond) => {
+                    let bound = match body.split_last() {
+                        Some((step, body)) if !has_continue(body) => {
+                            self.loop_bound(prev, cond, step, body, true)
+                        }
+                        _ => LoopBound::Unbounded,
+                    };
+
+                    self.add_loop(*loc, LoopKind::DoWhile, bound, outer, depth, body);
+                }
+                Statement::TryCatch(_, _, try_catch) => {
+                    self.statements(&try_catch.ok_stmt, outer, depth);
+
+                    for (_, _, stmts) in &try_catch.errors {
+                        self.statements(stmts, outer, depth);
+                    }
+
+                    self.statements(&try_catch.catch_stmt, outer, depth);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn add_loop(
+        &mut self,
+        loc: pt::Loc,
+        kind: LoopKind,
+        bound: LoopBound,
+        outer: &LoopBound,
+        depth: usize,
+        body: &[Statement],
+    ) {
+        let total = outer.multiply(&bound);
+
+        self.loops.push(LoopInfo {
+            loc,
+            kind,
+            depth,
+            bound,
+            total: total.clone(),
+        });
+
+        self.statements(body, &total, depth + 1);
+    }
+
+    /// Work out the bound of a loop with the given condition and induction step. The body
+    /// excludes the step, and the init is the statement which sets the start value.
+    fn loop_bound(
+        &self,
+        init: Option<&Statement>,
+        cond: &Expression,
+        step: &Statement,
+        body: &[Statement],
+        do_while: bool,
+    ) -> LoopBound {
+        let induction = match induction(step, self.ns) {
+            Some(induction) => induction,
+            None => return LoopBound::Unbounded,
+        };
+
+        let assigned = Assigned::new(body);
+
+        if assigned.assembly || assigned.vars.contains(&induction.var_no) {
+            return LoopBound::Unbounded;
+        }
+
+        let (cmp, ty, limit) = match condition(cond, induction.var_no) {
+            Some(cond) => cond,
+            None => return LoopBound::Unbounded,
+        };
+
+        // the condition must be counting towards the limit
+        match (induction.increasing, cmp) {
+            (true, Cmp::Less | Cmp::LessEqual) | (false, Cmp::More | Cmp::MoreEqual) => (),
+            (_, Cmp::NotEqual) if induction.step.is_one() => (),
+            _ => return LoopBound::Unbounded,
+        }
+
+        let start = init.and_then(|init| start_value(init, induction.var_no));
+
+        // with != the loop only ends when the variable hits the limit exactly, so the start must
+        // be a known constant which is not past the limit
+        if cmp == Cmp::NotEqual {
+            let start = match start.map(|e| self.classify(e)) {
+                Some(LoopBound::Constant(start)) => start,
+                _ => return LoopBound::Unbounded,
+            };
+
+            let reaches_limit = match self.classify(limit) {
+                LoopBound::Constant(limit) => {
+                    let before = if induction.increasing {
+                        start < limit
+                    } else {
+                        start > limit
+                    };
+
+                    // the body of a do while loop runs before the first comparison
+                    before || (!do_while && start == limit)
+                }
+                // an increasing variable which starts at the smallest value of its type cannot
+                // be past any limit
+                _ => {
+                    induction.increasing
+                        && !do_while
+                        && matches!(ty, Type::Uint(_) | Type::Int(_))
+                        && start == type_min_max(ty).0
+                }
+            };
+
+            if !reaches_limit {
+                return LoopBound::Unbounded;
+            }
+        }
+
+        let (low, high) = if induction.increasing {
+            (start, Some(limit))
+        } else {
+            (Some(limit), start)
+        };
+
+        let high = high.map_or(LoopBound::Unbounded, |e| self.classify(e));
+
+        // an unsigned variable cannot go below zero
+        let low = match low.map(|e| self.classify(e)) {
+            Some(LoopBound::Constant(n)) => LoopBound::Constant(n),
+            _ if !ty.is_signed_int() => LoopBound::Constant(BigInt::zero()),
+            Some(bound) => bound,
+            None => LoopBound::Unbounded,
+        };
+
+        let inclusive = matches!(cmp, Cmp::LessEqual | Cmp::MoreEqual);
+
+        match (low, high) {
+            (LoopBound::Constant(low), LoopBound::Constant(high)) => {
+                // if the limit is at or past the end of the range of the variable, the condition
+                // is always true, and the loop only ends when the variable overflows
+                if let Type::Uint(_) | Type::Int(_) = ty {
+                    let (min, max) = type_min_max(ty);
+
+                    let always_true = if induction.increasing {
+                        high > max || (inclusive && high == max)
+                    } else {
+                        low < min || (inclusive && low == min)
+                    };
+
+                    if always_true {
+                        return LoopBound::Unbounded;
+                    }
+                }
+
+                let mut count = high - low;
+
+                if inclusive {
+                    count += 1;
+                }
+
+                let mut iterations = if count.is_positive() {
+                    (count + &induction.step - 1) / &induction.step
+                } else {
+                    BigInt::zero()
+                };
+
+                // the body of a do while loop is executed at least once
+                if do_while && iterations.is_zero() {
+                    iterations = BigInt::one();
+                }
+
+                LoopBound::Constant(iterations)
+            }
+            (LoopBound::Unbounded, _) | (_, LoopBound::Unbounded) => LoopBound::Unbounded,
+            (_, LoopBound::State(state)) | (LoopBound::State(state), _) => LoopBound::State(state),
+        }
+    }
+
+    /// Classify the value of an expression used as a loop limit
+    fn classify(&self, expr: &Expression) -> LoopBound {
+        if let Ok((_, n)) = eval_const_number(expr, self.ns) {
+            return LoopBound::Constant(n);
+        }
+
+        match strip_casts(expr) {
+            Expression::Variable(_, _, var_no) => match self.constants.get(var_no) {
+                Some(init) => self.classify(init),
+                None => LoopBound::Unbounded,
+            },
+            Expression::StorageArrayLength { array, .. } => match array.ty().deref_any() {
+                Type::Array(_, dims) => match dims.last() {
+                    Some(ArrayLength::Fixed(len)) => LoopBound::Constant(len.clone()),
+                    _ => LoopBound::State(format!("{}.length", self.describe(array))),
+                },
+                _ => LoopBound::State(format!("{}.length", self.describe(array))),
+            },
+            Expression::StorageLoad(_, _, expr) => LoopBound::State(self.describe(expr)),
+            Expression::Add(_, _, _, l, r) => self.binary(l, "+", r),
+            Expression::Subtract(_, _, _, l, r) => self.binary(l, "-", r),
+            Expression::Multiply(_, _, _, l, r) => self.binary(l, "*", r),
+            Expression::Divide(_, _, _, l, r) => self.binary(l, "/", r),
+            _ => LoopBound::Unbounded,
+        }
+    }
+
+    fn binary(&self, l: &Expression, op: &str, r: &Expression) -> LoopBound {
+        match (self.classify(l), self.classify(r)) {
+            (LoopBound::Unbounded, _) | (_, LoopBound::Unbounded) => LoopBound::Unbounded,
+            (l, r) => LoopBound::State(format!("{} {} {}", l, op, r)),
+        }
+    }
+
+    /// Name of a storage location, for use in the report
+    fn describe(&self, expr: &Expression) -> String {
+        match strip_casts(expr) {
+            Expression::StorageVariable(_, _, contract_no, var_no) => {
+                self.ns.contracts[*contract_no].variables[*var_no]
+                    .name
+                    .to_string()
+            }
+            Expression::StorageLoad(_, _, expr) => self.describe(expr),
+            Expression::Subscript(_, _, _, array, _) => format!("{}[...]", self.describe(array)),
+            Expression::StructMember(_, _, expr, field_no) => match expr.ty().deref_any() {
+                Type::Struct(str_ty) => format!(
+                    "{}.{}",
+                    self.describe(expr),
+                    str_ty.definition(self.ns).fields[*field_no].name_as_str()
+                ),
+                _ => self.describe(expr),
+            },
+            _ => "storage".to_string(),
+        }
+    }
+}
+
+/// Recognize the induction step of a loop, e.g. `i++` or `i += 2`
+fn induction(stmt: &Statement, ns: &Namespace) -> Option<Induction> {
+    let expr = match stmt {
+        Statement::Expression(_, _, expr) => expr,
+        _ => return None,
+    };
+
+    let (var_no, step, increasing) = match expr {
+        Expression::PreIncrement(_, _, _, var) | Expression::PostIncrement(_, _, _, var) => {
+            (variable(var)?.0, BigInt::one(), true)
+        }
+        Expression::PreDecrement(_, _, _, var) | Expression::PostDecrement(_, _, _, var) => {
+            (variable(var)?.0, BigInt::one(), false)
+        }
+        Expression::Assign(_, _, var, value) => {
+            let (var_no, _) = variable(var)?;
+
+            let (l, r, increasing) = match value.as_ref() {
+                Expression::Add(_, _, _, l, r) => (l, r, true),
+                Expression::Subtract(_, _, _, l, r) => (l, r, false),
+                _ => return None,
+            };
+
+            if variable(l)?.0 != var_no {
+                return None;
+            }
+
+            (var_no, eval_const_number(r, ns).ok()?.1, increasing)
+        }
+        _ => return None,
+    };
+
+    if !step.is_positive() {
+        return None;
+    }
+
+    Some(Induction {
+        var_no,
+        step,
+        increasing,
+    })
+}
+
+/// Normalize a loop condition into `var <cmp> limit`
+fn condition(cond: &Expression, var_no: usize) -> Option<(Cmp, &Type, &Expression)> {
+    let (cmp, l, r) = match cond {
+        Expression::Less(_, l, r) => (Cmp::Less, l, r),
+        Expression::LessEqual(_, l, r) => (Cmp::LessEqual, l, r),
+        Expression::More(_, l, r) => (Cmp::More, l, r),
+        Expression::MoreEqual(_, l, r) => (Cmp::MoreEqual, l, r),
+        Expression::NotEqual(_, l, r) => (Cmp::NotEqual, l, r),
+        // sema resolves `a != b` to `!(a == b)`
+        Expression::Not(_, expr) => match expr.as_ref() {
+            Expression::Equal(_, l, r) => (Cmp::NotEqual, l, r),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    match (variable(l), variable(r)) {
+        (Some((no, ty)), _) if no == var_no => Some((cmp, ty, r.as_ref())),
+        (_, Some((no, ty))) if no == var_no => Some((cmp.flip(), ty, l.as_ref())),
+        _ => None,
+    }
+}
+
+/// The value a variable is set to by the statement before the loop
+fn start_value(stmt: &Statement, var_no: usize) -> Option<&Expression> {
+    match stmt {
+        Statement::VariableDecl(_, no, _, Some(init)) if *no == var_no => Some(init.as_ref()),
+        Statement::Expression(_, _, Expression::Assign(_, _, var, value))
+            if variable(var).map(|(no, _)| no) == Some(var_no) =>
+        {
+            Some(value.as_ref())
+        }
+        _ => None,
+    }
+}
@@ -23,6 +23,7 @@ pub(crate) mod expression;
 mod file;
 mod format;
 mod functions;
+pub mod loop_report;
 mod mutability;
 mod namespace;
 mod statements;
@@ -96,7 +97,7 @@ fn sema_file(file: &ResolvedFile, resolver: &mut FileResolver, ns: &mut ast::Nam
     for part in &pt.0 {
         match part {
             pt::SourceUnitPart::PragmaDirective(loc, name, value) => {
-                resolve_pragma(loc, name, value, ns);
+                resolve_pragma(loc, name, value, file_no, ns);
             }
             pt::SourceUnitPart::ImportDirective(import) => {
                 resolve_import(import, Some(file), file_no, resolver, ns);
@@ -140,17 +141,18 @@ fn sema_file(file: &ResolvedFile, resolver: &mut FileResolver, ns: &mut ast::Nam
         doc_comment_start = part.loc().end();
     }
 
-    // Now we can resolve the global using directives
+    // the global using directives are resolved with the contracts, since they may refer
+    // to library functions
+    let mut using = Vec::new();
+
     for part in &pt.0 {
-        if let pt::SourceUnitPart::Using(using) = part {
-            if let Ok(using) = using::using_decl(using, file_no, None, ns) {
-                ns.using.push(using);
-            }
+        if let pt::SourceUnitPart::Using(decl) = part {
+            using.push(decl.as_ref());
         }
     }
 
     // now resolve the contracts
-    contracts::resolve(&contracts_to_resolve, &comments, file_no, ns);
+    contracts::resolve(&contracts_to_resolve, &using, &comments, file_no, ns);
 
     // now we can resolve the body of functions outside of contracts
     for (func_no, func) in resolve_bodies {
@@ -353,18 +355,32 @@ fn resolve_import(
     }
 }
 
-/// Resolve pragma. We don't do anything with pragmas for now
+/// Resolve pragma. The only pragma which has any effect is the solidity version; if it only
+/// allows versions before 0.8.0, arithmetic in the file wraps on overflow like it did then.
 fn resolve_pragma(
     loc: &pt::Loc,
     name: &pt::Identifier,
     value: &pt::StringLiteral,
+    file_no: usize,
     ns: &mut ast::Namespace,
 ) {
     if name.name == "solidity" {
-        ns.diagnostics.push(ast::Diagnostic::debug(
-            *loc,
-            "pragma 'solidity' is ignored".to_string(),
-        ));
+        if version_req_before_0_8(&value.string) {
+            ns.files[file_no].wrapping_math = true;
+
+            ns.diagnostics.push(ast::Diagnostic::warning(
+                *loc,
+                format!(
+                    "pragma 'solidity' with value '{}' is before 0.8.0, so arithmetic wraps on overflow",
+                    value.string
+                ),
+            ));
+        } else {
+            ns.diagnostics.push(ast::Diagnostic::debug(
+                *loc,
+                "pragma 'solidity' is ignored".to_string(),
+            ));
+        }
     } else if name.name == "experimental" && value.string == "ABIEncoderV2" {
         ns.diagnostics.push(ast::Diagnostic::debug(
             *loc,
@@ -386,6 +402,59 @@ fn resolve_pragma(
     }
 }
 
+/// Does the version requirement of a solidity pragma, like `^0.7.0` or `>=0.6.0 <0.8.0`, exclude
+/// every version from 0.8.0 onwards. Alternatives separated by `||` must all exclude them.
+fn version_req_before_0_8(req: &str) -> bool {
+    // a missing or wildcard minor or patch version is None
+    fn parse_version(s: &str) -> Option<(u64, Option<u64>, Option<u64>)> {
+        let mut parts = s.split('.').map(|part| match part {
+            "x" | "X" | "*" => Ok(None),
+            _ => part.parse().map(Some),
+        });
+
+        let major = parts.next()?.ok()??;
+        let minor = parts.next().unwrap_or(Ok(None)).ok()?;
+        let patch = parts.next().unwrap_or(Ok(None)).ok()?;
+
+        Some((major, minor, patch))
+    }
+
+    req.split("||").all(|alternative| {
+        // the operator may be separated from the version by whitespace, like `>= 0.6.0`
+        let mut comparators: Vec<String> = Vec::new();
+        let mut op = String::new();
+
+        for token in alternative.split_whitespace() {
+            if token.chars().all(|c| "^~=<>".contains(c)) {
+                op.push_str(token);
+            } else {
+                comparators.push(format!("{}{}", op, token));
+                op.clear();
+            }
+        }
+
+        comparators.iter().any(|comparator| {
+            let (op, version) = match comparator.find(|c: char| c.is_ascii_digit()) {
+                Some(pos) => comparator.split_at(pos),
+                None => return false,
+            };
+
+            let (major, minor, patch) = match parse_version(version) {
+                Some(version) => version,
+                None => return false,
+            };
+
+            match (op, minor) {
+                // a caret on 0.x only allows 0.x, and tilde never changes the minor version
+                ("^" | "~" | "" | "=", Some(minor)) => major == 0 && minor < 8,
+                ("<", _) => (major, minor.unwrap_or(0), patch.unwrap_or(0)) <= (0, 8, 0),
+                ("<=", Some(minor)) => major == 0 && minor < 8,
+                _ => false,
+            }
+        })
+    })
+}
+
 pub trait Recurse {
     type ArgType;
     /// recurse over a structure
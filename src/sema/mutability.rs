@@ -1,8 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::ast::{
-    Builtin, DestructureField, Diagnostic, Expression, Function, Mutability, Namespace, Statement,
-    Type,
+    Builtin, CallTy, DestructureField, Diagnostic, Expression, Function, Mutability, Namespace,
+    Statement, Type,
 };
 use crate::sema::ast::RetrieveType;
 use crate::sema::yul::ast::{YulExpression, YulStatement};
@@ -218,6 +218,11 @@ fn recurse_statements(stmts: &[Statement], ns: &Namespace, state: &mut StateChec
                 recurse_statements(&try_catch.catch_stmt, ns, state);
             }
             Statement::Emit { loc, .. } => state.write(loc),
+            Statement::Revert { args, .. } => {
+                for arg in args {
+                    arg.recurse(state, read_expression);
+                }
+            }
             Statement::Break(_) | Statement::Continue(_) | Statement::Underscore(_) => (),
             Statement::Assembly(inline_assembly, _) => {
                 for function_no in inline_assembly.functions.start..inline_assembly.functions.end {
@@ -286,8 +291,9 @@ fn read_expression(expr: &Expression, state: &mut StateCheck) -> bool {
             }
             _ => unreachable!(),
         },
-        Expression::ExternalFunctionCallRaw { loc, .. } => {
-            if state.ns.target.is_substrate() {
+        Expression::ExternalFunctionCallRaw { loc, ty, .. } => {
+            // a static call cannot modify state, so it only reads
+            if state.ns.target.is_substrate() && *ty != CallTy::Static {
                 state.write(loc)
             } else {
                 state.read(loc)
@@ -30,6 +30,7 @@ impl Namespace {
             Target::Substrate {
                 address_length,
                 value_length,
+                ..
             } => (address_length, value_length),
             Target::Solana => (32, 8),
         };
@@ -40,6 +41,7 @@ impl Namespace {
             enums: Vec::new(),
             structs: Vec::new(),
             events: Vec::new(),
+            errors: Vec::new(),
             using: Vec::new(),
             contracts: Vec::new(),
             user_types: Vec::new(),
@@ -138,6 +140,14 @@ impl Namespace {
                         "location of previous definition".to_string(),
                     ));
                 }
+                Symbol::Error(loc, _) => {
+                    self.diagnostics.push(Diagnostic::error_with_note(
+                        id.loc,
+                        format!("{} is already defined as an error", id.name),
+                        *loc,
+                        "location of previous definition".to_string(),
+                    ));
+                }
                 Symbol::Variable(c, _, _) => {
                     self.diagnostics.push(Diagnostic::error_with_note(
                         id.loc,
@@ -227,6 +237,14 @@ impl Namespace {
                             "location of previous definition".to_string(),
                         ));
                     }
+                    Symbol::Error(loc, _) => {
+                        self.diagnostics.push(Diagnostic::warning_with_note(
+                            id.loc,
+                            format!("{} is already defined as an error", id.name),
+                            *loc,
+                            "location of previous definition".to_string(),
+                        ));
+                    }
                     Symbol::Variable(c, _, _) => {
                         self.diagnostics.push(Diagnostic::warning_with_note(
                             id.loc,
@@ -361,6 +379,33 @@ impl Namespace {
         }
     }
 
+    /// Resolve an error name with namespace. We should only be resolving errors for revert statements
+    pub fn resolve_error(
+        &mut self,
+        file_no: usize,
+        contract_no: Option<usize>,
+        name: &pt::IdentifierPath,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<usize, ()> {
+        let (id, namespace) = name
+            .identifiers
+            .split_last()
+            .map(|(id, namespace)| (id, namespace.iter().collect()))
+            .unwrap();
+
+        let s = self.resolve_namespace(namespace, file_no, contract_no, id, diagnostics)?;
+
+        if let Some(Symbol::Error(_, error_no)) = s {
+            Ok(*error_no)
+        } else {
+            let error = Namespace::wrong_symbol(s, id);
+
+            diagnostics.push(error);
+
+            Err(())
+        }
+    }
+
     /// Resolve an event. We should only be resolving events for emit statements
     pub fn resolve_event(
         &mut self,
@@ -496,6 +541,9 @@ impl Namespace {
             Some(Symbol::Event(_)) => {
                 Diagnostic::decl_error(id.loc, format!("'{}' is an event", id.name))
             }
+            Some(Symbol::Error(..)) => {
+                Diagnostic::decl_error(id.loc, format!("'{}' is an error", id.name))
+            }
             Some(Symbol::Function(_)) => {
                 Diagnostic::decl_error(id.loc, format!("'{}' is a function", id.name))
             }
@@ -693,6 +741,15 @@ impl Namespace {
                     notes,
                 ));
             }
+            Some(Symbol::Error(loc, _)) => {
+                let loc = *loc;
+                self.diagnostics.push(Diagnostic::warning_with_note(
+                    id.loc,
+                    format!("declaration of '{}' shadows error definition", id.name),
+                    loc,
+                    "previous definition of error".to_string(),
+                ));
+            }
             Some(Symbol::Function(v)) => {
                 let notes = v
                     .iter()
@@ -1034,9 +1091,24 @@ impl Namespace {
             _ => unreachable!(),
         };
 
+        let is_plain = namespace.is_empty();
+
         let s = self.resolve_namespace(namespace, file_no, contract_no, &id, diagnostics)?;
 
         match s {
+            // The native balance type of the target, unless something else has that name
+            None if is_plain && id.name == "valueType" => {
+                let ty = Type::Uint(self.value_length as u16 * 8);
+
+                if dimensions.is_empty() {
+                    Ok(ty)
+                } else {
+                    Ok(Type::Array(
+                        Box::new(ty),
+                        resolve_dimensions(&dimensions, diagnostics)?,
+                    ))
+                }
+            }
             None => {
                 diagnostics.push(Diagnostic::decl_error(
                     id.loc,
@@ -1066,6 +1138,13 @@ impl Namespace {
                 ));
                 Err(())
             }
+            Some(Symbol::Error(..)) => {
+                diagnostics.push(Diagnostic::decl_error(
+                    id.loc,
+                    format!("'{}' is an error", id.name),
+                ));
+                Err(())
+            }
             Some(Symbol::Function(_)) => {
                 diagnostics.push(Diagnostic::decl_error(
                     id.loc,
@@ -1162,6 +1241,13 @@ impl Namespace {
                     ));
                     return Err(());
                 }
+                Some(Symbol::Error(..)) => {
+                    diagnostics.push(Diagnostic::decl_error(
+                        contract_name.loc,
+                        format!("'{}' is an error", contract_name.name),
+                    ));
+                    return Err(());
+                }
                 Some(Symbol::Struct(..)) => {
                     diagnostics.push(Diagnostic::decl_error(
                         contract_name.loc,
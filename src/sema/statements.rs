@@ -36,7 +36,7 @@ pub fn resolve_function_body(
         file_no,
         contract_no,
         function_no: Some(function_no),
-        unchecked: false,
+        unchecked: ns.files[file_no].wrapping_math,
         constant: false,
         lvalue: false,
         yul_function: false,
@@ -385,8 +385,15 @@ fn statement(
             let mut context = context.clone();
             context.unchecked |= *unchecked;
 
+            let mut target_branch_ended = false;
+
             for stmt in statements {
                 if !reachable {
+                    // The rest of the block is for the other targets
+                    if target_branch_ended {
+                        break;
+                    }
+
                     ns.diagnostics.push(Diagnostic::error(
                         stmt.loc(),
                         "unreachable statement".to_string(),
@@ -394,6 +401,14 @@ fn statement(
                     return Err(());
                 }
                 reachable = statement(stmt, res, &context, symtable, loops, ns, diagnostics)?;
+
+                target_branch_ended = matches!(
+                    stmt,
+                    pt::Statement::If(_, cond, ..) if matches!(
+                        target_condition(cond, &context, symtable, ns, &mut Diagnostics::default()),
+                        Ok(Some(_))
+                    )
+                );
             }
 
             symtable.leave_scope();
@@ -486,6 +501,40 @@ fn statement(
             Ok(true)
         }
         pt::Statement::If(loc, cond_expr, then, else_) => {
+            if let Some(is_target) =
+                target_condition(cond_expr, context, symtable, ns, diagnostics)?
+            {
+                // Only resolve the branch for the target we are compiling for, so that the other
+                // branch may use functionality which does not exist on this target
+                let branch = if is_target {
+                    Some(then.as_ref())
+                } else {
+                    else_.as_deref()
+                };
+
+                let branch = match branch {
+                    Some(branch) => branch,
+                    None => return Ok(true),
+                };
+
+                symtable.new_scope();
+                let mut stmts = Vec::new();
+                let reachable = statement(
+                    branch,
+                    &mut stmts,
+                    context,
+                    symtable,
+                    loops,
+                    ns,
+                    diagnostics,
+                )?;
+                symtable.leave_scope();
+
+                res.extend(stmts);
+
+                return Ok(reachable);
+            }
+
             let expr = expression(
                 cond_expr,
                 context,
@@ -546,7 +595,7 @@ fn statement(
             let mut init = Vec::new();
 
             if let Some(init_stmt) = init_stmt {
-                statement(
+                for_part(
                     init_stmt,
                     &mut init,
                     context,
@@ -578,7 +627,7 @@ fn statement(
             let mut next = Vec::new();
 
             if let Some(next_stmt) = next_stmt {
-                statement(
+                for_part(
                     next_stmt,
                     &mut next,
                     context,
@@ -610,7 +659,7 @@ fn statement(
             let mut next = Vec::new();
 
             if let Some(init_stmt) = init_stmt {
-                statement(
+                for_part(
                     init_stmt,
                     &mut init,
                     context,
@@ -656,7 +705,7 @@ fn statement(
 
             if body_reachable {
                 if let Some(next_stmt) = next_stmt {
-                    statement(
+                    for_part(
                         next_stmt,
                         &mut next,
                         context,
@@ -724,7 +773,8 @@ fn statement(
                         if expr.ty().is_mapping() {
                             ns.diagnostics.push(Diagnostic::error(
                                 *loc,
-                                "'delete' cannot be applied to mapping type".to_string(),
+                                "cannot delete a mapping; delete individual keys instead"
+                                    .to_string(),
                             ));
                             return Err(());
                         }
@@ -831,10 +881,9 @@ fn statement(
             Ok(reachable)
         }
         pt::Statement::Emit(loc, ty) => {
-            // Modified to ignore emit as it's only a logging function
-            // if let Ok(emit) = emit_event(loc, ty, context, symtable, ns, diagnostics) {
-            //     res.push(emit);
-            // }
+            if let Ok(emit) = emit_event(loc, ty, context, symtable, ns, diagnostics) {
+                res.push(emit);
+            }
 
             Ok(true)
         }
@@ -866,7 +915,14 @@ fn statement(
             res.push(Statement::Assembly(resolved_asm.0, resolved_asm.1));
             Ok(resolved_asm.1)
         }
-        pt::Statement::Revert(loc, error, args) => {
+        pt::Statement::Revert(loc, Some(error), args) => {
+            let stmt = revert_error(loc, error, args, context, symtable, ns, diagnostics)?;
+
+            res.push(stmt);
+
+            Ok(false)
+        }
+        pt::Statement::Revert(loc, None, args) => {
             let id = pt::Identifier {
                 loc: pt::Loc::File(loc.file_no(), loc.start(), loc.start() + 6),
                 name: "revert".to_string(),
@@ -889,16 +945,96 @@ fn statement(
 
             Ok(reachable)
         }
-        pt::Statement::RevertNamedArgs(loc, _, _) => {
+        pt::Statement::RevertNamedArgs(loc, Some(error), args) => {
+            let stmt = revert_error_named(loc, error, args, context, symtable, ns, diagnostics)?;
+
+            res.push(stmt);
+
+            Ok(false)
+        }
+        pt::Statement::RevertNamedArgs(loc, None, _) => {
             ns.diagnostics.push(Diagnostic::error(
                 *loc,
-                "revert with custom errors or named arguments not supported yet".to_string(),
+                "revert with named arguments requires an error type".to_string(),
             ));
             Err(())
         }
     }
 }
 
+/// The init and next parts of a for statement can be comma separated lists, which the parser
+/// returns as a block. Their statements are resolved in the scope of the for statement, so that
+/// any variables declared in the init part are visible in the rest of the loop.
+fn for_part(
+    stmt: &pt::Statement,
+    res: &mut Vec<Statement>,
+    context: &ExprContext,
+    symtable: &mut Symtable,
+    loops: &mut LoopScopes,
+    ns: &mut Namespace,
+    diagnostics: &mut Diagnostics,
+) -> Result<bool, ()> {
+    if let pt::Statement::Block { statements, .. } = stmt {
+        for stmt in statements {
+            statement(stmt, res, context, symtable, loops, ns, diagnostics)?;
+        }
+
+        Ok(true)
+    } else {
+        statement(stmt, res, context, symtable, loops, ns, diagnostics)
+    }
+}
+
+/// Check whether the condition of an if statement compares the compilation target with a
+/// string literal, e.g. `if (target == "solana")`. Such conditions are folded during
+/// resolve, so that sections of code for different targets can coexist in one source file.
+/// Returns `None` if this is not a target condition, or `target` is a declared identifier.
+fn target_condition(
+    cond: &pt::Expression,
+    context: &ExprContext,
+    symtable: &Symtable,
+    ns: &Namespace,
+    diagnostics: &mut Diagnostics,
+) -> Result<Option<bool>, ()> {
+    let (left, right, equal) = match cond {
+        pt::Expression::Equal(_, left, right) => (left, right, true),
+        pt::Expression::NotEqual(_, left, right) => (left, right, false),
+        _ => return Ok(None),
+    };
+
+    let literal = match (left.as_ref(), right.as_ref()) {
+        (pt::Expression::Variable(id), pt::Expression::StringLiteral(literal))
+        | (pt::Expression::StringLiteral(literal), pt::Expression::Variable(id))
+            if id.name == "target"
+                && symtable.find(&id.name).is_none()
+                && ns
+                    .resolve_var(context.file_no, context.contract_no, id, false)
+                    .is_none() =>
+        {
+            literal
+        }
+        _ => return Ok(None),
+    };
+
+    let name = literal
+        .iter()
+        .map(|s| s.string.as_str())
+        .collect::<String>();
+
+    if !["solana", "substrate", "evm", "olive"].contains(&name.as_str()) {
+        diagnostics.push(Diagnostic::error(
+            literal[0].loc,
+            format!(
+                "unknown target '{}', expected one of 'solana', 'substrate', 'evm' or 'olive'",
+                name
+            ),
+        ));
+        return Err(());
+    }
+
+    Ok(Some((ns.target.to_string() == name) == equal))
+}
+
 /// Resolve emit event
 fn emit_event(
     loc: &pt::Loc,
@@ -973,18 +1109,22 @@ fn emit_event(
                         Ok(e) => e,
                         Err(()) => {
                             matches = false;
-                            break;
+                            continue;
                         }
                     };
                     used_variable(ns, &arg, symtable);
 
                     if let Some(ty) = &ty {
-                        match arg.cast(&arg.loc(), ty, true, ns, &mut errors) {
+                        let mut cast_errors = Diagnostics::default();
+
+                        match arg.cast(&arg.loc(), ty, true, ns, &mut cast_errors) {
                             Ok(expr) => cast_args.push(expr),
                             Err(_) => {
                                 matches = false;
                             }
                         }
+
+                        errors.extend(event_field_note(&ns.events[*event_no], i, cast_errors));
                     }
                 }
 
@@ -1133,12 +1273,20 @@ fn emit_event(
 
                     used_variable(ns, &arg, symtable);
 
-                    match arg.cast(&arg.loc(), &param.ty, true, ns, &mut temp_diagnostics) {
+                    let mut cast_errors = Diagnostics::default();
+
+                    match arg.cast(&arg.loc(), &param.ty, true, ns, &mut cast_errors) {
                         Ok(expr) => cast_args.push(expr),
                         Err(_) => {
                             matches = false;
                         }
                     }
+
+                    temp_diagnostics.extend(event_field_note(
+                        &ns.events[*event_no],
+                        i,
+                        cast_errors,
+                    ));
                 }
 
                 if matches {
@@ -1180,6 +1328,245 @@ fn emit_event(
     Err(())
 }
 
+/// Resolve a revert statement with a custom error, e.g. `revert Overflow(x);`
+fn revert_error(
+    loc: &pt::Loc,
+    error: &pt::IdentifierPath,
+    args: &[pt::Expression],
+    context: &ExprContext,
+    symtable: &mut Symtable,
+    ns: &mut Namespace,
+    diagnostics: &mut Diagnostics,
+) -> Result<Statement, ()> {
+    let error_no = match ns.resolve_error(context.file_no, context.contract_no, error, diagnostics)
+    {
+        Ok(no) => no,
+        Err(_) => {
+            for arg in args {
+                if let Ok(exp) =
+                    expression(arg, context, ns, symtable, diagnostics, ResolveTo::Unknown)
+                {
+                    used_variable(ns, &exp, symtable);
+                }
+            }
+            return Err(());
+        }
+    };
+
+    let fields = ns.errors[error_no].fields.clone();
+    let mut matches = true;
+
+    if args.len() != fields.len() {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            format!(
+                "error '{}' has {} fields, {} provided",
+                ns.errors[error_no].name,
+                fields.len(),
+                args.len()
+            ),
+        ));
+        matches = false;
+    }
+
+    let mut cast_args = Vec::new();
+
+    for (i, arg) in args.iter().enumerate() {
+        let resolve_to = fields
+            .get(i)
+            .map(|field| ResolveTo::Type(&field.ty))
+            .unwrap_or(ResolveTo::Unknown);
+
+        let arg = match expression(arg, context, ns, symtable, diagnostics, resolve_to) {
+            Ok(e) => e,
+            Err(()) => {
+                matches = false;
+                continue;
+            }
+        };
+
+        used_variable(ns, &arg, symtable);
+
+        if let Some(field) = fields.get(i) {
+            match arg.cast(&arg.loc(), &field.ty, true, ns, diagnostics) {
+                Ok(expr) => cast_args.push(expr),
+                Err(_) => {
+                    matches = false;
+                }
+            }
+        }
+    }
+
+    if matches {
+        Ok(Statement::Revert {
+            loc: *loc,
+            error_no,
+            args: cast_args,
+        })
+    } else {
+        Err(())
+    }
+}
+
+/// Resolve a revert statement with a custom error and named arguments, e.g.
+/// `revert Overflow({ value: x });`
+fn revert_error_named(
+    loc: &pt::Loc,
+    error: &pt::IdentifierPath,
+    args: &[pt::NamedArgument],
+    context: &ExprContext,
+    symtable: &mut Symtable,
+    ns: &mut Namespace,
+    diagnostics: &mut Diagnostics,
+) -> Result<Statement, ()> {
+    let mut arguments = HashMap::new();
+    let mut matches = true;
+
+    for arg in args {
+        if arguments.contains_key(arg.name.name.as_str()) {
+            diagnostics.push(Diagnostic::error(
+                arg.name.loc,
+                format!("duplicate argument with name '{}'", arg.name.name),
+            ));
+
+            let _ = expression(
+                &arg.expr,
+                context,
+                ns,
+                symtable,
+                diagnostics,
+                ResolveTo::Unknown,
+            );
+
+            matches = false;
+            continue;
+        }
+
+        arguments.insert(arg.name.name.as_str(), &arg.expr);
+    }
+
+    let error_no = match ns.resolve_error(context.file_no, context.contract_no, error, diagnostics)
+    {
+        Ok(no) => no,
+        Err(_) => {
+            for (_, arg) in arguments {
+                let _ = expression(arg, context, ns, symtable, diagnostics, ResolveTo::Unknown);
+            }
+            return Err(());
+        }
+    };
+
+    let error_name = ns.errors[error_no].name.clone();
+    let fields = ns.errors[error_no].fields.clone();
+
+    if fields.iter().any(|field| field.id.is_none()) {
+        diagnostics.push(Diagnostic::error_with_note(
+            *loc,
+            format!(
+                "error '{}' cannot be used with named arguments as not all of its fields have names",
+                error_name
+            ),
+            ns.errors[error_no].loc,
+            format!("definition of '{}'", error_name),
+        ));
+        return Err(());
+    }
+
+    for arg in args {
+        if !fields
+            .iter()
+            .any(|field| field.name_as_str() == arg.name.name)
+        {
+            diagnostics.push(Diagnostic::error(
+                arg.name.loc,
+                format!(
+                    "error '{}' has no field called '{}'",
+                    error_name, arg.name.name
+                ),
+            ));
+            matches = false;
+        }
+    }
+
+    let mut cast_args = Vec::new();
+
+    for field in &fields {
+        let arg = match arguments.get(field.name_as_str()) {
+            Some(a) => a,
+            None => {
+                diagnostics.push(Diagnostic::error(
+                    *loc,
+                    format!(
+                        "missing argument '{}' to error '{}'",
+                        field.name_as_str(),
+                        error_name
+                    ),
+                ));
+                matches = false;
+                continue;
+            }
+        };
+
+        let arg = match expression(
+            arg,
+            context,
+            ns,
+            symtable,
+            diagnostics,
+            ResolveTo::Type(&field.ty),
+        ) {
+            Ok(e) => e,
+            Err(()) => {
+                matches = false;
+                continue;
+            }
+        };
+
+        used_variable(ns, &arg, symtable);
+
+        match arg.cast(&arg.loc(), &field.ty, true, ns, diagnostics) {
+            Ok(expr) => cast_args.push(expr),
+            Err(_) => {
+                matches = false;
+            }
+        }
+    }
+
+    if matches {
+        Ok(Statement::Revert {
+            loc: *loc,
+            error_no,
+            args: cast_args,
+        })
+    } else {
+        Err(())
+    }
+}
+
+/// Add a note to the diagnostics for an event argument, naming the event field it was
+/// resolved against
+fn event_field_note(
+    event: &EventDecl,
+    field_no: usize,
+    mut diagnostics: Diagnostics,
+) -> Diagnostics {
+    let field = &event.fields[field_no];
+
+    let message = match &field.id {
+        Some(id) => format!("parameter '{}' of event '{}'", id.name, event.name),
+        None => format!("parameter {} of event '{}'", field_no + 1, event.name),
+    };
+
+    for diagnostic in diagnostics.iter_mut() {
+        diagnostic.notes.push(Note {
+            loc: field.loc,
+            message: message.clone(),
+        });
+    }
+
+    diagnostics
+}
+
 /// Resolve destructuring assignment
 fn destructure(
     loc: &pt::Loc,
@@ -1,8 +1,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::ast::{Diagnostic, Namespace, Parameter, Tag};
+use crate::Target;
+use num_bigint::{BigInt, Sign};
+use num_traits::Num;
 use solang_parser::{doccomment::DocComment, pt};
 use std::fmt::Write;
+use tiny_keccak::{Hasher, Keccak};
 
 /// Resolve the tags for a type from parsed doccomment
 pub fn resolve_tags(
@@ -148,6 +152,33 @@ pub fn resolve_tags(
                     ));
                 }
             }
+            "storage" if ty == "state variable" => {
+                if ns.target == Target::Solana {
+                    ns.diagnostics.push(Diagnostic::error(
+                        pt::Loc::File(file_no, c.tag_offset, c.tag_offset + c.tag.len()),
+                        "tag '@storage' is not supported on Solana".to_string(),
+                    ));
+                } else if res.iter().any(|e| e.tag == "storage") {
+                    ns.diagnostics.push(Diagnostic::error(
+                        pt::Loc::File(file_no, c.tag_offset, c.tag_offset + c.tag.len()),
+                        "duplicate tag '@storage'".to_string(),
+                    ));
+                } else if parse_storage_slot(&c.value).is_none() {
+                    ns.diagnostics.push(Diagnostic::error(
+                        pt::Loc::File(file_no, c.value_offset, c.value_offset + c.value.len()),
+                        format!(
+                            "tag '@storage' expects 'slot=<number>' or 'location=\"<name>\"', not '{}'",
+                            c.value
+                        ),
+                    ));
+                } else {
+                    res.push(Tag {
+                        tag: String::from("storage"),
+                        no: 0,
+                        value: c.value.to_owned(),
+                    });
+                }
+            }
             _ => {
                 ns.diagnostics.push(Diagnostic::error(
                     pt::Loc::File(file_no, c.tag_offset, c.tag_offset + c.tag.len()),
@@ -160,6 +191,41 @@ pub fn resolve_tags(
     res
 }
 
+/// Parse the value of a `@storage` tag, which pins a state variable to a storage slot. This is
+/// either `slot=<number>` in decimal or hex, or `location="<name>"`, which is the slot
+/// `keccak256(name) - 1` as used by EIP-1967 proxies.
+pub fn parse_storage_slot(value: &str) -> Option<BigInt> {
+    let (key, arg) = value.split_once('=')?;
+    let arg = arg.trim();
+
+    let slot = match key.trim() {
+        "slot" => {
+            if let Some(hex) = arg.strip_prefix("0x") {
+                BigInt::from_str_radix(hex, 16).ok()?
+            } else {
+                BigInt::from_str_radix(arg, 10).ok()?
+            }
+        }
+        "location" => {
+            let name = arg.strip_prefix('"')?.strip_suffix('"')?;
+
+            let mut hasher = Keccak::v256();
+            hasher.update(name.as_bytes());
+            let mut hash = [0u8; 32];
+            hasher.finalize(&mut hash);
+
+            BigInt::from_bytes_be(Sign::Plus, &hash) - 1
+        }
+        _ => return None,
+    };
+
+    if slot.sign() == Sign::Minus || slot.bits() > 256 {
+        None
+    } else {
+        Some(slot)
+    }
+}
+
 /// Render tags as plain text string
 pub fn render(tags: &[Tag]) -> String {
     let mut s = String::new();
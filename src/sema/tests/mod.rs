@@ -149,50 +149,50 @@ fn constant_overflow_checks() {
         }
     
         function test_add(int8 input) public returns (uint8) {
-            // value 133 does not fit into type int8.
+            // value 133 (0x85) does not fit into type int8 (range -128 to 127).
             int8 add_ovf = 127 + 6;
     
-            // negative value -1 does not fit into type uint8. Cannot implicitly convert signed literal to unsigned type.
+            // negative value -1 (-0x1) does not fit into type uint8 (range 0 to 255). Cannot implicitly convert signed literal to unsigned type.
             uint8 negative = 3 - 4;
     
-            // value 133 does not fit into type int8.
+            // value 133 (0x85) does not fit into type int8 (range -128 to 127).
             int8 mixed = 126 + 7 + input;
     
-            // negative value -1 does not fit into type uint8. Cannot implicitly convert signed literal to unsigned type.
+            // negative value -1 (-0x1) does not fit into type uint8 (range 0 to 255). Cannot implicitly convert signed literal to unsigned type.
             return 1 - 2;
         }
     
         function test_mul(int8 input) public {
-            // value 726 does not fit into type int8.
+            // value 726 (0x2d6) does not fit into type int8 (range -128 to 127).
             int8 mul_ovf = 127 * 6;
     
-            // value 882 does not fit into type int8.
+            // value 882 (0x372) does not fit into type int8 (range -128 to 127).
             int8 mixed = 126 * 7 * input;
         }
     
         function test_shift(int8 input) public {
-            // value 128 does not fit into type int8.
+            // value 128 (0x80) does not fit into type int8 (range -128 to 127).
             // warning: left shift by 7 may overflow the final result.
             int8 mul_ovf = 1 << 7;
     
-            // value 128 does not fit into type int8.
+            // value 128 (0x80) does not fit into type int8 (range -128 to 127).
             // warning: left shift by 7 may overflow the final result
             int8 mixed = (1 << 7) + input;
         }
     
         function test_call() public {
-            // negative value -1 does not fit into type uint8. Cannot implicitly convert signed literal to unsigned type.
-            // value 129 does not fit into type int8.
+            // negative value -1 (-0x1) does not fit into type uint8 (range 0 to 255). Cannot implicitly convert signed literal to unsigned type.
+            // value 129 (0x81) does not fit into type int8 (range -128 to 127).
             test_params(1 - 2, 127 + 2);
     
-            // negative value -1 does not fit into type uint8. Cannot implicitly convert signed literal to unsigned type.
-            // value 129 does not fit into type int8.
+            // negative value -1 (-0x1) does not fit into type uint8 (range 0 to 255). Cannot implicitly convert signed literal to unsigned type.
+            // value 129 (0x81) does not fit into type int8 (range -128 to 127).
             test_params({usesa: 1 - 2, sesa: 127 + 2});
         }
 
         function test_builtin (bytes input) public{
 
-            // value 4294967296 does not fit into type uint32.
+            // value 4294967296 (0x100000000) does not fit into type uint32 (range 0 to 4294967295).
             int16 sesa = input.readInt16LE(4294967296);
         }
 
@@ -209,13 +209,13 @@ fn constant_overflow_checks() {
             // no diagnostic
             uint8 b = 255 - 255/5 ;
 
-            // value 260 does not fit into type uint8.
+            // value 260 (0x104) does not fit into type uint8 (range 0 to 255).
             uint8 shift_r = (120 >> 2) + 230;
 
-            // value 261 does not fit into type uint8.
+            // value 261 (0x105) does not fit into type uint8 (range 0 to 255).
             uint8 mod_test = 254 + (500%17);
 
-            // value 269 does not fit into type uint8.
+            // value 269 (0x10d) does not fit into type uint8 (range 0 to 255).
             uint8 bb = 320 - (255/5) ;
 
             // left shift by 7 may overflow the final result
@@ -223,12 +223,12 @@ fn constant_overflow_checks() {
 
             int8 bitwise_or = (250 | 5) - 150;
 
-            // value 155 does not fit into type int8.
+            // value 155 (0x9b) does not fit into type int8 (range -128 to 127).
             int8 bitwise_or_ovf = (250 | 5) - 100;
 
             uint8 bitwise_and = 1000 & 5 ;
 
-            // value 262 does not fit into type uint8.
+            // value 262 (0x106) does not fit into type uint8 (range 0 to 255).
             uint8 bitwise_and_ovf = (1000 & 255) + 30 ;
 
             uint8 bitwise_xor = 1000 ^ 256;
@@ -257,58 +257,94 @@ fn constant_overflow_checks() {
     let errors = ns.diagnostics.errors();
     let warnings = ns.diagnostics.warnings();
 
-    assert_eq!(errors[0].message, "value 133 does not fit into type int8.");
-    assert_eq!(errors[1].message, "negative value -1 does not fit into type uint8. Cannot implicitly convert signed literal to unsigned type.");
-    assert_eq!(errors[2].message, "value 133 does not fit into type int8.");
-    assert_eq!(errors[3].message, "negative value -1 does not fit into type uint8. Cannot implicitly convert signed literal to unsigned type.");
-    assert_eq!(errors[4].message, "value 762 does not fit into type int8.");
-    assert_eq!(errors[5].message, "value 882 does not fit into type int8.");
-    assert_eq!(errors[6].message, "value 128 does not fit into type int8.");
-    assert_eq!(errors[7].message, "value 128 does not fit into type int8.");
-    assert_eq!(errors[8].message, "negative value -1 does not fit into type uint8. Cannot implicitly convert signed literal to unsigned type.");
-    assert_eq!(errors[9].message, "value 129 does not fit into type int8.");
-    assert_eq!(errors[10].message, "negative value -1 does not fit into type uint8. Cannot implicitly convert signed literal to unsigned type.");
-    assert_eq!(errors[11].message, "value 129 does not fit into type int8.");
+    assert_eq!(
+        errors[0].message,
+        "value 133 (0x85) does not fit into type int8 (range -128 to 127)."
+    );
+    assert_eq!(errors[1].message, "negative value -1 (-0x1) does not fit into type uint8 (range 0 to 255). Cannot implicitly convert signed literal to unsigned type.");
+    assert_eq!(
+        errors[2].message,
+        "value 133 (0x85) does not fit into type int8 (range -128 to 127)."
+    );
+    assert_eq!(errors[3].message, "negative value -1 (-0x1) does not fit into type uint8 (range 0 to 255). Cannot implicitly convert signed literal to unsigned type.");
+    assert_eq!(
+        errors[4].message,
+        "value 762 (0x2fa) does not fit into type int8 (range -128 to 127)."
+    );
+    assert_eq!(
+        errors[5].message,
+        "value 882 (0x372) does not fit into type int8 (range -128 to 127)."
+    );
+    assert_eq!(
+        errors[6].message,
+        "value 128 (0x80) does not fit into type int8 (range -128 to 127)."
+    );
+    assert_eq!(
+        errors[7].message,
+        "value 128 (0x80) does not fit into type int8 (range -128 to 127)."
+    );
+    assert_eq!(errors[8].message, "negative value -1 (-0x1) does not fit into type uint8 (range 0 to 255). Cannot implicitly convert signed literal to unsigned type.");
+    assert_eq!(
+        errors[9].message,
+        "value 129 (0x81) does not fit into type int8 (range -128 to 127)."
+    );
+    assert_eq!(errors[10].message, "negative value -1 (-0x1) does not fit into type uint8 (range 0 to 255). Cannot implicitly convert signed literal to unsigned type.");
+    assert_eq!(
+        errors[11].message,
+        "value 129 (0x81) does not fit into type int8 (range -128 to 127)."
+    );
     assert_eq!(
         errors[12].message,
-        "value 4294967296 does not fit into type uint32."
+        "value 4294967296 (0x100000000) does not fit into type uint32 (range 0 to 4294967295)."
+    );
+    assert_eq!(
+        errors[13].message,
+        "value 130 (0x82) does not fit into type int8 (range -128 to 127)."
     );
-    assert_eq!(errors[13].message, "value 130 does not fit into type int8.");
     assert_eq!(
         errors[14].message,
-        "value 300 does not fit into type uint8."
+        "value 300 (0x12c) does not fit into type uint8 (range 0 to 255)."
     );
     assert_eq!(
         errors[15].message,
-        "value 301 does not fit into type uint8."
+        "value 301 (0x12d) does not fit into type uint8 (range 0 to 255)."
     );
     assert_eq!(
         errors[16].message,
-        "value 4294967296 does not fit into type uint32."
+        "value 4294967296 (0x100000000) does not fit into type uint32 (range 0 to 4294967295)."
+    );
+    assert_eq!(
+        errors[17].message,
+        "value 240 (0xf0) does not fit into type int8 (range -128 to 127)."
+    );
+    assert_eq!(
+        errors[18].message,
+        "value 245 (0xf5) does not fit into type int8 (range -128 to 127)."
     );
-    assert_eq!(errors[17].message, "value 240 does not fit into type int8.");
-    assert_eq!(errors[18].message, "value 245 does not fit into type int8.");
     assert_eq!(
         errors[19].message,
-        "value 260 does not fit into type uint8."
+        "value 260 (0x104) does not fit into type uint8 (range 0 to 255)."
     );
     assert_eq!(
         errors[20].message,
-        "value 261 does not fit into type uint8."
+        "value 261 (0x105) does not fit into type uint8 (range 0 to 255)."
     );
     assert_eq!(
         errors[21].message,
-        "value 269 does not fit into type uint8."
+        "value 269 (0x10d) does not fit into type uint8 (range 0 to 255)."
+    );
+    assert_eq!(
+        errors[22].message,
+        "value 155 (0x9b) does not fit into type int8 (range -128 to 127)."
     );
-    assert_eq!(errors[22].message, "value 155 does not fit into type int8.");
     assert_eq!(
         errors[23].message,
-        "value 262 does not fit into type uint8."
+        "value 262 (0x106) does not fit into type uint8 (range 0 to 255)."
     );
 
     assert_eq!(
         errors[24].message,
-        "value 744 does not fit into type uint8."
+        "value 744 (0x2e8) does not fit into type uint8 (range 0 to 255)."
     );
     assert_eq!(errors[25].message, "divide by zero");
     assert_eq!(errors[26].message, "divide by zero");
@@ -339,26 +375,26 @@ fn test_types() {
     let file = r#"
     contract test_contract {
         function test_types32(bytes input) public {
-            // value 2147483648 does not fit into type int32.
+            // value 2147483648 (0x80000000) does not fit into type int32 (range -2147483648 to 2147483647).
             int32 add_ovf = 2147483647 + 1;
     
-            // value 2147483648 does not fit into type int32.
+            // value 2147483648 (0x80000000) does not fit into type int32 (range -2147483648 to 2147483647).
             int32 add_normal = 2147483647 + 0;
     
-            // value 2147483648 does not fit into type int32.
+            // value 2147483648 (0x80000000) does not fit into type int32 (range -2147483648 to 2147483647).
             int32 mixed = 2147483647 + 1 + input.readInt32LE(2);
         }
     
         function test_types64(bytes input) public {
-            // value 9223372036854775808 does not fit into type int64.
+            // value 9223372036854775808 (0x8000000000000000) does not fit into type int64 (range -9223372036854775808 to 9223372036854775807).
             int64 add_ovf = 9223372036854775807 + 1;
     
             int64 add_normal = 9223372036854775807;
     
-            // value 9223372036854775808 does not fit into type int64.
+            // value 9223372036854775808 (0x8000000000000000) does not fit into type int64 (range -9223372036854775808 to 9223372036854775807).
             int64 mixed = 9223372036854775807 + 1 + input.readInt64LE(2);
     
-            // value 18446744073709551616 does not fit into type uint64.
+            // value 18446744073709551616 (0x10000000000000000) does not fit into type uint64 (range 0 to 18446744073709551615).
             uint64 pow_ovf = 2**64;
     
             uint64 normal_pow = (2**64) - 1;
@@ -366,15 +402,15 @@ fn test_types() {
     
         function test_types_128_256(bytes input) public {
             while (true) {
-                // value 340282366920938463463374607431768211456 does not fit into type uint64.
+                // value 340282366920938463463374607431768211456 (0x100000000000000000000000000000000) does not fit into type uint64 (range 0 to 18446744073709551615).
                 uint128 ovf = 2**128;
                 uint128 normal = 2**128 - 1;
             }
             uint128[] arr;
-            // negative value -1 does not fit into type uint32. Cannot implicitly convert signed literal to unsigned type.
-            // value 340282366920938463463374607431768211456 does not fit into type uint128.
+            // negative value -1 (-0x1) does not fit into type uint32 (range 0 to 4294967295). Cannot implicitly convert signed literal to unsigned type.
+            // value 340282366920938463463374607431768211456 (0x100000000000000000000000000000000) does not fit into type uint128 (range 0 to 340282366920938463463374607431768211455).
             uint128 access = arr[1 - 2] + 1 + (2**128);
-            // value 3000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000 does not fit into type uint256.
+            // value 30000000…00000000 [256 digits] (0x199309cc…00000000 [213 digits]) does not fit into type uint256 (range 0 to 11579208…29639935 [78 digits]).
             uint256 num = 3e255;
     
             uint256 num_2 = 115792089237316195423570985008687907853269984665640564039457584007913129639935 *
@@ -400,56 +436,1850 @@ fn test_types() {
 
     assert_eq!(
         errors[0].message,
-        "value 2147483648 does not fit into type int32."
+        "value 2147483648 (0x80000000) does not fit into type int32 (range -2147483648 to 2147483647)."
     );
     assert_eq!(
         errors[1].message,
-        "value 2147483648 does not fit into type int32."
+        "value 2147483648 (0x80000000) does not fit into type int32 (range -2147483648 to 2147483647)."
     );
     assert_eq!(
         errors[2].message,
-        "value 9223372036854775808 does not fit into type int64."
+        "value 9223372036854775808 (0x8000000000000000) does not fit into type int64 (range -9223372036854775808 to 9223372036854775807)."
     );
     assert_eq!(
         errors[3].message,
-        "value 9223372036854775808 does not fit into type int64."
+        "value 9223372036854775808 (0x8000000000000000) does not fit into type int64 (range -9223372036854775808 to 9223372036854775807)."
     );
     assert_eq!(
         errors[4].message,
-        "value 18446744073709551616 does not fit into type uint64."
+        "value 18446744073709551616 (0x10000000000000000) does not fit into type uint64 (range 0 to 18446744073709551615)."
     );
     assert_eq!(
         errors[5].message,
-        "value 340282366920938463463374607431768211456 does not fit into type uint128."
+        "value 340282366920938463463374607431768211456 (0x100000000000000000000000000000000) does not fit into type uint128 (range 0 to 340282366920938463463374607431768211455)."
     );
-    assert_eq!(errors[6].message, "negative value -1 does not fit into type uint32. Cannot implicitly convert signed literal to unsigned type.");
+    assert_eq!(errors[6].message, "negative value -1 (-0x1) does not fit into type uint32 (range 0 to 4294967295). Cannot implicitly convert signed literal to unsigned type.");
     assert_eq!(
         errors[7].message,
-        "value 340282366920938463463374607431768211456 does not fit into type uint128."
+        "value 340282366920938463463374607431768211456 (0x100000000000000000000000000000000) does not fit into type uint128 (range 0 to 340282366920938463463374607431768211455)."
     );
 
-    assert_eq!(errors[8].message, "value 3000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000 does not fit into type uint256.");
+    assert_eq!(errors[8].message, "value 30000000…00000000 [256 digits] (0x199309cc…00000000 [213 digits]) does not fit into type uint256 (range 0 to 11579208…29639935 [78 digits]).");
 
-    assert_eq!(errors[9].message, "value 463168356949264781694283940034751631413079938662562256157830336031652518559740 does not fit into type uint256.");
+    assert_eq!(errors[9].message, "value 46316835…18559740 [78 digits] (0x3fffffff…fffffffc [65 digits]) does not fit into type uint256 (range 0 to 11579208…29639935 [78 digits]).");
     assert_eq!(
         errors[10].message,
-        "value 450000 does not fit into type uint16."
+        "value 450000 (0x6ddd0) does not fit into type uint16 (range 0 to 65535)."
     );
     assert_eq!(
         errors[11].message,
-        "value 65546 does not fit into type uint16."
+        "value 65546 (0x1000a) does not fit into type uint16 (range 0 to 65535)."
     );
     assert_eq!(
         errors[12].message,
-        "value 370 does not fit into type uint8."
+        "value 370 (0x172) does not fit into type uint8 (range 0 to 255)."
     );
     assert_eq!(
         errors[13].message,
-        "value 500 does not fit into type uint8."
+        "value 500 (0x1f4) does not fit into type uint8 (range 0 to 255)."
     );
     assert_eq!(
         errors[14].message,
-        "value 300 does not fit into type uint8."
+        "value 300 (0x12c) does not fit into type uint8 (range 0 to 255)."
     );
     assert_eq!(errors.len(), 15);
 }
+
+#[test]
+fn builtins_unavailable_on_target() {
+    let substrate = Target::default_substrate();
+
+    let test_cases = [
+        (
+            "selfdestruct",
+            "selfdestruct(payable(msg.sender))",
+            vec![Target::Solana],
+        ),
+        (
+            "blake2_128",
+            "blake2_128(hex\"00\")",
+            vec![Target::EVM, Target::Solana],
+        ),
+        (
+            "blake2_256",
+            "blake2_256(hex\"00\")",
+            vec![Target::EVM, Target::Solana],
+        ),
+        ("gasleft", "gasleft()", vec![Target::Solana]),
+        ("blockhash", "blockhash(1)", vec![substrate, Target::Solana]),
+        (
+            "random",
+            "random(hex\"00\")",
+            vec![Target::EVM, Target::Solana],
+        ),
+        (
+            "abi.borshDecode",
+            "abi.borshDecode(hex\"00\", 0)",
+            vec![Target::EVM, substrate],
+        ),
+        (
+            "signatureVerify",
+            "signatureVerify(msg.sender, hex\"00\", hex\"00\")",
+            vec![Target::EVM, substrate],
+        ),
+        ("tx.gasprice", "tx.gasprice(1)", vec![Target::Solana]),
+        ("tx.gasprice", "tx.gasprice", vec![Target::Solana]),
+        ("tx.origin", "tx.origin", vec![substrate, Target::Solana]),
+        (
+            "tx.program_id",
+            "tx.program_id",
+            vec![Target::EVM, substrate],
+        ),
+        ("tx.accounts", "tx.accounts", vec![Target::EVM, substrate]),
+        (
+            "block.coinbase",
+            "block.coinbase",
+            vec![substrate, Target::Solana],
+        ),
+        (
+            "block.difficulty",
+            "block.difficulty",
+            vec![substrate, Target::Solana],
+        ),
+        (
+            "block.gaslimit",
+            "block.gaslimit",
+            vec![substrate, Target::Solana],
+        ),
+        ("block.slot", "block.slot", vec![Target::EVM, substrate]),
+        (
+            "block.minimum_balance",
+            "block.minimum_balance",
+            vec![Target::EVM, Target::Solana],
+        ),
+    ];
+
+    for (name, expr, targets) in test_cases {
+        for target in targets {
+            let mut cache = FileResolver::new();
+            cache.set_file_contents(
+                "test.sol",
+                format!(
+                    r#"
+                    contract c {{
+                        function f() public {{
+                            {};
+                        }}
+                    }}"#,
+                    expr
+                ),
+            );
+
+            let ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, target);
+
+            let message = format!("builtin '{}' is not available for target {}", name, target);
+
+            assert!(ns.diagnostics.contains_message(&message), "{}", message);
+            assert_eq!(ns.diagnostics.errors().len(), 1, "{}", message);
+        }
+    }
+}
+
+#[test]
+fn loop_report() {
+    use crate::sema::loop_report::{LoopBound, LoopKind};
+    use num_bigint::BigInt;
+
+    let ns = parse(
+        r#"
+        contract c {
+            uint[] arr;
+            uint64 count;
+
+            function constant_bound() public pure returns (uint sum) {
+                for (uint i = 0; i < 10; i++) {
+                    sum += i;
+                }
+
+                uint j = 20;
+                while (j > 4) {
+                    sum += j;
+                    j -= 2;
+                }
+
+                int k = -3;
+                do {
+                    sum += 1;
+                    k++;
+                } while (k <= 3);
+            }
+
+            function state_bound() public view returns (uint sum) {
+                for (uint i = 0; i < arr.length; i++) {
+                    sum += arr[i];
+                }
+
+                uint len = count;
+                for (uint i = 0; i < len; i += 1) {
+                    sum += i;
+                }
+            }
+
+            function unbounded(uint n) public pure returns (uint sum) {
+                for (uint i = 0; i < n; i++) {
+                    sum += i;
+                }
+
+                for (uint i = 0; i < 10; i++) {
+                    i = sum;
+                }
+
+                while (sum < n) {
+                    sum += 3;
+                }
+            }
+
+            function nested() public view returns (uint sum) {
+                for (uint i = 0; i < 10; i++) {
+                    for (uint j = 0; j < 5; j++) {
+                        sum += j;
+                    }
+
+                    for (uint j = 0; j < arr.length; j++) {
+                        sum += arr[j];
+                    }
+                }
+            }
+
+            function inclusive_bounds() public pure returns (uint sum) {
+                for (uint i = 1; i <= 10; i++) {
+                    sum += i;
+                }
+
+                for (uint8 i = 0; i <= 254; i++) {
+                    sum += i;
+                }
+
+                for (uint i = 10; i >= 1; i--) {
+                    sum += i;
+                }
+            }
+
+            function type_max() public pure returns (uint sum) {
+                for (uint i = 0; i <= type(uint).max; i++) {
+                    sum += i;
+                }
+
+                for (uint8 i = 0; i <= 255; i++) {
+                    sum += i;
+                }
+
+                for (uint8 i = 0; i < 255; i++) {
+                    sum += i;
+                }
+
+                for (uint i = 10; i >= 0; i--) {
+                    sum += i;
+                }
+
+                for (int8 i = 0; i >= -128; i--) {
+                    sum += 1;
+                }
+            }
+
+            function not_equal(uint n) public view returns (uint sum) {
+                for (uint i = 0; i != 10; i++) {
+                    sum += i;
+                }
+
+                for (uint i = 10; i != 0; i--) {
+                    sum += i;
+                }
+
+                for (uint i = 0; i != arr.length; i++) {
+                    sum += arr[i];
+                }
+
+                for (uint i = 20; i != 10; i++) {
+                    sum += i;
+                }
+
+                for (uint i = n; i != 10; i++) {
+                    sum += i;
+                }
+
+                uint j = 5;
+                do {
+                    sum += j;
+                    j++;
+                } while (j != 5);
+            }
+
+            function internal_loop() internal pure {
+                for (uint i = 0; i < 10; i++) {}
+            }
+        }"#,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let report = ns.loop_report();
+
+    let bounds = |name: &str| {
+        report
+            .iter()
+            .find(|f| ns.functions[f.function_no].name == name)
+            .unwrap()
+            .loops
+            .iter()
+            .map(|l| (l.kind, l.depth, l.bound.clone(), l.total.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    let constant = |n: i64| LoopBound::Constant(BigInt::from(n));
+    let state = |s: &str| LoopBound::State(s.to_string());
+
+    assert_eq!(
+        bounds("constant_bound"),
+        vec![
+            (LoopKind::For, 0, constant(10), constant(10)),
+            (LoopKind::While, 0, constant(8), constant(8)),
+            (LoopKind::DoWhile, 0, constant(7), constant(7)),
+        ]
+    );
+
+    assert_eq!(
+        bounds("state_bound"),
+        vec![
+            (LoopKind::For, 0, state("arr.length"), state("arr.length")),
+            (LoopKind::For, 0, state("count"), state("count")),
+        ]
+    );
+
+    assert_eq!(
+        bounds("unbounded"),
+        vec![
+            (LoopKind::For, 0, LoopBound::Unbounded, LoopBound::Unbounded),
+            (LoopKind::For, 0, LoopBound::Unbounded, LoopBound::Unbounded),
+            (
+                LoopKind::While,
+                0,
+                LoopBound::Unbounded,
+                LoopBound::Unbounded
+            ),
+        ]
+    );
+
+    assert_eq!(
+        bounds("nested"),
+        vec![
+            (LoopKind::For, 0, constant(10), constant(10)),
+            (LoopKind::For, 1, constant(5), constant(50)),
+            (
+                LoopKind::For,
+                1,
+                state("arr.length"),
+                state("10 * arr.length")
+            ),
+        ]
+    );
+
+    assert_eq!(
+        bounds("inclusive_bounds"),
+        vec![
+            (LoopKind::For, 0, constant(10), constant(10)),
+            (LoopKind::For, 0, constant(255), constant(255)),
+            (LoopKind::For, 0, constant(10), constant(10)),
+        ]
+    );
+
+    // a limit at the end of the range of the variable is always true
+    let unbounded = (LoopKind::For, 0, LoopBound::Unbounded, LoopBound::Unbounded);
+
+    assert_eq!(
+        bounds("type_max"),
+        vec![
+            unbounded.clone(),
+            unbounded.clone(),
+            (LoopKind::For, 0, constant(255), constant(255)),
+            unbounded.clone(),
+            unbounded.clone(),
+        ]
+    );
+
+    // with != the start must be a known constant which does not skip past the limit
+    assert_eq!(
+        bounds("not_equal"),
+        vec![
+            (LoopKind::For, 0, constant(10), constant(10)),
+            (LoopKind::For, 0, constant(10), constant(10)),
+            (LoopKind::For, 0, state("arr.length"), state("arr.length")),
+            unbounded.clone(),
+            unbounded,
+            (
+                LoopKind::DoWhile,
+                0,
+                LoopBound::Unbounded,
+                LoopBound::Unbounded
+            ),
+        ]
+    );
+
+    assert!(!report
+        .iter()
+        .any(|f| ns.functions[f.function_no].name == "internal_loop"));
+
+    let text = ns.loop_report_text(&report);
+
+    assert!(text.contains("c.nested():\n  for loop at test.sol:"));
+    assert!(text.contains(": constant bound 5, total 50\n"));
+    assert!(text.contains(": bounded by state arr.length, total 10 * arr.length\n"));
+
+    let json: serde_json::Value = serde_json::from_str(&ns.loop_report_json(&report)).unwrap();
+
+    assert_eq!(json[0]["contract"], "c");
+    assert_eq!(json[0]["function"], "constant_bound()");
+    assert_eq!(json[0]["loops"][0]["bound"]["kind"], "constant");
+    assert_eq!(json[0]["loops"][0]["bound"]["value"], "10");
+}
+
+#[test]
+fn constant_array_size() {
+    use crate::sema::ast::ArrayLength;
+    use num_bigint::BigInt;
+
+    let ns = parse(
+        r#"
+        uint constant ROWS = 2;
+
+        contract c {
+            uint constant SIZE = 3;
+            uint8[SIZE] arr;
+            int64[SIZE * ROWS][ROWS] grid;
+
+            function get() public view returns (uint8) {
+                uint16[SIZE + 1] memory local;
+
+                local[3] = arr[2];
+
+                return arr[2];
+            }
+        }"#,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let contract = &ns.contracts[0];
+
+    let var_ty = |name: &str| {
+        contract
+            .variables
+            .iter()
+            .find(|var| var.name == name)
+            .unwrap()
+            .ty
+            .clone()
+    };
+
+    let fixed = |n: u32| ArrayLength::Fixed(BigInt::from(n));
+
+    assert_eq!(
+        var_ty("arr"),
+        Type::Array(Box::new(Type::Uint(8)), vec![fixed(3)])
+    );
+    assert_eq!(
+        var_ty("grid"),
+        Type::Array(Box::new(Type::Int(64)), vec![fixed(6), fixed(2)])
+    );
+
+    let ns = parse(
+        r#"
+        contract c {
+            uint constant SIZE = 0;
+            uint8[SIZE] arr;
+        }"#,
+    );
+
+    assert_eq!(
+        ns.diagnostics.first_error(),
+        "zero size array not permitted"
+    );
+}
+
+#[test]
+fn overflow_diagnostic_values() {
+    let ns = parse(
+        r#"
+        contract c {
+            function f() public {
+                int8 a = 100 + 100;
+                uint128 b = 1e30 * 1e10;
+                uint256 c = 1e78;
+            }
+        }"#,
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(
+        errors[0].message,
+        "value 200 (0xc8) does not fit into type int8 (range -128 to 127)."
+    );
+    assert_eq!(
+        errors[1].message,
+        "value 10000000…00000000 [41 digits] (0x1d6329f1c35ca4bfabb9f5610000000000) does not fit into type uint128 (range 0 to 340282366920938463463374607431768211455)."
+    );
+    assert_eq!(
+        errors[2].message,
+        "value 10000000…00000000 [79 digits] (0x8a2dbf14…00000000 [65 digits]) does not fit into type uint256 (range 0 to 11579208…29639935 [78 digits])."
+    );
+    assert_eq!(errors.len(), 3);
+}
+
+#[test]
+fn bytes_address_conversion() {
+    let ns = parse(
+        r#"
+        contract c {
+            function f(bytes20 b) public pure returns (bytes20) {
+                address a = address(b);
+                return bytes20(a);
+            }
+        }"#,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let ns = parse(
+        r#"
+        contract c {
+            function f(bytes32 b) public pure returns (address) {
+                return address(b);
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        ns.diagnostics.first_error(),
+        "conversion to address from bytes32 not allowed"
+    );
+}
+
+#[test]
+fn value_type_alias() {
+    let src = r#"
+        contract wallet {
+            valueType total;
+            valueType[2] history;
+
+            function deposit() public {
+                valueType b = address(this).balance;
+                total += b;
+                history[0] = valueType(b);
+            }
+
+            function max() public pure returns (valueType) {
+                return type(valueType).max;
+            }
+        }"#;
+
+    for (target, bits) in [
+        (Target::default_substrate(), 128),
+        (Target::Solana, 64),
+        (Target::EVM, 128),
+    ] {
+        let mut cache = FileResolver::new();
+        cache.set_file_contents("test.sol", src.to_string());
+
+        let ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, target);
+
+        assert!(!ns.diagnostics.any_errors(), "target {}", target);
+
+        let vars = &ns.contracts[0].variables;
+
+        assert_eq!(vars[0].ty, Type::Uint(bits));
+        assert_eq!(
+            vars[1].ty,
+            Type::Array(
+                Box::new(Type::Uint(bits)),
+                vec![ast::ArrayLength::Fixed(2.into())]
+            )
+        );
+    }
+
+    // a user defined type of the same name takes precedence
+    let ns = parse(
+        r#"
+        struct valueType {
+            bool b;
+        }
+
+        contract c {
+            valueType v;
+        }"#,
+    );
+
+    assert!(matches!(ns.contracts[0].variables[0].ty, Type::Struct(_)));
+}
+
+#[test]
+fn checked_arithmetic_folding() {
+    use num_bigint::BigInt;
+
+    let ns = parse(
+        r#"
+        contract c {
+            uint64 total;
+
+            function sub() public pure returns (bool, int128) {
+                return int128(0).checkedSub(type(int128).min);
+            }
+
+            function mul() public pure returns (bool, uint8) {
+                return uint8(15).checkedMul(17);
+            }
+
+            function add(uint64 a) public view returns (bool, uint64) {
+                return total.checkedAdd(a);
+            }
+        }"#,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let returned = |name: &str| {
+        let func = ns.functions.iter().find(|f| f.name == name).unwrap();
+
+        match &func.body[0] {
+            Statement::Return(_, Some(expr)) => expr.clone(),
+            _ => unreachable!(),
+        }
+    };
+
+    let min: BigInt = -(BigInt::from(1) << 127usize);
+
+    assert!(matches!(
+        returned("sub"),
+        Expression::List(_, list) if matches!(
+            list.as_slice(),
+            [Expression::BoolLiteral(_, false), Expression::NumberLiteral(_, Type::Int(128), n)] if *n == min
+        )
+    ));
+
+    assert!(matches!(
+        returned("mul"),
+        Expression::List(_, list) if matches!(
+            list.as_slice(),
+            [Expression::BoolLiteral(_, true), Expression::NumberLiteral(_, Type::Uint(8), n)] if *n == BigInt::from(255)
+        )
+    ));
+
+    assert!(matches!(
+        returned("add"),
+        Expression::Builtin(_, _, ast::Builtin::CheckedAdd, _)
+    ));
+
+    let ns = parse(
+        r#"
+        contract c {
+            function f(uint8 a) public pure returns (uint8) {
+                uint8 x = a.checkedAdd(1);
+                return x;
+            }
+
+            function g(uint8 a, int8 b) public pure returns (uint8) {
+                (bool ok, uint8 r) = a.checkedAdd(b);
+                return r;
+            }
+        }"#,
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(
+        errors[0].message,
+        "destucturing statement needed for function that returns multiple values"
+    );
+    assert_eq!(
+        errors[1].message,
+        "implicit conversion would change sign from int8 to uint8"
+    );
+}
+
+#[test]
+fn struct_comparison() {
+    let ns = parse(
+        r#"
+        contract c {
+            struct inner {
+                bytes4 b;
+                address a;
+            }
+
+            struct S {
+                uint64 f1;
+                inner f2;
+            }
+
+            struct T {
+                uint64 f1;
+                string f2;
+            }
+
+            struct U {
+                int8 f1;
+                T f2;
+            }
+
+            function f(S memory a, S memory b) public pure returns (bool) {
+                return a == b || a != b;
+            }
+
+            function g(T memory a, T memory b) public pure returns (bool) {
+                return a == b;
+            }
+
+            function h(U memory a, U memory b) public pure returns (bool) {
+                return a != b;
+            }
+        }"#,
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].message,
+        "struct 'c.T' cannot be compared since field 'f2' has type string"
+    );
+    assert_eq!(
+        errors[1].message,
+        "struct 'c.U' cannot be compared since field 'f2' has type string"
+    );
+}
+
+#[test]
+fn print_arguments() {
+    let ns = parse(
+        r#"
+        contract c {
+            enum E { a, b }
+
+            function f(int8 a, address b, bytes3 c, E e) public {
+                print("values", a, b, c, e, true, hex"01");
+                print(a);
+            }
+        }"#,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let ns = parse(
+        r#"
+        contract c {
+            function f() public {
+                print();
+            }
+
+            function g(uint8[] a) public {
+                print("array", a);
+            }
+        }"#,
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(
+        errors[0].message,
+        "builtin function 'print' expects at least 1 argument, 0 provided"
+    );
+    assert_eq!(errors[1].message, "cannot print value of type uint8[]");
+}
+
+#[test]
+fn new_interface() {
+    let ns = parse(
+        r#"
+        interface I {
+            function f() external;
+        }
+
+        abstract contract A {
+            function g() public virtual;
+        }
+
+        contract c {
+            function f() public {
+                I i = new I();
+            }
+
+            function g() public {
+                A a = new A();
+            }
+        }"#,
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].message, "cannot instantiate an interface");
+    assert_eq!(
+        errors[1].message,
+        "cannot construct 'A' of type 'abstract contract'"
+    );
+}
+
+#[test]
+fn literal_does_not_fit() {
+    let ns = parse(
+        r#"
+        contract c {
+            function f() public returns (int8) {
+                uint8 x = 256;
+                int8 y;
+                y = 128;
+                return y;
+            }
+        }"#,
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].message,
+        "value 256 (0x100) does not fit into type uint8 (range 0 to 255)."
+    );
+    assert_eq!(
+        errors[1].message,
+        "value 128 (0x80) does not fit into type int8 (range -128 to 127)."
+    );
+}
+
+#[test]
+fn initializer_forward_reference() {
+    let ns = parse(
+        r#"
+        contract c {
+            int a = b + 1;
+            int b = 2;
+            int d = d;
+            int e = a + b;
+        }"#,
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].message,
+        "initializer of variable 'a' refers to variable 'b' which is declared later"
+    );
+    assert_eq!(errors[0].notes[0].message, "definition of 'b'");
+    assert_eq!(
+        errors[1].message,
+        "initializer of variable 'd' refers to itself"
+    );
+}
+
+#[test]
+fn code_hash() {
+    let ns = parse(
+        r#"
+        contract a {
+            function f() public returns (bytes32) {
+                return type(b).codeHash;
+            }
+        }
+
+        contract b {}"#,
+    );
+
+    assert_eq!(
+        ns.diagnostics.first_error(),
+        "type().codeHash is not available for target evm"
+    );
+
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract a {
+            function f() public returns (bytes32) {
+                return type(a).codeHash;
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    assert_eq!(
+        ns.diagnostics.first_error(),
+        "containing our own contract code for 'a' would generate infinite size contract"
+    );
+}
+
+#[test]
+fn creation_code_solana() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract a {
+            function f() public returns (bytes) {
+                return type(b).creationCode;
+            }
+
+            function g() public returns (bytes) {
+                return type(b).runtimeCode;
+            }
+        }
+
+        contract b {}"#
+            .to_string(),
+    );
+
+    let ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, Target::Solana);
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].message,
+        "type().creationCode is not available for target solana"
+    );
+    assert_eq!(
+        errors[1].message,
+        "type().runtimeCode is not available for target solana"
+    );
+}
+
+#[test]
+fn transfer_all() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract a {
+            function f(address to) public {
+                to.transferAll();
+            }
+
+            function g(address payable to) public {
+                to.transferAll(1);
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(
+        errors[0].message,
+        "method 'transferAll' available on type 'address payable' not 'address'"
+    );
+    assert_eq!(
+        errors[1].message,
+        "'transferAll' expects 0 arguments, 1 provided"
+    );
+}
+
+#[test]
+fn selector_collision() {
+    let mut cache = FileResolver::new();
+
+    // both selectors are 0x42966c68
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract a {
+            function burn(uint256 amount) public {}
+
+            function collate_propagate_storage(bytes16 x) external {}
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message,
+        "function selector collision between burn(uint256) and collate_propagate_storage(bytes16)"
+    );
+}
+
+#[test]
+fn pinned_storage() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract a {
+            int64[4] arr;
+
+            /// @storage slot=2
+            int64 x;
+
+            /// @storage slot=0x10
+            int64[4] y;
+
+            /// @storage slot=19
+            int64 z;
+
+            /// @storage location="eip1967.proxy.admin"
+            int64 admin;
+
+            /// @storage place=3
+            int64 bad;
+
+            /// @storage slot=5
+            int64 constant c = 1;
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 4);
+    assert_eq!(
+        errors[0].message,
+        "storage slot of 'x' collides with the sequentially assigned slots 0 to 4"
+    );
+    assert_eq!(errors[1].message, "storage slot of 'z' collides with 'y'");
+    assert_eq!(
+        errors[2].message,
+        "tag '@storage' expects 'slot=<number>' or 'location=\"<name>\"', not 'place=3'"
+    );
+    assert_eq!(
+        errors[3].message,
+        "constant variable 'c' is not in storage, so cannot have tag '@storage'"
+    );
+}
+
+#[test]
+fn emit_argument_mismatch() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract a {
+            event Transfer(address to, uint256 value);
+
+            function f(int8 neg) public {
+                emit Transfer(address(this), 1);
+                emit Transfer(address(this), neg);
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message,
+        "implicit conversion would change sign from int8 to uint256"
+    );
+    assert_eq!(errors[0].notes.len(), 1);
+    assert_eq!(
+        errors[0].notes[0].message,
+        "parameter 'value' of event 'Transfer'"
+    );
+}
+
+#[test]
+fn debug_assert_side_effects() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract a {
+            uint64 count;
+            uint64[] list;
+
+            function f(uint64 n) public {
+                debugAssert(count < n && valid(n));
+                debugAssert(n++ > 1);
+            }
+
+            function g() public {
+                debugAssert(bump() > 1);
+                debugAssert(list.length < 10);
+            }
+
+            function valid(uint64 n) internal pure returns (bool) {
+                return n != 0;
+            }
+
+            function bump() internal returns (uint64) {
+                count += 1;
+                return count;
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].message,
+        "condition of debugAssert() must not have side effects"
+    );
+    assert_eq!(errors[0].loc, Loc::File(0, 198, 205));
+    assert_eq!(
+        errors[1].message,
+        "condition of debugAssert() must not have side effects"
+    );
+    assert_eq!(errors[1].loc, Loc::File(0, 285, 295));
+}
+
+#[test]
+fn bytes_concat() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract a {
+            bytes data;
+            bytes4 tag;
+            string name;
+
+            function f(bytes32 h, bytes b, string s) public pure returns (bytes) {
+                return bytes.concat(h, b, "x", hex"00", s);
+            }
+
+            function h(bytes b) public view returns (bytes) {
+                return bytes.concat(data, tag, b, name);
+            }
+
+            function g(uint64 n) public pure returns (bytes) {
+                return bytes.concat(n);
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message,
+        "bytes.concat() expects arguments of type bytes, bytesN or string, not 'uint64'"
+    );
+}
+
+#[test]
+fn string_concat() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract a {
+            string name;
+
+            function f(string s) public pure returns (string) {
+                return string.concat(s, "x", s);
+            }
+
+            function h(string s) public view returns (string) {
+                return string.concat(name, s);
+            }
+
+            function g(bytes b) public pure returns (string) {
+                return string.concat("x", b);
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message,
+        "string.concat() expects arguments of type string, not 'bytes'"
+    );
+}
+
+#[test]
+fn diamond_override_list() {
+    let errors = |bases: &str, body: &str| {
+        let mut cache = FileResolver::new();
+
+        cache.set_file_contents(
+            "test.sol",
+            format!(
+                r#"
+                contract A {{
+                    function f() public virtual returns (uint32) {{ return 1; }}
+                }}
+
+                contract B is A {{
+                    function f() public virtual override returns (uint32) {{ return 2; }}
+                }}
+
+                contract C is A {{
+                    function f() public virtual override returns (uint32) {{ return 3; }}
+                }}
+
+                abstract contract E {{}}
+
+                contract D is {} {{
+                    {}
+                }}"#,
+                bases, body
+            ),
+        );
+
+        let ns = parse_and_resolve(
+            OsStr::new("test.sol"),
+            &mut cache,
+            Target::default_substrate(),
+        );
+
+        ns.diagnostics
+            .errors()
+            .iter()
+            .map(|diag| diag.message.to_owned())
+            .collect::<Vec<String>>()
+    };
+
+    assert!(errors(
+        "B, C",
+        "function f() public override(B, C) returns (uint32) { return 4; }"
+    )
+    .is_empty());
+
+    assert_eq!(
+        errors(
+            "B, C",
+            "function f() public override(B) returns (uint32) { return 4; }"
+        ),
+        vec!["function 'f' missing overrides 'C', specify 'override(C,B)'"]
+    );
+
+    assert_eq!(
+        errors(
+            "B, C",
+            "function f() public override(A, B, C) returns (uint32) { return 4; }"
+        ),
+        vec!["function 'f' includes extraneous overrides 'A', specify 'override(C,B)'"]
+    );
+
+    assert_eq!(
+        errors(
+            "B, C",
+            "function f() public override returns (uint32) { return 4; }"
+        ),
+        vec!["function 'f' should specify override list 'override(C,B)'"]
+    );
+
+    assert_eq!(
+        errors("B, C", ""),
+        vec!["function 'f' with this signature already defined"]
+    );
+
+    assert_eq!(
+        errors(
+            "B, E",
+            "function f() public override(B, E) returns (uint32) { return 4; }"
+        ),
+        vec!["function 'f' includes extraneous overrides 'E', specify 'override(B)'"]
+    );
+}
+
+#[test]
+fn using_function_list() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        function add(uint64 a, uint64 b) pure returns (uint64) {
+            return a + b;
+        }
+
+        function neg(int64 a) pure returns (int64) {
+            return -a;
+        }
+
+        library L {
+            function sub(uint64 a, uint64 b) internal pure returns (uint64) {
+                return a - b;
+            }
+        }
+
+        using {add, L.sub, add, neg, mul} for uint64;
+
+        contract c {
+            function f(uint64 x) public pure returns (uint64) {
+                return x.add(2).sub(1);
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0].message, "function 'add' is already attached");
+    assert_eq!(
+        errors[1].message,
+        "function cannot be used since first argument is 'int64' rather than the required 'uint64'"
+    );
+    assert_eq!(errors[2].message, "'mul' not found");
+}
+
+#[test]
+fn constructor_visibility() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract c {
+            int64 x;
+
+            constructor(int64 a) public {
+                x = a;
+            }
+
+            function get() public view returns (int64) {
+                return x;
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    assert!(ns.diagnostics.errors().is_empty());
+
+    let warnings = ns.diagnostics.warnings();
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].message,
+        "'public': visibility on constructors is deprecated"
+    );
+
+    // the constructor is still resolved
+    assert!(ns.contracts[0]
+        .functions
+        .iter()
+        .any(|function_no| ns.functions[*function_no].is_constructor()));
+}
+
+#[test]
+fn pragma_solidity_wrapping_math() {
+    for (version, wraps) in [
+        ("^0.7.0", true),
+        ("0.6.12", true),
+        (">=0.6.0 <0.8.0", true),
+        (">= 0.5.0 < 0.8", true),
+        ("<=0.7.6", true),
+        ("^0.5.0 || ^0.6.0", true),
+        ("0", false),
+        ("^0.8.0", false),
+        (">=0.4.22 <0.9.0", false),
+        ("^0.7.0 || ^0.8.0", false),
+        ("<0.8.1", false),
+    ] {
+        let mut cache = FileResolver::new();
+
+        cache.set_file_contents(
+            "test.sol",
+            format!(
+                r#"
+                pragma solidity {};
+
+                contract c {{
+                    function f(uint64 a) public pure returns (uint64) {{
+                        return a + 1;
+                    }}
+                }}"#,
+                version
+            ),
+        );
+
+        let ns = parse_and_resolve(
+            OsStr::new("test.sol"),
+            &mut cache,
+            Target::default_substrate(),
+        );
+
+        assert_eq!(ns.files[0].wrapping_math, wraps, "{}", version);
+
+        let warnings = ns.diagnostics.warnings();
+
+        if wraps {
+            assert_eq!(warnings.len(), 1);
+            assert_eq!(
+                warnings[0].message,
+                format!(
+                    "pragma 'solidity' with value '{}' is before 0.8.0, so arithmetic wraps on overflow",
+                    version
+                )
+            );
+        } else {
+            assert!(warnings.is_empty());
+        }
+    }
+}
+
+#[test]
+fn target_condition() {
+    let src = r#"
+        contract c {
+            function f() public view returns (uint64) {
+                if (target != "solana") {
+                    return uint64(block.minimum_balance);
+                }
+
+                return block.slot;
+            }
+        }"#;
+
+    for target in [Target::Solana, Target::default_substrate()] {
+        let mut cache = FileResolver::new();
+
+        cache.set_file_contents("test.sol", src.to_string());
+
+        let ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, target);
+
+        assert!(ns.diagnostics.errors().is_empty(), "{}", target);
+    }
+
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract c {
+            function f() public pure returns (uint64) {
+                if (target == "ethereum") {
+                    return 1;
+                }
+
+                return 2;
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, Target::Solana);
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message,
+        "unknown target 'ethereum', expected one of 'solana', 'substrate', 'evm' or 'olive'"
+    );
+
+    // a declared variable named target is an ordinary condition
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract c {
+            function f(string target) public pure returns (uint64) {
+                if (target == "ethereum") {
+                    return 1;
+                }
+
+                return 2;
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, Target::Solana);
+
+    assert!(ns.diagnostics.errors().is_empty());
+}
+
+#[test]
+fn array_length_truncated() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract c {
+            uint64[] store;
+
+            function f(int8[] arr) public view returns (uint64, int64) {
+                uint8 a = uint8(arr.length);
+                uint64 b = uint64(arr.length);
+                uint32 d = uint32(store.length);
+                int32 e = int32(arr.length);
+                int64 g = int64(arr.length);
+
+                return (a + b + d, e + g);
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let warnings = ns.diagnostics.warnings();
+
+    assert_eq!(warnings.len(), 3);
+    assert_eq!(
+        warnings[0].message,
+        "array length of type 'uint32' is truncated to 'uint8'; lengths which do not fit will wrap"
+    );
+    assert_eq!(
+        warnings[1].message,
+        "array length of type 'uint256' is truncated to 'uint32'; lengths which do not fit will wrap"
+    );
+    assert_eq!(
+        warnings[2].message,
+        "array length of type 'uint32' is truncated to 'int32'; lengths which do not fit will wrap"
+    );
+}
+
+#[test]
+fn substrate_delegatecall() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract c {
+            function f(address a) public returns (bool) {
+                (bool success, bytes rawresult) = a.delegatecall(hex"01");
+
+                return success && rawresult.length == 0;
+            }
+
+            function g(address a) public {
+                a.delegatecall{gas: 1000}(hex"01");
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message,
+        "'delegatecall' cannot have gas specified on Substrate"
+    );
+}
+
+#[test]
+fn substrate_staticcall() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract c {
+            function f(address a) public view returns (bool) {
+                (bool success, bytes rawresult) = a.staticcall(hex"01");
+
+                return success && rawresult.length == 0;
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract c {
+            function g(address a) public {
+                a.staticcall{value: 1}(hex"01");
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "'staticcall' cannot have value specifed");
+
+    // the read-only call flag is not available before api version 3
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract c {
+            function f(address a) public view returns (bool) {
+                (bool success, ) = a.staticcall(hex"01");
+
+                return success;
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::Substrate {
+            address_length: 32,
+            value_length: 16,
+            api_version: 2,
+            max_code_size: 128 * 1024,
+        },
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message,
+        "'staticcall' requires substrate api version 3, but version 2 was selected"
+    );
+}
+
+#[test]
+fn abi_encode_call_mismatch() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        contract c {
+            function test() public {
+                bytes enc = abi.encodeCall(c.bar, (true, 102));
+            }
+
+            function test2(string s) public {
+                bytes enc = abi.encodeCall(c.baz, (s));
+            }
+
+            function test3(address to, uint256 amount) public {
+                bytes enc = abi.encodeCall(IToken.transfer, (to, amount));
+            }
+
+            function bar(int32 a, bool b) public {}
+
+            function baz(uint64 a) public {}
+        }
+
+        interface IToken {
+            function transfer(address to, uint64 amount) external returns (bool);
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 3);
+    assert_eq!(
+        errors[0].message,
+        "argument type mismatch for abi.encodeCall: expected 'int32', found 'bool'"
+    );
+    assert_eq!(
+        errors[1].message,
+        "argument type mismatch for abi.encodeCall: expected 'uint64', found 'string'"
+    );
+    assert_eq!(
+        errors[2].message,
+        "argument type mismatch for abi.encodeCall: expected 'uint64', found 'uint256'"
+    );
+}
+
+#[test]
+fn revert_custom_error() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        error Unauthorized();
+
+        contract c {
+            error Overflow(uint256 value, bool up);
+
+            function count() public {
+                revert Overflow(1);
+            }
+
+            function missing() public {
+                revert Overflow({ value: 1 });
+            }
+
+            function unknown() public {
+                revert Underflow(1);
+            }
+
+            function global() public {
+                revert Unauthorized();
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 3);
+    assert_eq!(
+        errors[0].message,
+        "error 'Overflow' has 2 fields, 1 provided"
+    );
+    assert_eq!(
+        errors[1].message,
+        "missing argument 'up' to error 'Overflow'"
+    );
+    assert_eq!(errors[2].message, "'Underflow' not found");
+
+    assert_eq!(ns.errors.len(), 2);
+    assert_eq!(ns.errors[0].signature, "Unauthorized()");
+    assert_eq!(ns.errors[1].signature, "Overflow(uint256,bool)");
+}
+
+#[test]
+fn user_type_no_implicit_conversion() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"
+        type Timestamp is uint256;
+
+        contract a {
+            function later(Timestamp t) internal pure returns (Timestamp) {
+                return Timestamp.wrap(Timestamp.unwrap(t) + 1);
+            }
+
+            function f(uint256 n) public pure returns (Timestamp) {
+                return later(n);
+            }
+
+            function g(Timestamp t) public pure returns (uint256) {
+                uint256 n = t;
+                return n;
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].message,
+        "conversion from uint256 to usertype Timestamp not possible"
+    );
+    assert_eq!(
+        errors[1].message,
+        "conversion from usertype Timestamp to uint256 not possible"
+    );
+}
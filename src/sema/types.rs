@@ -4,8 +4,8 @@ use super::tags::resolve_tags;
 use super::SOLANA_BUCKET_SIZE;
 use super::{
     ast::{
-        ArrayLength, Contract, Diagnostic, EnumDecl, EventDecl, Namespace, Parameter, StructDecl,
-        StructType, Symbol, Tag, Type, UserTypeDecl,
+        ArrayLength, Contract, Diagnostic, EnumDecl, ErrorDecl, EventDecl, Namespace, Parameter,
+        StructDecl, StructType, Symbol, Tag, Type, UserTypeDecl,
     },
     diagnostics::Diagnostics,
     SOLANA_SPARSE_ARRAY_SIZE,
@@ -25,6 +25,7 @@ use std::{collections::HashMap, fmt::Write, ops::Mul};
 pub struct ResolveFields<'a> {
     structs: Vec<ResolveStructFields<'a>>,
     events: Vec<ResolveEventFields<'a>>,
+    errors: Vec<ResolveErrorFields<'a>>,
 }
 
 struct ResolveEventFields<'a> {
@@ -34,6 +35,13 @@ struct ResolveEventFields<'a> {
     contract: Option<usize>,
 }
 
+struct ResolveErrorFields<'a> {
+    error_no: usize,
+    pt: &'a pt::ErrorDefinition,
+    comments: Vec<DocComment>,
+    contract: Option<usize>,
+}
+
 struct ResolveStructFields<'a> {
     struct_no: usize,
     pt: &'a pt::StructDefinition,
@@ -52,6 +60,7 @@ pub fn resolve_typenames<'a>(
     let mut delay = ResolveFields {
         structs: Vec::new(),
         events: Vec::new(),
+        errors: Vec::new(),
     };
 
     // Find all the types: contracts, enums, and structs. Either in a contract or not
@@ -137,6 +146,34 @@ pub fn resolve_typenames<'a>(
                     contract: None,
                 });
             }
+            pt::SourceUnitPart::ErrorDefinition(def) => {
+                let tags = parse_doccomments(comments, doc_comment_start, def.loc.start());
+
+                let error_no = ns.errors.len();
+
+                if ns.add_symbol(
+                    file_no,
+                    None,
+                    &def.name,
+                    Symbol::Error(def.name.loc, error_no),
+                ) {
+                    ns.errors.push(ErrorDecl {
+                        tags: Vec::new(),
+                        name: def.name.name.to_owned(),
+                        loc: def.name.loc,
+                        contract: None,
+                        fields: Vec::new(),
+                        signature: String::new(),
+                    });
+
+                    delay.errors.push(ResolveErrorFields {
+                        error_no,
+                        pt: def,
+                        comments: tags,
+                        contract: None,
+                    });
+                }
+            }
             pt::SourceUnitPart::TypeDefinition(ty) => {
                 let tags = parse_doccomments(comments, doc_comment_start, ty.loc.start());
 
@@ -268,6 +305,16 @@ pub fn resolve_fields(delay: ResolveFields, file_no: usize, ns: &mut Namespace)
         ns.events[event.event_no].fields = fields;
         ns.events[event.event_no].tags = tags;
     }
+
+    // and the fields for the errors
+    for error in delay.errors {
+        let (tags, fields) = error_decl(error.pt, file_no, &error.comments, error.contract, ns);
+
+        ns.errors[error.error_no].signature =
+            ns.signature(&ns.errors[error.error_no].name, &fields);
+        ns.errors[error.error_no].fields = fields;
+        ns.errors[error.error_no].tags = tags;
+    }
 }
 
 /// Resolve all the types in a contract
@@ -392,6 +439,36 @@ fn resolve_contract<'a>(
                     contract: Some(contract_no),
                 });
             }
+            pt::ContractPart::ErrorDefinition(ref pt) => {
+                let tags = parse_doccomments(comments, doc_comment_start, pt.loc.start());
+
+                let error_no = ns.errors.len();
+
+                if ns.add_symbol(
+                    file_no,
+                    Some(contract_no),
+                    &pt.name,
+                    Symbol::Error(pt.name.loc, error_no),
+                ) {
+                    ns.errors.push(ErrorDecl {
+                        tags: Vec::new(),
+                        name: pt.name.name.to_owned(),
+                        loc: pt.name.loc,
+                        contract: Some(contract_no),
+                        fields: Vec::new(),
+                        signature: String::new(),
+                    });
+
+                    delay.errors.push(ResolveErrorFields {
+                        error_no,
+                        pt,
+                        comments: tags,
+                        contract: Some(contract_no),
+                    });
+                } else {
+                    broken = true;
+                }
+            }
             pt::ContractPart::TypeDefinition(ty) => {
                 let tags = parse_doccomments(comments, doc_comment_start, ty.loc.start());
 
@@ -610,6 +687,87 @@ fn event_decl(
     (doc, fields)
 }
 
+/// Resolve the fields of an error declaration
+fn error_decl(
+    def: &pt::ErrorDefinition,
+    file_no: usize,
+    tags: &[DocComment],
+    contract_no: Option<usize>,
+    ns: &mut Namespace,
+) -> (Vec<Tag>, Vec<Parameter>) {
+    let mut fields: Vec<Parameter> = Vec::new();
+
+    for field in &def.fields {
+        let mut diagnostics = Diagnostics::default();
+
+        let mut ty = match ns.resolve_type(file_no, contract_no, false, &field.ty, &mut diagnostics)
+        {
+            Ok(s) => s,
+            Err(()) => {
+                ns.diagnostics.extend(diagnostics);
+                Type::Unresolved
+            }
+        };
+
+        if ty.contains_mapping(ns) {
+            ns.diagnostics.push(Diagnostic::error(
+                field.loc,
+                "mapping type is not permitted as error field".to_string(),
+            ));
+            ty = Type::Unresolved;
+        }
+
+        let name = if let Some(name) = &field.name {
+            if let Some(other) = fields
+                .iter()
+                .find(|f| f.id.as_ref().map(|id| id.name.as_str()) == Some(name.name.as_str()))
+            {
+                ns.diagnostics.push(Diagnostic::error_with_note(
+                    name.loc,
+                    format!(
+                        "error '{}' has duplicate field name '{}'",
+                        def.name.name, name.name
+                    ),
+                    other.loc,
+                    format!(
+                        "location of previous declaration of '{}'",
+                        other.name_as_str()
+                    ),
+                ));
+                continue;
+            }
+            Some(pt::Identifier {
+                name: name.name.to_owned(),
+                loc: name.loc,
+            })
+        } else {
+            None
+        };
+
+        fields.push(Parameter {
+            loc: field.loc,
+            id: name,
+            ty,
+            ty_loc: Some(field.ty.loc()),
+            indexed: false,
+            readonly: false,
+            recursive: false,
+        });
+    }
+
+    let doc = resolve_tags(
+        def.name.loc.file_no(),
+        "error",
+        tags,
+        Some(&fields),
+        None,
+        None,
+        ns,
+    );
+
+    (doc, fields)
+}
+
 /// Parse enum declaration. If the declaration is invalid, it is still generated
 /// so that we can continue parsing, with errors recorded.
 fn enum_decl(
@@ -123,6 +123,16 @@ pub(crate) fn using_decl(
                         }
                     }
 
+                    if res.contains(&func_no) {
+                        diagnostics.push(Diagnostic::error_with_note(
+                            function_name.loc,
+                            format!("function '{}' is already attached", function_name),
+                            loc,
+                            format!("definition of '{}'", function_name),
+                        ));
+                        continue;
+                    }
+
                     res.push(func_no);
                 }
             }
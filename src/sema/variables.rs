@@ -397,6 +397,16 @@ pub fn variable_decl<'a>(
         ns,
     );
 
+    if constant && tags.iter().any(|tag| tag.tag == "storage") {
+        ns.diagnostics.push(Diagnostic::error(
+            def.loc,
+            format!(
+                "constant variable '{}' is not in storage, so cannot have tag '@storage'",
+                def.name.name
+            ),
+        ));
+    }
+
     let sdecl = Variable {
         name: def.name.name.to_string(),
         loc: def.loc,
@@ -660,7 +670,7 @@ pub fn resolve_initializers(
 
         let context = ExprContext {
             file_no,
-            unchecked: false,
+            unchecked: ns.files[file_no].wrapping_math,
             contract_no: Some(*contract_no),
             function_no: None,
             constant: false,
@@ -678,6 +688,7 @@ pub fn resolve_initializers(
         ) {
             if let Ok(res) = res.cast(&initializer.loc(), &ty, true, ns, &mut diagnostics) {
                 res.recurse(ns, check_term_for_constant_overflow);
+                check_forward_references(&res, *contract_no, *var_no, ns, &mut diagnostics);
                 ns.contracts[*contract_no].variables[*var_no].initializer = Some(res);
             }
         }
@@ -685,3 +696,50 @@ pub fn resolve_initializers(
 
     ns.diagnostics.extend(diagnostics);
 }
+
+/// Initializers are evaluated in declaration order, base contracts first, so an initializer may
+/// only read state variables declared before it. A later variable would still be zero.
+fn check_forward_references(
+    initializer: &Expression,
+    contract_no: usize,
+    var_no: usize,
+    ns: &Namespace,
+    diagnostics: &mut Diagnostics,
+) {
+    let mut references: Vec<(pt::Loc, usize, usize)> = Vec::new();
+
+    initializer.recurse(&mut references, |expr, references| {
+        if let Expression::StorageVariable(loc, _, var_contract_no, var_no) = expr {
+            references.push((*loc, *var_contract_no, *var_no));
+        }
+
+        true
+    });
+
+    let var = &ns.contracts[contract_no].variables[var_no];
+
+    for (loc, other_contract_no, other_var_no) in references {
+        if other_contract_no != contract_no || other_var_no < var_no {
+            continue;
+        }
+
+        let other = &ns.contracts[contract_no].variables[other_var_no];
+
+        if other_var_no == var_no {
+            diagnostics.push(Diagnostic::error(
+                loc,
+                format!("initializer of variable '{}' refers to itself", var.name),
+            ));
+        } else {
+            diagnostics.push(Diagnostic::error_with_note(
+                loc,
+                format!(
+                    "initializer of variable '{}' refers to variable '{}' which is declared later",
+                    var.name, other.name
+                ),
+                other.loc,
+                format!("definition of '{}'", other.name),
+            ));
+        }
+    }
+}
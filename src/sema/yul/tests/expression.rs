@@ -1606,6 +1606,8 @@ contract foo {
         Target::Substrate {
             address_length: 32,
             value_length: 16,
+            api_version: 2,
+            max_code_size: 128 * 1024,
         },
     );
 
@@ -46,4 +46,5 @@ pub struct OutputJson {
     pub severity: String,
     pub message: String,
     pub formattedMessage: String,
+    pub target: String,
 }
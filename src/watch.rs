@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Check Solidity files again when they, or any of the files they import, change. This is
+//! what `solang compile --watch` uses to give fast feedback while editing.
+
+use crate::file_resolver::FileResolver;
+use crate::sema::ast::Namespace;
+use crate::{parse_and_resolve, Target};
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub struct Watcher {
+    target: Target,
+    inputs: Vec<Input>,
+}
+
+struct Input {
+    filename: OsString,
+    /// The contents of the input file and its imports when it was last checked, or None if it
+    /// has not been checked yet. This is empty if the input file could not be loaded.
+    files: Option<Vec<(PathBuf, Arc<str>)>>,
+}
+
+impl Watcher {
+    /// Watch the given input files
+    pub fn new(target: Target, filenames: &[OsString]) -> Self {
+        Watcher {
+            target,
+            inputs: filenames
+                .iter()
+                .map(|filename| Input {
+                    filename: filename.clone(),
+                    files: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Parse and resolve the inputs for which the input file or any of its imports changed since
+    /// the last check. On the first call, all the inputs are checked. Returns the input number
+    /// and the resolved namespace for each input which was checked.
+    pub fn check(&mut self, resolver: &mut FileResolver) -> Vec<(usize, Namespace)> {
+        resolver.reload_changed_files();
+
+        let mut checked = Vec::new();
+
+        for (input_no, input) in self.inputs.iter_mut().enumerate() {
+            if !input.changed(resolver) {
+                continue;
+            }
+
+            let ns = parse_and_resolve(&input.filename, resolver, self.target);
+
+            input.files = Some(
+                ns.files
+                    .iter()
+                    .filter_map(|file| {
+                        resolver
+                            .get_file_contents(&file.path)
+                            .map(|contents| (file.path.clone(), contents))
+                    })
+                    .collect(),
+            );
+
+            checked.push((input_no, ns));
+        }
+
+        checked
+    }
+
+    /// The input filename for the given input number
+    pub fn filename(&self, input_no: usize) -> &OsStr {
+        &self.inputs[input_no].filename
+    }
+
+    /// All the files which the inputs were resolved from at the last check, and the input files
+    /// which could not be loaded
+    pub fn watched_files(&self) -> Vec<&Path> {
+        let mut files: Vec<&Path> = self
+            .inputs
+            .iter()
+            .flat_map(|input| match &input.files {
+                Some(files) if files.is_empty() => vec![Path::new(&input.filename)],
+                files => files
+                    .iter()
+                    .flatten()
+                    .map(|(path, _)| path.as_path())
+                    .collect(),
+            })
+            .collect();
+
+        files.sort();
+        files.dedup();
+
+        files
+    }
+}
+
+impl Input {
+    /// Has the input file or any of its imports changed since the last check. An input file
+    /// which could not be loaded has changed once its contents are in the cache.
+    fn changed(&self, resolver: &mut FileResolver) -> bool {
+        match &self.files {
+            Some(files) if files.is_empty() => resolver
+                .resolve_file(None, &self.filename)
+                .map_or(false, |file| {
+                    resolver.get_file_contents(&file.full_path).is_some()
+                }),
+            Some(files) => files.iter().any(|(path, contents)| {
+                resolver
+                    .get_file_contents(path)
+                    .map_or(true, |current| current != *contents)
+            }),
+            None => true,
+        }
+    }
+}
+
+#[test]
+fn only_changed_inputs_are_checked() {
+    let mut resolver = FileResolver::new();
+
+    resolver.set_file_contents(
+        "a.sol",
+        r#"import "lib.sol";
+        contract a {
+            function f() public pure returns (uint) {
+                return l();
+            }
+        }"#
+        .to_string(),
+    );
+    resolver.set_file_contents("b.sol", "contract b {}".to_string());
+    resolver.set_file_contents(
+        "lib.sol",
+        "function l() pure returns (uint) { return 1; }".to_string(),
+    );
+
+    let mut watcher = Watcher::new(
+        Target::EVM,
+        &[OsString::from("a.sol"), OsString::from("b.sol")],
+    );
+
+    let check = |watcher: &mut Watcher, resolver: &mut FileResolver| -> Vec<(usize, bool)> {
+        watcher
+            .check(resolver)
+            .into_iter()
+            .map(|(input_no, ns)| (input_no, ns.diagnostics.any_errors()))
+            .collect()
+    };
+
+    // everything is checked the first time
+    assert_eq!(
+        check(&mut watcher, &mut resolver),
+        vec![(0, false), (1, false)]
+    );
+    assert_eq!(
+        watcher.watched_files(),
+        vec![Path::new("a.sol"), Path::new("b.sol"), Path::new("lib.sol")]
+    );
+
+    assert!(check(&mut watcher, &mut resolver).is_empty());
+
+    // an import only affects the inputs which import it
+    resolver.set_file_contents(
+        "lib.sol",
+        "function l() pure returns (uint) { return x; }".to_string(),
+    );
+
+    assert_eq!(check(&mut watcher, &mut resolver), vec![(0, true)]);
+    assert_eq!(watcher.filename(0), "a.sol");
+
+    resolver.set_file_contents("b.sol", "contract b { int x; }".to_string());
+
+    assert_eq!(check(&mut watcher, &mut resolver), vec![(1, false)]);
+
+    resolver.set_file_contents(
+        "lib.sol",
+        "function l() pure returns (uint) { return 2; }".to_string(),
+    );
+
+    assert_eq!(check(&mut watcher, &mut resolver), vec![(0, false)]);
+    assert!(check(&mut watcher, &mut resolver).is_empty());
+}
+
+#[test]
+fn input_which_fails_to_load_is_checked_again() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut resolver = FileResolver::new();
+
+    resolver.add_import_path(dir.path()).unwrap();
+
+    let mut watcher = Watcher::new(Target::EVM, &[OsString::from("a.sol")]);
+
+    let checked = watcher.check(&mut resolver);
+
+    assert_eq!(checked.len(), 1);
+    assert!(checked[0].1.diagnostics.any_errors());
+    assert_eq!(watcher.watched_files(), vec![Path::new("a.sol")]);
+
+    assert!(watcher.check(&mut resolver).is_empty());
+
+    std::fs::write(dir.path().join("a.sol"), "contract a {}").unwrap();
+
+    let checked = watcher.check(&mut resolver);
+
+    assert_eq!(checked.len(), 1);
+    assert!(!checked[0].1.diagnostics.any_errors());
+
+    let path = dir.path().join("a.sol").canonicalize().unwrap();
+
+    assert_eq!(watcher.watched_files(), vec![path.as_path()]);
+
+    assert!(watcher.check(&mut resolver).is_empty());
+}
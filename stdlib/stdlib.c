@@ -133,7 +133,7 @@ struct vector *vector_new(uint32_t members, uint32_t size, uint8_t *initial)
 
 	v = __malloc(sizeof(*v) + size_array);
 	v->len = members;
-	v->size = members;
+	v->capacity = members;
 
 	uint8_t *data = v->data;
 
@@ -188,7 +188,7 @@ struct vector *concat(uint8_t *left, uint32_t left_len, uint8_t *right, uint32_t
 	size_t size_array = left_len + right_len;
 	struct vector *v = __malloc(sizeof(*v) + size_array);
 	v->len = size_array;
-	v->size = size_array;
+	v->capacity = size_array;
 
 	uint8_t *data = v->data;
 
@@ -1,10 +1,12 @@
 /*
- * Vector is used for dynamic array
+ * Vector is used for dynamic array. The capacity is the number of elements
+ * allocated, which may be more than len. The element size is not stored; it
+ * follows from the type of the array.
  */
 struct vector
 {
     uint32_t len;
-    uint32_t size;
+    uint32_t capacity;
     uint8_t data[];
 };
 
@@ -77,7 +77,7 @@ struct vector *scale_decode_string(uint8_t **from)
     struct vector *v = __malloc(sizeof(*v) + size_array);
 
     v->len = size_array;
-    v->size = size_array;
+    v->capacity = size_array;
 
     uint8_t *data = v->data;
 
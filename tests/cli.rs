@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use assert_cmd::Command;
-use std::fs::File;
+use std::fs::{self, File};
 
 #[test]
 fn create_output_dir() {
@@ -33,3 +33,55 @@ fn create_output_dir() {
     .assert()
     .failure();
 }
+
+#[test]
+fn multiple_targets() {
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    cmd.args([
+        "compile",
+        "examples/target_conditional.sol",
+        "--target",
+        "solana",
+        "--target",
+        "substrate",
+        "--output",
+        "tests/multiple_targets",
+    ])
+    .assert()
+    .success();
+
+    File::open("tests/multiple_targets/solana/bundle.so").expect("should exist");
+    File::open("tests/multiple_targets/solana/target_conditional.abi").expect("should exist");
+    File::open("tests/multiple_targets/substrate/target_conditional.wasm").expect("should exist");
+    File::open("tests/multiple_targets/substrate/target_conditional.contract")
+        .expect("should exist");
+
+    // diagnostics which are only found on one target say which target they are for
+    fs::write(
+        "tests/multiple_targets/minimum_balance.sol",
+        "contract c { function f() public view returns (uint128) { return block.minimum_balance; } }",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    let assert = cmd
+        .args([
+            "compile",
+            "tests/multiple_targets/minimum_balance.sol",
+            "--target",
+            "solana",
+            "--target",
+            "substrate",
+            "--output",
+            "tests/multiple_targets",
+        ])
+        .assert()
+        .failure();
+
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+
+    assert!(stderr.contains("info: diagnostics for target 'solana'"));
+    assert!(stderr.contains("not all contracts are valid for target 'solana'"));
+}
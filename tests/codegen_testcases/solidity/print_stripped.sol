@@ -0,0 +1,16 @@
+// RUN: --target substrate --emit cfg
+contract c {
+    // without --log-prints, the conversion of the arguments is removed but the call is kept
+// BEGIN-CHECK: c::function::f
+    function f(uint64 a) public returns (uint64) {
+        print("a:", a, g());
+        return a;
+    }
+// CHECK: call c::c::function::g
+// NOT-CHECK: print
+// CHECK: return (arg #0)
+
+    function g() internal returns (bool) {
+        return true;
+    }
+}
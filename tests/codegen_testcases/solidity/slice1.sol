@@ -1,4 +1,4 @@
-// RUN: --target substrate --emit cfg
+// RUN: --target substrate --emit cfg --log-prints
 contract c {
 // BEGIN-CHECK: c::function::test1
 	function test1() public pure{
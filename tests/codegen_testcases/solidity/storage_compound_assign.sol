@@ -0,0 +1,14 @@
+// RUN: --target substrate --emit cfg
+contract c {
+    mapping(uint => uint) balances;
+
+    // the slot is hashed once, and used for both the load and the store
+// BEGIN-CHECK: c::function::add
+    function add(uint user, uint amount) public {
+        balances[user] += amount;
+    }
+// CHECK: = (keccak256
+// NOT-CHECK: keccak256
+// CHECK: load storage slot(%slot.temp.
+// CHECK: store storage slot(%slot.temp.
+}
@@ -1,4 +1,4 @@
-// RUN: --target substrate --emit cfg
+// RUN: --target substrate --emit cfg --log-prints
 contract test {
 /******************/
 /* Multiply tests */
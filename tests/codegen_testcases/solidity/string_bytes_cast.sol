@@ -0,0 +1,13 @@
+// RUN: --target substrate --emit cfg
+contract c {
+// BEGIN-CHECK: c::function::to_bytes_and_back
+	function to_bytes_and_back(string s) public pure returns (string) {
+		// string and bytes have the same representation, so the cast does not copy
+		bytes b = bytes(s);
+// CHECK: ty:bytes %b = bytes(
+		b[0] = "H";
+
+		return string(b);
+// CHECK: return string(%b)
+	}
+}
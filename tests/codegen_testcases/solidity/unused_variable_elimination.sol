@@ -66,7 +66,7 @@ contract c {
         x = 102 + t*y/(t+5*y) + g + test3() - vec.push(2) + ct.sum(1, 2);
 		return 2;
 // CHECK: push array ty:int32[] value:int32 2
-// CHECK: _ = external call::regular address:%ct payload:(abiencode packed:hex"91577f73" non-packed:int32 1, int32 2) value:uint128 0 gas:uint64 0
+// CHECK: _ = external call::regular address:%ct payload:(abiencode packed:hex"91577f73" non-packed:int32 1, int32 2) value:uint128 0 gas:((builtin Gasleft ()) - ((builtin Gasleft ()) >> uint64 6))
 	}
 }
 
@@ -76,16 +76,16 @@ contract c3 {
         c2 ct = new c2();
 
         return 3;
-// CHECK: constructor salt: value: gas:uint64 0 space: c2 (encoded buffer: %temp.78, buffer len: (builtin ArrayLength (%temp.78)))
+// CHECK: constructor salt: value: gas:((builtin Gasleft ()) - ((builtin Gasleft ()) >> uint64 6)) space: c2 (encoded buffer: %temp.78, buffer len: (builtin ArrayLength (%temp.78)))
     }
 
 // BEGIN-CHECK: c3::function::test7
     function test7() public returns (int32) {
         c2 ct = new c2();
-// CHECK: constructor salt: value: gas:uint64 0 space: c2 (encoded buffer: %temp.80, buffer len: (builtin ArrayLength (%temp.80)))
+// CHECK: constructor salt: value: gas:((builtin Gasleft ()) - ((builtin Gasleft ()) >> uint64 6)) space: c2 (encoded buffer: %temp.80, buffer len: (builtin ArrayLength (%temp.80)))
         address ad = address(ct);
         (bool p, ) = ad.call(hex'ba');
-// CHECK: external call::regular address:%ad payload:(alloc bytes uint32 1 hex"ba") value:uint128 0 gas:uint64 0
+// CHECK: external call::regular address:%ad payload:(alloc bytes uint32 1 hex"ba") value:uint128 0 gas:((builtin Gasleft ()) - ((builtin Gasleft ()) >> uint64 6))
 // NOT-CHECk: ty:bool %p = %success.temp.30
 return 3;
     }
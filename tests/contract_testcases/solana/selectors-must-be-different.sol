@@ -1,8 +1,8 @@
 abstract contract c {
 	function f1() selector=hex"01" public {}
-	// error: selector must be unique
+	// error: selector collision between f1() and f2()
 	function f2() selector=hex"01" public {}
-	// error: selector must be unique
+	// error: selector collision between f1() and f3()
 	function f3() selector=hex"01" public {}
 }
 
@@ -3,6 +3,6 @@
             function test() public {
                 address x = address(0);
 
-                x.delegatecall(hex"1222");
+                x.delegatecall{value: 1}(hex"1222");
             }
         }
\ No newline at end of file
@@ -3,6 +3,6 @@
             function test() public {
                 address x = address(0);
 
-                x.staticcall(hex"1222");
+                x.staticcall{value: 1}(hex"1222");
             }
         }
\ No newline at end of file
@@ -166,3 +166,63 @@ contract testing  {
 
     assert!(!ns.diagnostics.any_errors());
 }
+
+#[test]
+fn delete_mapping() {
+    let ns = test_solidity(
+        r#"
+        contract c {
+            struct S {
+                mapping(uint => bool) flags;
+                uint count;
+            }
+
+            mapping(uint => bool) data;
+            S s;
+
+            function test() public {
+                delete data;
+            }
+
+            function test2() public {
+                delete s.flags;
+            }
+
+            function test3() public {
+                delete data[1];
+                delete s.flags[2];
+            }
+        }"#,
+    );
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].message,
+        "cannot delete a mapping; delete individual keys instead"
+    );
+    assert_eq!(
+        errors[1].message,
+        "cannot delete a mapping; delete individual keys instead"
+    );
+}
+
+#[test]
+fn storage_slot_not_assignable() {
+    let ns = test_solidity(
+        r#"
+        contract c {
+            uint64 a;
+
+            function test() public {
+                a.slot = 2;
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        ns.diagnostics.first_error(),
+        "cannot assign to slot of storage variable"
+    );
+}
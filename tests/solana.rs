@@ -61,12 +61,16 @@ type CallParametersCheck = fn(vm: &VirtualMachine, instr: &Instruction, pda: &[P
 struct VirtualMachine {
     account_data: HashMap<Account, AccountState>,
     origin: Account,
+    /// Lamports sent with the next instruction
+    value: u64,
     programs: Vec<Contract>,
     stack: Vec<Contract>,
     logs: String,
     events: Vec<Vec<Vec<u8>>>,
     return_data: Option<(Account, Vec<u8>)>,
     call_params_check: HashMap<Pubkey, CallParametersCheck>,
+    /// Number of instructions the last program run executed
+    instruction_count: u64,
 }
 
 #[derive(Clone)]
@@ -103,6 +107,12 @@ struct CreateAccountWithSeed {
     program_id: Account,
 }
 
+#[derive(Deserialize)]
+struct Transfer {
+    instruction: u32,
+    lamports: u64,
+}
+
 #[derive(Deserialize)]
 struct Allocate {
     instruction: u32,
@@ -127,7 +137,13 @@ fn build_solidity_with_overflow_check(src: &str, math_overflow_flag: bool) -> Vi
     let mut ns = solang::parse_and_resolve(OsStr::new("test.sol"), &mut cache, Target::Solana);
 
     // codegen all the contracts; some additional errors/warnings will be detected here
-    codegen(&mut ns, &Options::default());
+    codegen(
+        &mut ns,
+        &Options {
+            math_overflow_check: math_overflow_flag,
+            ..Default::default()
+        },
+    );
 
     ns.print_diagnostics_in_plain(&cache, false);
 
@@ -228,12 +244,14 @@ fn build_solidity_with_overflow_check(src: &str, math_overflow_flag: bool) -> Vi
     VirtualMachine {
         account_data,
         origin,
+        value: 0,
         programs,
         stack: vec![cur],
         logs: String::new(),
         events: Vec::new(),
         return_data: None,
         call_params_check: HashMap::new(),
+        instruction_count: 0,
     }
 }
 
@@ -1333,6 +1351,54 @@ impl<'a> SyscallObject<UserError> for SyscallInvokeSignedC<'a> {
                             entry.owner = Some(assign.owner);
                         }
                     }
+                    2 => {
+                        let transfer: Transfer = bincode::deserialize(&instruction.data).unwrap();
+
+                        assert_eq!(transfer.instruction, 2);
+
+                        let from = &instruction.accounts[0].pubkey;
+                        let to = &instruction.accounts[1].pubkey;
+
+                        println!(
+                            "transfer {} lamports from {} to {}",
+                            transfer.lamports,
+                            from.0.to_base58(),
+                            to.0.to_base58()
+                        );
+
+                        // the lamports of the running program live in its parameter region
+                        let refs = self.context.refs.try_borrow().unwrap();
+
+                        let lamports_offset = |account: &Pubkey| {
+                            refs.iter().find(|r| r.account == account.0).unwrap().offset - 16
+                        };
+
+                        let input = self.context.input;
+
+                        let read_lamports = |offset: usize| {
+                            u64::from_le_bytes(input[offset..offset + 8].try_into().unwrap())
+                        };
+
+                        let from_offset = lamports_offset(from);
+                        let to_offset = lamports_offset(to);
+
+                        let from_lamports = read_lamports(from_offset)
+                            .checked_sub(transfer.lamports)
+                            .expect("insufficient lamports");
+                        let to_lamports = read_lamports(to_offset) + transfer.lamports;
+
+                        for (offset, lamports) in
+                            [(from_offset, from_lamports), (to_offset, to_lamports)]
+                        {
+                            unsafe {
+                                std::ptr::copy(
+                                    lamports.to_le_bytes().as_ptr(),
+                                    input[offset..].as_ptr() as *mut u8,
+                                    8,
+                                );
+                            }
+                        }
+                    }
                     3 => {
                         let create_account: CreateAccountWithSeed =
                             bincode::deserialize(&instruction.data).unwrap();
@@ -1558,8 +1624,12 @@ impl VirtualMachine {
 
         let res = vm.execute_program_interpreted(&mut TestInstructionMeter { remaining: 1000000 });
 
+        let instruction_count = vm.get_total_instruction_count();
+
         deserialize_parameters(&parameter_bytes, &refs, &mut self.account_data);
 
+        self.instruction_count = instruction_count;
+
         self.validate_account_data_heap();
 
         if let Some((_, return_data)) = &self.return_data {
@@ -1574,7 +1644,8 @@ impl VirtualMachine {
 
         println!("constructor for {}", hex::encode(program.data));
 
-        let mut calldata = VirtualMachine::input(&program.data, &self.origin, name, &[]);
+        let mut calldata =
+            VirtualMachine::input(&program.data, &self.origin, self.value, name, &[]);
 
         if let Some(constructor) = &program.abi.as_ref().unwrap().constructor {
             calldata.extend(&constructor.encode_input(vec![], args).unwrap());
@@ -1603,6 +1674,7 @@ impl VirtualMachine {
             } else {
                 &self.origin
             },
+            self.value,
             name,
             seeds,
         );
@@ -1654,6 +1726,7 @@ impl VirtualMachine {
             } else {
                 &self.origin
             },
+            self.value,
             name,
             seeds,
         );
@@ -1673,12 +1746,13 @@ impl VirtualMachine {
     fn input(
         recv: &Account,
         sender: &Account,
+        value: u64,
         name: &str,
         seeds: &[&(Account, Vec<u8>)],
     ) -> Vec<u8> {
         let mut calldata: Vec<u8> = recv.to_vec();
         calldata.extend_from_slice(sender);
-        calldata.extend_from_slice(&0u64.to_le_bytes());
+        calldata.extend_from_slice(&value.to_le_bytes());
 
         let mut hasher = Keccak::v256();
         let mut hash = [0u8; 32];
@@ -1706,6 +1780,11 @@ impl VirtualMachine {
         &self.account_data[&program.data].data
     }
 
+    /// The bytes of the data account of the current contract, starting at the given offset
+    fn data_at(&self, offset: usize, len: usize) -> &[u8] {
+        &self.data()[offset..offset + len]
+    }
+
     fn set_program(&mut self, no: usize) {
         let cur = self.programs[no].clone();
 
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::build_solidity;
+use ethabi::{ethereum_types::U256, Token};
 
 #[test]
 fn packed() {
@@ -73,3 +74,44 @@ fn inherited() {
 
     vm.function("test", &[], &[], None);
 }
+
+#[test]
+fn decode_dynamic_then_static() {
+    let mut vm = build_solidity(
+        r#"
+        contract bar {
+            function test() public {
+                bytes enc = abi.encode(hex"deadcafe", uint256(102));
+
+                (bytes b, uint256 n) = abi.decode(enc, (bytes, uint256));
+
+                assert(b == hex"deadcafe");
+                assert(n == 102);
+            }
+
+            function decode(bytes enc) public returns (bytes, uint256) {
+                return abi.decode(enc, (bytes, uint256));
+            }
+        }"#,
+    );
+
+    vm.constructor("bar", &[]);
+
+    vm.function("test", &[], &[], None);
+
+    // the bytes are found through the offset in the head, and the uint follows the offset
+    let enc = ethabi::encode(&[
+        Token::Bytes(b"hello, world".to_vec()),
+        Token::Uint(U256::from(0xfeed)),
+    ]);
+
+    let returns = vm.function("decode", &[Token::Bytes(enc)], &[], None);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Bytes(b"hello, world".to_vec()),
+            Token::Uint(U256::from(0xfeed)),
+        ]
+    );
+}
@@ -117,3 +117,30 @@ fn data() {
 
     assert_eq!(returns[0], Token::Uint(U256::from(val)));
 }
+
+#[test]
+fn not_enough_accounts() {
+    let mut vm = build_solidity(
+        r#"
+        import 'solana';
+        contract c {
+            function test(uint32 i) public returns (address) {
+                AccountInfo ai = tx.accounts[i];
+
+                return ai.key;
+            }
+        }"#,
+    );
+
+    vm.constructor("c", &[]);
+
+    let res = vm.function_must_fail("test", &[Token::Uint(U256::from(100))], &[], None);
+
+    assert_eq!(res.ok(), Some(4294967296));
+
+    let mut panic = vec![0x4e, 0x48, 0x7b, 0x71];
+    panic.resize(36, 0);
+    panic[35] = 0x64;
+
+    assert_eq!(vm.return_data.as_ref().unwrap().1, panic);
+}
@@ -398,3 +398,104 @@ fn value_overflows() {
         103
     );
 }
+
+#[test]
+fn payable_value() {
+    let mut vm = build_solidity(
+        r#"
+        contract c {
+            uint64 received;
+
+            function deposit() public payable returns (uint64) {
+                received += msg.value;
+
+                return address(this).balance;
+            }
+
+            function total() public view returns (uint64) {
+                return received;
+            }
+        }"#,
+    );
+
+    vm.constructor("c", &[]);
+
+    vm.account_data.get_mut(&vm.origin).unwrap().lamports = 1000;
+
+    vm.value = 300;
+
+    let returns = vm.function("deposit", &[], &[], None);
+
+    vm.value = 0;
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(300))]);
+
+    assert_eq!(vm.account_data[&vm.origin].lamports, 700);
+    assert_eq!(vm.account_data[&vm.stack[0].data].lamports, 300);
+
+    let returns = vm.function("total", &[], &[], None);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(300))]);
+}
+
+#[test]
+fn nonpayable_value() {
+    let mut vm = build_solidity(
+        r#"
+        contract c {
+            function test() public returns (uint64) {
+                return msg.value;
+            }
+        }"#,
+    );
+
+    vm.constructor("c", &[]);
+
+    vm.account_data.get_mut(&vm.origin).unwrap().lamports = 1000;
+
+    vm.value = 1;
+
+    let res = vm.function_must_fail("test", &[], &[], None);
+
+    vm.value = 0;
+
+    assert_eq!(res.ok(), Some(4294967296));
+
+    let mut panic = vec![0x4e, 0x48, 0x7b, 0x71];
+    panic.resize(36, 0);
+    panic[35] = 0x63;
+
+    assert_eq!(vm.return_data.as_ref().unwrap().1, panic);
+
+    assert_eq!(vm.account_data[&vm.origin].lamports, 1000);
+    assert_eq!(vm.account_data[&vm.stack[0].data].lamports, 0);
+
+    let returns = vm.function("test", &[], &[], None);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(0))]);
+}
+
+#[test]
+fn value_type_alias() {
+    let mut vm = build_solidity(include_str!("../../examples/value_type.sol"));
+
+    vm.constructor("wallet", &[]);
+
+    vm.account_data.get_mut(&vm.origin).unwrap().lamports = 1000;
+
+    vm.value = 300;
+
+    let returns = vm.function("deposit", &[], &[], None);
+
+    vm.value = 0;
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(300))]);
+
+    let returns = vm.function("max", &[], &[], None);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(u64::MAX))]);
+
+    let returns = vm.function("me", &[], &[], None);
+
+    assert_eq!(returns, vec![Token::FixedBytes(vm.stack[0].data.to_vec())]);
+}
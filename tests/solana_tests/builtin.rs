@@ -252,3 +252,18 @@ fn out_of_bounds_string_write() {
     vm.constructor("Testing", &[]);
     let _ = vm.function("testStringOut", &[], &[], None);
 }
+
+#[test]
+fn target_conditional() {
+    let mut vm = build_solidity(include_str!("../../examples/target_conditional.sol"));
+
+    vm.constructor("target_conditional", &[]);
+
+    let returns = vm.function("chain", &[], &[], None);
+
+    assert_eq!(returns, vec![Token::String(String::from("solana"))]);
+
+    let returns = vm.function("threshold", &[], &[], None);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(70818331))]);
+}
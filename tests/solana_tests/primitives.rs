@@ -134,6 +134,39 @@ fn address() {
     );
 }
 
+#[test]
+fn bytes32_address_conversion() {
+    let mut vm = build_solidity(
+        r#"
+        contract foo {
+            function to_address(bytes32 b) public returns (address) {
+                return address(b);
+            }
+
+            function to_bytes(address a) public returns (bytes32) {
+                return bytes32(a);
+            }
+        }"#,
+    );
+
+    vm.constructor("foo", &[]);
+
+    let b: Vec<u8> = (1..=32).collect();
+
+    let returns = vm.function(
+        "to_address",
+        &[ethabi::Token::FixedBytes(b.clone())],
+        &[],
+        None,
+    );
+
+    assert_eq!(returns, vec![ethabi::Token::FixedBytes(b.clone())]);
+
+    let returns = vm.function("to_bytes", &returns, &[], None);
+
+    assert_eq!(returns, vec![ethabi::Token::FixedBytes(b)]);
+}
+
 #[test]
 fn test_enum() {
     // we need to test enum literals
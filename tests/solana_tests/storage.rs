@@ -763,3 +763,55 @@ fn complex_struct() {
 }
 
 // dereference struct storage member (read/write)
+
+#[test]
+fn aligned_layout() {
+    let mut vm = build_solidity(
+        r#"
+        contract c {
+            bool f;
+            uint32 a;
+            uint64 b;
+
+            function set() public {
+                f = true;
+                a = 0x01020304;
+                b = 0x0a0b0c0d0e0f1011;
+            }
+        }"#,
+    );
+
+    vm.constructor("c", &[]);
+
+    assert_eq!(vm.data_at(16, 16), [0u8; 16]);
+
+    vm.function("set", &[], &[], None);
+
+    // a is aligned to 4 bytes and b to 8 bytes
+    assert_eq!(
+        vm.data_at(16, 16),
+        [1, 0, 0, 0, 4, 3, 2, 1, 0x11, 0x10, 0x0f, 0x0e, 0x0d, 0x0c, 0x0b, 0x0a]
+    );
+}
+
+#[test]
+fn default_initializers_deploy_cost() {
+    let deploy_cost = |vars: &str| {
+        let mut vm = build_solidity(&format!("contract c {{ {} }}", vars));
+
+        vm.constructor("c", &[]);
+
+        vm.instruction_count
+    };
+
+    let uninitialized = deploy_cost(r#"uint64 a; bool b; string s; bytes4 h; int128 n;"#);
+    let defaults =
+        deploy_cost(r#"uint64 a = 0; bool b = false; string s = ""; bytes4 h = 0; int128 n = 0;"#);
+    let non_defaults = deploy_cost(
+        r#"uint64 a = 1; bool b = true; string s = "x"; bytes4 h = "abcd"; int128 n = -1;"#,
+    );
+
+    // initializers with the default value are not stored, so they cost nothing on deploy
+    assert_eq!(defaults, uninitialized);
+    assert!(non_defaults > defaults);
+}
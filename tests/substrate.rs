@@ -5,14 +5,18 @@ use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 use rand::Rng;
 use sha2::{Digest, Sha256};
-use std::{collections::HashMap, ffi::OsStr, fmt, fmt::Write};
+use std::{collections::HashMap, ffi::OsStr, fmt, fmt::Write, rc::Rc};
 use tiny_keccak::{Hasher, Keccak};
 use wasmi::memory_units::Pages;
 use wasmi::*;
 
 use solang::abi;
+use solang::abi::scale::{self, Value};
+use solang::codegen::{codegen, Options};
 use solang::file_resolver::FileResolver;
-use solang::{compile, Target};
+use solang::sema::ast::{Function, Namespace};
+use solang::Target;
+use solang_parser::pt;
 
 mod substrate_tests;
 
@@ -21,6 +25,8 @@ type Account = [u8; 32];
 
 /// In `ink!`, u32::MAX (which is -1 in 2s complement) represents a `None` value
 const NONE_SENTINEL: RuntimeValue = RuntimeValue::I32(-1);
+/// The existential deposit of the mock chain
+const MINIMUM_BALANCE: u128 = 500;
 
 fn account_new() -> Account {
     let mut rng = rand::thread_rng();
@@ -54,6 +60,26 @@ impl fmt::Display for HostCodeReturn {
 
 impl HostError for HostCodeReturn {}
 
+/// The return code of seal_call or seal_instantiate when the callee called seal_return. Like
+/// substrate, this is CalleeReverted if the revert flag was set
+fn callee_return_code(ret: &HostCodeReturn) -> i32 {
+    if ret.0 == 1 {
+        2
+    } else {
+        0
+    }
+}
+
+/// The seal_call flag which forbids the callee from modifying any state
+const CALL_FLAG_READ_ONLY: u32 = 0x10;
+
+/// The state of the chain which is restored when a call or instantiation fails
+struct Snapshot {
+    store: HashMap<(Account, StorageKey), Vec<u8>>,
+    accounts: HashMap<Account, (Vec<u8>, u128)>,
+    events: usize,
+}
+
 #[derive(FromPrimitive)]
 #[allow(non_camel_case_types)]
 enum SubstrateExternal {
@@ -82,6 +108,8 @@ enum SubstrateExternal {
     seal_caller,
     seal_deposit_event,
     seal_transfer,
+    seal_code_hash,
+    seal_delegate_call,
 }
 
 pub struct Event {
@@ -96,6 +124,10 @@ pub struct VirtualMachine {
     input: Vec<u8>,
     pub output: Vec<u8>,
     pub value: u128,
+    /// Gas is not metered, so this is simply the limit the call was given
+    gas_left: u64,
+    /// Set for the callee of a static call, which may not modify any state
+    read_only: bool,
 }
 
 impl VirtualMachine {
@@ -107,6 +139,8 @@ impl VirtualMachine {
             account,
             caller,
             value,
+            gas_left: 2_224_097_461,
+            read_only: false,
         }
     }
 }
@@ -114,6 +148,7 @@ impl VirtualMachine {
 pub struct Program {
     abi: abi::substrate::Abi,
     code: Vec<u8>,
+    contract_no: usize,
 }
 pub struct MockSubstrate {
     pub store: HashMap<(Account, StorageKey), Vec<u8>>,
@@ -123,6 +158,9 @@ pub struct MockSubstrate {
     pub current_program: usize,
     pub vm: VirtualMachine,
     pub events: Vec<Event>,
+    /// Block timestamp in milliseconds, as returned by seal_now
+    pub now: u64,
+    ns: Rc<Namespace>,
 }
 
 impl Externals for MockSubstrate {
@@ -246,6 +284,11 @@ impl Externals for MockSubstrate {
                 }
 
                 println!("seal_clear_storage: {:?}", key);
+
+                if self.vm.read_only {
+                    return Err(Trap::new(TrapKind::Unreachable));
+                }
+
                 let pre_existing_len = self
                     .store
                     .remove(&(self.vm.account, key))
@@ -277,6 +320,10 @@ impl Externals for MockSubstrate {
                 }
                 println!("seal_set_storage: {:?} = {:?}", key, data);
 
+                if self.vm.read_only {
+                    return Err(Trap::new(TrapKind::Unreachable));
+                }
+
                 let pre_existing_len = self
                     .store
                     .insert((self.vm.account, key), data)
@@ -477,14 +524,14 @@ impl Externals for MockSubstrate {
             Some(SubstrateExternal::seal_call) => {
                 let flags: u32 = args.nth_checked(0)?;
                 let account_ptr: u32 = args.nth_checked(1)?;
-                // Gas usage is ignored in the mock VM
+                let gas: u64 = args.nth_checked(2)?;
                 let value_ptr: u32 = args.nth_checked(3)?;
                 let input_ptr: u32 = args.nth_checked(4)?;
                 let input_len: u32 = args.nth_checked(5)?;
                 let output_ptr: u32 = args.nth_checked(6)?;
                 let output_len_ptr: u32 = args.nth_checked(7)?;
 
-                assert_eq!(flags, 0); //TODO: Call flags are not yet implemented
+                assert_eq!(flags & !CALL_FLAG_READ_ONLY, 0);
                 let mut account = [0u8; 32];
 
                 if let Err(e) = self.vm.memory.get_into(account_ptr, &mut account) {
@@ -499,6 +546,11 @@ impl Externals for MockSubstrate {
 
                 let value = u128::from_le_bytes(value);
 
+                // a read-only call may not transfer any value
+                if self.vm.read_only && value != 0 {
+                    return Err(Trap::new(TrapKind::Unreachable));
+                }
+
                 if !self.accounts.contains_key(&account) {
                     // substrate would return NotCallable
                     return Ok(Some(RuntimeValue::I32(0x8)));
@@ -519,27 +571,42 @@ impl Externals for MockSubstrate {
 
                 let mut vm = VirtualMachine::new(account, self.vm.account, value);
 
+                vm.read_only = self.vm.read_only || (flags & CALL_FLAG_READ_ONLY) != 0;
+
+                // a gas limit of zero means all the remaining gas
+                vm.gas_left = if gas == 0 {
+                    self.vm.gas_left
+                } else {
+                    gas.min(self.vm.gas_left)
+                };
+
                 std::mem::swap(&mut self.vm, &mut vm);
 
                 let module = self.create_module(&self.accounts.get(&self.vm.account).unwrap().0);
 
                 self.vm.input = input;
 
+                let snapshot = self.snapshot();
+
                 let ret = module.invoke_export("call", &[], self);
 
                 let ret = match ret {
                     Err(wasmi::Error::Trap(trap)) => match trap.kind() {
                         TrapKind::Host(host_error) => {
                             if let Some(ret) = host_error.downcast_ref::<HostCodeReturn>() {
-                                Some(RuntimeValue::I32(ret.0))
+                                Some(RuntimeValue::I32(callee_return_code(ret)))
                             } else if host_error.downcast_ref::<HostCodeTerminate>().is_some() {
-                                Some(RuntimeValue::I32(1))
+                                // a terminated callee is a successful call with no output
+                                self.vm.output.clear();
+                                Some(RuntimeValue::I32(0))
                             } else {
                                 return Err(trap);
                             }
                         }
                         _ => {
-                            return Err(trap);
+                            // substrate would return CalleeTrapped
+                            self.vm.output.clear();
+                            Some(RuntimeValue::I32(1))
                         }
                     },
                     Ok(v) => v,
@@ -552,7 +619,9 @@ impl Externals for MockSubstrate {
 
                 println!("seal_call ret={:?} buf={}", ret, hex::encode(&output));
 
-                if let Some(acc) = self.accounts.get_mut(&vm.account) {
+                if matches!(ret, Some(RuntimeValue::I32(code)) if code != 0) {
+                    self.rollback(snapshot);
+                } else if let Some(acc) = self.accounts.get_mut(&vm.account) {
                     acc.1 += vm.value;
                 }
 
@@ -560,6 +629,127 @@ impl Externals for MockSubstrate {
 
                 Ok(ret)
             }
+            Some(SubstrateExternal::seal_code_hash) => {
+                let account_ptr: u32 = args.nth_checked(0)?;
+                let output_ptr: u32 = args.nth_checked(1)?;
+                let output_len_ptr: u32 = args.nth_checked(2)?;
+
+                let mut account = [0u8; 32];
+
+                if let Err(e) = self.vm.memory.get_into(account_ptr, &mut account) {
+                    panic!("seal_code_hash: {}", e);
+                }
+
+                if let Some((code, _)) = self.accounts.get(&account) {
+                    let code_hash = blake2_rfc::blake2b::blake2b(32, &[], code);
+
+                    set_seal_value!(
+                        "seal_code_hash",
+                        output_ptr,
+                        output_len_ptr,
+                        code_hash.as_bytes()
+                    );
+
+                    Ok(Some(RuntimeValue::I32(0)))
+                } else {
+                    // substrate would return KeyNotFound
+                    Ok(Some(RuntimeValue::I32(3)))
+                }
+            }
+            Some(SubstrateExternal::seal_delegate_call) => {
+                let flags: u32 = args.nth_checked(0)?;
+                let codehash_ptr: u32 = args.nth_checked(1)?;
+                let input_ptr: u32 = args.nth_checked(2)?;
+                let input_len: u32 = args.nth_checked(3)?;
+                let output_ptr: u32 = args.nth_checked(4)?;
+                let output_len_ptr: u32 = args.nth_checked(5)?;
+
+                assert_eq!(flags, 0);
+
+                let mut codehash = [0u8; 32];
+
+                if let Err(e) = self.vm.memory.get_into(codehash_ptr, &mut codehash) {
+                    panic!("seal_delegate_call: {}", e);
+                }
+
+                let code = match self.programs.iter().find(|program| {
+                    blake2_rfc::blake2b::blake2b(32, &[], &program.code).as_bytes() == codehash
+                }) {
+                    Some(program) => program.code.clone(),
+                    // substrate would return CodeNotFound
+                    None => return Ok(Some(RuntimeValue::I32(7))),
+                };
+
+                let mut input = Vec::new();
+                input.resize(input_len as usize, 0u8);
+
+                if let Err(e) = self.vm.memory.get_into(input_ptr, &mut input) {
+                    panic!("seal_delegate_call: {}", e);
+                }
+
+                println!(
+                    "seal_delegate_call: codehash={} input={}",
+                    hex::encode(codehash),
+                    hex::encode(&input)
+                );
+
+                // the callee code runs with the account, caller and value of the current call,
+                // so it reads and writes our storage
+                let mut vm = VirtualMachine::new(self.vm.account, self.vm.caller, self.vm.value);
+
+                vm.gas_left = self.vm.gas_left;
+                vm.read_only = self.vm.read_only;
+
+                std::mem::swap(&mut self.vm, &mut vm);
+
+                let module = self.create_module(&code);
+
+                self.vm.input = input;
+
+                let snapshot = self.snapshot();
+
+                let ret = match module.invoke_export("call", &[], self) {
+                    Err(wasmi::Error::Trap(trap)) => match trap.kind() {
+                        TrapKind::Host(host_error) => {
+                            if let Some(ret) = host_error.downcast_ref::<HostCodeReturn>() {
+                                Some(RuntimeValue::I32(callee_return_code(ret)))
+                            } else {
+                                return Err(trap);
+                            }
+                        }
+                        _ => {
+                            // substrate would return CalleeTrapped
+                            self.vm.output.clear();
+                            Some(RuntimeValue::I32(1))
+                        }
+                    },
+                    Ok(v) => v,
+                    Err(e) => panic!("fail to invoke call: {}", e),
+                };
+
+                let output = self.vm.output.clone();
+
+                std::mem::swap(&mut self.vm, &mut vm);
+
+                println!(
+                    "seal_delegate_call ret={:?} buf={}",
+                    ret,
+                    hex::encode(&output)
+                );
+
+                if matches!(ret, Some(RuntimeValue::I32(code)) if code != 0) {
+                    self.rollback(snapshot);
+                }
+
+                set_seal_value!(
+                    "seal_delegate_call return buf",
+                    output_ptr,
+                    output_len_ptr,
+                    &output
+                );
+
+                Ok(ret)
+            }
             Some(SubstrateExternal::seal_transfer) => {
                 let account_ptr: u32 = args.nth_checked(0)?;
                 let account_len: u32 = args.nth_checked(1)?;
@@ -584,11 +774,23 @@ impl Externals for MockSubstrate {
 
                 let value = u128::from_le_bytes(value);
 
-                if !self.accounts.contains_key(&account) {
+                if self.vm.read_only {
+                    return Err(Trap::new(TrapKind::Unreachable));
+                }
+
+                // like substrate, the sender has to keep the minimum balance
+                let sender_balance = self.accounts[&self.vm.account].1;
+                let keeps_minimum = sender_balance
+                    .checked_sub(value)
+                    .map_or(false, |left| left >= MINIMUM_BALANCE);
+
+                if !self.accounts.contains_key(&account) || !keeps_minimum {
                     // substrate would return TransferFailed
                     return Ok(Some(RuntimeValue::I32(0x5)));
                 }
 
+                self.accounts.get_mut(&self.vm.account).unwrap().1 -= value;
+
                 if let Some(acc) = self.accounts.get_mut(&account) {
                     acc.1 += value;
                 }
@@ -636,6 +838,10 @@ impl Externals for MockSubstrate {
                     panic!("seal_instantiate: {}", e);
                 }
 
+                if self.vm.read_only {
+                    return Err(Trap::new(TrapKind::Unreachable));
+                }
+
                 println!(
                     "seal_instantiate value:{} input={} salt={}",
                     value,
@@ -663,6 +869,8 @@ impl Externals for MockSubstrate {
                     })
                     .expect("codehash not found");
 
+                let snapshot = self.snapshot();
+
                 self.accounts.insert(account, (program.code.clone(), 0));
 
                 let mut input = Vec::new();
@@ -684,13 +892,15 @@ impl Externals for MockSubstrate {
                     Err(wasmi::Error::Trap(trap)) => match trap.kind() {
                         TrapKind::Host(host_error) => {
                             if let Some(ret) = host_error.downcast_ref::<HostCodeReturn>() {
-                                Some(RuntimeValue::I32(ret.0))
+                                Some(RuntimeValue::I32(callee_return_code(ret)))
                             } else {
                                 return Err(trap);
                             }
                         }
                         _ => {
-                            return Err(trap);
+                            // substrate would return CalleeTrapped
+                            self.vm.output.clear();
+                            Some(RuntimeValue::I32(1))
                         }
                     },
                     Ok(v) => v,
@@ -716,6 +926,9 @@ impl Externals for MockSubstrate {
                         account_len_ptr,
                         &account
                     );
+                } else if matches!(ret, Some(RuntimeValue::I32(code)) if code != 0) {
+                    // the contract is not created
+                    self.rollback(snapshot);
                 }
 
                 println!("seal_instantiate ret:{:?}", ret);
@@ -766,7 +979,7 @@ impl Externals for MockSubstrate {
                 let dest_ptr: u32 = args.nth_checked(0)?;
                 let len_ptr: u32 = args.nth_checked(1)?;
 
-                let scratch = 500u128.to_le_bytes();
+                let scratch = MINIMUM_BALANCE.to_le_bytes();
 
                 set_seal_value!("seal_minimum_balance", dest_ptr, len_ptr, &scratch);
 
@@ -786,7 +999,7 @@ impl Externals for MockSubstrate {
                 let dest_ptr: u32 = args.nth_checked(0)?;
                 let len_ptr: u32 = args.nth_checked(1)?;
 
-                let scratch = 1594035638000u64.to_le_bytes();
+                let scratch = self.now.to_le_bytes();
 
                 set_seal_value!("seal_now", dest_ptr, len_ptr, &scratch);
 
@@ -796,7 +1009,7 @@ impl Externals for MockSubstrate {
                 let dest_ptr: u32 = args.nth_checked(0)?;
                 let len_ptr: u32 = args.nth_checked(1)?;
 
-                let scratch = 2_224_097_461u64.to_le_bytes();
+                let scratch = self.vm.gas_left.to_le_bytes();
 
                 set_seal_value!("seal_gas_left", dest_ptr, len_ptr, &scratch);
 
@@ -822,6 +1035,10 @@ impl Externals for MockSubstrate {
                     panic!("seal_terminate: {}", e);
                 }
 
+                if self.vm.read_only {
+                    return Err(Trap::new(TrapKind::Unreachable));
+                }
+
                 let remaining = self.accounts[&self.vm.account].1;
 
                 self.accounts.get_mut(&account).unwrap().1 += remaining;
@@ -882,6 +1099,10 @@ impl Externals for MockSubstrate {
                     hex::encode(&data)
                 );
 
+                if self.vm.read_only {
+                    return Err(Trap::new(TrapKind::Unreachable));
+                }
+
                 self.events.push(Event { topics, data });
 
                 Ok(None)
@@ -919,6 +1140,8 @@ impl ModuleImportResolver for MockSubstrate {
             "seal_caller" => SubstrateExternal::seal_caller,
             "seal_deposit_event" => SubstrateExternal::seal_deposit_event,
             "seal_transfer" => SubstrateExternal::seal_transfer,
+            "seal_code_hash" => SubstrateExternal::seal_code_hash,
+            "seal_delegate_call" => SubstrateExternal::seal_delegate_call,
             _ => {
                 panic!("{} not implemented", field_name);
             }
@@ -977,7 +1200,7 @@ impl MockSubstrate {
                     if let Some(ret) = host_error.downcast_ref::<HostCodeReturn>() {
                         Some(RuntimeValue::I32(ret.0))
                     } else if host_error.downcast_ref::<HostCodeTerminate>().is_some() {
-                        Some(RuntimeValue::I32(1))
+                        Some(RuntimeValue::I32(0))
                     } else {
                         panic!("did not go as planned");
                     }
@@ -1053,6 +1276,42 @@ impl MockSubstrate {
         }
     }
 
+    /// Call a function with the arguments encoded from values, and decode its return values
+    pub fn function_typed(&mut self, name: &str, args: &[Value]) -> Vec<Value> {
+        let ns = self.ns.clone();
+        let func = self.message(name, &ns);
+
+        let params: Vec<_> = func.params.iter().map(|p| p.ty.clone()).collect();
+        let returns: Vec<_> = func.returns.iter().map(|p| p.ty.clone()).collect();
+
+        let args = scale::encode(&params, args, &ns).unwrap();
+
+        self.function(name, args);
+
+        scale::decode(&returns, &self.vm.output, &ns).unwrap()
+    }
+
+    /// Find the public function which has the given name in the metadata. Overloaded functions
+    /// are listed by their mangled name.
+    fn message<'a>(&self, name: &str, ns: &'a Namespace) -> &'a Function {
+        let contract_no = self.programs[self.current_program].contract_no;
+
+        let mut funcs = ns.contracts[contract_no]
+            .all_functions
+            .keys()
+            .map(|function_no| &ns.functions[*function_no])
+            .filter(|f| f.is_public() && f.ty == pt::FunctionTy::Function);
+
+        let by_name: Vec<_> = funcs.clone().filter(|f| f.name == name).collect();
+
+        match by_name.as_slice() {
+            [func] => func,
+            _ => funcs
+                .find(|f| f.mangled_name == name)
+                .unwrap_or_else(|| panic!("function '{}' not found", name)),
+        }
+    }
+
     pub fn function_expect_failure(&mut self, name: &str, args: Vec<u8>) {
         let m = self.programs[self.current_program]
             .abi
@@ -1066,6 +1325,9 @@ impl MockSubstrate {
         match module.invoke_export("call", &[], self) {
             Err(wasmi::Error::Trap(trap)) => match trap.kind() {
                 TrapKind::Unreachable => (),
+                // a revert is a failure too
+                TrapKind::Host(host_error)
+                    if host_error.downcast_ref::<HostCodeReturn>() == Some(&HostCodeReturn(1)) => {}
                 _ => panic!("trap: {:?}", trap),
             },
             Err(err) => {
@@ -1077,6 +1339,17 @@ impl MockSubstrate {
         }
     }
 
+    /// Call a function which should revert with `Panic(uint256)` with the given code
+    pub fn function_expect_panic(&mut self, name: &str, args: Vec<u8>, code: u8) {
+        self.function_expect_failure(name, args);
+
+        // the code is SCALE encoded, so it is in the first byte
+        let mut data = vec![0x4e, 0x48, 0x7b, 0x71, code];
+        data.resize(36, 0);
+
+        assert_eq!(self.vm.output, data, "expected Panic({:#04x})", code);
+    }
+
     pub fn raw_function(&mut self, input: Vec<u8>) {
         let module = self.create_module(&self.accounts.get(&self.vm.account).unwrap().0);
 
@@ -1097,6 +1370,9 @@ impl MockSubstrate {
         match module.invoke_export("call", &[], self) {
             Err(wasmi::Error::Trap(trap)) => match trap.kind() {
                 TrapKind::Unreachable => (),
+                // a revert is a failure too
+                TrapKind::Host(host_error)
+                    if host_error.downcast_ref::<HostCodeReturn>() == Some(&HostCodeReturn(1)) => {}
                 _ => panic!("trap: {:?}", trap),
             },
             Err(err) => {
@@ -1120,6 +1396,50 @@ impl MockSubstrate {
         }
     }
 
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            store: self.store.clone(),
+            accounts: self.accounts.clone(),
+            events: self.events.len(),
+        }
+    }
+
+    /// Undo the changes made by a failed call or instantiation
+    fn rollback(&mut self, snapshot: Snapshot) {
+        self.store = snapshot.store;
+        self.accounts = snapshot.accounts;
+        self.events.truncate(snapshot.events);
+    }
+
+    /// The storage of the current contract, by key. Storage is not cleared between the
+    /// constructor and function calls, so tests can see how storage changes.
+    pub fn storage(&self) -> HashMap<StorageKey, Vec<u8>> {
+        self.store
+            .iter()
+            .filter(|((account, _), _)| *account == self.vm.account)
+            .map(|((_, key), value)| (*key, value.clone()))
+            .collect()
+    }
+
+    /// The value stored at the given key of the current contract, if any
+    pub fn storage_at(&self, key: &StorageKey) -> Option<&Vec<u8>> {
+        self.store.get(&(self.vm.account, *key))
+    }
+
+    /// Overwrite the value stored at the given key of the current contract
+    pub fn set_storage(&mut self, key: StorageKey, value: Vec<u8>) {
+        self.store.insert((self.vm.account, key), value);
+    }
+
+    /// The topics and data of the events emitted so far, in the order they were emitted. Events
+    /// from calls which were reverted are not included.
+    pub fn events(&self) -> Vec<(&[[u8; 32]], &[u8])> {
+        self.events
+            .iter()
+            .map(|event| (event.topics.as_slice(), event.data.as_slice()))
+            .collect()
+    }
+
     pub fn heap_verify(&self) {
         let memsize = self.vm.memory.current_size().0 as usize * 0x10000;
         println!("memory size:{}", memsize);
@@ -1189,27 +1509,58 @@ pub fn build_solidity(src: &str) -> MockSubstrate {
     build_solidity_with_overflow_check(src, false)
 }
 pub fn build_solidity_with_overflow_check(src: &str, math_overflow_flag: bool) -> MockSubstrate {
+    build_solidity_with_options(
+        src,
+        &Options {
+            math_overflow_check: math_overflow_flag,
+            log_prints: true,
+            ..Default::default()
+        },
+    )
+}
+
+/// Build with specific codegen options, for example to generate debug information
+pub fn build_solidity_with_options(src: &str, opt: &Options) -> MockSubstrate {
     let mut cache = FileResolver::new();
 
     cache.set_file_contents("test.sol", src.to_string());
 
-    let (res, ns) = compile(
+    let mut ns = solang::parse_and_resolve(
         OsStr::new("test.sol"),
         &mut cache,
-        inkwell::OptimizationLevel::Default,
         Target::default_substrate(),
-        math_overflow_flag,
     );
 
+    if !ns.diagnostics.any_errors() {
+        codegen(&mut ns, opt);
+    }
+
     ns.print_diagnostics_in_plain(&cache, false);
 
+    let res = (0..ns.contracts.len())
+        .filter(|c| ns.contracts[*c].instantiable)
+        .map(|c| {
+            let code = ns.contracts[c].code.clone();
+            let (abistr, _) = abi::generate_abi(c, &ns, &code, false);
+
+            (code, abistr)
+        })
+        .collect();
+
+    mock_substrate(res, ns)
+}
+
+fn mock_substrate(res: Vec<(Vec<u8>, String)>, ns: Namespace) -> MockSubstrate {
     assert!(!res.is_empty());
 
+    // compile() returns the instantiable contracts in order
     let programs: Vec<Program> = res
         .iter()
-        .map(|res| Program {
+        .zip((0..ns.contracts.len()).filter(|c| ns.contracts[*c].instantiable))
+        .map(|(res, contract_no)| Program {
             code: res.0.clone(),
             abi: abi::substrate::load(&res.1).unwrap(),
+            contract_no,
         })
         .collect();
 
@@ -1229,5 +1580,7 @@ pub fn build_solidity_with_overflow_check(src: &str, math_overflow_flag: bool) -
         vm,
         current_program: 0,
         events: Vec::new(),
+        now: 1594035638000,
+        ns: Rc::new(ns),
     }
 }
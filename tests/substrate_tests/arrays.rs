@@ -579,6 +579,51 @@ fn memory_dynamic_array_new() {
     runtime.function("test", Vec::new());
 }
 
+#[test]
+fn memory_dynamic_array_empty_push() {
+    let mut runtime = build_solidity(
+        r#"
+        contract foo {
+            function test() public {
+                uint[] memory a = new uint[](0);
+
+                assert(a.length == 0);
+
+                for (uint i = 0; i < a.length; i++) {
+                    assert(false);
+                }
+
+                a.push(102);
+                a.push(103);
+
+                assert(a.length == 2);
+                assert(a[0] == 102);
+                assert(a[1] == 103);
+                assert(a.pop() == 103);
+                assert(a.length == 1);
+
+                bytes memory b = "";
+
+                assert(b.length == 0);
+
+                b.push(0x41);
+
+                assert(b.length == 1);
+                assert(b[0] == 0x41);
+            }
+
+            function pop_empty() public {
+                bytes memory b = "";
+
+                b.pop();
+            }
+        }"#,
+    );
+
+    runtime.function("test", Vec::new());
+    runtime.function_expect_failure("pop_empty", Vec::new());
+}
+
 #[test]
 fn memory_dynamic_array_deref() {
     // The Ethereum Foundation solc allows you to create arrays of length 0
@@ -942,6 +987,71 @@ fn dynamic_array_pop() {
     runtime.function("test", Vec::new());
 }
 
+#[test]
+fn dynamic_array_capacity() {
+    let mut runtime = build_solidity(
+        r#"
+        contract foo {
+            function test() public returns (int64[]) {
+                int64[] bar = new int64[](0);
+
+                bar.push(1);
+                bar.push(2);
+                bar.push(3);
+
+                assert(bar.pop() == 3);
+                assert(bar.pop() == 2);
+                assert(bar.length == 1);
+
+                // reuses the space freed by pop
+                bar.push(4);
+                bar.push(5);
+
+                // this one needs more space
+                bar.push(6);
+
+                assert(bar.length == 4);
+
+                return bar;
+            }
+        }"#,
+    );
+
+    runtime.function("test", Vec::new());
+    runtime.heap_verify();
+
+    assert_eq!(runtime.vm.output, vec![1i64, 4, 5, 6].encode());
+
+    let mut runtime = build_solidity(
+        r#"
+        contract foo {
+            function test() public returns (bytes, string) {
+                bytes bar = new bytes(0);
+
+                bar.push("a");
+                bar.push("b");
+                bar.push("c");
+                bar.pop();
+
+                assert(bar.length == 2);
+                assert(keccak256(bar) == keccak256("ab"));
+
+                string baz = string(bar) + "!";
+
+                return (bar, baz);
+            }
+        }"#,
+    );
+
+    runtime.function("test", Vec::new());
+    runtime.heap_verify();
+
+    assert_eq!(
+        runtime.vm.output,
+        (b"ab".to_vec(), String::from("ab!")).encode()
+    );
+}
+
 #[test]
 #[should_panic]
 fn dynamic_array_pop_empty_array() {
@@ -1459,3 +1569,131 @@ fn alloc_size_from_storage() {
     runtime.function("contfunc", Vec::new());
     assert_eq!(runtime.vm.output, vec![0u64].encode());
 }
+
+#[test]
+fn storage_array_incdec() {
+    let mut runtime = build_solidity(
+        r#"
+        contract Test {
+            uint64[] arr;
+            uint64[3] fixed_arr;
+            uint32 calls;
+
+            constructor() {
+                arr.push(10);
+                arr.push(20);
+            }
+
+            function index(uint32 i) internal returns (uint32) {
+                calls += 1;
+                return i;
+            }
+
+            function inc(uint32 i) public returns (uint64, uint64, uint32) {
+                uint64 old = arr[index(i)]++;
+                return (old, arr[i], calls);
+            }
+
+            function dec(uint32 i) public returns (uint64, uint64) {
+                uint64 updated = --arr[i];
+                return (updated, arr[i]);
+            }
+
+            function fixed_inc(uint32 i) public returns (uint64, uint64, uint64) {
+                uint64 old = fixed_arr[i]++;
+                fixed_arr[i]++;
+                uint64 updated = --fixed_arr[i];
+                return (old, updated, fixed_arr[i]);
+            }
+        }"#,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    // the index expression is only evaluated once
+    runtime.function("inc", 1u32.encode());
+    assert_eq!(runtime.vm.output, (20u64, 21u64, 1u32).encode());
+
+    runtime.function("inc", 0u32.encode());
+    assert_eq!(runtime.vm.output, (10u64, 11u64, 2u32).encode());
+
+    runtime.function("dec", 1u32.encode());
+    assert_eq!(runtime.vm.output, (20u64, 20u64).encode());
+
+    runtime.function("fixed_inc", 2u32.encode());
+    assert_eq!(runtime.vm.output, (0u64, 1u64, 1u64).encode());
+
+    runtime.function_expect_failure("inc", 2u32.encode());
+}
+
+#[test]
+fn array_length_type() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint64[] store;
+
+            function test(uint8[] arr) public returns (uint64) {
+                uint256 n = arr.length;
+
+                assert(n == arr.length);
+                assert(arr.length <= type(uint32).max);
+                assert(!(arr.length > type(uint64).max));
+
+                bytes b = new bytes(n);
+                assert(b.length == n);
+
+                store.push(1);
+                store.push(2);
+
+                uint256 m = store.length;
+                assert(m == 2);
+                assert(store.length < type(uint256).max);
+
+                return uint64(n + m);
+            }
+        }"##,
+    );
+
+    runtime.function("test", vec![1u8, 2, 3].encode());
+
+    assert_eq!(runtime.vm.output, 5u64.encode());
+}
+
+#[test]
+fn array_element_widening() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint16[] store;
+            int32[3] signed;
+
+            function memory_array(uint8 a, uint8 b) public pure returns (uint16[]) {
+                uint16[] arr = new uint16[](2);
+
+                arr[0] = a;
+                arr[1] = 0x100;
+                arr[1] += b;
+
+                return arr;
+            }
+
+            function storage_array(uint8 a, int8 b) public returns (uint16, int32) {
+                store.push(0xff00);
+                store[0] = a;
+
+                signed[2] = b;
+
+                return (store[0], signed[2]);
+            }
+        }"##,
+    );
+
+    runtime.function("memory_array", (0xfeu8, 0x7fu8).encode());
+
+    assert_eq!(runtime.vm.output, vec![0xfeu16, 0x17f].encode());
+
+    runtime.function("storage_array", (0xffu8, -2i8).encode());
+
+    assert_eq!(runtime.vm.output, (0xffu16, -2i32).encode());
+}
@@ -1,8 +1,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use parity_scale_codec::{Decode, Encode};
+use sha2::{Digest, Sha256};
+use tiny_keccak::{Hasher, Keccak};
 
-use crate::build_solidity;
+use crate::{build_solidity, build_solidity_with_options};
+use solang::codegen::Options;
+use solang::file_resolver::FileResolver;
+use solang::{compile, Target};
+use std::ffi::OsStr;
 
 #[test]
 fn abi_decode() {
@@ -78,6 +84,183 @@ fn abi_encode() {
     runtime.heap_verify();
 }
 
+#[test]
+fn abi_encode_decode_bool_array() {
+    let mut runtime = build_solidity(
+        r##"
+        contract bar {
+            function roundtrip(bool[] a) public pure returns (bytes, bool[]) {
+                bytes enc = abi.encode(a);
+                bool[] b = abi.decode(enc, (bool[]));
+
+                assert(b.length == a.length);
+
+                for (uint32 i = 0; i < a.length; i++) {
+                    assert(a[i] == b[i]);
+                }
+
+                return (enc, b);
+            }
+
+            function fixed_array() public pure returns (bool[3]) {
+                bool[3] a = [false, true, true];
+
+                return abi.decode(abi.encode(a), (bool[3]));
+            }
+
+            function invalid() public pure returns (bool[]) {
+                // 2 is not a valid bool
+                return abi.decode(hex"080102", (bool[]));
+            }
+        }"##,
+    );
+
+    let values = vec![true, false, false, true, true, false];
+
+    runtime.function("roundtrip", values.encode());
+    assert_eq!(runtime.vm.output, (values.encode(), values).encode());
+
+    runtime.function("roundtrip", Vec::<bool>::new().encode());
+    assert_eq!(runtime.vm.output, (vec![0u8], Vec::<bool>::new()).encode());
+
+    runtime.function("fixed_array", Vec::new());
+    assert_eq!(runtime.vm.output, [false, true, true].encode());
+
+    runtime.function_expect_failure("invalid", Vec::new());
+}
+
+#[test]
+fn abi_encode_nested_dynamic_struct() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Inner {
+        name: String,
+        values: Vec<u32>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Outer {
+        tag: u8,
+        inner: Inner,
+        flag: bool,
+    }
+
+    let mut runtime = build_solidity(
+        r##"
+        struct Inner {
+            string name;
+            uint32[] values;
+        }
+
+        struct Outer {
+            uint8 tag;
+            Inner inner;
+            bool flag;
+        }
+
+        contract bar {
+            function roundtrip(Outer o) public pure returns (bytes) {
+                bytes enc = abi.encode(o);
+                Outer d = abi.decode(enc, (Outer));
+
+                assert(d.tag == o.tag);
+                assert(d.inner.name == o.inner.name);
+                assert(d.inner.values.length == o.inner.values.length);
+
+                for (uint32 i = 0; i < o.inner.values.length; i++) {
+                    assert(d.inner.values[i] == o.inner.values[i]);
+                }
+
+                assert(d.flag == o.flag);
+
+                return enc;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    let val = Outer {
+        tag: 7,
+        inner: Inner {
+            name: String::from("nested"),
+            values: vec![1, 0xffff_ffff, 300],
+        },
+        flag: true,
+    };
+
+    runtime.function("roundtrip", val.encode());
+    assert_eq!(runtime.vm.output, val.encode().encode());
+
+    let val = Outer {
+        tag: 0,
+        inner: Inner {
+            name: String::new(),
+            values: Vec::new(),
+        },
+        flag: false,
+    };
+
+    runtime.function("roundtrip", val.encode());
+    assert_eq!(runtime.vm.output, val.encode().encode());
+}
+
+#[test]
+fn hash_encoded_struct() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct S {
+        a: u64,
+        b: bool,
+        c: [u8; 4],
+        d: String,
+    }
+
+    let mut runtime = build_solidity(
+        r##"
+        struct S {
+            uint64 a;
+            bool b;
+            bytes4 c;
+            string d;
+        }
+
+        contract bar {
+            function hash(S s) public pure returns (bytes32, bytes32) {
+                return (keccak256(abi.encode(s)), sha256(abi.encode(s)));
+            }
+
+            function hash_memory(uint64 a, string d) public pure returns (bytes32) {
+                S memory s = S({ a: a, b: true, c: hex"01020304", d: d });
+
+                return keccak256(abi.encode(s));
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    let val = S {
+        a: 0xdead_beef,
+        b: true,
+        c: [1, 2, 3, 4],
+        d: String::from("Hello, World!"),
+    };
+
+    let encoded = val.encode();
+
+    let mut keccak = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(&encoded);
+    hasher.finalize(&mut keccak);
+
+    let sha256: [u8; 32] = Sha256::digest(&encoded).into();
+
+    runtime.function("hash", val.encode());
+    assert_eq!(runtime.vm.output, (keccak, sha256).encode());
+
+    runtime.function("hash_memory", (0xdead_beefu64, val.d).encode());
+    assert_eq!(runtime.vm.output, keccak.encode());
+}
+
 #[test]
 fn abi_encode_packed() {
     let mut runtime = build_solidity(
@@ -120,6 +303,44 @@ fn abi_encode_packed() {
     runtime.function("test3", Vec::new());
 }
 
+#[test]
+fn bytes_concat() {
+    let mut runtime = build_solidity(
+        r##"
+        contract bar {
+            function test() public {
+                bytes2 a = 0xfd01;
+                bytes b = hex"aabbcc";
+                bytes1 c = 0x7f;
+
+                bytes r = bytes.concat(a, b, c);
+                assert(r.length == 6);
+                assert(r == hex"fd01aabbcc7f");
+
+                assert(bytes.concat(b, b) == hex"aabbccaabbcc");
+                assert(bytes.concat().length == 0);
+                assert(bytes.concat(hex"dead", bytes2(0xbeef)) == hex"deadbeef");
+
+                string s = "ok";
+                assert(bytes.concat(s, c) == hex"6f6b7f");
+            }
+
+            function test2(bytes x, bytes4 y) public returns (bytes) {
+                return bytes.concat(y, x, "foo", y);
+            }
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+
+    runtime.function("test2", (vec![1u8, 2, 3], [0xdu8, 0xe, 0xa, 0xd]).encode());
+
+    assert_eq!(
+        runtime.vm.output,
+        vec![0xdu8, 0xe, 0xa, 0xd, 1, 2, 3, b'f', b'o', b'o', 0xd, 0xe, 0xa, 0xd].encode()
+    );
+}
+
 #[test]
 fn abi_encode_with_selector() {
     let mut runtime = build_solidity(
@@ -143,12 +364,94 @@ fn abi_encode_with_selector() {
 
                 assert(abi.encodeWithSelector(hex"01020304", arr) == hex"010203040cfefcf8");
             }
+
+            function test3() public {
+                uint32 a = 0x11223344;
+                uint8[] arr = new uint8[](3);
+
+                arr[0] = 0xfe;
+                arr[1] = 0xfc;
+                arr[2] = 0xf8;
+
+                bytes enc = abi.encodeWithSelector(hex"01020304", a, arr);
+
+                assert(enc == hex"01020304443322110cfefcf8");
+                assert(enc == bytes.concat(hex"01020304", abi.encode(a, arr)));
+            }
         }"##,
     );
 
     runtime.function("test1", Vec::new());
 
     runtime.function("test2", Vec::new());
+
+    runtime.function("test3", Vec::new());
+}
+
+#[test]
+fn abi_encode_call() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function test() public {
+                bytes enc = abi.encodeCall(c.bar, (102, true));
+
+                assert(enc == abi.encodeWithSelector(c.bar.selector, int32(102), true));
+                assert(enc == abi.encodeCall(c.bar, 102, true));
+                assert(enc == bytes.concat(c.bar.selector, abi.encode(int32(102), true)));
+            }
+
+            function bar(int32 a, bool b) public {}
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Ret(bool, u64);
+
+    let mut runtime = build_solidity(
+        r##"
+        interface IToken {
+            function transfer(address to, uint64 amount) external returns (bool);
+        }
+
+        contract c {
+            token t;
+
+            constructor() {
+                t = new token();
+            }
+
+            function test(address to, uint64 amount) public returns (bool, uint64) {
+                bytes payload = abi.encodeCall(IToken.transfer, (to, amount));
+
+                assert(payload == abi.encodeWithSelector(IToken.transfer.selector, to, amount));
+
+                (bool success, bytes rawresult) = address(t).call(payload);
+
+                assert(success);
+                assert(abi.decode(rawresult, (bool)));
+
+                return (success, t.balances(to));
+            }
+        }
+
+        contract token is IToken {
+            mapping(address => uint64) public balances;
+
+            function transfer(address to, uint64 amount) external override returns (bool) {
+                balances[to] += amount;
+                return true;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("test", ([7u8; 32], 1000u64).encode());
+
+    assert_eq!(runtime.vm.output, Ret(true, 1000).encode());
 }
 
 #[test]
@@ -275,6 +578,12 @@ fn call() {
                 assert(success == true);
 
                 assert(abi.decode(bs, (uint64)) == 0xfeed);
+
+                (success, bs) = address(i).call(abi.encodeCall(inferior.test2, (uint64(257))));
+
+                assert(success == true);
+
+                assert(abi.decode(bs, (uint64)) == 256);
             }
         }
 
@@ -335,6 +644,97 @@ fn block() {
     runtime.function("test", Vec::new());
 }
 
+#[test]
+fn timestamp_deadline() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Deadline(u64);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract auction {
+            uint64 deadline;
+
+            constructor(uint64 _deadline) {
+                deadline = _deadline;
+            }
+
+            function expired() public view returns (bool) {
+                return block.timestamp > deadline + 1 days;
+            }
+
+            function remaining() public view returns (uint64) {
+                if (block.timestamp >= deadline + 2 hours) {
+                    return 0;
+                }
+                return deadline + 2 hours - block.timestamp;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Deadline(1594035638).encode());
+
+    runtime.function("expired", Vec::new());
+    assert_eq!(runtime.vm.output, false.encode());
+
+    runtime.function("remaining", Vec::new());
+    assert_eq!(runtime.vm.output, 7200u64.encode());
+
+    // one day later, the deadline is met but not passed
+    runtime.now += 86_400_000;
+
+    runtime.function("expired", Vec::new());
+    assert_eq!(runtime.vm.output, false.encode());
+
+    runtime.function("remaining", Vec::new());
+    assert_eq!(runtime.vm.output, 0u64.encode());
+
+    runtime.now += 1000;
+
+    runtime.function("expired", Vec::new());
+    assert_eq!(runtime.vm.output, true.encode());
+}
+
+#[test]
+fn debug_assert() {
+    let src = r##"
+        contract c {
+            uint64 count;
+
+            function add(uint64 n) public {
+                count += n;
+
+                debugAssert(count >= n);
+                debugAssert(below_limit());
+            }
+
+            function below_limit() internal view returns (bool) {
+                return count < 100;
+            }
+        }"##;
+
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(0, Vec::new());
+    runtime.function("add", 10u64.encode());
+    runtime.function_expect_failure("add", 100u64.encode());
+
+    let mut stripped = build_solidity_with_options(
+        src,
+        &Options {
+            math_overflow_check: false,
+            log_prints: true,
+            strip_debug_asserts: true,
+            ..Default::default()
+        },
+    );
+
+    stripped.constructor(0, Vec::new());
+    stripped.function("add", 10u64.encode());
+    stripped.function("add", 100u64.encode());
+
+    assert!(stripped.programs[0].code.len() < runtime.programs[0].code.len());
+}
+
 #[test]
 fn tx() {
     let mut runtime = build_solidity(
@@ -402,6 +802,43 @@ fn msg() {
     runtime.function("test", Vec::new());
 }
 
+#[test]
+fn this_address_in_constructor() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            address public creation_address;
+
+            constructor() {
+                creation_address = address(this);
+            }
+
+            function test() public {
+                assert(creation_address == address(this));
+
+                other o = new other();
+
+                assert(o.creation_address() == address(o));
+            }
+        }
+
+        contract other {
+            address public creation_address;
+
+            constructor() {
+                creation_address = address(this);
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("creation_address", Vec::new());
+    assert_eq!(runtime.vm.output, runtime.vm.account.to_vec());
+
+    runtime.function("test", Vec::new());
+}
+
 #[test]
 fn functions() {
     let mut runtime = build_solidity(
@@ -720,3 +1157,169 @@ fn my_token() {
     );
     assert_eq!(&runtime.vm.caller[..], &runtime.vm.output[..]);
 }
+
+#[test]
+fn substrate_api_version() {
+    let src = r##"
+        contract c {
+            function f(bytes s) public returns (bytes32) {
+                return random(s);
+            }
+        }"##;
+
+    let errors = |api_version| {
+        let mut cache = FileResolver::new();
+
+        cache.set_file_contents("test.sol", src.to_string());
+
+        let (res, ns) = compile(
+            OsStr::new("test.sol"),
+            &mut cache,
+            inkwell::OptimizationLevel::Default,
+            Target::Substrate {
+                address_length: 32,
+                value_length: 16,
+                api_version,
+                max_code_size: 128 * 1024,
+            },
+            false,
+            false,
+        );
+
+        assert_eq!(res.len(), 1);
+
+        ns.diagnostics
+            .errors()
+            .iter()
+            .map(|diag| diag.message.to_owned())
+            .collect::<Vec<String>>()
+    };
+
+    // random() uses seal1.seal_random, which is not available in the first version
+    assert_eq!(
+        errors(1),
+        vec!["contract 'c' cannot be deployed: host function 'seal1.seal_random' requires substrate api version 2, but version 1 was selected"]
+    );
+
+    assert!(errors(2).is_empty());
+}
+
+#[test]
+fn account_id_length() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r##"
+        contract c {
+            function f() public view returns (address) {
+                return address(this);
+            }
+        }"##
+        .to_string(),
+    );
+
+    let (res, ns) = compile(
+        OsStr::new("test.sol"),
+        &mut cache,
+        inkwell::OptimizationLevel::Default,
+        Target::Substrate {
+            address_length: 20,
+            value_length: 16,
+            api_version: 2,
+            max_code_size: 128 * 1024,
+        },
+        false,
+        false,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let metadata: serde_json::Value = serde_json::from_str(&res[0].1).unwrap();
+    let types = metadata["types"].as_array().unwrap();
+
+    // the AccountId in the metadata has the address length the contract was compiled with
+    let account_id = types
+        .iter()
+        .find(|ty| ty["path"] == serde_json::json!(["AccountId"]))
+        .unwrap();
+    let bytes = account_id["def"]["composite"]["fields"][0]["type"]
+        .as_u64()
+        .unwrap();
+
+    assert_eq!(types[bytes as usize - 1]["def"]["array"]["len"], 20);
+}
+
+#[test]
+fn max_code_size() {
+    // a constant array which does not fit in the default maximum code size
+    let src = format!(
+        r##"
+        contract huge {{
+            bytes constant data = hex"{}";
+
+            function get(uint32 i) public pure returns (bytes1) {{
+                return data[i];
+            }}
+        }}"##,
+        "a5".repeat(140 * 1024)
+    );
+
+    let diagnostics = |max_code_size| {
+        let mut cache = FileResolver::new();
+
+        cache.set_file_contents("test.sol", src.clone());
+
+        let (_, ns) = compile(
+            OsStr::new("test.sol"),
+            &mut cache,
+            inkwell::OptimizationLevel::Default,
+            Target::Substrate {
+                address_length: 32,
+                value_length: 16,
+                api_version: 2,
+                max_code_size,
+            },
+            false,
+            false,
+        );
+
+        ns.diagnostics
+    };
+
+    let diagnostics_default = diagnostics(128 * 1024);
+
+    let errors = diagnostics_default.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0]
+        .message
+        .starts_with("contract 'huge' code size of "));
+    assert!(errors[0]
+        .message
+        .contains("bytes exceeds the maximum of 131072 bytes; largest functions: "));
+
+    // within 90% of the limit gives a warning
+    let diagnostics_close = diagnostics(150 * 1024);
+
+    assert!(diagnostics_close.errors().is_empty());
+    assert!(diagnostics_close
+        .warnings()
+        .iter()
+        .any(|warning| warning.message.starts_with("contract 'huge' code size of ")));
+
+    assert!(diagnostics(1024 * 1024).warnings().is_empty());
+}
+
+#[test]
+fn target_conditional() {
+    let mut runtime = build_solidity(include_str!("../../examples/target_conditional.sol"));
+
+    runtime.function("chain", Vec::new());
+
+    assert_eq!(runtime.vm.output, String::from("substrate").encode());
+
+    runtime.function("threshold", Vec::new());
+
+    assert_eq!(runtime.vm.output, 500u64.encode());
+}
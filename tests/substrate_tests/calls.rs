@@ -2,9 +2,13 @@
 
 use crate::build_solidity;
 use parity_scale_codec::{Decode, Encode};
+use tiny_keccak::{Hasher, Keccak};
 
 #[derive(Debug, PartialEq, Eq, Encode, Decode)]
-struct RevertReturn(u32, String);
+struct RevertReturn([u8; 4], String);
+
+/// The selector of Error(string)
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
 
 #[test]
 fn revert() {
@@ -35,11 +39,21 @@ fn revert() {
 
     runtime.function_expect_failure("test", Vec::new());
 
-    assert_eq!(runtime.vm.output.len(), 0);
+    assert_eq!(
+        runtime.vm.output,
+        RevertReturn(ERROR_SELECTOR, String::from("yo!")).encode()
+    );
 
     runtime.function_expect_failure("a", Vec::new());
 
-    assert_eq!(runtime.vm.output.len(), 0);
+    assert_eq!(
+        runtime.vm.output,
+        RevertReturn(
+            ERROR_SELECTOR,
+            String::from("revert value has to be passed down the stack")
+        )
+        .encode()
+    );
 
     let mut runtime = build_solidity(
         r##"
@@ -72,14 +86,85 @@ fn require() {
 
     runtime.function_expect_failure("test1", Vec::new());
 
-    // The reason is lost
-    assert_eq!(runtime.vm.output.len(), 0);
+    assert_eq!(
+        runtime.vm.output,
+        RevertReturn(
+            ERROR_SELECTOR,
+            String::from("Program testing can be used to show the presence of bugs, but never to show their absence!")
+        )
+        .encode()
+    );
 
     runtime.function("test2", Vec::new());
 
     assert_eq!(runtime.vm.output.len(), 0);
 }
 
+#[test]
+fn require_bool_function() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            other o;
+
+            constructor() {
+                o = new other();
+            }
+
+            function isValid(int32 x) internal pure returns (bool) {
+                return x > 10;
+            }
+
+            function internal_call(int32 x) public pure returns (int32) {
+                require(isValid(x), "not valid");
+                assert(isValid(x + 1));
+
+                return x;
+            }
+
+            function external_call(int32 x) public view returns (int32) {
+                require(o.isValid(x), "not valid");
+                assert(o.isValid(x + 1));
+
+                return x;
+            }
+        }
+
+        contract other {
+            function isValid(int32 x) public pure returns (bool) {
+                return x < 100;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("internal_call", 11i32.encode());
+
+    assert_eq!(runtime.vm.output, 11i32.encode());
+
+    runtime.function_expect_failure("internal_call", 10i32.encode());
+
+    assert_eq!(
+        runtime.vm.output,
+        RevertReturn(ERROR_SELECTOR, String::from("not valid")).encode()
+    );
+
+    runtime.function("external_call", 98i32.encode());
+
+    assert_eq!(runtime.vm.output, 98i32.encode());
+
+    runtime.function_expect_failure("external_call", 100i32.encode());
+
+    assert_eq!(
+        runtime.vm.output,
+        RevertReturn(ERROR_SELECTOR, String::from("not valid")).encode()
+    );
+
+    // require passes, but assert fails
+    runtime.function_expect_failure("external_call", 99i32.encode());
+}
+
 #[test]
 fn input_wrong_size() {
     let mut runtime = build_solidity(
@@ -195,7 +280,7 @@ fn try_catch_external_calls() {
                 try o.test() returns (int32 y, bool) {
                     x = y;
                 } catch (bytes c) {
-                    assert(c == hex"a079c3080c666f6f");
+                    assert(c == hex"08c379a00c666f6f");
                     x = 2;
                 }
                 assert(x == 2);
@@ -210,7 +295,7 @@ fn try_catch_external_calls() {
         "##,
     );
 
-    runtime.function_expect_failure("test", Vec::new());
+    runtime.function("test", Vec::new());
 
     let mut runtime = build_solidity(
         r##"
@@ -266,7 +351,7 @@ fn try_catch_external_calls() {
         "##,
     );
 
-    runtime.function_expect_failure("test", Vec::new());
+    runtime.function("test", Vec::new());
 
     #[derive(Debug, PartialEq, Eq, Encode, Decode)]
     struct Ret(u32);
@@ -324,7 +409,112 @@ fn try_catch_external_calls() {
 
     runtime.function("create_child", Vec::new());
 
-    runtime.function_expect_failure("test", Vec::new());
+    runtime.function("test", Vec::new());
+
+    assert_eq!(runtime.vm.output, Ret(4000).encode());
+}
+
+#[test]
+fn try_catch_revert_reason() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Caught(bool, String, Vec<u8>);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract superior {
+            inferior i;
+
+            constructor() {
+                i = new inferior();
+            }
+
+            function test(int32 x) public returns (bool, string, bytes) {
+                try i.check(x) returns (int32 y) {
+                    assert(y == x);
+
+                    return (true, "", "");
+                } catch Error(string reason) {
+                    // the storage changes of the inferior were rolled back
+                    assert(i.get() != x);
+
+                    return (false, reason, "");
+                } catch (bytes low) {
+                    return (false, "", low);
+                }
+            }
+
+            function create(bool fail) public returns (string) {
+                try new inferior2(fail) returns (inferior2 c) {
+                    return "created";
+                } catch Error(string reason) {
+                    return reason;
+                } catch (bytes) {
+                    return "no reason";
+                }
+            }
+        }
+
+        contract inferior {
+            int32 last;
+
+            function check(int32 x) public returns (int32) {
+                last = x;
+
+                require(x > 0, "x must be positive");
+
+                if (x == 42) {
+                    revert();
+                }
+
+                return x;
+            }
+
+            function get() public view returns (int32) {
+                return last;
+            }
+        }
+
+        contract inferior2 {
+            constructor(bool fail) {
+                require(!fail, "constructor failed");
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("test", 5i32.encode());
+
+    assert_eq!(
+        runtime.vm.output,
+        Caught(true, String::new(), Vec::new()).encode()
+    );
+
+    runtime.function("test", (-1i32).encode());
+
+    assert_eq!(
+        runtime.vm.output,
+        Caught(false, String::from("x must be positive"), Vec::new()).encode()
+    );
+
+    // revert without a reason has no return data
+    runtime.function("test", 42i32.encode());
+
+    assert_eq!(
+        runtime.vm.output,
+        Caught(false, String::new(), Vec::new()).encode()
+    );
+
+    runtime.function("create", false.encode());
+
+    assert_eq!(runtime.vm.output, String::from("created").encode());
+
+    runtime.function("create", true.encode());
+
+    assert_eq!(
+        runtime.vm.output,
+        String::from("constructor failed").encode()
+    );
 }
 
 #[test]
@@ -389,7 +579,7 @@ fn try_catch_constructor() {
                 try new other(true) {
                     x = 1;
                 } catch (bytes c) {
-                    assert(c == hex"a079c3080c666f6f");
+                    assert(c == hex"08c379a00c666f6f");
                     x = 2;
                 }
                 assert(x == 2);
@@ -406,7 +596,48 @@ fn try_catch_constructor() {
         "##,
     );
 
-    runtime.function_expect_failure("test", Vec::new());
+    runtime.function("test", Vec::new());
+}
+
+#[test]
+fn try_catch_reverting_constructor() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Ret(i32, String);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract creator {
+            function test(bool ok) public returns (int32, string) {
+                try new child(ok) returns (child c) {
+                    return (c.get(), "created");
+                } catch Error(string reason) {
+                    return (-1, reason);
+                } catch (bytes) {
+                    return (-2, "");
+                }
+            }
+        }
+
+        contract child {
+            constructor(bool ok) {
+                if (!ok) {
+                    revert("not ok");
+                }
+            }
+
+            function get() public pure returns (int32) {
+                return 7;
+            }
+        }"##,
+    );
+
+    runtime.function("test", true.encode());
+
+    assert_eq!(runtime.vm.output, Ret(7, String::from("created")).encode());
+
+    runtime.function("test", false.encode());
+
+    assert_eq!(runtime.vm.output, Ret(-1, String::from("not ok")).encode());
 }
 
 #[test]
@@ -541,7 +772,7 @@ fn payable_functions() {
 
     runtime.constructor(0, Vec::new());
     runtime.vm.value = 1;
-    runtime.raw_function(b"abde".to_vec());
+    runtime.raw_function(Vec::new());
     runtime.vm.value = 0;
     runtime.function("get_x", Vec::new());
 
@@ -574,7 +805,7 @@ fn payable_functions() {
 
     runtime.constructor(0, Vec::new());
     runtime.vm.value = 1;
-    runtime.raw_function(b"abde".to_vec());
+    runtime.raw_function(Vec::new());
     runtime.vm.value = 0;
     runtime.function("get_x", Vec::new());
 
@@ -762,3 +993,619 @@ fn try_catch_reachable() {
         }"##,
     );
 }
+
+#[test]
+fn missing_return_data() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Ret(u64, bool);
+
+    let mut runtime = build_solidity(
+        r##"
+        interface token {
+            function balance() external returns (uint64);
+            function info() external returns (bool, string memory);
+        }
+
+        contract c {
+            function strict() public returns (uint64) {
+                silent s = new silent();
+
+                return token(address(s)).balance();
+            }
+
+            function relaxed() public returns (uint64, bool) {
+                silent s = new silent();
+                token t = token(address(s));
+
+                uint64 b = t.balance{relaxedDecode: true}();
+                (bool f, string memory n) = t.info{relaxedDecode: true}();
+
+                assert(n == "");
+
+                return (b, f);
+            }
+
+            function relaxed_with_data() public returns (uint64, bool) {
+                loud l = new loud();
+                token t = token(address(l));
+
+                uint64 b = t.balance{relaxedDecode: true}();
+                (bool f, string memory n) = t.info{relaxedDecode: true}();
+
+                assert(n == "loud");
+
+                return (b, f);
+            }
+        }
+
+        contract silent {
+            function balance() public {}
+            function info() public {}
+        }
+
+        contract loud {
+            function balance() public returns (uint64) {
+                return 42;
+            }
+
+            function info() public returns (bool, string memory) {
+                return (true, "loud");
+            }
+        }"##,
+    );
+
+    runtime.function_expect_panic("strict", Vec::new(), 0x62);
+
+    runtime.function("relaxed", Vec::new());
+
+    assert_eq!(runtime.vm.output, Ret(0, false).encode());
+
+    runtime.function("relaxed_with_data", Vec::new());
+
+    assert_eq!(runtime.vm.output, Ret(42, true).encode());
+}
+
+#[test]
+fn try_catch_decode_failure() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Ret(u32, u32);
+
+    let mut runtime = build_solidity(
+        r##"
+        interface wide {
+            function get() external returns (uint64, uint64);
+        }
+
+        contract c {
+            function test() public returns (uint32, uint32) {
+                narrow n = new narrow();
+
+                try wide(address(n)).get() returns (uint64 a, uint64 b) {
+                    return (1, 0);
+                } catch Error(string) {
+                    return (2, 0);
+                } catch (bytes raw) {
+                    return (3, raw.length);
+                }
+            }
+        }
+
+        contract narrow {
+            function get() public returns (uint32) {
+                return 7;
+            }
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+
+    assert_eq!(runtime.vm.output, Ret(3, 4).encode());
+}
+
+#[test]
+fn default_gas_forwarded() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Ret(u64, u64);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function test() public returns (uint64, uint64) {
+                other o = new other();
+
+                return (o.remaining(), o.remaining{gas: 1000}());
+            }
+        }
+
+        contract other {
+            function remaining() public view returns (uint64) {
+                return gasleft();
+            }
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+
+    // one 64th of the gas is kept back by the caller
+    assert_eq!(runtime.vm.output, Ret(2_189_345_939, 1000).encode());
+}
+
+#[test]
+fn struct_argument() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Ret(u64, String, bool, Vec<u8>);
+
+    let mut runtime = build_solidity(
+        r##"
+        struct Order {
+            uint64 amount;
+            string memo;
+            Flags flags;
+        }
+
+        struct Flags {
+            bool urgent;
+            bytes tags;
+        }
+
+        contract c {
+            function test() public returns (uint64, string, bool, bytes) {
+                other o = new other();
+
+                Order order = Order({
+                    amount: 102,
+                    memo: "rent",
+                    flags: Flags({ urgent: true, tags: hex"0102" })
+                });
+
+                Order ret = o.double(order);
+
+                // the caller's copy is not changed by the callee
+                assert(order.amount == 102);
+
+                return (ret.amount, ret.memo, ret.flags.urgent, ret.flags.tags);
+            }
+        }
+
+        contract other {
+            function double(Order order) public pure returns (Order) {
+                assert(order.flags.urgent);
+                assert(order.flags.tags.length == 2);
+
+                order.amount *= 2;
+                order.memo = order.memo + " due";
+                order.flags.tags[1] = 3;
+
+                return order;
+            }
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+
+    assert_eq!(
+        runtime.vm.output,
+        Ret(204, String::from("rent due"), true, vec![1, 3]).encode()
+    );
+}
+
+#[test]
+fn callee_revert_data() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            other o;
+
+            constructor() {
+                o = new other();
+            }
+
+            function low_level() public {
+                (bool ok, bytes ret) = address(o).call(abi.encodeWithSignature("fail()"));
+                assert(!ok);
+                assert(ret == hex"08c379a0106e6f7065");
+            }
+
+            function bubble() public {
+                (bool ok, bytes ret) = address(o).call(abi.encodeWithSignature("fail()"));
+                if (!ok) {
+                    revertWith(ret);
+                }
+            }
+
+            function nested() public {
+                o.fail();
+            }
+
+            function create() public {
+                new other2();
+            }
+
+            function trapped() public {
+                o.trap();
+            }
+        }
+
+        contract other {
+            function fail() public {
+                require(false, "nope");
+            }
+
+            function trap() public {
+                require(false);
+            }
+        }
+
+        contract other2 {
+            constructor() {
+                revert("not today");
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("low_level", Vec::new());
+
+    runtime.function_expect_failure("bubble", Vec::new());
+    assert_eq!(
+        runtime.vm.output,
+        RevertReturn(ERROR_SELECTOR, String::from("nope")).encode()
+    );
+
+    runtime.function_expect_failure("nested", Vec::new());
+    assert_eq!(
+        runtime.vm.output,
+        RevertReturn(ERROR_SELECTOR, String::from("nope")).encode()
+    );
+
+    runtime.function_expect_failure("create", Vec::new());
+    assert_eq!(
+        runtime.vm.output,
+        RevertReturn(ERROR_SELECTOR, String::from("not today")).encode()
+    );
+
+    // the callee trapped, so there is no revert data to pass on
+    runtime.function_expect_panic("trapped", Vec::new(), 0x61);
+}
+
+#[test]
+fn delegatecall() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Val(i64);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract proxy {
+            int64 public value;
+            logic l;
+
+            constructor() {
+                l = new logic();
+            }
+
+            function set(int64 v) public returns (int64) {
+                (bool success, bytes rawresult) = address(l).delegatecall(abi.encodeWithSignature("set(int64)", v));
+
+                assert(success);
+
+                return abi.decode(rawresult, (int64));
+            }
+
+            function fail() public returns (bool) {
+                (bool success, bytes rawresult) = address(l).delegatecall(abi.encodeWithSignature("fail()"));
+
+                assert(rawresult.length > 0);
+
+                return success;
+            }
+
+            function not_contract() public returns (bool) {
+                (bool success, bytes rawresult) = address(0).delegatecall("");
+
+                // nothing was called, so there is no return data
+                assert(rawresult.length == 0);
+
+                return success;
+            }
+        }
+
+        contract logic {
+            int64 public value;
+
+            function set(int64 v) public returns (int64) {
+                value = v * 2;
+
+                return value;
+            }
+
+            function fail() public {
+                value = 100;
+
+                revert("no");
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("set", Val(21).encode());
+
+    assert_eq!(runtime.vm.output, Val(42).encode());
+
+    // the logic contract wrote to the storage of the proxy
+    runtime.function("value", Vec::new());
+
+    assert_eq!(runtime.vm.output, Val(42).encode());
+
+    // a reverting delegate call rolls back its storage changes
+    runtime.function("fail", Vec::new());
+
+    assert_eq!(runtime.vm.output, false.encode());
+
+    runtime.function("value", Vec::new());
+
+    assert_eq!(runtime.vm.output, Val(42).encode());
+
+    runtime.function("not_contract", Vec::new());
+
+    assert_eq!(runtime.vm.output, false.encode());
+}
+
+#[test]
+fn custom_error() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            error Overflow(uint256 value);
+
+            other o;
+
+            constructor() {
+                o = new other();
+            }
+
+            function overflow() public {
+                revert Overflow(255);
+            }
+
+            function named() public {
+                revert Overflow({ value: 7 });
+            }
+
+            function caught() public {
+                try o.fail(255) {
+                    assert(false);
+                } catch (bytes raw) {
+                    (bytes4 selector, uint256 value) = abi.decode(raw, (bytes4, uint256));
+                    assert(selector == bytes4(keccak256("Overflow(uint256)")));
+                    assert(value == 255);
+                }
+            }
+        }
+
+        contract other {
+            error Overflow(uint256 value);
+
+            function fail(uint256 v) public {
+                revert Overflow(v);
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    let mut selector = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(b"Overflow(uint256)");
+    hasher.finalize(&mut selector);
+
+    let revert_data = |value: u8| {
+        let mut data = selector[..4].to_vec();
+        let mut encoded = [0u8; 32];
+        encoded[0] = value;
+        data.extend_from_slice(&encoded);
+        data
+    };
+
+    runtime.function_expect_failure("overflow", Vec::new());
+
+    assert_eq!(runtime.vm.output, revert_data(255));
+
+    runtime.function_expect_failure("named", Vec::new());
+
+    assert_eq!(runtime.vm.output, revert_data(7));
+
+    runtime.function("caught", Vec::new());
+}
+
+#[test]
+fn staticcall() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Ret(bool, i64);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            other o;
+
+            constructor() {
+                o = new other{value: 1000}();
+            }
+
+            function get() public view returns (int64) {
+                (bool success, bytes rawresult) = address(o).staticcall(abi.encodeWithSignature("get()"));
+
+                assert(success);
+
+                return abi.decode(rawresult, (int64));
+            }
+
+            function set() public returns (bool, int64) {
+                (bool success, ) = address(o).staticcall(abi.encodeWithSignature("set(int64)", int64(5)));
+
+                return (success, o.get());
+            }
+
+            function change(uint8 n, bool read_only) public returns (bool) {
+                bytes data;
+
+                if (n == 0) {
+                    data = abi.encodeWithSignature("pay()");
+                } else if (n == 1) {
+                    data = abi.encodeWithSignature("create()");
+                } else if (n == 2) {
+                    data = abi.encodeWithSignature("log()");
+                } else {
+                    data = abi.encodeWithSignature("kill()");
+                }
+
+                bool success;
+
+                if (read_only) {
+                    (success, ) = address(o).staticcall(data);
+                } else {
+                    (success, ) = address(o).call(data);
+                }
+
+                return success;
+            }
+        }
+
+        contract other {
+            int64 value = 102;
+
+            event Logged(int64 value);
+
+            constructor() payable {}
+
+            function get() public view returns (int64) {
+                return value;
+            }
+
+            function set(int64 v) public {
+                value = v;
+            }
+
+            function pay() public {
+                payable(msg.sender).transfer(1);
+            }
+
+            function create() public {
+                new third();
+            }
+
+            function log() public {
+                emit Logged(value);
+            }
+
+            function kill() public {
+                selfdestruct(payable(msg.sender));
+            }
+        }
+
+        contract third {
+            function f() public pure {}
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("get", Vec::new());
+
+    assert_eq!(runtime.vm.output, 102i64.encode());
+
+    // the callee of a static call may not write to storage
+    runtime.function("set", Vec::new());
+
+    assert_eq!(runtime.vm.output, Ret(false, 102).encode());
+
+    // nor transfer value, create a contract, emit an event or self-destruct
+    for n in 0u8..4 {
+        runtime.function("change", (n, true).encode());
+
+        assert_eq!(runtime.vm.output, false.encode());
+    }
+
+    assert_eq!(runtime.accounts.len(), 2);
+    assert!(runtime.events().is_empty());
+
+    let other = *runtime
+        .accounts
+        .keys()
+        .find(|account| **account != runtime.vm.account)
+        .unwrap();
+
+    assert_eq!(runtime.accounts[&other].1, 1000);
+
+    // the same calls succeed when they are not read-only
+    runtime.function("change", (2u8, false).encode());
+
+    assert_eq!(runtime.vm.output, true.encode());
+    assert_eq!(runtime.events().len(), 1);
+
+    runtime.function("change", (0u8, false).encode());
+
+    assert_eq!(runtime.vm.output, true.encode());
+    assert_eq!(runtime.accounts[&other].1, 999);
+}
+
+#[test]
+fn forward_calldata_bytes() {
+    // This only covers forwarding the whole calldata, which is copied. TODO: returning a calldata
+    // slice like data[4:] without a copy needs calldata slices, which are not supported yet
+    let mut runtime = build_solidity(
+        r##"
+        contract proxy {
+            other o;
+
+            constructor() {
+                o = new other();
+            }
+
+            function echo(bytes calldata data) external pure returns (bytes) {
+                return data;
+            }
+
+            function forward(bytes calldata data) external returns (bytes) {
+                (bool success, bytes rawresult) = address(o).call(data);
+
+                assert(success);
+
+                return rawresult;
+            }
+        }
+
+        contract other {
+            function reverse(bytes memory b) public pure returns (bytes) {
+                bytes r = new bytes(b.length);
+
+                for (uint32 i = 0; i < b.length; i++) {
+                    r[b.length - i - 1] = b[i];
+                }
+
+                return r;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    let data = vec![0xdeu8, 0xad, 0xca, 0xfe, 1, 2, 3];
+
+    runtime.function("echo", data.encode());
+
+    assert_eq!(runtime.vm.output, data.encode());
+
+    let mut selector = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(b"reverse(bytes)");
+    hasher.finalize(&mut selector);
+
+    let mut input = selector[..4].to_vec();
+    input.extend(vec![1u8, 2, 3].encode());
+
+    runtime.function("forward", input.encode());
+
+    assert_eq!(runtime.vm.output, vec![3u8, 2, 1].encode().encode());
+}
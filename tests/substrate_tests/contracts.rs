@@ -1,7 +1,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::build_solidity;
+use num_bigint::BigUint;
 use parity_scale_codec::{Decode, Encode};
+use solang::abi::scale::Value;
+use solang::file_resolver::FileResolver;
+use solang::{compile, Target};
+use std::ffi::OsStr;
 
 #[derive(Debug, PartialEq, Eq, Encode, Decode)]
 struct RevertReturn(u32, String);
@@ -105,6 +110,114 @@ fn revert_constructor() {
     assert_eq!(runtime.vm.output.len(), 0);
 }
 
+#[test]
+fn factory_observes_constructor_revert() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Ret(u32, String);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract factory {
+            child last;
+
+            function make(int32 x) public returns (uint32, string) {
+                try new child(x) returns (child c) {
+                    last = c;
+                    return (1, "");
+                } catch Error(string reason) {
+                    return (2, reason);
+                } catch (bytes) {
+                    return (3, "");
+                }
+            }
+
+            function update(int32 x) public returns (uint32, string) {
+                try last.set(x) {
+                    return (1, "");
+                } catch Error(string reason) {
+                    return (2, reason);
+                } catch (bytes) {
+                    return (3, "");
+                }
+            }
+
+            function get() public view returns (int32) {
+                return last.a();
+            }
+
+            function make_without_try() public {
+                last = new child(0);
+            }
+        }
+
+        contract child {
+            int32 public a;
+
+            modifier positive(int32 x) {
+                require(x > 0, "not positive");
+                _;
+            }
+
+            constructor(int32 x) {
+                require(x != 0, "zero");
+
+                if (x > 100) {
+                    revert("too large");
+                } else if (x == 100) {
+                    revert();
+                }
+
+                a = x;
+            }
+
+            function set(int32 x) public positive(x) {
+                a = x;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("make", 5i32.encode());
+    assert_eq!(runtime.vm.output, Ret(1, String::new()).encode());
+
+    runtime.function("make", 0i32.encode());
+    assert_eq!(runtime.vm.output, Ret(2, String::from("zero")).encode());
+
+    runtime.function("make", 101i32.encode());
+    assert_eq!(
+        runtime.vm.output,
+        Ret(2, String::from("too large")).encode()
+    );
+
+    // revert() without a reason has no Error(string)
+    runtime.function("make", 100i32.encode());
+    assert_eq!(runtime.vm.output, Ret(3, String::new()).encode());
+
+    // the failed constructors did not replace the first child
+    runtime.function("get", Vec::new());
+    assert_eq!(runtime.vm.output, 5i32.encode());
+
+    // the modifier reverts before the function body runs
+    runtime.function("update", (-3i32).encode());
+    assert_eq!(
+        runtime.vm.output,
+        Ret(2, String::from("not positive")).encode()
+    );
+
+    runtime.function("get", Vec::new());
+    assert_eq!(runtime.vm.output, 5i32.encode());
+
+    runtime.function("update", 7i32.encode());
+    assert_eq!(runtime.vm.output, Ret(1, String::new()).encode());
+
+    runtime.function("get", Vec::new());
+    assert_eq!(runtime.vm.output, 7i32.encode());
+
+    // without try, the failure of the constructor is the failure of the factory
+    runtime.function_expect_failure("make_without_try", Vec::new());
+}
+
 #[test]
 fn external_datatypes() {
     #[derive(Debug, PartialEq, Eq, Encode, Decode)]
@@ -201,6 +314,161 @@ fn creation_code() {
     );
 }
 
+#[test]
+fn code_hash() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function test() public returns (address, bytes32) {
+                child ch = new child(5);
+
+                return (address(ch), type(child).codeHash);
+            }
+        }
+
+        contract child {
+            int public x;
+            constructor(int a) {
+                x = a;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    let ret = runtime.function_typed("test", &[]);
+
+    let (address, code_hash) = match ret.as_slice() {
+        [Value::Address(address), Value::FixedBytes(code_hash)] => (address, code_hash),
+        _ => panic!("unexpected return {:?}", ret),
+    };
+
+    // compare with the hash of the code deployed on chain
+    let account: [u8; 32] = address.as_slice().try_into().unwrap();
+    let code = &runtime.accounts[&account].0;
+
+    assert_eq!(
+        code_hash.as_slice(),
+        blake2_rfc::blake2b::blake2b(32, &[], code).as_bytes()
+    );
+}
+
+#[test]
+fn contract_type_in_storage_containers() {
+    let mut runtime = build_solidity(
+        r##"
+        interface IToken {
+            function transfer(address to, uint64 amount) external returns (bool);
+            function balance(address who) external view returns (uint64);
+        }
+
+        contract c {
+            mapping(uint32 => IToken) tokens;
+            IToken[] public list;
+
+            function test() public returns (uint64, uint64) {
+                token t = new token();
+
+                tokens[1] = t;
+                list.push(t);
+
+                tokens[1].transfer(address(this), 10);
+                list[0].transfer(address(this), 20);
+
+                return (tokens[1].balance(address(this)), list[0].balance(address(this)));
+            }
+
+            function get() public view returns (address, IToken, IToken) {
+                IToken[] memory copy = new IToken[](2);
+
+                copy[0] = list[0];
+
+                return (address(tokens[1]), copy[0], copy[1]);
+            }
+
+            function unset() public view returns (IToken) {
+                return tokens[2];
+            }
+        }
+
+        contract token is IToken {
+            mapping(address => uint64) balances;
+
+            function transfer(address to, uint64 amount) public override returns (bool) {
+                balances[to] += amount;
+                return true;
+            }
+
+            function balance(address who) public view override returns (uint64) {
+                return balances[who];
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    let ret = runtime.function_typed("test", &[]);
+    assert_eq!(
+        ret,
+        vec![
+            Value::Uint(BigUint::from(30u8)),
+            Value::Uint(BigUint::from(30u8))
+        ]
+    );
+
+    let ret = runtime.function_typed("get", &[]);
+    let address = ret[0].clone();
+    assert_eq!(ret[1], address);
+    assert_eq!(ret[2], Value::Address(vec![0; 32]));
+    assert_ne!(address, ret[2]);
+
+    // the public accessor returns the address
+    let ret = runtime.function_typed("list", &[Value::Uint(BigUint::from(0u8))]);
+    assert_eq!(ret, vec![address]);
+
+    let ret = runtime.function_typed("unset", &[]);
+    assert_eq!(ret, vec![Value::Address(vec![0; 32])]);
+}
+
+#[test]
+fn code_size_and_hash_in_metadata() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r##"
+        contract c {
+            function test() public pure returns (int32) {
+                return 102;
+            }
+        }"##
+        .to_string(),
+    );
+
+    let (res, ns) = compile(
+        OsStr::new("test.sol"),
+        &mut cache,
+        inkwell::OptimizationLevel::Default,
+        Target::default_substrate(),
+        false,
+        false,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let (code, metadata) = &res[0];
+    let metadata: serde_json::Value = serde_json::from_str(metadata).unwrap();
+
+    assert_eq!(metadata["user"]["code_size"], code.len());
+    assert_eq!(
+        metadata["source"]["hash"],
+        format!(
+            "0x{}",
+            hex::encode(blake2_rfc::blake2b::blake2b(32, &[], code).as_bytes())
+        )
+    );
+}
+
 #[test]
 fn issue666() {
     let mut runtime = build_solidity(
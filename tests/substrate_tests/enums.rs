@@ -82,3 +82,36 @@ fn enums_other_contracts() {
 
     runtime.function("test", Val(1).encode());
 }
+
+#[test]
+fn inherited_enum_conversion() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c is base {
+            function to_state(uint32 x) public pure returns (State) {
+                return State(x);
+            }
+
+            function to_signed_state(int8 x) public pure returns (State) {
+                return State(x);
+            }
+        }
+
+        abstract contract base {
+            enum State { Idle, Running, Stopped }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("to_state", 2u32.encode());
+    assert_eq!(runtime.vm.output, 2u8.encode());
+
+    runtime.function("to_signed_state", 1i8.encode());
+    assert_eq!(runtime.vm.output, 1u8.encode());
+
+    // State only has three members
+    runtime.function_expect_failure("to_state", 3u32.encode());
+    runtime.function_expect_failure("to_state", 0x100u32.encode());
+    runtime.function_expect_failure("to_signed_state", (-1i8).encode());
+}
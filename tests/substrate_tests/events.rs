@@ -1,9 +1,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::build_solidity;
+use num_bigint::BigInt;
 use parity_scale_codec::{Decode, Encode};
+use solang::abi::events::{decode_event, DecodedField, DecodedValue};
 use solang::{file_resolver::FileResolver, Target};
 use std::ffi::OsStr;
+use tiny_keccak::{Hasher, Keccak};
 
 #[test]
 fn emit() {
@@ -183,3 +186,239 @@ fn event_imported() {
 
     assert!(!ns.diagnostics.any_errors());
 }
+
+#[test]
+fn decode_events() {
+    let src = r##"
+        contract a {
+            enum Color { Red, Green, Blue }
+
+            struct S {
+                int32 f1;
+                string f2;
+            }
+
+            event foo(bool indexed, uint24 indexed x, string indexed s, int64 y, Color c);
+            event bar(S s, uint16[2] a, bytes4 b, address indexed who) anonymous;
+
+            function emit_event() public {
+                emit foo(true, 0x102030, "hello", -100, Color.Blue);
+                emit bar(S(-5, "world"), [1, 2], hex"deadcafe", address(this));
+            }
+        }"##;
+
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(0, Vec::new());
+    runtime.function("emit_event", Vec::new());
+
+    assert_eq!(runtime.events.len(), 2);
+
+    // the runtime does not keep the namespace, so compile it again
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let (_, ns) = solang::compile(
+        OsStr::new("test.sol"),
+        &mut cache,
+        inkwell::OptimizationLevel::Default,
+        Target::default_substrate(),
+        false,
+        false,
+    );
+
+    let field = |name: &str, indexed, value| DecodedField {
+        name: name.to_string(),
+        indexed,
+        value,
+    };
+
+    let event = &runtime.events[0];
+    let decoded = decode_event(0, &event.topics, &event.data, &ns).unwrap();
+
+    let mut hash = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(b"hello");
+    hasher.finalize(&mut hash);
+
+    assert_eq!(decoded.name, "foo");
+    assert_eq!(
+        decoded.fields,
+        vec![
+            field("", true, DecodedValue::Bool(true)),
+            field("x", true, DecodedValue::Int(BigInt::from(0x102030))),
+            field("s", true, DecodedValue::Hash(hash)),
+            field("y", false, DecodedValue::Int(BigInt::from(-100))),
+            field("c", false, DecodedValue::Int(BigInt::from(2))),
+        ]
+    );
+
+    let event = &runtime.events[1];
+    let decoded = decode_event(0, &event.topics, &event.data, &ns).unwrap();
+
+    assert_eq!(decoded.name, "bar");
+    assert!(ns.events[decoded.event_no].anonymous);
+    assert_eq!(
+        decoded.fields,
+        vec![
+            field(
+                "s",
+                false,
+                DecodedValue::Struct(vec![
+                    ("f1".to_string(), DecodedValue::Int(BigInt::from(-5))),
+                    ("f2".to_string(), DecodedValue::String("world".to_string())),
+                ])
+            ),
+            field(
+                "a",
+                false,
+                DecodedValue::Array(vec![
+                    DecodedValue::Int(BigInt::from(1)),
+                    DecodedValue::Int(BigInt::from(2)),
+                ])
+            ),
+            field(
+                "b",
+                false,
+                DecodedValue::Bytes(vec![0xde, 0xad, 0xca, 0xfe])
+            ),
+            field(
+                "who",
+                true,
+                DecodedValue::Address(runtime.vm.account.to_vec())
+            ),
+        ]
+    );
+
+    // topics do not match the event
+    let event = &runtime.events[0];
+    assert_eq!(
+        decode_event(0, &event.topics[1..], &event.data, &ns),
+        Err("event 'foo' has 3 indexed fields but 2 topics given".to_string())
+    );
+
+    // unknown event index
+    assert_eq!(
+        decode_event(0, &[], &[5], &ns),
+        Err("contract 'a' does not emit an event with index 5".to_string())
+    );
+}
+
+#[test]
+fn emit_implicit_conversions() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Args(u32, i8);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract a {
+            struct Point {
+                int64 x;
+                int64 y;
+            }
+
+            event Transfer(address to, uint256 value);
+            event Widened(uint64 a, int256 b);
+            event Moved(Point p);
+
+            function emit_event(uint32 small, int8 neg) public {
+                emit Transfer(address(this), 1);
+                emit Widened(small, neg);
+                emit Moved(Point({x: 1, y: neg}));
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+    runtime.function("emit_event", Args(7, -3).encode());
+
+    assert_eq!(runtime.events.len(), 3);
+
+    let mut value = [0u8; 32];
+    value[0] = 1;
+    assert_eq!(
+        runtime.events[0].data,
+        (0u8, runtime.vm.account, value).encode()
+    );
+
+    let mut b = [0xffu8; 32];
+    b[0] = 0xfd;
+    assert_eq!(runtime.events[1].data, (1u8, 7u64, b).encode());
+
+    assert_eq!(runtime.events[2].data, (2u8, 1i64, -3i64).encode());
+}
+
+#[test]
+fn indexed_bytes_topics() {
+    let mut runtime = build_solidity(
+        r##"
+        contract a {
+            event E(bytes32 indexed id, bytes4 indexed tag, bytes indexed blob);
+
+            function emit_event() public {
+                emit E(
+                    hex"00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff",
+                    hex"deadcafe",
+                    hex"deadcafe"
+                );
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+    runtime.function("emit_event", Vec::new());
+
+    assert_eq!(runtime.events.len(), 1);
+    let event = &runtime.events[0];
+    assert_eq!(event.topics.len(), 3);
+
+    // bytes32 is the topic as-is
+    assert_eq!(
+        event.topics[0].to_vec(),
+        hex::decode("00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff").unwrap()
+    );
+
+    // bytes4 is right-padded with zeros
+    let mut tag = [0u8; 32];
+    tag[..4].copy_from_slice(&[0xde, 0xad, 0xca, 0xfe]);
+    assert_eq!(event.topics[1], tag);
+
+    // dynamic bytes is hashed
+    let mut hash = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(&[0xde, 0xad, 0xca, 0xfe]);
+    hasher.finalize(&mut hash);
+    assert_eq!(event.topics[2], hash);
+}
+
+#[test]
+fn events_accessor() {
+    let mut runtime = build_solidity(
+        r##"
+        contract a {
+            event Transfer(address indexed from, uint64 value, string memo);
+
+            function transfer(uint64 value) public {
+                emit Transfer(msg.sender, value, "memo");
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+    assert!(runtime.events().is_empty());
+
+    runtime.function("transfer", 500u64.encode());
+
+    let events = runtime.events();
+    assert_eq!(events.len(), 1);
+
+    let (topics, data) = events[0];
+    assert_eq!(topics.len(), 1);
+    assert_eq!(topics[0], runtime.vm.caller);
+
+    let (event_index, value, memo) = <(u8, u64, String)>::decode(&mut &data[..]).unwrap();
+    assert_eq!(event_index, 0);
+    assert_eq!(value, 500);
+    assert_eq!(memo, "memo");
+}
@@ -5,6 +5,10 @@ use num_bigint::{BigInt, BigUint, RandBigInt, Sign};
 use parity_scale_codec::{Decode, Encode};
 use rand::seq::SliceRandom;
 use rand::Rng;
+use solang::abi::scale::Value;
+use solang::file_resolver::FileResolver;
+use solang::{compile, Target};
+use std::ffi::OsStr;
 use std::ops::Add;
 use std::ops::Div;
 use std::ops::Mul;
@@ -996,6 +1000,51 @@ fn test_power_overflow_boundaries() {
     }
 }
 
+#[test]
+fn power_runtime_exponent() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Args(u64, u64);
+
+    let mut runtime = build_solidity_with_overflow_check(
+        r#"
+        contract c {
+            function power(uint64 base, uint64 exp) public returns (uint64) {
+                return base ** exp;
+            }
+
+            function power_unchecked(uint64 base, uint64 exp) public returns (uint64) {
+                unchecked {
+                    return base ** exp;
+                }
+            }
+        }"#,
+        true,
+    );
+
+    for (base, exp) in [
+        (7, 0),
+        (0, 0),
+        (7, 1),
+        (0, 5),
+        // squaring the base again would overflow, but is not needed
+        (1 << 32, 1),
+        (1 << 20, 3),
+        (3, 40),
+        (u64::MAX, 1),
+    ] {
+        runtime.function("power", Args(base, exp).encode());
+
+        assert_eq!(runtime.vm.output, base.pow(exp as u32).encode());
+    }
+
+    runtime.function_expect_failure("power", Args(3, 41).encode());
+    runtime.function_expect_failure("power", Args(1 << 32, 2).encode());
+
+    runtime.function("power_unchecked", Args(3, 41).encode());
+
+    assert_eq!(runtime.vm.output, 3u64.wrapping_pow(41).encode());
+}
+
 #[test]
 fn multiply() {
     let mut rng = rand::thread_rng();
@@ -1901,3 +1950,551 @@ fn sign_extend(sign: Sign) -> u8 {
         0
     }
 }
+
+#[test]
+fn checked_arithmetic() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Args8(u8, u8);
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Ret8(bool, u8);
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Args128(i128, i128);
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Ret128(bool, i128);
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Args256([u8; 32], [u8; 32]);
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Ret256(bool, [u8; 32]);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function add8(uint8 a, uint8 b) public returns (bool, uint8) {
+                return a.checkedAdd(b);
+            }
+
+            function sub8(uint8 a, uint8 b) public returns (bool, uint8) {
+                return a.checkedSub(b);
+            }
+
+            function mul8(uint8 a, uint8 b) public returns (bool, uint8) {
+                (bool ok, uint8 r) = a.checkedMul(b);
+                return (ok, r);
+            }
+
+            function add128(int128 a, int128 b) public returns (bool, int128) {
+                return a.checkedAdd(b);
+            }
+
+            function sub128(int128 a, int128 b) public returns (bool, int128) {
+                return a.checkedSub(b);
+            }
+
+            function mul128(int128 a, int128 b) public returns (bool, int128) {
+                return a.checkedMul(b);
+            }
+
+            function add256(uint256 a, uint256 b) public returns (bool, uint256) {
+                return a.checkedAdd(b);
+            }
+
+            function sub256(uint256 a, uint256 b) public returns (bool, uint256) {
+                return a.checkedSub(b);
+            }
+
+            function mul256(uint256 a, uint256 b) public returns (bool, uint256) {
+                return a.checkedMul(b);
+            }
+
+            function constants() public {
+                (bool ok, uint8 r) = uint8(200).checkedAdd(100);
+                assert(!ok && r == 44);
+
+                (bool ok2, int128 x) = int128(0).checkedSub(type(int128).min);
+                assert(!ok2 && x == type(int128).min);
+
+                (bool ok3, uint256 y) = uint256(2**128).checkedMul(2**127);
+                assert(ok3 && y == 2**255);
+            }
+        }"##,
+    );
+
+    let u256 = |high: u128, low: u128| {
+        let mut v = [0u8; 32];
+        v[..16].copy_from_slice(&low.to_le_bytes());
+        v[16..].copy_from_slice(&high.to_le_bytes());
+        v
+    };
+
+    for (name, a, b, ok, r) in [
+        ("add8", 100, 100, true, 200),
+        ("add8", 200, 100, false, 44),
+        ("sub8", 5, 2, true, 3),
+        ("sub8", 1, 2, false, 255),
+        ("mul8", 15, 17, true, 255),
+        ("mul8", 16, 16, false, 0),
+    ] {
+        runtime.function(name, Args8(a, b).encode());
+        assert_eq!(runtime.vm.output, Ret8(ok, r).encode());
+    }
+
+    for (name, a, b, ok, r) in [
+        ("add128", -5, 10, true, 5),
+        ("add128", i128::MAX, 1, false, i128::MIN),
+        ("sub128", -5, 10, true, -15),
+        ("sub128", 0, i128::MIN, false, i128::MIN),
+        ("sub128", -1, i128::MIN, true, i128::MAX),
+        ("sub128", i128::MIN, 1, false, i128::MAX),
+        ("mul128", -3, 5, true, -15),
+        ("mul128", i128::MIN, -1, false, i128::MIN),
+        ("mul128", 1 << 64, 1 << 63, false, i128::MIN),
+    ] {
+        runtime.function(name, Args128(a, b).encode());
+        assert_eq!(runtime.vm.output, Ret128(ok, r).encode());
+    }
+
+    for (name, a, b, ok, r) in [
+        (
+            "add256",
+            u256(1, 0),
+            u256(0, u128::MAX),
+            true,
+            u256(1, u128::MAX),
+        ),
+        (
+            "add256",
+            u256(u128::MAX, u128::MAX),
+            u256(0, 1),
+            false,
+            u256(0, 0),
+        ),
+        ("sub256", u256(1, 0), u256(0, 1), true, u256(0, u128::MAX)),
+        (
+            "sub256",
+            u256(0, 0),
+            u256(0, 1),
+            false,
+            u256(u128::MAX, u128::MAX),
+        ),
+        ("mul256", u256(0, 1 << 127), u256(0, 2), true, u256(1, 0)),
+        ("mul256", u256(1, 0), u256(1, 0), false, u256(0, 0)),
+    ] {
+        runtime.function(name, Args256(a, b).encode());
+        assert_eq!(runtime.vm.output, Ret256(ok, r).encode());
+    }
+
+    runtime.function("constants", Vec::new());
+}
+
+#[test]
+fn signed_comparisons() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function constants() public pure {
+                assert(int8(-1) < int8(1));
+                assert(int8(1) > int8(-1));
+                assert(int8(-1) <= int8(1));
+                assert(int8(1) >= int8(-1));
+            }
+
+            function compare(int8 a, int8 b) public pure returns (bool, bool, bool, bool) {
+                return (a < b, a > b, a <= b, a >= b);
+            }
+
+            function compare_unsigned(uint8 a, uint8 b) public pure returns (bool, bool) {
+                return (a < b, a > b);
+            }
+
+            function yul(int256 a, int256 b) public pure returns (uint256 unsigned_lt, uint256 signed_lt) {
+                assembly {
+                    unsigned_lt := lt(a, b)
+                    signed_lt := slt(a, b)
+                }
+            }
+        }"##,
+    );
+
+    runtime.function("constants", Vec::new());
+
+    let ret = runtime.function_typed("compare", &[Value::Int((-1).into()), Value::Int(1.into())]);
+
+    assert_eq!(
+        ret,
+        vec![
+            Value::Bool(true),
+            Value::Bool(false),
+            Value::Bool(true),
+            Value::Bool(false)
+        ]
+    );
+
+    let ret = runtime.function_typed(
+        "compare",
+        &[Value::Int((-128).into()), Value::Int((-128).into())],
+    );
+
+    assert_eq!(
+        ret,
+        vec![
+            Value::Bool(false),
+            Value::Bool(false),
+            Value::Bool(true),
+            Value::Bool(true)
+        ]
+    );
+
+    // 255 is the same bit pattern as int8(-1)
+    let ret = runtime.function_typed(
+        "compare_unsigned",
+        &[Value::Uint(255u8.into()), Value::Uint(1u8.into())],
+    );
+
+    assert_eq!(ret, vec![Value::Bool(false), Value::Bool(true)]);
+
+    let ret = runtime.function_typed("yul", &[Value::Int((-1).into()), Value::Int(1.into())]);
+
+    assert_eq!(ret, vec![Value::Uint(0u8.into()), Value::Uint(1u8.into())]);
+}
+
+#[test]
+fn math_semantics() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Args(u64, u64);
+
+    let src = r#"
+        contract c {
+            function add(uint64 a, uint64 b) public returns (uint64) {
+                return a + b;
+            }
+
+            function add_unchecked(uint64 a, uint64 b) public returns (uint64) {
+                unchecked {
+                    return a + b;
+                }
+            }
+        }"#;
+
+    // checked
+    let mut runtime = build_solidity_with_overflow_check(src, true);
+
+    runtime.function("add", Args(1, 2).encode());
+    assert_eq!(runtime.vm.output, 3u64.encode());
+
+    runtime.function_expect_failure("add", Args(u64::MAX, 2).encode());
+
+    runtime.function("add_unchecked", Args(u64::MAX, 2).encode());
+    assert_eq!(runtime.vm.output, 1u64.encode());
+
+    // wrapping
+    let mut runtime = build_solidity_with_overflow_check(src, false);
+
+    runtime.function("add", Args(u64::MAX, 2).encode());
+    assert_eq!(runtime.vm.output, 1u64.encode());
+
+    runtime.function("add_unchecked", Args(u64::MAX, 2).encode());
+    assert_eq!(runtime.vm.output, 1u64.encode());
+
+    // a pragma before 0.8.0 wraps, even when checked
+    let mut runtime = build_solidity_with_overflow_check(
+        &format!("pragma solidity >=0.6.0 <0.8.0;\n{}", src),
+        true,
+    );
+
+    runtime.function("add", Args(u64::MAX, 2).encode());
+    assert_eq!(runtime.vm.output, 1u64.encode());
+
+    let mut runtime =
+        build_solidity_with_overflow_check(&format!("pragma solidity ^0.8.0;\n{}", src), true);
+
+    runtime.function_expect_failure("add", Args(u64::MAX, 2).encode());
+}
+
+#[test]
+fn unchecked_block_wraps() {
+    let src = r#"
+        contract c {
+            function wrapping() public returns (uint8) {
+                unchecked {
+                    return uint8(255) + uint8(1);
+                }
+            }
+
+            function checked() public returns (uint8) {
+                uint8 a = 255;
+                return a + uint8(1);
+            }
+        }"#;
+
+    let mut runtime = build_solidity_with_overflow_check(src, true);
+
+    runtime.function("wrapping", Vec::new());
+    assert_eq!(runtime.vm.output, 0u8.encode());
+
+    runtime.function_expect_failure("checked", Vec::new());
+}
+
+#[test]
+fn unchecked_increment_decrement() {
+    let src = r#"
+        contract c {
+            function inc_unchecked(uint8 x) public returns (uint8) {
+                unchecked {
+                    x++;
+                }
+                return x;
+            }
+
+            function inc(uint8 x) public returns (uint8) {
+                x++;
+                return x;
+            }
+
+            function dec_unchecked(uint8 x) public returns (uint8, uint8) {
+                unchecked {
+                    uint8 y = --x;
+                    return (x, y);
+                }
+            }
+
+            function dec(uint8 x) public returns (uint8) {
+                return --x;
+            }
+        }"#;
+
+    let mut runtime = build_solidity_with_overflow_check(src, true);
+
+    runtime.function("inc_unchecked", 255u8.encode());
+    assert_eq!(runtime.vm.output, 0u8.encode());
+
+    runtime.function("inc", 254u8.encode());
+    assert_eq!(runtime.vm.output, 255u8.encode());
+
+    runtime.function_expect_failure("inc", 255u8.encode());
+
+    runtime.function("dec_unchecked", 0u8.encode());
+    assert_eq!(runtime.vm.output, (255u8, 255u8).encode());
+
+    runtime.function_expect_failure("dec", 0u8.encode());
+}
+
+#[test]
+fn panic_codes() {
+    let mut runtime = build_solidity_with_overflow_check(
+        r#"
+        contract c {
+            enum E { A, B }
+            bytes b;
+
+            function assertion() public {
+                assert(b.length == 1);
+            }
+
+            function overflow(uint8 x) public returns (uint8) {
+                return x + 1;
+            }
+
+            function divide(uint256 x) public returns (uint256) {
+                return 100 / x;
+            }
+
+            function to_enum(uint8 x) public returns (E) {
+                return E(x);
+            }
+
+            function pop() public {
+                uint32[] arr;
+                arr.pop();
+            }
+
+            function pop_storage() public {
+                b.pop();
+            }
+
+            function index(uint32 i) public returns (uint64) {
+                uint64[] arr = new uint64[](2);
+                return arr[i];
+            }
+        }"#,
+        true,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function_expect_panic("assertion", Vec::new(), 0x01);
+    runtime.function_expect_panic("overflow", 255u8.encode(), 0x11);
+    runtime.function_expect_panic("divide", [0u8; 32].encode(), 0x12);
+    runtime.function_expect_panic("to_enum", 2u8.encode(), 0x21);
+    runtime.function_expect_panic("pop", Vec::new(), 0x31);
+    runtime.function_expect_panic("pop_storage", Vec::new(), 0x31);
+    runtime.function_expect_panic("index", 2u32.encode(), 0x32);
+
+    // the argument is missing
+    runtime.function_expect_panic("overflow", Vec::new(), 0x60);
+
+    // none of the functions are payable
+    runtime.vm.value = 1;
+    runtime.function_expect_panic("index", 1u32.encode(), 0x63);
+    runtime.vm.value = 0;
+
+    // the functions work with valid arguments
+    let mut four = [0u8; 32];
+    four[0] = 4;
+    let mut twenty_five = [0u8; 32];
+    twenty_five[0] = 25;
+
+    runtime.function("divide", four.encode());
+    assert_eq!(runtime.vm.output, twenty_five.encode());
+
+    runtime.function("index", 1u32.encode());
+    assert_eq!(runtime.vm.output, 0u64.encode());
+}
+
+#[test]
+fn panic_codes_in_metadata() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents("test.sol", "contract c {}".to_string());
+
+    let (res, ns) = compile(
+        OsStr::new("test.sol"),
+        &mut cache,
+        inkwell::OptimizationLevel::Default,
+        Target::default_substrate(),
+        false,
+        false,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let metadata: serde_json::Value = serde_json::from_str(&res[0].1).unwrap();
+    let codes = &metadata["user"]["panic_codes"];
+
+    assert_eq!(codes.as_object().unwrap().len(), 15);
+    assert_eq!(codes["0x01"], "assertion failed");
+    assert_eq!(codes["0x32"], "array index out of bounds");
+    assert_eq!(codes["0x60"], "input or return data could not be decoded");
+    assert_eq!(
+        codes["0x64"],
+        "not enough accounts passed to the instruction"
+    );
+}
+
+#[test]
+fn checked_arithmetic_panics() {
+    let mut runtime = build_solidity_with_overflow_check(
+        r#"
+        contract c {
+            function sub64(uint64 a, uint64 b) public returns (uint64) {
+                return a - b;
+            }
+
+            function add128(int128 a, int128 b) public returns (int128) {
+                return a + b;
+            }
+
+            function mul256(uint256 a, uint256 b) public returns (uint256) {
+                return a * b;
+            }
+
+            function neg(int8 a) public returns (int8) {
+                return -a;
+            }
+
+            function neg_unchecked(int8 a) public returns (int8) {
+                unchecked {
+                    return -a;
+                }
+            }
+
+            function div32(uint32 a, uint32 b) public returns (uint32) {
+                return a / b;
+            }
+
+            function mod64(int64 a, int64 b) public returns (int64) {
+                unchecked {
+                    // division by zero is checked in unchecked blocks too
+                    return a % b;
+                }
+            }
+        }"#,
+        true,
+    );
+
+    runtime.function_expect_panic("sub64", (1u64, 2u64).encode(), 0x11);
+    runtime.function_expect_panic("add128", (i128::MAX, 1i128).encode(), 0x11);
+    runtime.function_expect_panic("mul256", ([0xffu8; 32], [2u8; 32]).encode(), 0x11);
+    runtime.function_expect_panic("neg", (-128i8).encode(), 0x11);
+    runtime.function_expect_panic("div32", (7u32, 0u32).encode(), 0x12);
+    runtime.function_expect_panic("mod64", (7i64, 0i64).encode(), 0x12);
+
+    runtime.function("neg", (-127i8).encode());
+    assert_eq!(runtime.vm.output, 127i8.encode());
+
+    runtime.function("neg_unchecked", (-128i8).encode());
+    assert_eq!(runtime.vm.output, (-128i8).encode());
+
+    runtime.function("div32", (7u32, 2u32).encode());
+    assert_eq!(runtime.vm.output, 3u32.encode());
+
+    runtime.function("mod64", (-7i64, 2i64).encode());
+    assert_eq!(runtime.vm.output, (-1i64).encode());
+}
+
+#[test]
+fn signed_division_overflow() {
+    let src = r#"
+        contract c {
+            function div64(int64 a, int64 b) public returns (int64) {
+                return a / b;
+            }
+
+            function div128(int128 a, int128 b) public returns (int128) {
+                return a / b;
+            }
+        }"#;
+
+    let mut runtime = build_solidity_with_overflow_check(src, true);
+
+    runtime.function_expect_panic("div64", (i64::MIN, -1i64).encode(), 0x11);
+    runtime.function_expect_panic("div128", (i128::MIN, -1i128).encode(), 0x11);
+
+    runtime.function("div64", (i64::MIN, 1i64).encode());
+    assert_eq!(runtime.vm.output, i64::MIN.encode());
+
+    runtime.function("div128", (-7i128, -1i128).encode());
+    assert_eq!(runtime.vm.output, 7i128.encode());
+
+    let mut runtime = build_solidity_with_overflow_check(src, false);
+
+    runtime.function("div64", (i64::MIN, -1i64).encode());
+    assert_eq!(runtime.vm.output, i64::MIN.encode());
+
+    runtime.function("div128", (i128::MIN, -1i128).encode());
+    assert_eq!(runtime.vm.output, i128::MIN.encode());
+
+    let mut runtime = build_solidity_with_overflow_check(
+        r#"
+        contract c {
+            function div64(int64 a, int64 b) public returns (int64) {
+                unchecked {
+                    return a / b;
+                }
+            }
+
+            function div128(int128 a, int128 b) public returns (int128) {
+                unchecked {
+                    a /= b;
+                }
+                return a;
+            }
+        }"#,
+        true,
+    );
+
+    runtime.function("div64", (i64::MIN, -1i64).encode());
+    assert_eq!(runtime.vm.output, i64::MIN.encode());
+
+    runtime.function("div128", (i128::MIN, -1i128).encode());
+    assert_eq!(runtime.vm.output, i128::MIN.encode());
+}
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use parity_scale_codec::{Decode, Encode};
+use solang::abi::scale::Value;
 
 use crate::build_solidity;
 
@@ -100,6 +101,48 @@ fn contract_storage_initializers() {
     assert_eq!(runtime.vm.output, ret.encode());
 }
 
+#[test]
+fn storage_initializers_in_order() {
+    let mut runtime = build_solidity(
+        "
+        abstract contract base {
+            uint64 x = 5;
+            uint64 y = x * 2 + double(x);
+
+            function double(uint64 v) internal pure returns (uint64) {
+                return v * 2;
+            }
+        }
+
+        contract test is base {
+            uint64 z = y + x;
+            uint64 w;
+
+            constructor() {
+                w = z + 1;
+            }
+
+            function get() public view returns (uint64, uint64, uint64, uint64) {
+                return (x, y, z, w);
+            }
+        }",
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    let ret = runtime.function_typed("get", &[]);
+
+    assert_eq!(
+        ret,
+        vec![
+            Value::Uint(5u64.into()),
+            Value::Uint(20u64.into()),
+            Value::Uint(25u64.into()),
+            Value::Uint(26u64.into()),
+        ]
+    );
+}
+
 #[test]
 fn contract_constants() {
     #[derive(Debug, PartialEq, Eq, Encode, Decode)]
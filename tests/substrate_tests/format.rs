@@ -254,3 +254,43 @@ fn div128() {
 
     assert_eq!(runtime.vm.output, 18462643383279502884i128.encode());
 }
+
+#[test]
+fn print_multiple_args() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function foo(int64 a, uint128 b, bool c, bytes4 d, bytes e, string s) public {
+                print("vals:", a, b, c, d, e, s);
+            }
+
+            function bar(uint8 a) public {
+                print(a);
+            }
+        }"##,
+    );
+
+    runtime.function(
+        "foo",
+        (
+            -5i64,
+            12_345_678_901_234_567_890_123u128,
+            true,
+            [1u8, 2, 3, 4],
+            vec![0xcau8, 0xfe],
+            String::from("hello"),
+        )
+            .encode(),
+    );
+
+    assert_eq!(
+        runtime.printbuf,
+        "vals: -5 12345678901234567890123 true 01020304 cafe hello"
+    );
+
+    runtime.printbuf.truncate(0);
+
+    runtime.function("bar", 42u8.encode());
+
+    assert_eq!(runtime.printbuf, "42");
+}
@@ -330,3 +330,55 @@ fn ext() {
     runtime.function("test1", Vec::new());
     runtime.function("test2", Vec::new());
 }
+
+#[test]
+fn external_function_callback() {
+    let mut runtime = build_solidity(
+        r##"
+        contract app {
+            notifier n;
+            listener l;
+
+            constructor() {
+                n = new notifier();
+                l = new listener();
+            }
+
+            function test(uint64 x) public returns (uint64) {
+                // the callback is passed to another contract, which stores it and calls it later
+                n.register(l.onDone);
+                n.fire(x);
+
+                return l.total();
+            }
+        }
+
+        contract notifier {
+            function(uint64) external callback;
+
+            function register(function(uint64) external f) public {
+                callback = f;
+            }
+
+            function fire(uint64 x) public {
+                callback(x);
+            }
+        }
+
+        contract listener {
+            uint64 public total;
+
+            function onDone(uint64 x) public {
+                total += x;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("test", 5u64.encode());
+    assert_eq!(runtime.vm.output, 5u64.encode());
+
+    runtime.function("test", 37u64.encode());
+    assert_eq!(runtime.vm.output, 42u64.encode());
+}
@@ -1,8 +1,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use parity_scale_codec::{Decode, Encode};
+use tiny_keccak::{Hasher, Keccak};
 
-use crate::build_solidity;
+use crate::{build_solidity, build_solidity_with_options};
+use solang::codegen::Options;
 
 #[test]
 fn constructors() {
@@ -129,6 +131,69 @@ fn function_override_selector() {
     assert_eq!(runtime.vm.output, 0xaa_bb_cc_ddu64.encode());
 }
 
+#[test]
+fn private_functions_not_dispatched() {
+    let selector = |signature: &str| {
+        let mut hash = [0u8; 32];
+        let mut hasher = Keccak::v256();
+        hasher.update(signature.as_bytes());
+        hasher.finalize(&mut hash);
+        hash[..4].to_vec()
+    };
+
+    let mut runtime = build_solidity(
+        r#"
+        contract test {
+            uint64 result;
+
+            function set(uint64 x) public {
+                result = x;
+            }
+
+            function set_private(uint64 x) private {
+                result = x;
+            }
+
+            function set_internal(uint64 x) internal {
+                set_private(x);
+            }
+
+            function get() public returns (uint64) {
+                return result;
+            }
+        }"#,
+    );
+
+    let messages: Vec<String> = runtime.programs[0]
+        .abi
+        .spec
+        .messages
+        .iter()
+        .map(|m| m.name.clone())
+        .collect();
+
+    assert_eq!(messages, vec!["set", "get"]);
+
+    runtime.constructor(0, Vec::new());
+
+    let mut input = selector("set(uint64)");
+    input.extend(7u64.encode());
+    runtime.raw_function(input);
+
+    // there are no dispatch entries for private or internal functions
+    let mut input = selector("set_private(uint64)");
+    input.extend(8u64.encode());
+    runtime.raw_function_failure(input);
+
+    let mut input = selector("set_internal(uint64)");
+    input.extend(9u64.encode());
+    runtime.raw_function_failure(input);
+
+    runtime.function("get", Vec::new());
+
+    assert_eq!(runtime.vm.output, 7u64.encode());
+}
+
 #[test]
 fn fallback() {
     #[derive(Debug, PartialEq, Eq, Encode, Decode)]
@@ -201,6 +266,186 @@ fn nofallback() {
     assert_eq!(runtime.vm.output, Val(356).encode());
 }
 
+#[test]
+fn receive_fallback_dispatch() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Val(u32);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint32 handler;
+
+            function get() public returns (uint32) {
+                return handler;
+            }
+
+            fallback() external {
+                handler = 1;
+            }
+
+            receive() payable external {
+                handler = 2;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    // empty calldata with value goes to receive
+    runtime.vm.value = 1;
+    runtime.raw_function(Vec::new());
+    runtime.vm.value = 0;
+    runtime.function("get", Vec::new());
+    assert_eq!(runtime.vm.output, Val(2).encode());
+
+    // empty calldata without value goes to receive too
+    runtime.raw_function(b"abcd".to_vec());
+    runtime.raw_function(Vec::new());
+    runtime.function("get", Vec::new());
+    assert_eq!(runtime.vm.output, Val(2).encode());
+
+    // unmatched calldata goes to fallback, even if it is shorter than a selector
+    runtime.raw_function(b"abcd".to_vec());
+    runtime.function("get", Vec::new());
+    assert_eq!(runtime.vm.output, Val(1).encode());
+
+    runtime.raw_function(Vec::new());
+    runtime.raw_function(b"ab".to_vec());
+    runtime.function("get", Vec::new());
+    assert_eq!(runtime.vm.output, Val(1).encode());
+
+    // fallback is not payable, so unmatched calldata with value reverts
+    runtime.vm.value = 1;
+    runtime.raw_function_failure(b"abcd".to_vec());
+
+    // without receive, empty calldata goes to fallback
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint32 handler;
+
+            function get() public returns (uint32) {
+                return handler;
+            }
+
+            function pay() public payable {}
+
+            fallback() external {
+                handler = 1;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.raw_function(Vec::new());
+    runtime.function("get", Vec::new());
+    assert_eq!(runtime.vm.output, Val(1).encode());
+
+    runtime.vm.value = 1;
+    runtime.raw_function_failure(Vec::new());
+
+    // without fallback, unmatched calldata reverts even with value
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint32 handler;
+
+            function get() public returns (uint32) {
+                return handler;
+            }
+
+            receive() payable external {
+                handler = 2;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.vm.value = 1;
+    runtime.raw_function_failure(b"abcd".to_vec());
+    runtime.vm.value = 0;
+    runtime.raw_function_failure(b"abcd".to_vec());
+
+    runtime.raw_function(Vec::new());
+    runtime.function("get", Vec::new());
+    assert_eq!(runtime.vm.output, Val(2).encode());
+}
+
+#[test]
+fn decode_failure_reports_parameter() {
+    let src = r##"
+        contract c {
+            enum Colour { Red, Green, Blue }
+
+            function f(uint32 a, bool b, Colour c, string s) public pure returns (uint32) {
+                return a;
+            }
+        }"##;
+
+    let mut runtime = build_solidity_with_options(
+        src,
+        &Options {
+            math_overflow_check: false,
+            generate_debug_information: true,
+            log_prints: true,
+            ..Default::default()
+        },
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("f", (7u32, true, 2u8, String::from("x")).encode());
+    assert_eq!(runtime.vm.output, 7u32.encode());
+    assert_eq!(runtime.printbuf, "");
+
+    // truncated uint32
+    runtime.function_expect_failure("f", vec![7, 0]);
+    assert_eq!(
+        runtime.printbuf,
+        "abi decode failed: parameter #0 of type uint32"
+    );
+
+    // bool which is not 0 or 1
+    runtime.printbuf.clear();
+    runtime.function_expect_failure("f", (7u32, 2u8, 0u8, String::new()).encode());
+    assert_eq!(
+        runtime.printbuf,
+        "abi decode failed: parameter #1 of type bool"
+    );
+
+    // enum value out of range
+    runtime.printbuf.clear();
+    runtime.function_expect_failure("f", (7u32, false, 3u8, String::new()).encode());
+    assert_eq!(
+        runtime.printbuf,
+        "abi decode failed: parameter #2 of type enum c.Colour"
+    );
+
+    // string longer than the data
+    runtime.printbuf.clear();
+    runtime.function_expect_failure("f", (7u32, false, 0u8, [40u8, b'a']).encode());
+    assert_eq!(
+        runtime.printbuf,
+        "abi decode failed: parameter #3 of type string"
+    );
+
+    // trailing data
+    runtime.printbuf.clear();
+    runtime.function_expect_failure("f", (7u32, false, 0u8, String::new(), 1u8).encode());
+    assert_eq!(runtime.printbuf, "abi decode failed: trailing data");
+
+    // without debug info, malformed data still fails but nothing is reported
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function_expect_failure("f", (7u32, 2u8, 0u8, String::new()).encode());
+    assert_eq!(runtime.printbuf, "");
+}
+
 #[test]
 fn test_overloading() {
     // parse
@@ -597,3 +842,30 @@ fn global_functions() {
 
     runtime.function("test", Vec::new());
 }
+
+#[test]
+fn many_functions() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Val(u64);
+
+    let mut src = String::from("contract many {\n");
+
+    for i in 0..400 {
+        src.push_str(&format!(
+            "    function f{}(uint64 x) public pure returns (uint64) {{ return x + {}; }}\n",
+            i, i
+        ));
+    }
+
+    src.push('}');
+
+    let mut runtime = build_solidity(&src);
+
+    assert_eq!(runtime.programs[0].abi.spec.messages.len(), 400);
+
+    runtime.function("f0", Val(5).encode());
+    assert_eq!(runtime.vm.output, Val(5).encode());
+
+    runtime.function("f399", Val(5).encode());
+    assert_eq!(runtime.vm.output, Val(404).encode());
+}
@@ -36,6 +36,7 @@ fn test_abstract() {
         inkwell::OptimizationLevel::Default,
         Target::default_substrate(),
         false,
+        false,
     );
 
     assert!(!ns.diagnostics.any_errors());
@@ -74,6 +75,7 @@ fn test_abstract() {
         inkwell::OptimizationLevel::Default,
         Target::default_substrate(),
         false,
+        false,
     );
 
     assert!(!ns.diagnostics.any_errors());
@@ -674,3 +676,92 @@ fn var_or_function() {
 
     assert_eq!(runtime.vm.output, 102u64.encode());
 }
+
+#[test]
+fn inherited_storage_layout() {
+    let mut runtime = build_solidity(
+        r##"
+        contract d is b, c {
+            uint32 var_d;
+
+            function test() public {
+                set_a(7);
+                set_b(8);
+                set_c(9);
+                var_d = 10;
+            }
+
+            function get() public view returns (uint32, uint32, uint32, uint32) {
+                return (var_a, var_b, var_c, var_d);
+            }
+        }
+
+        abstract contract b is a {
+            uint32 var_b;
+
+            function set_b(uint32 v) internal {
+                var_b = v;
+            }
+        }
+
+        abstract contract c is a {
+            uint32 var_c;
+
+            function set_c(uint32 v) internal {
+                var_c = v;
+            }
+        }
+
+        abstract contract a {
+            uint32 var_a;
+
+            function set_a(uint32 v) internal {
+                var_a = v;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+    runtime.function("test", Vec::new());
+
+    runtime.function("get", Vec::new());
+
+    assert_eq!(runtime.vm.output, (7u32, 8u32, 9u32, 10u32).encode());
+
+    // the shared base is laid out once, so each variable has its own slot
+    assert_eq!(runtime.storage().len(), 4);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract c is b {
+            function get() public view returns (uint64) {
+                return var_b;
+            }
+        }
+
+        abstract contract b is a {
+            uint64 var_b;
+
+            constructor() {
+                var_b = var_a + 1;
+            }
+        }
+
+        abstract contract a {
+            uint64 var_a = 102;
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+    runtime.function("get", Vec::new());
+
+    assert_eq!(runtime.vm.output, 103u64.encode());
+
+    // the most base contract comes first
+    for (no, value) in [102u64, 103].iter().enumerate() {
+        let mut slot = [0u8; 32];
+        slot[0] = no as u8;
+
+        assert_eq!(runtime.storage_at(&slot).unwrap(), &value.encode());
+    }
+}
@@ -154,3 +154,140 @@ fn using_in_base() {
 
     assert_eq!(runtime.vm.output, true.encode());
 }
+
+#[test]
+fn using_function_list() {
+    let mut runtime = build_solidity(
+        r##"
+        type Fixed is uint64;
+
+        function add(Fixed a, Fixed b) pure returns (Fixed) {
+            return Fixed.wrap(Fixed.unwrap(a) + Fixed.unwrap(b));
+        }
+
+        library FixedMath {
+            function sub(Fixed a, Fixed b) internal pure returns (Fixed) {
+                return Fixed.wrap(Fixed.unwrap(a) - Fixed.unwrap(b));
+            }
+
+            function mul(Fixed a, Fixed b) internal pure returns (Fixed) {
+                return Fixed.wrap(Fixed.unwrap(a) * Fixed.unwrap(b));
+            }
+        }
+
+        using {add, FixedMath.sub} for Fixed global;
+
+        contract c {
+            function f(uint64 x, uint64 y) public pure returns (uint64) {
+                Fixed a = Fixed.wrap(x);
+                Fixed b = Fixed.wrap(y);
+
+                return Fixed.unwrap(a.add(b).sub(Fixed.wrap(1)));
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("f", (100u64, 23u64).encode());
+
+    assert_eq!(runtime.vm.output, 122u64.encode());
+}
+
+#[test]
+fn using_storage_struct_receiver() {
+    let mut runtime = build_solidity(
+        r##"
+        library EnumerableSet {
+            struct Set {
+                uint64[] values;
+                // position of the value in values, plus 1
+                mapping(uint64 => uint64) indexes;
+            }
+
+            function add(Set storage set, uint64 value) internal returns (bool) {
+                if (contains(set, value)) {
+                    return false;
+                }
+
+                set.values.push(value);
+                set.indexes[value] = uint64(set.values.length);
+
+                return true;
+            }
+
+            function remove(Set storage set, uint64 value) internal returns (bool) {
+                uint64 index = set.indexes[value];
+
+                if (index == 0) {
+                    return false;
+                }
+
+                uint64 last = set.values[set.values.length - 1];
+
+                set.values[index - 1] = last;
+                set.indexes[last] = index;
+                set.values.pop();
+
+                delete set.indexes[value];
+
+                return true;
+            }
+
+            function contains(Set storage set, uint64 value) internal view returns (bool) {
+                return set.indexes[value] != 0;
+            }
+
+            function length(Set storage set) internal view returns (uint64) {
+                return uint64(set.values.length);
+            }
+        }
+
+        contract c {
+            using EnumerableSet for EnumerableSet.Set;
+
+            struct Registry {
+                mapping(uint32 => EnumerableSet.Set) sets;
+            }
+
+            EnumerableSet.Set members;
+            Registry registry;
+
+            function test() public {
+                assert(members.add(5));
+                assert(members.add(7));
+                assert(!members.add(5));
+                assert(members.add(9));
+                assert(members.length() == 3);
+
+                assert(members.remove(5));
+                assert(!members.remove(5));
+                assert(!members.contains(5));
+                assert(members.contains(7));
+                assert(members.contains(9));
+                assert(members.length() == 2);
+
+                assert(registry.sets[1].add(100));
+                assert(registry.sets[2].add(200));
+                assert(registry.sets[1].contains(100));
+                assert(!registry.sets[2].contains(100));
+                assert(registry.sets[2].remove(200));
+                assert(registry.sets[2].length() == 0);
+                assert(registry.sets[1].length() == 1);
+            }
+
+            function contains(uint32 id, uint64 value) public view returns (bool) {
+                return registry.sets[id].contains(value);
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+    runtime.function("test", Vec::new());
+
+    runtime.function("contains", (1u32, 100u64).encode());
+    assert_eq!(runtime.vm.output, true.encode());
+
+    runtime.function("contains", (2u32, 200u64).encode());
+    assert_eq!(runtime.vm.output, false.encode());
+}
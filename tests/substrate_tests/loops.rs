@@ -20,3 +20,47 @@ fn for_loop_no_cond_or_next() {
     runtime.constructor(0, Vec::new());
     runtime.function("foo", true.encode());
 }
+
+#[test]
+fn for_loop_multiple_init_next() {
+    let mut runtime = build_solidity(
+        r##"
+        contract test {
+            function converge(uint64 n) public pure returns (uint64) {
+                uint64 count = 0;
+
+                for (uint64 i = 0, j = n; i < j; i++, j--) {
+                    count += 1;
+                }
+
+                return count;
+            }
+
+            function assign(uint64 n) public pure returns (uint64) {
+                uint64 i;
+                uint64 j;
+                uint64 count = 0;
+
+                for (i = 0, j = n; i < j; i += 2, j -= 1) {
+                    count += 1;
+                }
+
+                return count + i * 100;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("converge", 10u64.encode());
+    assert_eq!(runtime.vm.output, 5u64.encode());
+
+    runtime.function("converge", 7u64.encode());
+    assert_eq!(runtime.vm.output, 4u64.encode());
+
+    runtime.function("converge", 0u64.encode());
+    assert_eq!(runtime.vm.output, 0u64.encode());
+
+    runtime.function("assign", 9u64.encode());
+    assert_eq!(runtime.vm.output, 603u64.encode());
+}
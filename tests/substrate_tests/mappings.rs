@@ -2,7 +2,11 @@
 
 use parity_scale_codec::{Decode, Encode};
 use rand::Rng;
+use solang::file_resolver::FileResolver;
+use solang::{compile, Target};
 use std::collections::HashMap;
+use std::ffi::OsStr;
+use tiny_keccak::{Hasher, Keccak};
 
 use crate::build_solidity;
 
@@ -401,3 +405,228 @@ fn test_address() {
         assert_eq!(runtime.vm.output, Val(val.1).encode());
     }
 }
+
+#[test]
+fn compound_assign_key_evaluated_once() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            mapping(uint64 => uint64) balances;
+            uint64[4] arr;
+            uint64 calls;
+
+            function key(uint64 k) internal returns (uint64) {
+                calls += 1;
+                return k;
+            }
+
+            function test() public {
+                balances[key(5)] += 10;
+                balances[key(5)] *= 3;
+                balances[key(5)] -= 1;
+                arr[key(2)] |= 6;
+                arr[key(2)] <<= 1;
+
+                assert(calls == 5);
+                assert(balances[5] == 29);
+                assert(arr[2] == 12);
+            }
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+}
+
+#[test]
+fn keccak256_key() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            mapping(bytes32 => uint64) m;
+
+            function set(string name, uint32 id, uint64 v) public {
+                m[keccak256(abi.encodePacked(name, id))] = v;
+            }
+
+            function get(string name, uint32 id) public view returns (uint64) {
+                return m[keccak256(abi.encodePacked(name, id))];
+            }
+
+            function get_by_hash(bytes32 hash) public view returns (uint64) {
+                return m[hash];
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("set", ("foo".to_string(), 1u32, 100u64).encode());
+    runtime.function("set", ("foo".to_string(), 2u32, 200u64).encode());
+    runtime.function("set", ("bar".to_string(), 1u32, 300u64).encode());
+
+    runtime.function("get", ("foo".to_string(), 1u32).encode());
+    assert_eq!(runtime.vm.output, 100u64.encode());
+
+    runtime.function("get", ("foo".to_string(), 2u32).encode());
+    assert_eq!(runtime.vm.output, 200u64.encode());
+
+    runtime.function("get", ("bar".to_string(), 1u32).encode());
+    assert_eq!(runtime.vm.output, 300u64.encode());
+
+    runtime.function("get", ("bar".to_string(), 2u32).encode());
+    assert_eq!(runtime.vm.output, 0u64.encode());
+
+    // the key is the hash of the packed encoding
+    let mut hash = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(b"foo");
+    hasher.update(&2u32.to_le_bytes());
+    hasher.finalize(&mut hash);
+
+    runtime.function("get_by_hash", hash.encode());
+    assert_eq!(runtime.vm.output, 200u64.encode());
+}
+
+#[test]
+fn bytes32_and_uint256_keys() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            mapping(bytes32 => uint64) by_hash;
+            mapping(uint256 => uint64) by_number;
+
+            function set_hash(bytes32 key, uint64 value) public {
+                by_hash[key] = value;
+            }
+
+            function set_const() public {
+                by_hash[hex"0102030405060708091011121314151617181920212223242526272829303132"] = 5;
+                by_hash[bytes32(uint256(1))] = 6;
+            }
+
+            function set_pair(string a, string b, uint64 value) public {
+                by_hash[keccak256(abi.encodePacked(a, b))] = value;
+            }
+
+            function set_number(uint256 key, uint64 value) public {
+                by_number[key] = value;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    // the key of a mapping entry is keccak256 of the slot followed by the key; the slot is
+    // little endian, a bytesN key is used as is, and integer keys are little endian
+    let storage_key = |slot: u8, key: &[u8]| {
+        let mut buf = [0u8; 32].to_vec();
+        buf[0] = slot;
+        buf.extend_from_slice(key);
+
+        let mut hash = [0u8; 32];
+        let mut hasher = Keccak::v256();
+        hasher.update(&buf);
+        hasher.finalize(&mut hash);
+        hash
+    };
+
+    let mut key = [0u8; 32];
+    key[0] = 0xde;
+    key[31] = 0x01;
+
+    runtime.function("set_hash", (key, 100u64).encode());
+    assert_eq!(
+        runtime.storage_at(&storage_key(0, &key)),
+        Some(&100u64.encode())
+    );
+
+    // constant keys are folded at compile time, and must give the same slot as at runtime
+    runtime.function("set_const", Vec::new());
+
+    let key: Vec<u8> = (1..=32).map(|n: u8| (n / 10) << 4 | (n % 10)).collect();
+    assert_eq!(
+        runtime.storage_at(&storage_key(0, &key)),
+        Some(&5u64.encode())
+    );
+
+    let mut key = [0u8; 32];
+    key[31] = 1;
+    assert_eq!(
+        runtime.storage_at(&storage_key(0, &key)),
+        Some(&6u64.encode())
+    );
+
+    // a hash used as key is not hashed again
+    runtime.function(
+        "set_pair",
+        (String::from("foo"), String::from("bar"), 7u64).encode(),
+    );
+
+    let mut hash = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(b"foobar");
+    hasher.finalize(&mut hash);
+
+    assert_eq!(
+        runtime.storage_at(&storage_key(0, &hash)),
+        Some(&7u64.encode())
+    );
+
+    let mut key = [0u8; 32];
+    key[0] = 0x34;
+    key[1] = 0x12;
+
+    runtime.function("set_number", (key, 8u64).encode());
+    assert_eq!(
+        runtime.storage_at(&storage_key(1, &key)),
+        Some(&8u64.encode())
+    );
+}
+
+#[test]
+fn mapping_storage_layout() {
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r##"
+        contract c {
+            uint64 a;
+            mapping(bytes32 => uint64) by_hash;
+            mapping(uint256 => mapping(uint256 => bool)) nested;
+        }"##
+        .to_string(),
+    );
+
+    let (res, ns) = compile(
+        OsStr::new("test.sol"),
+        &mut cache,
+        inkwell::OptimizationLevel::Default,
+        Target::default_substrate(),
+        false,
+        false,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let metadata: serde_json::Value = serde_json::from_str(&res[0].1).unwrap();
+    let fields = &metadata["storage"]["struct"]["fields"];
+
+    assert_eq!(fields.as_array().unwrap().len(), 2);
+    assert_eq!(fields[0]["name"], "a");
+    assert_eq!(fields[1]["name"], "by_hash");
+
+    let hash = &fields[1]["layout"]["hash"];
+
+    assert_eq!(
+        hash["offset"],
+        "0x0000000000000000000000000000000000000000000000000000000000000001"
+    );
+    assert_eq!(hash["strategy"]["hasher"], "Keccak256");
+    assert_eq!(hash["strategy"]["prefix"], "");
+    assert_eq!(hash["strategy"]["postfix"], "");
+    assert_eq!(
+        hash["layout"]["cell"]["key"],
+        "0x0000000000000000000000000000000000000000000000000000000000000000"
+    );
+}
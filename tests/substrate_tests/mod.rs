@@ -22,6 +22,7 @@ mod loops;
 mod mappings;
 mod modifier;
 mod primitives;
+mod source_map;
 mod storage;
 mod strings;
 mod structs;
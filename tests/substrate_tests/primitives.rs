@@ -2,14 +2,10 @@
 
 use crate::build_solidity;
 use parity_scale_codec::{Decode, Encode};
+use solang::abi::scale::Value;
 
 #[test]
 fn various_constants() {
-    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
-    struct FooReturn(u32);
-    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
-    struct Foo64Return(i64);
-
     // parse
     let mut runtime = build_solidity(
         "
@@ -20,9 +16,9 @@ fn various_constants() {
         }",
     );
 
-    runtime.function("foo", Vec::new());
+    let ret = runtime.function_typed("foo", &[]);
 
-    assert_eq!(runtime.vm.output, FooReturn(2).encode());
+    assert_eq!(ret, vec![Value::Uint(2u32.into())]);
 
     // parse
     let mut runtime = build_solidity(
@@ -34,9 +30,9 @@ fn various_constants() {
         }",
     );
 
-    runtime.function("foo", Vec::new());
+    let ret = runtime.function_typed("foo", &[]);
 
-    assert_eq!(runtime.vm.output, FooReturn(0xdead_cafe).encode());
+    assert_eq!(ret, vec![Value::Uint(0xdead_cafeu32.into())]);
 
     // parse
     let mut runtime = build_solidity(
@@ -48,9 +44,9 @@ fn various_constants() {
         }",
     );
 
-    runtime.function("foo", Vec::new());
+    let ret = runtime.function_typed("foo", &[]);
 
-    assert_eq!(runtime.vm.output, FooReturn(1000).encode());
+    assert_eq!(ret, vec![Value::Uint(1000u32.into())]);
 
     // parse
     let mut runtime = build_solidity(
@@ -62,9 +58,9 @@ fn various_constants() {
         }",
     );
 
-    runtime.function("foo", Vec::new());
+    let ret = runtime.function_typed("foo", &[]);
 
-    assert_eq!(runtime.vm.output, Foo64Return(-7000).encode());
+    assert_eq!(ret, vec![Value::Int((-7000i64).into())]);
 
     // parse
     let mut runtime = build_solidity(
@@ -76,12 +72,9 @@ fn various_constants() {
         }",
     );
 
-    runtime.function("foo", Vec::new());
+    let ret = runtime.function_typed("foo", &[]);
 
-    assert_eq!(
-        runtime.vm.output,
-        Foo64Return(-0x7afe_dead_deed_cafe).encode()
-    );
+    assert_eq!(ret, vec![Value::Int((-0x7afe_dead_deed_cafei64).into())]);
 }
 
 #[test]
@@ -210,6 +203,32 @@ fn address() {
     runtime.function("check_param", val);
 }
 
+#[test]
+fn bytes32_address_conversion() {
+    let mut runtime = build_solidity(
+        r##"
+        contract test {
+            function to_address(bytes32 b) public returns (address) {
+                return address(b);
+            }
+
+            function to_bytes(address a) public returns (bytes32) {
+                return bytes32(a);
+            }
+        }"##,
+    );
+
+    let b: Vec<u8> = (1..=32).collect();
+
+    runtime.function("to_address", b.clone());
+
+    assert_eq!(runtime.vm.output, b);
+
+    runtime.function("to_bytes", b.clone());
+
+    assert_eq!(runtime.vm.output, b);
+}
+
 #[test]
 fn type_name() {
     // parse
@@ -241,6 +260,7 @@ fn type_name() {
                 assert(type(int16).min == -32768);
                 assert(type(int64).min == -9223372036854775808);
                 assert(type(int48).min == -140737488355328);
+                assert(type(int24).min == -8388608);
                 return 2;
             }
 
@@ -249,6 +269,7 @@ fn type_name() {
                 assert(type(int16).max == 32767);
                 assert(type(int64).max == 9223372036854775807);
                 assert(type(int48).max == 140737488355327);
+                assert(type(int24).max == 8388607);
                 return 2;
             }
 
@@ -257,14 +278,30 @@ fn type_name() {
                 assert(type(uint16).max == 65535);
                 assert(type(uint64).max == 18446744073709551615);
                 assert(type(uint48).max == 281474976710655);
+                assert(type(uint32).max == 0xffffffff);
+                assert(type(uint24).max == 16777215);
+
+                uint24 x = 16777215;
+                assert(x == type(uint24).max);
+                assert(type(uint24).min < x);
                 return 2;
             }
+
+            function array_size() public returns (uint32) {
+                uint16[type(uint8).max] arr;
+                arr[254] = 102;
+                assert(arr[254] == 102);
+                return arr.length;
+            }
         }"##,
     );
 
     runtime.function("min", Vec::new());
     runtime.function("max_int", Vec::new());
     runtime.function("max_uint", Vec::new());
+    runtime.function("array_size", Vec::new());
+
+    assert_eq!(runtime.vm.output, 255u32.encode());
 }
 
 #[test]
@@ -322,6 +359,41 @@ fn literal_bytes_cast() {
     runtime.function("foo", Vec::new());
 }
 
+#[test]
+fn bytes_narrowing_cast() {
+    let mut runtime = build_solidity(
+        r##"
+        contract test {
+            function narrow(bytes32 h) public returns (bytes4) {
+                bytes4 x = bytes4(h);
+
+                assert(x[0] == h[0]);
+                assert(x[3] == h[3]);
+
+                return x;
+            }
+
+            function narrow_to_one(bytes32 h) public returns (bytes1) {
+                return bytes1(h);
+            }
+        }"##,
+    );
+
+    let mut h = [0u8; 32];
+
+    for (i, b) in h.iter_mut().enumerate() {
+        *b = 0xa0 + i as u8;
+    }
+
+    runtime.function("narrow", h.to_vec());
+
+    assert_eq!(runtime.vm.output, h[..4].to_vec());
+
+    runtime.function("narrow_to_one", h.to_vec());
+
+    assert_eq!(runtime.vm.output, vec![0xa0]);
+}
+
 #[test]
 fn implicit_bytes_cast() {
     let mut runtime = build_solidity(
@@ -391,3 +463,34 @@ fn signed_literal_unsigned_cast() {
 
     runtime.function("foo", Vec::new());
 }
+
+#[test]
+fn user_defined_value_type() {
+    let mut runtime = build_solidity(
+        r##"
+        type Timestamp is uint64;
+
+        contract c {
+            type Delta is int32;
+
+            function later(Timestamp t, Delta d) public pure returns (Timestamp) {
+                return Timestamp.wrap(uint64(int64(Timestamp.unwrap(t)) + Delta.unwrap(d)));
+            }
+
+            function roundtrip(uint64 v) public pure returns (uint64) {
+                Timestamp t = Timestamp.wrap(v);
+
+                return Timestamp.unwrap(t);
+            }
+        }"##,
+    );
+
+    runtime.function("roundtrip", 1_660_000_000u64.encode());
+
+    assert_eq!(runtime.vm.output, 1_660_000_000u64.encode());
+
+    // user defined value types are encoded like their underlying type
+    runtime.function("later", (1_660_000_000u64, -60i32).encode());
+
+    assert_eq!(runtime.vm.output, 1_659_999_940u64.encode());
+}
@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use solang::codegen::{codegen, Options};
+use solang::emit::Generate;
+use solang::file_resolver::FileResolver;
+use solang::Target;
+use solang_parser::pt::CodeLocation;
+use std::ffi::OsStr;
+
+#[test]
+fn source_map_covers_public_functions() {
+    let src = r##"
+        contract c {
+            uint64 count;
+
+            function inc(uint64 x) public returns (uint64) {
+                count += x;
+                return count * 2;
+            }
+
+            function dec(uint64 x) public {
+                require(count >= x, "too small");
+                count -= x;
+            }
+
+            function get() public view returns (uint64) {
+                return count;
+            }
+        }"##;
+
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let mut ns = solang::parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    codegen(
+        &mut ns,
+        &Options {
+            generate_debug_information: true,
+            ..Default::default()
+        },
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let context = inkwell::context::Context::create();
+
+    let binary = ns.contracts[0].emit(
+        &ns,
+        &context,
+        "test.sol",
+        inkwell::OptimizationLevel::Default,
+        false,
+        true,
+    );
+
+    let code = binary.code(Generate::Linked).unwrap();
+
+    let map = binary.source_map(&code).unwrap();
+
+    assert!(!map.is_empty());
+
+    for entry in &map {
+        assert_eq!(entry.file_no, 0);
+        assert!(entry.start <= entry.end);
+        assert!(entry.end <= src.len());
+    }
+
+    for func_no in &ns.contracts[0].functions {
+        let func = &ns.functions[*func_no];
+
+        if !func.is_public() || func.body.is_empty() {
+            continue;
+        }
+
+        let start = func.body.first().unwrap().loc().start();
+        let end = func.body.last().unwrap().loc().end();
+
+        assert!(
+            map.iter()
+                .any(|entry| entry.start >= start && entry.end <= end),
+            "no source map entry for function {}",
+            func.name
+        );
+    }
+}
+
+#[test]
+fn source_map_imported_file() {
+    // the statements in both files are at the same lines and columns
+    let src = r##"
+        import "lib.sol";
+        contract c {
+            function f(uint64 x) public returns (uint64) {
+                return twice(x) + 7;
+            }
+        }"##;
+
+    let lib = r##"
+        // library
+        function twice(uint64 x) pure returns (uint64) {
+
+                return x * 2 + 7;
+        }"##;
+
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+    cache.set_file_contents("lib.sol", lib.to_string());
+
+    let mut ns = solang::parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_substrate(),
+    );
+
+    codegen(
+        &mut ns,
+        &Options {
+            generate_debug_information: true,
+            ..Default::default()
+        },
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let context = inkwell::context::Context::create();
+
+    let binary = ns.contracts[0].emit(
+        &ns,
+        &context,
+        "test.sol",
+        inkwell::OptimizationLevel::None,
+        false,
+        true,
+    );
+
+    let code = binary.code(Generate::Linked).unwrap();
+
+    let map = binary.source_map(&code).unwrap();
+
+    let lib_no = ns
+        .files
+        .iter()
+        .position(|file| file.path.ends_with("lib.sol"))
+        .unwrap();
+
+    let twice_start = lib.find("function twice").unwrap();
+
+    assert!(map.iter().any(|entry| entry.file_no == lib_no));
+    assert!(map.iter().any(|entry| entry.file_no != lib_no));
+
+    for entry in &map {
+        if entry.file_no == lib_no {
+            // entries for the imported file point into twice(), not into test.sol
+            assert!(entry.start >= twice_start, "{:?}", entry);
+            assert!(entry.end <= lib.len(), "{:?}", entry);
+        } else {
+            assert!(entry.end <= src.len(), "{:?}", entry);
+        }
+    }
+}
@@ -1,7 +1,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::build_solidity;
+use crate::{build_solidity, build_solidity_with_options};
 use parity_scale_codec::{Decode, Encode};
+use solang::codegen::Options;
+use solang::file_resolver::FileResolver;
+use solang::{compile, Target};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use tiny_keccak::{Hasher, Keccak};
 
 #[test]
 fn storage_load_on_return() {
@@ -37,3 +43,405 @@ contract foo {
         [SStruct { f1: 1 }, SStruct { f1: 2 }].encode(),
     );
 }
+
+#[test]
+fn storage_slot() {
+    let src = r##"
+        contract c {
+            uint64 a;
+            bool b;
+            mapping(uint64 => uint64) m;
+            uint64[] arr;
+
+            function slots() public view returns (uint256, uint256, uint256, uint256) {
+                return (a.slot, b.slot, m.slot, arr.slot);
+            }
+
+            function set() public {
+                a = 1;
+                b = true;
+                m[5] = 7;
+                arr.push(3);
+            }
+
+            function entry_slot(uint64 key) public view returns (uint256) {
+                return m[key].slot;
+            }
+
+            function elem_slot(uint64 index) public view returns (uint256) {
+                return arr[index].slot;
+            }
+        }"##;
+
+    let mut cache = FileResolver::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let (res, ns) = compile(
+        OsStr::new("test.sol"),
+        &mut cache,
+        inkwell::OptimizationLevel::Default,
+        Target::default_substrate(),
+        false,
+        false,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let metadata: serde_json::Value = serde_json::from_str(&res[0].1).unwrap();
+    let fields = metadata["storage"]["struct"]["fields"].as_array().unwrap();
+
+    // the layout has the slots as big endian hex, the storage keys are little endian
+    let layout_slot = |name: &str| {
+        let field = fields.iter().find(|field| field["name"] == name).unwrap();
+
+        let key = if let Some(hash) = field["layout"].get("hash") {
+            &hash["offset"]
+        } else {
+            &field["layout"]["cell"]["key"]
+        };
+
+        let mut slot = [0u8; 32];
+        slot.copy_from_slice(&hex::decode(&key.as_str().unwrap()[2..]).unwrap());
+        slot.reverse();
+        slot
+    };
+
+    let a = layout_slot("a");
+    let b = layout_slot("b");
+    let m = layout_slot("m");
+    let arr = layout_slot("arr");
+
+    let mut runtime = build_solidity(src);
+
+    runtime.function("slots", Vec::new());
+
+    assert_eq!(runtime.vm.output, (a, b, m, arr).encode());
+
+    runtime.function("set", Vec::new());
+
+    assert_eq!(runtime.storage_at(&a).unwrap(), &1u64.encode());
+    assert_eq!(runtime.storage_at(&b).unwrap(), &vec!(1));
+
+    // mapping entries are stored at keccak256 of the offset of the mapping, followed by the key
+    let mut entry = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(&m);
+    hasher.update(&5u64.to_le_bytes());
+    hasher.finalize(&mut entry);
+
+    runtime.function("entry_slot", 5u64.encode());
+
+    assert_eq!(runtime.vm.output, entry.encode());
+    assert_eq!(runtime.storage_at(&entry).unwrap(), &7u64.encode());
+
+    runtime.function("elem_slot", 0u64.encode());
+
+    let mut elem = [0u8; 32];
+    elem.copy_from_slice(&runtime.vm.output);
+
+    assert_eq!(runtime.storage_at(&elem).unwrap(), &3u64.encode());
+}
+
+#[test]
+fn pinned_storage_slot() {
+    let mut runtime = build_solidity(
+        r##"
+        contract proxy {
+            uint64 a;
+
+            /// @storage location="eip1967.proxy.implementation"
+            address implementation;
+
+            /// @storage slot=100
+            uint64 b;
+
+            uint64 c;
+
+            function set(address impl) public {
+                a = 1;
+                implementation = impl;
+                b = 2;
+                c = 3;
+            }
+
+            function get() public view returns (address, uint64, uint256) {
+                return (implementation, b, b.slot);
+            }
+        }"##,
+    );
+
+    let implementation = [0xaau8; 32];
+
+    runtime.function("set", implementation.encode());
+
+    // keccak256("eip1967.proxy.implementation") - 1, stored little endian
+    let mut pinned =
+        hex::decode("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc").unwrap();
+    pinned.reverse();
+
+    let mut key = [0u8; 32];
+
+    key.copy_from_slice(&pinned);
+
+    assert_eq!(runtime.storage_at(&key).unwrap(), &implementation.to_vec());
+
+    let mut key = [0u8; 32];
+    key[0] = 100;
+
+    assert_eq!(runtime.storage_at(&key).unwrap(), &2u64.encode());
+
+    // the sequential variables do not skip the pinned ones
+    let mut key = [0u8; 32];
+
+    assert_eq!(runtime.storage_at(&key).unwrap(), &1u64.encode());
+
+    key[0] = 1;
+
+    assert_eq!(runtime.storage_at(&key).unwrap(), &3u64.encode());
+
+    runtime.function("get", Vec::new());
+
+    let mut slot = [0u8; 32];
+    slot[0] = 100;
+
+    assert_eq!(runtime.vm.output, (implementation, 2u64, slot).encode());
+}
+
+#[test]
+fn stored_bytes() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint8 a = 7;
+            bool b;
+            string s;
+            int64 d;
+
+            constructor() {
+                b = true;
+                s = "hi";
+                d = -2;
+            }
+
+            function clear() public {
+                s = "";
+                delete d;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    let key = |slot: u8| {
+        let mut key = [0u8; 32];
+        key[0] = slot;
+        key
+    };
+
+    assert_eq!(
+        runtime.storage(),
+        HashMap::from([
+            (key(0), vec![7]),
+            (key(1), vec![1]),
+            (key(2), b"hi".to_vec()),
+            (key(3), (-2i64).encode()),
+        ])
+    );
+
+    runtime.function("clear", Vec::new());
+
+    // empty strings and deleted variables are removed from storage
+    assert_eq!(runtime.storage_at(&key(2)), None);
+    assert_eq!(runtime.storage_at(&key(3)), None);
+    assert_eq!(runtime.storage().len(), 2);
+}
+
+#[test]
+fn stored_bytes_grow_shrink() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            bytes b;
+            string s;
+
+            function push(bytes1 v) public {
+                b.push(v);
+            }
+
+            function pop() public returns (bytes1) {
+                return b.pop();
+            }
+
+            function set(string v) public {
+                s = v;
+            }
+
+            function get() public view returns (bytes, string) {
+                return (b, s);
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    let key = |slot: u8| {
+        let mut key = [0u8; 32];
+        key[0] = slot;
+        key
+    };
+
+    let mut expected = Vec::new();
+
+    // every length is stored as a single item holding just the bytes
+    for n in 1..=100u8 {
+        runtime.function("push", n.encode());
+        expected.push(n);
+
+        let text = "x".repeat(n as usize);
+
+        runtime.function("set", text.encode());
+
+        assert_eq!(runtime.storage_at(&key(0)), Some(&expected));
+        assert_eq!(runtime.storage_at(&key(1)), Some(&text.as_bytes().to_vec()));
+        assert_eq!(runtime.storage().len(), 2);
+
+        runtime.function("get", Vec::new());
+        assert_eq!(runtime.vm.output, (expected.clone(), text).encode());
+    }
+
+    for n in (1..=100u8).rev() {
+        runtime.function("pop", Vec::new());
+        assert_eq!(runtime.vm.output, n.encode());
+        expected.pop();
+
+        let text = "y".repeat(n as usize - 1);
+
+        runtime.function("set", text.encode());
+
+        runtime.function("get", Vec::new());
+        assert_eq!(runtime.vm.output, (expected.clone(), text).encode());
+    }
+
+    // empty values are removed from storage
+    assert_eq!(runtime.storage_at(&key(0)), None);
+    assert_eq!(runtime.storage_at(&key(1)), None);
+}
+
+#[test]
+fn storage_int_width_check() {
+    let src = r##"
+        contract c {
+            uint32 a;
+            int32 b;
+
+            function get_a() public returns (uint32) {
+                return a;
+            }
+
+            function get_b() public returns (int32) {
+                return b;
+            }
+        }"##;
+
+    let key = |slot: u8| {
+        let mut key = [0u8; 32];
+        key[0] = slot;
+        key
+    };
+
+    // with debug info, values wider than the type are accepted if they are zero or sign extended
+    let mut runtime = build_solidity_with_options(
+        src,
+        &Options {
+            math_overflow_check: false,
+            generate_debug_information: true,
+            log_prints: true,
+            ..Default::default()
+        },
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.set_storage(key(0), 300u64.encode());
+    runtime.function("get_a", Vec::new());
+    assert_eq!(runtime.vm.output, 300u32.encode());
+
+    runtime.set_storage(key(1), (-5i128).encode());
+    runtime.function("get_b", Vec::new());
+    assert_eq!(runtime.vm.output, (-5i32).encode());
+
+    // values which would be truncated cause a Panic(0x22)
+    let mut panic = vec![0x4e, 0x48, 0x7b, 0x71, 0x22];
+    panic.resize(36, 0);
+
+    runtime.set_storage(key(0), 0x1_0000_0001u64.encode());
+    runtime.function_expect_failure("get_a", Vec::new());
+    assert_eq!(runtime.vm.output, panic);
+
+    runtime.set_storage(key(1), (i32::MIN as i64 - 1).encode());
+    runtime.function_expect_failure("get_b", Vec::new());
+    assert_eq!(runtime.vm.output, panic);
+
+    // unsigned types only accept the zero extension, so 0xffffffffffffffff is not u32::MAX
+    runtime.set_storage(key(0), (-1i64).encode());
+    runtime.function_expect_panic("get_a", Vec::new(), 0x22);
+
+    // without debug info, the value is read with the width of the type
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.set_storage(key(0), 300u32.encode());
+    runtime.function("get_a", Vec::new());
+    assert_eq!(runtime.vm.output, 300u32.encode());
+}
+
+#[test]
+fn default_initializers_not_stored() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint64 a = 0;
+            bool b = false;
+            string s = "";
+            bytes4 h = 0;
+            int16 n = 7;
+            bytes d;
+            function() external f;
+            mapping(uint32 => int64) m;
+
+            function get() public view returns (uint64, bool, string, bytes4, int16, bytes, int64) {
+                assert(f.address == address(0));
+                assert(f.selector == 0);
+
+                return (a, b, s, h, n, d, m[1]);
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    // only the variable with a non-default initializer is written on deploy
+    let mut key = [0u8; 32];
+    key[0] = 4;
+
+    assert_eq!(runtime.storage(), HashMap::from([(key, 7i16.encode())]));
+
+    // never written storage reads as the default value
+    runtime.function("get", Vec::new());
+
+    assert_eq!(
+        runtime.vm.output,
+        (
+            0u64,
+            false,
+            String::new(),
+            [0u8; 4],
+            7i16,
+            Vec::<u8>::new(),
+            0i64
+        )
+            .encode()
+    );
+}
@@ -2,6 +2,7 @@
 
 use parity_scale_codec::{Decode, Encode};
 use rand::Rng;
+use solang::abi::scale::Value;
 
 use crate::build_solidity;
 
@@ -169,6 +170,112 @@ fn string_concat() {
     runtime.function("test", Vec::new());
 }
 
+#[test]
+fn string_concat_builtin() {
+    let mut runtime = build_solidity(
+        r##"
+        contract foo {
+            function test() public {
+                assert(string.concat("foo", "bar") == "foobar");
+                assert(string.concat() == "");
+            }
+
+            function join(string a, string b) public returns (string) {
+                return string.concat(a, "", b, "!");
+            }
+
+            function single(string a) public returns (string) {
+                return string.concat(a);
+            }
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+
+    runtime.function("join", (String::from("foo"), String::from("bar")).encode());
+
+    assert_eq!(runtime.vm.output, String::from("foobar!").encode());
+
+    runtime.function("join", (String::new(), String::new()).encode());
+
+    assert_eq!(runtime.vm.output, String::from("!").encode());
+
+    runtime.function("single", String::from("€ ok").encode());
+
+    assert_eq!(runtime.vm.output, String::from("€ ok").encode());
+}
+
+#[test]
+fn string_concat_three() {
+    let mut runtime = build_solidity(
+        r##"
+        contract foo {
+            function path(string dir, string file, string ext) public returns (string) {
+                return string.concat(dir, string.concat("/", file), ".", ext);
+            }
+
+            function test() public {
+                string a = "one";
+                string b = "two";
+                string c = "three";
+
+                assert(string.concat(a, b, c) == "onetwothree");
+                assert(bytes(string.concat(a, b, c)).length == 11);
+            }
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+
+    runtime.function(
+        "path",
+        (
+            String::from("/usr/lib"),
+            String::from("libsolang"),
+            String::from("so"),
+        )
+            .encode(),
+    );
+
+    assert_eq!(
+        runtime.vm.output,
+        String::from("/usr/lib/libsolang.so").encode()
+    );
+}
+
+#[test]
+fn uint_to_string() {
+    let mut runtime = build_solidity(
+        r##"
+        contract foo {
+            function test() public {
+                assert(uint256(0).toString() == "0");
+                assert(uint256(12345).toString() == "12345");
+                assert(uint256(2**256 - 1).toString() == "115792089237316195423570985008687907853269984665640564039457584007913129639935");
+
+                uint8 a = 255;
+                assert(a.toString() == "255");
+
+                uint64 b = 18446744073709551615;
+                assert(b.toString() == "18446744073709551615");
+
+                uint128 c = 10000000000000000000;
+                assert(c.toString() == "10000000000000000000");
+            }
+
+            function dec(uint64 x) public pure returns (string) {
+                return x.toString();
+            }
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+
+    runtime.function("dec", 1000000u64.encode());
+
+    assert_eq!(runtime.vm.output, String::from("1000000").encode());
+}
+
 #[test]
 fn string_abi_encode() {
     #[derive(Debug, PartialEq, Eq, Encode, Decode)]
@@ -257,12 +364,6 @@ fn string_abi_encode() {
 
 #[test]
 fn string_abi_decode() {
-    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
-    struct Val(String);
-
-    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
-    struct ValB(Vec<u8>);
-
     // we should try lengths: 0 to 63, 64 to 0x800
     let mut runtime = build_solidity(
         r##"
@@ -275,14 +376,14 @@ fn string_abi_decode() {
 
     let moby_dick_first_para = "Call me Ishmael. Some years ago—never mind how long precisely—having little or no money in my purse, and nothing particular to interest me on shore, I thought I would sail about a little and see the watery part of the world. It is a way I have of driving off the spleen and regulating the circulation. Whenever I find myself growing grim about the mouth; whenever it is a damp, drizzly November in my soul; whenever I find myself involuntarily pausing before coffin warehouses, and bringing up the rear of every funeral I meet; and especially whenever my hypos get such an upper hand of me, that it requires a strong moral principle to prevent me from deliberately stepping into the street, and methodically knocking people’s hats off—then, I account it high time to get to sea as soon as I can. This is my substitute for pistol and ball. With a philosophical flourish Cato throws himself upon his sword; I quietly take to the ship. There is nothing surprising in this. If they but knew it, almost all men in their degree, some time or other, cherish very nearly the same feelings towards the ocean with me.";
 
-    runtime.function("test", Val("foobar".to_string()).encode());
-    assert_eq!(runtime.vm.output, Val(" foobar ".to_string()).encode());
+    let ret = runtime.function_typed("test", &[Value::String("foobar".to_string())]);
+    assert_eq!(ret, vec![Value::String(" foobar ".to_string())]);
 
-    runtime.function("test", Val(moby_dick_first_para.to_string()).encode());
+    let ret = runtime.function_typed("test", &[Value::String(moby_dick_first_para.to_string())]);
 
     assert_eq!(
-        runtime.vm.output,
-        Val(format!(" {} ", moby_dick_first_para)).encode()
+        ret,
+        vec![Value::String(format!(" {} ", moby_dick_first_para))]
     );
 
     let mut rng = rand::thread_rng();
@@ -303,15 +404,11 @@ fn string_abi_decode() {
             }"##,
         );
 
-        let arg = ValB(s.clone()).encode();
-
-        runtime.function("test", arg.clone());
+        let ret = runtime.function_typed("test", &[Value::Bytes(s.clone())]);
 
         s.insert(0, 0xfeu8);
 
-        let ret = ValB(s).encode();
-
-        assert_eq!(runtime.vm.output, ret);
+        assert_eq!(ret, vec![Value::Bytes(s)]);
     }
 }
 
@@ -595,6 +692,82 @@ fn bytes_memory_subscript() {
     );
 }
 
+#[test]
+fn string_bytes_cast() {
+    let mut runtime = build_solidity(
+        r##"
+        contract foo {
+            function upper(string s) public returns (string) {
+                bytes b = bytes(s);
+
+                assert(b.length == 5);
+                assert(b[0] == "h");
+                assert(b[4] == "o");
+
+                for (uint32 i = 0; i < b.length; i++) {
+                    b[i] = bytes1(uint8(b[i]) - 32);
+                }
+
+                return string(b);
+            }
+
+            function roundtrip(string s) public returns (string) {
+                return string(bytes(s));
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("upper", "hello".to_string().encode());
+
+    assert_eq!(runtime.vm.output, "HELLO".to_string().encode());
+
+    runtime.function("roundtrip", "€ ok".to_string().encode());
+
+    assert_eq!(runtime.vm.output, "€ ok".to_string().encode());
+}
+
+#[test]
+fn bytes_fixed_dynamic_cast() {
+    let mut runtime = build_solidity(
+        r##"
+        contract foo {
+            function roundtrip(bytes32 h) public returns (bytes32, bytes) {
+                bytes b = bytes.concat(h);
+                assert(b.length == 32);
+
+                bytes32 h2 = bytes32(b);
+                assert(h2 == bytes32(abi.encode(h)));
+
+                return (h2, bytes.concat(b, hex"ff", bytes4(0x01020304)));
+            }
+
+            function fixed(bytes b) public returns (bytes32) {
+                return bytes32(b);
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    let h: [u8; 32] = core::array::from_fn(|i| i as u8 + 1);
+
+    runtime.function("roundtrip", h.encode());
+
+    let mut b = h.to_vec();
+    b.extend([0xff, 1, 2, 3, 4]);
+
+    assert_eq!(runtime.vm.output, (h, b).encode());
+
+    runtime.function("fixed", h.to_vec().encode());
+
+    assert_eq!(runtime.vm.output, h.encode());
+
+    // the length must match exactly
+    runtime.function_expect_failure("fixed", h[1..].to_vec().encode());
+}
+
 #[test]
 fn string_escape() {
     let mut runtime = build_solidity(
@@ -612,3 +785,50 @@ fn string_escape() {
 
     assert_eq!(runtime.printbuf, " € A \u{c}\u{8}\r\n\u{b}\\'\"\t");
 }
+
+#[test]
+fn identical_literals_interned() {
+    let message = "the value should be less than the limit";
+
+    let mut src = String::from("contract c {\n");
+
+    for i in 0..20 {
+        src.push_str(&format!(
+            "    function f{}(uint64 x) public {{ require(x < {}, \"{}\"); }}\n",
+            i,
+            i + 100,
+            message
+        ));
+    }
+
+    // a prefix of the message can share its storage
+    src.push_str("    function g(uint64 x) public { require(x < 1, \"the value\"); }\n}");
+
+    let mut runtime = build_solidity(&src);
+
+    let code = &runtime.programs[0].code;
+
+    let occurrences = code
+        .windows(message.len())
+        .filter(|w| *w == message.as_bytes())
+        .count();
+
+    assert_eq!(occurrences, 1);
+
+    let prefix = "the value";
+
+    let occurrences = code
+        .windows(prefix.len())
+        .filter(|w| *w == prefix.as_bytes())
+        .count();
+
+    assert_eq!(occurrences, 1);
+
+    runtime.function_expect_failure("f3", 200u64.encode());
+
+    assert_eq!(&runtime.vm.output[5..], message.as_bytes());
+
+    runtime.function_expect_failure("g", 1u64.encode());
+
+    assert_eq!(&runtime.vm.output[5..], prefix.as_bytes());
+}
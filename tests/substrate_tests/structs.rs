@@ -2,6 +2,7 @@
 
 use parity_scale_codec::{Decode, Encode};
 use serde_derive::Deserialize;
+use solang::abi::scale::Value;
 
 use crate::build_solidity;
 
@@ -92,12 +93,6 @@ fn structs_as_ref_args() {
 
 #[test]
 fn structs_encode() {
-    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
-    struct Foo {
-        f1: [u8; 3],
-        f2: bool,
-    }
-
     let mut runtime = build_solidity(
         r##"
         contract test_struct_parsing {
@@ -113,13 +108,12 @@ fn structs_encode() {
         }"##,
     );
 
-    runtime.function(
+    runtime.function_typed(
         "test",
-        Foo {
-            f1: [0x41, 0x42, 0x43],
-            f2: true,
-        }
-        .encode(),
+        &[Value::Struct(vec![
+            Value::FixedBytes(b"ABC".to_vec()),
+            Value::Bool(true),
+        ])],
     );
 }
 
@@ -296,18 +290,6 @@ fn struct_in_struct() {
 
 #[test]
 fn structs_in_structs_decode() {
-    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
-    struct Foo {
-        f1: [u8; 3],
-        f2: i32,
-    }
-    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
-    struct Bar {
-        a: bool,
-        b: Foo,
-        c: Foo,
-    }
-
     let mut runtime = build_solidity(
         r##"
         contract test_struct_parsing {
@@ -330,39 +312,26 @@ fn structs_in_structs_decode() {
         }"##,
     );
 
-    runtime.function("test", Vec::new());
+    let ret = runtime.function_typed("test", &[]);
 
     assert_eq!(
-        runtime.vm.output,
-        Bar {
-            a: true,
-            b: Foo {
-                f1: [0xc3, 0x00, 0x00],
-                f2: 0xff7f,
-            },
-            c: Foo {
-                f1: [0xf7, 0xf6, 0xf5],
-                f2: 0x4002,
-            }
-        }
-        .encode(),
+        ret,
+        vec![Value::Struct(vec![
+            Value::Bool(true),
+            Value::Struct(vec![
+                Value::FixedBytes(vec![0xc3, 0x00, 0x00]),
+                Value::Int(0xff7f.into()),
+            ]),
+            Value::Struct(vec![
+                Value::FixedBytes(vec![0xf7, 0xf6, 0xf5]),
+                Value::Int(0x4002.into()),
+            ]),
+        ])]
     );
 }
 
 #[test]
 fn structs_in_structs_encode() {
-    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
-    struct Foo {
-        f1: [u8; 3],
-        f2: i32,
-    }
-    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
-    struct Bar {
-        a: bool,
-        b: Foo,
-        c: Foo,
-    }
-
     let mut runtime = build_solidity(
         r##"
         contract test_struct_parsing {
@@ -386,20 +355,19 @@ fn structs_in_structs_encode() {
         }"##,
     );
 
-    runtime.function(
+    runtime.function_typed(
         "test",
-        Bar {
-            a: true,
-            b: Foo {
-                f1: [0xc3, 0x00, 0x00],
-                f2: 0xff7f,
-            },
-            c: Foo {
-                f1: [0xf7, 0xf6, 0xf5],
-                f2: 0x4002,
-            },
-        }
-        .encode(),
+        &[Value::Struct(vec![
+            Value::Bool(true),
+            Value::Struct(vec![
+                Value::FixedBytes(vec![0xc3, 0x00, 0x00]),
+                Value::Int(0xff7f.into()),
+            ]),
+            Value::Struct(vec![
+                Value::FixedBytes(vec![0xf7, 0xf6, 0xf5]),
+                Value::Int(0x4002.into()),
+            ]),
+        ])],
     );
 }
 
@@ -593,3 +561,111 @@ fn struct_struct_in_init_and_return() {
 
     runtime.function("test", Vec::new());
 }
+
+#[test]
+fn struct_storage_parameter() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            struct account {
+                uint64 balance;
+                string name;
+            }
+
+            account acc = account(100, "alice");
+
+            function deposit(account storage a, uint64 amount) internal {
+                a.balance += amount;
+                a.name = "bob";
+            }
+
+            function test(uint64 amount) public {
+                deposit(acc, amount);
+            }
+
+            function get() public view returns (account) {
+                return acc;
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function_typed("test", &[Value::Uint(23u64.into())]);
+
+    let ret = runtime.function_typed("get", &[]);
+
+    assert_eq!(
+        ret,
+        vec![Value::Struct(vec![
+            Value::Uint(123u64.into()),
+            Value::String("bob".to_string()),
+        ])]
+    );
+}
+
+#[test]
+fn struct_equality() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Point(i32, i32, bool);
+
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            enum suit { club, diamonds, hearts, spades }
+
+            struct card {
+                suit s;
+                uint8 v;
+            }
+
+            struct point {
+                int32 x;
+                int32 y;
+                card c;
+                address owner;
+            }
+
+            point stored;
+
+            function compare(int32 x, int32 y, bool equal) public {
+                point a = point(x, y, card(suit.hearts, 2), address(this));
+                point b = point(1, 2, card(suit.hearts, 2), address(this));
+
+                assert((a == b) == equal);
+                assert((a != b) != equal);
+            }
+
+            function nested() public {
+                point a = point(1, 2, card(suit.hearts, 2), address(this));
+                point b = point(1, 2, card(suit.spades, 2), address(this));
+
+                assert(a == a);
+                assert(a != b);
+
+                b.c.s = suit.hearts;
+                assert(a == b);
+
+                b.owner = address(0);
+                assert(a != b);
+            }
+
+            function in_storage() public {
+                point a = point(1, 2, card(suit.club, 9), address(this));
+
+                assert(stored != a);
+
+                stored = a;
+
+                assert(stored == a);
+                assert(a == stored);
+            }
+        }"##,
+    );
+
+    runtime.function("compare", Point(1, 2, true).encode());
+    runtime.function("compare", Point(1, 3, false).encode());
+    runtime.function("compare", Point(-1, 2, false).encode());
+    runtime.function("nested", Vec::new());
+    runtime.function("in_storage", Vec::new());
+}
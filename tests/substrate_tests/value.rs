@@ -401,7 +401,7 @@ fn selfdestruct() {
         }
 
         contract other {
-            function goaway(address payable recipient) public returns (bool) {
+            function goaway(address payable recipient) public {
                 selfdestruct(recipient);
             }
         }"##,
@@ -411,11 +411,43 @@ fn selfdestruct() {
     runtime.function("step1", Vec::new());
     assert_eq!(runtime.accounts.get_mut(&runtime.vm.account).unwrap().1, 0);
 
-    runtime.function_expect_failure("step2", Vec::new());
+    let other = *runtime
+        .accounts
+        .keys()
+        .find(|account| **account != runtime.vm.account)
+        .unwrap();
+    assert_eq!(runtime.accounts[&other].1, 511);
+
+    runtime.function("step2", Vec::new());
     assert_eq!(
         runtime.accounts.get_mut(&runtime.vm.account).unwrap().1,
         511
     );
+    assert!(!runtime.accounts.contains_key(&other));
+}
+
+#[test]
+fn selfdestruct_to_third_party() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function goaway(address payable recipient) public {
+                selfdestruct(recipient);
+            }
+        }"##,
+    );
+    runtime.constructor(0, Vec::new());
+
+    let recipient = [0x42u8; 32];
+    let account = runtime.vm.account;
+
+    runtime.accounts.insert(recipient, (Vec::new(), 100));
+    runtime.accounts.get_mut(&account).unwrap().1 = 1000;
+
+    runtime.function("goaway", recipient.to_vec());
+
+    assert_eq!(runtime.accounts[&recipient].1, 1100);
+    assert!(!runtime.accounts.contains_key(&account));
 }
 
 #[test]
@@ -442,6 +474,8 @@ fn send_and_transfer() {
 
     runtime.constructor(0, Vec::new());
 
+    runtime.accounts.get_mut(&runtime.vm.account).unwrap().1 = 2000;
+
     runtime.function("step1", Vec::new());
 
     // no receive() required for send/transfer
@@ -477,6 +511,8 @@ fn send_and_transfer() {
 
     runtime.constructor(0, Vec::new());
 
+    runtime.accounts.get_mut(&runtime.vm.account).unwrap().1 = 2000;
+
     runtime.function("step1", Vec::new());
 
     assert_eq!(runtime.vm.output, true.encode());
@@ -511,6 +547,8 @@ fn send_and_transfer() {
 
     runtime.constructor(0, Vec::new());
 
+    runtime.accounts.get_mut(&runtime.vm.account).unwrap().1 = 2000;
+
     runtime.function("step1", Vec::new());
 
     for (address, account) in runtime.accounts {
@@ -543,6 +581,8 @@ fn send_and_transfer() {
 
     runtime.constructor(0, Vec::new());
 
+    runtime.accounts.get_mut(&runtime.vm.account).unwrap().1 = 2000;
+
     runtime.function("step1", Vec::new());
 
     for (address, account) in runtime.accounts {
@@ -553,3 +593,79 @@ fn send_and_transfer() {
         assert_eq!(account.1, 1011);
     }
 }
+
+#[test]
+fn transfer_all() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            other o;
+
+            constructor() {
+                o = new other();
+            }
+
+            function all() public {
+                payable(o).transferAll();
+            }
+
+            function one() public returns (bool) {
+                return payable(o).send(1);
+            }
+
+            function other_address() public view returns (address) {
+                return address(o);
+            }
+        }
+
+        contract other {
+            function giveme() public {
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("other_address", Vec::new());
+
+    let other = <[u8; 32]>::decode(&mut &runtime.vm.output[..]).unwrap();
+
+    assert_eq!(runtime.accounts[&other].1, 500);
+
+    runtime.accounts.get_mut(&runtime.vm.account).unwrap().1 = 10_000;
+
+    runtime.function("all", Vec::new());
+
+    // the minimum balance stays behind
+    assert_eq!(runtime.accounts[&runtime.vm.account].1, 500);
+    assert_eq!(runtime.accounts[&other].1, 10_000);
+
+    // nothing more can be transferred
+    runtime.function("one", Vec::new());
+    assert_eq!(runtime.vm.output, false.encode());
+
+    runtime.function("all", Vec::new());
+    assert_eq!(runtime.accounts[&runtime.vm.account].1, 500);
+    assert_eq!(runtime.accounts[&other].1, 10_000);
+}
+
+#[test]
+fn value_type_alias() {
+    let mut runtime = build_solidity(include_str!("../../examples/value_type.sol"));
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.vm.value = 300;
+    runtime.function("deposit", Vec::new());
+    runtime.vm.value = 0;
+
+    assert_eq!(runtime.vm.output, 300u128.encode());
+
+    runtime.function("max", Vec::new());
+
+    assert_eq!(runtime.vm.output, u128::MAX.encode());
+
+    runtime.function("me", Vec::new());
+
+    assert_eq!(runtime.vm.output, runtime.vm.account.to_vec());
+}
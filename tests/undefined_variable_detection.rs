@@ -20,6 +20,8 @@ fn parse_and_codegen(src: &'static str) -> Namespace {
         opt_level: OptimizationLevel::Default,
         math_overflow_check: false,
         generate_debug_information: false,
+        log_prints: true,
+        strip_debug_asserts: false,
     };
 
     codegen(&mut ns, &opt);